@@ -187,11 +187,15 @@ lazy_static! {
 		arr[OR as usize] =				InstructionInfo::new("OR",				2, 1, GasPriceTier::VeryLow);
 		arr[XOR as usize] = 			InstructionInfo::new("XOR",				2, 1, GasPriceTier::VeryLow);
 		arr[BYTE as usize] =			InstructionInfo::new("BYTE",			2, 1, GasPriceTier::VeryLow);
+		arr[SHL as usize] =				InstructionInfo::new("SHL",				2, 1, GasPriceTier::VeryLow);
+		arr[SHR as usize] =				InstructionInfo::new("SHR",				2, 1, GasPriceTier::VeryLow);
+		arr[SAR as usize] =				InstructionInfo::new("SAR",				2, 1, GasPriceTier::VeryLow);
 		arr[ADDMOD as usize] =			InstructionInfo::new("ADDMOD",			3, 1, GasPriceTier::Mid);
 		arr[MULMOD as usize] =			InstructionInfo::new("MULMOD",			3, 1, GasPriceTier::Mid);
 		arr[SIGNEXTEND as usize] =		InstructionInfo::new("SIGNEXTEND",		2, 1, GasPriceTier::Low);
 		arr[RETURNDATASIZE as usize] =	InstructionInfo::new("RETURNDATASIZE",	0, 1, GasPriceTier::Base);
 		arr[RETURNDATACOPY as usize] =	InstructionInfo::new("RETURNDATACOPY",	3, 0, GasPriceTier::VeryLow);
+		arr[EXTCODEHASH as usize] =		InstructionInfo::new("EXTCODEHASH",		1, 1, GasPriceTier::Special);
 		arr[SHA3 as usize] =			InstructionInfo::new("SHA3",			2, 1, GasPriceTier::Special);
 		arr[ADDRESS as usize] = 		InstructionInfo::new("ADDRESS",			0, 1, GasPriceTier::Base);
 		arr[BALANCE as usize] = 		InstructionInfo::new("BALANCE",			1, 1, GasPriceTier::Special);
@@ -300,7 +304,7 @@ lazy_static! {
 		arr[DELEGATECALL as usize] =	InstructionInfo::new("DELEGATECALL",	6, 1, GasPriceTier::Special);
 		arr[STATICCALL as usize] =		InstructionInfo::new("STATICCALL",		6, 1, GasPriceTier::Special);
 		arr[SUICIDE as usize] = 		InstructionInfo::new("SUICIDE",			1, 0, GasPriceTier::Special);
-		arr[CREATE2 as usize] = 		InstructionInfo::new("CREATE2",			3, 1, GasPriceTier::Special);
+		arr[CREATE2 as usize] = 		InstructionInfo::new("CREATE2",			4, 1, GasPriceTier::Special);
 		arr[REVERT as usize] =			InstructionInfo::new("REVERT",			2, 0, GasPriceTier::Zero);
 		arr
 	};
@@ -354,6 +358,12 @@ pub const XOR: Instruction = 0x18;
 pub const NOT: Instruction = 0x19;
 /// retrieve single byte from word
 pub const BYTE: Instruction = 0x1a;
+/// shift left operation
+pub const SHL: Instruction = 0x1b;
+/// logical shift right operation
+pub const SHR: Instruction = 0x1c;
+/// arithmetic shift right operation
+pub const SAR: Instruction = 0x1d;
 
 /// compute SHA3-256 hash
 pub const SHA3: Instruction = 0x20;
@@ -388,6 +398,8 @@ pub const EXTCODECOPY: Instruction = 0x3c;
 pub const RETURNDATASIZE: Instruction = 0x3d;
 /// copy return data buffer to memory
 pub const RETURNDATACOPY: Instruction = 0x3e;
+/// get external code hash (from another contract)
+pub const EXTCODEHASH: Instruction = 0x3f;
 
 /// get hash of most recent complete block
 pub const BLOCKHASH: Instruction = 0x40;
@@ -581,7 +593,7 @@ pub const CALLCODE: Instruction = 0xf2;
 pub const RETURN: Instruction = 0xf3;
 /// like CALLCODE but keeps caller's value and sender
 pub const DELEGATECALL: Instruction = 0xf4;
-/// create a new account and set creation address to sha3(sender + sha3(init code)) % 2**160
+/// create a new account and set creation address to sha3(0xff + sender + salt + sha3(init code)) % 2**160
 pub const CREATE2: Instruction = 0xfb;
 /// stop execution and revert state changes. Return output data.
 pub const REVERT: Instruction = 0xfd;
@@ -127,7 +127,10 @@ impl<Gas: evm::CostType> Gasometer<Gas> {
 				let newval = stack.peek(1);
 				let val = U256::from(&*ext.storage_at(&address)?);
 
-				let gas = if val.is_zero() && !newval.is_zero() {
+				let gas = if schedule.eip1283 {
+					let orig = U256::from(&*ext.initial_storage_at(&address)?);
+					calculate_eip1283_sstore_gas(schedule, &orig, &val, &newval)
+				} else if val.is_zero() && !newval.is_zero() {
 					schedule.sstore_set_gas
 				} else {
 					// Refund for below case is added when actually executing sstore
@@ -145,6 +148,9 @@ impl<Gas: evm::CostType> Gasometer<Gas> {
 			instructions::EXTCODESIZE => {
 				Request::Gas(Gas::from(schedule.extcodesize_gas))
 			},
+			instructions::EXTCODEHASH => {
+				Request::Gas(Gas::from(schedule.extcodehash_gas))
+			},
 			instructions::SUICIDE => {
 				let mut gas = Gas::from(schedule.suicide_gas);
 
@@ -225,12 +231,22 @@ impl<Gas: evm::CostType> Gasometer<Gas> {
 
 				Request::GasMemProvide(gas, mem, Some(requested))
 			},
-			instructions::CREATE | instructions::CREATE2 => {
+			instructions::CREATE => {
 				let gas = Gas::from(schedule.create_gas);
 				let mem = mem_needed(stack.peek(1), stack.peek(2))?;
 
 				Request::GasMemProvide(gas, mem, None)
 			},
+			instructions::CREATE2 => {
+				// init code has to be hashed to compute the address
+				let w = overflowing!(add_gas_usize(Gas::from_u256(*stack.peek(2))?, 31));
+				let words = w >> 5;
+				let hash_gas = overflowing!(Gas::from(schedule.sha3_word_gas).overflow_mul(words));
+				let gas = overflowing!(Gas::from(schedule.create_gas).overflow_add(hash_gas));
+				let mem = mem_needed(stack.peek(1), stack.peek(2))?;
+
+				Request::GasMemProvide(gas, mem, None)
+			},
 			instructions::EXP => {
 				let expon = stack.peek(1);
 				let bytes = ((expon.bits() + 7) / 8) as usize;
@@ -318,6 +334,58 @@ impl<Gas: evm::CostType> Gasometer<Gas> {
 	}
 }
 
+/// Gas cost of `SSTORE` under EIP-1283 net gas metering.
+pub fn calculate_eip1283_sstore_gas(schedule: &Schedule, original: &U256, current: &U256, new: &U256) -> usize {
+	if current == new {
+		// no-op
+		schedule.sload_gas
+	} else if original == current {
+		// fresh slot within this transaction
+		if original.is_zero() {
+			schedule.sstore_set_gas
+		} else {
+			// Refund for clearing is added when actually executing sstore
+			schedule.sstore_reset_gas
+		}
+	} else {
+		// dirty slot, already paid for
+		schedule.sload_gas
+	}
+}
+
+/// Adjust `SSTORE` refunds under EIP-1283 net gas metering.
+pub fn handle_eip1283_sstore_clears_refund(ext: &mut vm::Ext, original: &U256, current: &U256, new: &U256) {
+	let sstore_clears_schedule = ext.schedule().sstore_refund_gas;
+
+	if current == new {
+		// no refund for a no-op
+	} else if original == current {
+		if !original.is_zero() && new.is_zero() {
+			ext.add_sstore_refund(sstore_clears_schedule);
+		}
+	} else {
+		if !original.is_zero() {
+			if current.is_zero() {
+				// slot was cleared earlier in this transaction and is now being set again
+				ext.sub_sstore_refund(sstore_clears_schedule);
+			} else if new.is_zero() {
+				ext.add_sstore_refund(sstore_clears_schedule);
+			}
+		}
+
+		if original == new {
+			// slot is reset to its original value
+			if original.is_zero() {
+				let refund = ext.schedule().sstore_set_gas - ext.schedule().sload_gas;
+				ext.add_sstore_refund(refund);
+			} else {
+				let refund = ext.schedule().sstore_reset_gas - ext.schedule().sload_gas;
+				ext.add_sstore_refund(refund);
+			}
+		}
+	}
+}
+
 
 #[inline]
 fn mem_needed_const<Gas: evm::CostType>(mem: &U256, add: usize) -> vm::Result<Gas> {
@@ -142,6 +142,7 @@ impl<Cost: CostType> vm::Vm for Interpreter<Cost> {
 			let requirements = gasometer.requirements(ext, instruction, info, &stack, self.mem.size())?;
 			if do_trace {
 				ext.trace_prepare_execute(reader.position - 1, instruction, requirements.gas_cost.as_u256());
+				ext.trace_machine_state(gasometer.current_gas.as_u256(), stack.peek_top(stack.size()), &self.mem);
 			}
 
 			gasometer.verify_gas(&requirements.gas_cost)?;
@@ -223,6 +224,8 @@ impl<Cost: CostType> Interpreter<Cost> {
 			(instruction == instructions::CREATE2 && !schedule.have_create2) ||
 			(instruction == instructions::STATICCALL && !schedule.have_static_call) ||
 			((instruction == instructions::RETURNDATACOPY || instruction == instructions::RETURNDATASIZE) && !schedule.have_return_data) ||
+			((instruction == instructions::SHL || instruction == instructions::SHR || instruction == instructions::SAR) && !schedule.have_bitwise_shifting) ||
+			(instruction == instructions::EXTCODEHASH && !schedule.have_extcodehash) ||
 			(instruction == instructions::REVERT && !schedule.have_revert) {
 
 			return Err(vm::Error::BadInstruction {
@@ -319,7 +322,11 @@ impl<Cost: CostType> Interpreter<Cost> {
 				let init_off = stack.pop_back();
 				let init_size = stack.pop_back();
 
-				let address_scheme = if instruction == instructions::CREATE { CreateContractAddress::FromSenderAndNonce } else { CreateContractAddress::FromSenderAndCodeHash };
+				let address_scheme = match instruction {
+					instructions::CREATE => CreateContractAddress::FromSenderAndNonce,
+					instructions::CREATE2 => CreateContractAddress::FromSenderSaltAndCodeHash(H256::from(&stack.pop_back())),
+					_ => unreachable!("instruction can only be CREATE/CREATE2 checked above; qed"),
+				};
 				let create_gas = provided.expect("`provided` comes through Self::exec from `Gasometer::get_gas_cost_mem`; `gas_gas_mem_cost` guarantees `Some` when instruction is `CALL`/`CALLCODE`/`DELEGATECALL`/`CREATE`; this is `CREATE`; qed");
 
 				let contract_code = self.mem.read_slice(init_off, init_size);
@@ -477,9 +484,15 @@ impl<Cost: CostType> Interpreter<Cost> {
 				let val = stack.pop_back();
 
 				let current_val = U256::from(&*ext.storage_at(&address)?);
-				// Increase refund for clear
-				if !self.is_zero(&current_val) && self.is_zero(&val) {
-					ext.inc_sstore_clears();
+				if ext.schedule().eip1283 {
+					let original_val = U256::from(&*ext.initial_storage_at(&address)?);
+					gasometer::handle_eip1283_sstore_clears_refund(ext, &original_val, &current_val, &val);
+				} else {
+					// Increase refund for clear
+					if !self.is_zero(&current_val) && self.is_zero(&val) {
+						let sstore_clears_schedule = ext.schedule().sstore_refund_gas;
+						ext.add_sstore_refund(sstore_clears_schedule);
+					}
 				}
 				ext.set_storage(address, H256::from(&val))?;
 			},
@@ -539,6 +552,11 @@ impl<Cost: CostType> Interpreter<Cost> {
 				let len = ext.extcodesize(&address)?;
 				stack.push(U256::from(len));
 			},
+			instructions::EXTCODEHASH => {
+				let address = u256_to_address(&stack.pop_back());
+				let hash = ext.extcodehash(&address)?;
+				stack.push(U256::from(&*hash));
+			},
 			instructions::CALLDATACOPY => {
 				Self::copy_data_to_memory(&mut self.mem, stack, params.data.as_ref().map_or_else(|| &[] as &[u8], |d| &*d as &[u8]));
 			},
@@ -794,6 +812,56 @@ impl<Cost: CostType> Interpreter<Cost> {
 				};
 				stack.push(byte);
 			},
+			instructions::SHL => {
+				const CONST_256: U256 = U256([256, 0, 0, 0]);
+
+				let shift = stack.pop_back();
+				let value = stack.pop_back();
+
+				let result = if shift >= CONST_256 {
+					U256::zero()
+				} else {
+					value << (shift.as_u32() as usize)
+				};
+				stack.push(result);
+			},
+			instructions::SHR => {
+				const CONST_256: U256 = U256([256, 0, 0, 0]);
+
+				let shift = stack.pop_back();
+				let value = stack.pop_back();
+
+				let result = if shift >= CONST_256 {
+					U256::zero()
+				} else {
+					value >> (shift.as_u32() as usize)
+				};
+				stack.push(result);
+			},
+			instructions::SAR => {
+				// We cannot use get_and_reset_sign/set_sign here, because the rounding looks different.
+				const CONST_256: U256 = U256([256, 0, 0, 0]);
+
+				let shift = stack.pop_back();
+				let value = stack.pop_back();
+				let sign = value.bit(255);
+
+				let result = if shift >= CONST_256 {
+					if sign {
+						!U256::zero()
+					} else {
+						U256::zero()
+					}
+				} else {
+					let shift = shift.as_u32() as usize;
+					let mut shifted = value >> shift;
+					if sign && shift > 0 {
+						shifted = shifted | (!U256::zero() << (256 - shift));
+					}
+					shifted
+				};
+				stack.push(result);
+			},
 			instructions::ADDMOD => {
 				let a = stack.pop_back();
 				let b = stack.pop_back();
@@ -139,9 +139,10 @@ impl<'a> evmjit::Ext for ExtAdapter<'a> {
 		let key = unsafe { H256::from_jit(&*key) };
 		let value = unsafe { H256::from_jit(&*value) };
 		let old_value = self.ext.storage_at(&key);
-		// if SSTORE nonzero -> zero, increment refund count
+		// if SSTORE nonzero -> zero, increase refund counter
 		if !old_value.is_zero() && value.is_zero() {
-			self.ext.inc_sstore_clears();
+			let sstore_clears_schedule = self.ext.schedule().sstore_refund_gas;
+			self.ext.add_sstore_refund(sstore_clears_schedule);
 		}
 		self.ext.set_storage(key, value);
 	}
@@ -165,6 +165,39 @@ fn test_extcodecopy(factory: super::Factory) {
 	assert_store(&ext, 0, "6005600055000000000000000000000000000000000000000000000000000000");
 }
 
+evm_test!{ignorejit => test_extcodehash: test_extcodehash_jit, test_extcodehash_int}
+fn test_extcodehash(factory: super::Factory) {
+	let address = Address::from_str("0f572e5295c57f15886f9b263e2f6d2d6c7b5ec6").unwrap();
+	let sender = Address::from_str("cd1722f2947def4cf144679da39c4c32bdc35681").unwrap();
+	// 73 cd1722f2947def4cf144679da39c4c32bdc35681 - push sender address
+	// 3f - extcodehash
+	// 60 00 - push 0
+	// 55 - sstore
+	// 73 cd1722f2947def4cf144679da39c4c32bdc35682 - push non-existent address
+	// 3f - extcodehash
+	// 60 01 - push 1
+	// 55 - sstore
+	let code = "73cd1722f2947def4cf144679da39c4c32bdc356813f60005573cd1722f2947def4cf144679da39c4c32bdc356823f600155".from_hex().unwrap();
+	let sender_code = "600160005401600055".from_hex().unwrap();
+
+	let mut params = ActionParams::default();
+	params.address = address.clone();
+	params.sender = sender.clone();
+	params.gas = U256::from(100_000);
+	params.code = Some(Arc::new(code));
+	let mut ext = FakeExt::new_constantinople();
+	ext.codes.insert(sender, Arc::new(sender_code));
+
+	let gas_left = {
+		let mut vm = factory.create(params.gas);
+		test_finalize(vm.exec(params, &mut ext)).unwrap()
+	};
+
+	assert_eq!(gas_left, U256::from(78_988));
+	assert_store(&ext, 0, "bcfbb14639506d4e1be8b81663bcc972303aeca04134c0f7861b183bc6077af4");
+	assert_store(&ext, 1, "0000000000000000000000000000000000000000000000000000000000000000");
+}
+
 evm_test!{test_log_empty: test_log_empty_jit, test_log_empty_int}
 fn test_log_empty(factory: super::Factory) {
 	let address = Address::from_str("0f572e5295c57f15886f9b263e2f6d2d6c7b5ec6").unwrap();
@@ -716,7 +749,7 @@ fn test_jumps(factory: super::Factory) {
 		test_finalize(vm.exec(params, &mut ext)).unwrap()
 	};
 
-	assert_eq!(ext.sstore_clears, 1);
+	assert_eq!(ext.sstore_clears, ext.schedule.sstore_refund_gas as i64);
 	assert_store(&ext, 0, "0000000000000000000000000000000000000000000000000000000000000000"); // 5!
 	assert_store(&ext, 1, "0000000000000000000000000000000000000000000000000000000000000078"); // 5!
 	assert_eq!(gas_left, U256::from(54_117));
@@ -767,6 +800,356 @@ fn test_calls(factory: super::Factory) {
 	assert_eq!(ext.calls.len(), 2);
 }
 
+evm_test!{ignorejit => test_shl: test_shl_jit, test_shl_int}
+fn test_shl(factory: super::Factory) {
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1b,
+		"01",
+		"00",
+		"0000000000000000000000000000000000000000000000000000000000000001");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1b,
+		"01",
+		"01",
+		"0000000000000000000000000000000000000000000000000000000000000002");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1b,
+		"01",
+		"ff",
+		"8000000000000000000000000000000000000000000000000000000000000000");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1b,
+		"01",
+		"0100",
+		"0000000000000000000000000000000000000000000000000000000000000000");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1b,
+		"01",
+		"0101",
+		"0000000000000000000000000000000000000000000000000000000000000000");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1b,
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+		"00",
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1b,
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+		"01",
+		"fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffe");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1b,
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+		"ff",
+		"8000000000000000000000000000000000000000000000000000000000000000");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1b,
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+		"0100",
+		"0000000000000000000000000000000000000000000000000000000000000000");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1b,
+		"00",
+		"01",
+		"0000000000000000000000000000000000000000000000000000000000000000");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1b,
+		"7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+		"01",
+		"fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffe");
+}
+
+evm_test!{ignorejit => test_shr: test_shr_jit, test_shr_int}
+fn test_shr(factory: super::Factory) {
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1c,
+		"01",
+		"00",
+		"0000000000000000000000000000000000000000000000000000000000000001");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1c,
+		"01",
+		"01",
+		"0000000000000000000000000000000000000000000000000000000000000000");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1c,
+		"8000000000000000000000000000000000000000000000000000000000000000",
+		"01",
+		"4000000000000000000000000000000000000000000000000000000000000000");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1c,
+		"8000000000000000000000000000000000000000000000000000000000000000",
+		"ff",
+		"0000000000000000000000000000000000000000000000000000000000000001");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1c,
+		"8000000000000000000000000000000000000000000000000000000000000000",
+		"0100",
+		"0000000000000000000000000000000000000000000000000000000000000000");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1c,
+		"8000000000000000000000000000000000000000000000000000000000000000",
+		"0101",
+		"0000000000000000000000000000000000000000000000000000000000000000");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1c,
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+		"00",
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1c,
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+		"01",
+		"7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1c,
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+		"ff",
+		"0000000000000000000000000000000000000000000000000000000000000001");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1c,
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+		"0100",
+		"0000000000000000000000000000000000000000000000000000000000000000");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1c,
+		"00",
+		"01",
+		"0000000000000000000000000000000000000000000000000000000000000000");
+}
+
+evm_test!{ignorejit => test_sar: test_sar_jit, test_sar_int}
+fn test_sar(factory: super::Factory) {
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1d,
+		"01",
+		"00",
+		"0000000000000000000000000000000000000000000000000000000000000001");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1d,
+		"01",
+		"01",
+		"0000000000000000000000000000000000000000000000000000000000000000");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1d,
+		"8000000000000000000000000000000000000000000000000000000000000000",
+		"01",
+		"c000000000000000000000000000000000000000000000000000000000000000");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1d,
+		"8000000000000000000000000000000000000000000000000000000000000000",
+		"ff",
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1d,
+		"8000000000000000000000000000000000000000000000000000000000000000",
+		"0100",
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1d,
+		"8000000000000000000000000000000000000000000000000000000000000000",
+		"0101",
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1d,
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+		"00",
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1d,
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+		"01",
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1d,
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+		"ff",
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1d,
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+		"0100",
+		"ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1d,
+		"00",
+		"01",
+		"0000000000000000000000000000000000000000000000000000000000000000");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1d,
+		"4000000000000000000000000000000000000000000000000000000000000000",
+		"fe",
+		"0000000000000000000000000000000000000000000000000000000000000001");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1d,
+		"7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+		"f8",
+		"000000000000000000000000000000000000000000000000000000000000007f");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1d,
+		"7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+		"fe",
+		"0000000000000000000000000000000000000000000000000000000000000001");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1d,
+		"7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+		"ff",
+		"0000000000000000000000000000000000000000000000000000000000000000");
+	push_two_pop_one_constantinople_test(
+		&factory,
+		0x1d,
+		"7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+		"0100",
+		"0000000000000000000000000000000000000000000000000000000000000000");
+}
+
+#[test] // JIT does not support bitwise shifting
+fn test_shift_bad_instruction_before_constantinople_int() {
+	let factory = super::Factory::new(VMType::Interpreter, 1024 * 32);
+	let code = "600160001b".from_hex().unwrap();
+
+	let mut params = ActionParams::default();
+	params.gas = U256::from(100_000);
+	params.code = Some(Arc::new(code));
+	let mut ext = FakeExt::new();
+
+	let err = {
+		let mut vm = factory.create(params.gas);
+		test_finalize(vm.exec(params, &mut ext)).unwrap_err()
+	};
+
+	match err {
+		vm::Error::BadInstruction { instruction: 0x1b } => (),
+		_ => assert!(false, "Expected bad instruction")
+	}
+}
+
+evm_test!{ignorejit => test_sstore_eip1283: test_sstore_eip1283_jit, test_sstore_eip1283_int}
+fn test_sstore_eip1283(factory: super::Factory) {
+	// code, original value, gas used, refund
+	let cases = [
+		("60006000556000600055", 0, 412, 0),
+		("60006000556001600055", 0, 20212, 0),
+		("60016000556000600055", 0, 20212, 19800),
+		("60016000556002600055", 0, 20212, 0),
+		("60016000556001600055", 0, 20212, 0),
+		("60006000556000600055", 1, 5212, 15000),
+		("60006000556001600055", 1, 5212, 4800),
+		("60006000556002600055", 1, 5212, 0),
+		("60026000556000600055", 1, 5212, 15000),
+		("60026000556003600055", 1, 5212, 0),
+		("60026000556001600055", 1, 5212, 4800),
+		("60026000556002600055", 1, 5212, 0),
+		("60016000556000600055", 1, 5212, 15000),
+		("60016000556002600055", 1, 5212, 0),
+		("60016000556001600055", 1, 412, 0),
+		("600160005560006000556001600055", 0, 40218, 19800),
+		("600060005560016000556000600055", 1, 10218, 19800),
+	];
+
+	for &(code, original, used_gas, refund) in cases.iter() {
+		let code = code.from_hex().unwrap();
+		let original = H256::from(original as u64);
+
+		let mut params = ActionParams::default();
+		params.gas = U256::from(100_000);
+		params.code = Some(Arc::new(code.clone()));
+		let mut ext = FakeExt::new_constantinople();
+		ext.store.insert(H256::new(), original.clone());
+		ext.initial_store.insert(H256::new(), original);
+
+		let gas_left = {
+			let mut vm = factory.create(params.gas);
+			test_finalize(vm.exec(params, &mut ext)).unwrap()
+		};
+
+		assert_eq!(U256::from(100_000) - gas_left, U256::from(used_gas as u64), "gas used by {:?}", code);
+		assert_eq!(ext.sstore_clears, refund, "refund of {:?}", code);
+	}
+}
+
+evm_test!{test_sstore_before_eip1283: test_sstore_before_eip1283_jit, test_sstore_before_eip1283_int}
+fn test_sstore_before_eip1283(factory: super::Factory) {
+	let code = "60016000556000600055".from_hex().unwrap();
+
+	let mut params = ActionParams::default();
+	params.gas = U256::from(100_000);
+	params.code = Some(Arc::new(code));
+	let mut ext = FakeExt::new();
+
+	let gas_left = {
+		let mut vm = factory.create(params.gas);
+		test_finalize(vm.exec(params, &mut ext)).unwrap()
+	};
+
+	assert_eq!(gas_left, U256::from(100_000 - 25_012));
+	assert_eq!(ext.sstore_clears, ext.schedule.sstore_refund_gas as i64);
+}
+
+fn push_two_pop_one_constantinople_test(factory: &super::Factory, opcode: u8, push1: &str, push2: &str, result: &str) {
+	let mut push1 = push1.from_hex().unwrap();
+	let mut push2 = push2.from_hex().unwrap();
+	assert!(push1.len() <= 32 && push1.len() != 0);
+	assert!(push2.len() <= 32 && push2.len() != 0);
+
+	let mut code = Vec::new();
+	code.push(0x60 + ((push1.len() - 1) as u8));
+	code.append(&mut push1);
+	code.push(0x60 + ((push2.len() - 1) as u8));
+	code.append(&mut push2);
+	code.push(opcode);
+	code.append(&mut vec![0x60, 0x00, 0x55]);
+
+	let mut params = ActionParams::default();
+	params.gas = U256::from(100_000);
+	params.code = Some(Arc::new(code));
+	let mut ext = FakeExt::new_constantinople();
+
+	let _ = {
+		let mut vm = factory.create(params.gas);
+		test_finalize(vm.exec(params, &mut ext)).unwrap()
+	};
+
+	assert_store(&ext, 0, result);
+}
+
 fn assert_set_contains<T : Debug + Eq + PartialEq + Hash>(set: &HashSet<T>, val: &T) {
 	let contains = set.contains(val);
 	if !contains {
@@ -19,9 +19,15 @@
 //! request an inclusion proof of a specific block number against the trie with the
 //! root has. A correct proof implies that the claimed block is identical to the one
 //! we discarded.
+//!
+//! Alongside each CHT a "bloom bits" trie is generated for the same range of blocks.
+//! It holds the section's log blooms rotated by bit: entry `i` is a vector with one bit
+//! per block, set when that block's bloom has bit `i` set. This lets a light client
+//! narrow down the blocks which may contain logs matching a filter by requesting only
+//! the bits set in the filter's blooms.
 
 use ethcore::ids::BlockId;
-use util::{Bytes, H256, U256, HashDB, MemoryDB};
+use util::{Bytes, H256, H2048, U256, HashDB, MemoryDB};
 use util::trie::{self, TrieMut, TrieDBMut, Trie, TrieDB, Recorder};
 use rlp::{RlpStream, UntrustedRlp};
 
@@ -41,6 +47,9 @@ macro_rules! val {
 /// The size of each CHT.
 pub const SIZE: u64 = 2048;
 
+/// The number of bits in a log bloom, and so the number of entries in each bloom bits trie.
+pub const BLOOM_BITS: usize = 2048;
+
 /// A canonical hash trie. This is generic over any database it can query.
 /// See module docs for more details.
 #[derive(Debug, Clone)]
@@ -71,6 +80,36 @@ impl<DB: HashDB> CHT<DB> {
 	}
 }
 
+/// A trie of bit-rotated log blooms for the blocks covered by a single CHT.
+/// See module docs for more details.
+#[derive(Debug, Clone)]
+pub struct BloomBitsTrie<DB: HashDB> {
+	db: DB,
+	root: H256, // the root of this trie.
+	number: u64,
+}
+
+impl<DB: HashDB> BloomBitsTrie<DB> {
+	/// Query the root of the trie.
+	pub fn root(&self) -> H256 { self.root }
+
+	/// Query the number of the CHT this trie corresponds to.
+	pub fn number(&self) -> u64 { self.number }
+
+	/// Generate an inclusion proof for the vector of a specific bloom bit.
+	/// Nodes before level `from_level` will be omitted.
+	/// Returns an error on an incomplete trie, and `Ok(None)` on an unprovable request.
+	pub fn prove(&self, bit: usize, from_level: u32) -> trie::Result<Option<Vec<Bytes>>> {
+		if bit >= BLOOM_BITS { return Ok(None) }
+
+		let mut recorder = Recorder::with_depth(from_level);
+		let t = TrieDB::new(&self.db, &self.root)?;
+		t.get_with(&key!(bit as u64), &mut recorder)?;
+
+		Ok(Some(recorder.drain().into_iter().map(|x| x.data).collect()))
+	}
+}
+
 /// Block information necessary to build a CHT.
 pub struct BlockInfo {
 	/// The block's hash.
@@ -160,6 +199,113 @@ pub fn check_proof(proof: &[Bytes], num: u64, root: H256) -> Option<(H256, U256)
 	}
 }
 
+/// Whether the given bit of a log bloom is set.
+/// Bits are numbered from the most significant bit of the first byte.
+pub fn bloom_bit(bloom: &H2048, bit: usize) -> bool {
+	bloom[bit / 8] & (0x80 >> (bit % 8)) != 0
+}
+
+/// Whether the block at the given index within a bloom bits vector has the bit set.
+pub fn bit_vector_contains(bits: &[u8], index: usize) -> bool {
+	bits.get(index / 8).map_or(false, |byte| byte & (0x80 >> (index % 8)) != 0)
+}
+
+// rotate a section's worth of blooms into one vector per bloom bit.
+// fails if shorter than SIZE items.
+fn rotate_blooms<I>(iterable: I) -> Option<Vec<Bytes>>
+	where I: IntoIterator<Item=H2048>
+{
+	let mut bits = vec![vec![0u8; SIZE as usize / 8]; BLOOM_BITS];
+	let mut len = 0;
+
+	for (i, bloom) in iterable.into_iter().take(SIZE as usize).enumerate() {
+		for (byte_idx, &byte) in bloom.iter().enumerate().filter(|&(_, &b)| b != 0) {
+			for bit in (0..8).filter(|&b| byte & (0x80 >> b) != 0) {
+				bits[byte_idx * 8 + bit][i / 8] |= 0x80 >> (i % 8);
+			}
+		}
+		len += 1;
+	}
+
+	if len == SIZE as usize {
+		Some(bits)
+	} else {
+		None
+	}
+}
+
+/// Build an in-memory bloom bits trie from a closure which provides the log bloom
+/// of each block in the CHT by number. If the fetcher ever fails to provide
+/// a bloom, the trie will not be generated.
+pub fn build_bloom_bits<F>(cht_num: u64, mut fetcher: F) -> Option<BloomBitsTrie<MemoryDB>>
+	where F: FnMut(u64) -> Option<H2048>
+{
+	let start_num = start_number(cht_num);
+	let mut blooms = Vec::with_capacity(SIZE as usize);
+	for num in start_num..(start_num + SIZE) {
+		match fetcher(num) {
+			Some(bloom) => blooms.push(bloom),
+			None => return None,
+		}
+	}
+
+	let bits = match rotate_blooms(blooms) {
+		Some(bits) => bits,
+		None => return None,
+	};
+
+	let mut db = MemoryDB::new();
+	let mut root = H256::default();
+
+	{
+		let mut t = TrieDBMut::new(&mut db, &mut root);
+		for (bit, vector) in bits.into_iter().enumerate() {
+			t.insert(&key!(bit as u64), &vector)
+				.expect("fresh in-memory database is infallible; qed");
+		}
+	}
+
+	Some(BloomBitsTrie {
+		db: db,
+		root: root,
+		number: cht_num,
+	})
+}
+
+/// Compute a bloom bits trie root from an iterator of log blooms. Fails if shorter
+/// than SIZE items. The items are assumed to proceed sequentially from the start of
+/// a CHT. Discards the trie's nodes.
+pub fn compute_bloom_bits_root<I>(iterable: I) -> Option<H256>
+	where I: IntoIterator<Item=H2048>
+{
+	rotate_blooms(iterable).map(|bits| {
+		let v = bits.into_iter()
+			.enumerate()
+			.map(|(bit, vector)| (key!(bit as u64).into_vec(), vector))
+			.collect();
+
+		::util::triehash::trie_root(v)
+	})
+}
+
+/// Check a proof for a bloom bits trie.
+/// Given a set of trie nodes, a bloom bit to query, and a trie root,
+/// verify the given trie branch and extract the bit vector.
+pub fn check_bloom_bits_proof(proof: &[Bytes], bit: usize, root: H256) -> Option<Bytes> {
+	let mut db = MemoryDB::new();
+
+	for node in proof { db.insert(&node[..]); }
+	let res = match TrieDB::new(&db, &root) {
+		Err(_) => return None,
+		Ok(trie) => trie.get_with(&key!(bit as u64), |val: &[u8]| val.to_vec()),
+	};
+
+	match res {
+		Ok(Some(ref bits)) if bits.len() == SIZE as usize / 8 => Some(bits.clone()),
+		_ => None,
+	}
+}
+
 /// Convert a block number to a CHT number.
 /// Returns `None` for `block_num` == 0, `Some` otherwise.
 pub fn block_to_cht_number(block_num: u64) -> Option<u64> {
@@ -201,4 +347,32 @@ mod tests {
 		assert_eq!(::cht::start_number(1), ::cht::SIZE + 1);
 		assert_eq!(::cht::start_number(2), ::cht::SIZE * 2 + 1);
 	}
+
+	#[test]
+	fn bloom_bits_roundtrip() {
+		use util::H2048;
+
+		// block `n` of the section has bit `n % BLOOM_BITS` set, and block 5
+		// additionally has bit 7.
+		let bloom = |num: u64| {
+			let idx = ((num - ::cht::start_number(1)) as usize) % ::cht::BLOOM_BITS;
+			let mut bloom = H2048::default();
+			bloom[idx / 8] |= 0x80 >> (idx % 8);
+			if idx == 5 { bloom[0] |= 0x01; }
+			bloom
+		};
+
+		let trie = ::cht::build_bloom_bits(1, |n| Some(bloom(n))).unwrap();
+		let blooms = (0..::cht::SIZE).map(|i| bloom(::cht::start_number(1) + i));
+		assert_eq!(::cht::compute_bloom_bits_root(blooms).unwrap(), trie.root());
+
+		let proof = trie.prove(7, 0).unwrap().unwrap();
+		let bits = ::cht::check_bloom_bits_proof(&proof, 7, trie.root()).unwrap();
+		let set: Vec<_> = (0..::cht::SIZE as usize).filter(|&i| ::cht::bit_vector_contains(&bits, i)).collect();
+		assert_eq!(set, vec![5, 7]);
+
+		assert!(::cht::check_bloom_bits_proof(&proof, 8, trie.root()).is_none());
+		assert!(trie.prove(::cht::BLOOM_BITS, 0).unwrap().is_none());
+		assert!(::cht::compute_bloom_bits_root((0..10).map(|_| H2048::default())).is_none());
+	}
 }
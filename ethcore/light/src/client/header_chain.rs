@@ -18,7 +18,8 @@
 //!
 //! Unlike a full node's `BlockChain` this doesn't store much in the database.
 //! It stores candidates for the last 2048-4096 blocks as well as CHT roots for
-//! historical blocks all the way to the genesis.
+//! historical blocks all the way to the genesis. Each CHT root is accompanied by
+//! the root of the corresponding bloom bits trie, used for searching logs.
 //!
 //! This is separate from the `BlockChain` for two reasons:
 //!   - It stores only headers (and a pruned subset of them)
@@ -34,9 +35,10 @@ use ethcore::error::BlockError;
 use ethcore::encoded;
 use ethcore::header::Header;
 use ethcore::ids::BlockId;
+use ethcore::spec::TrustedCheckpoint;
 
 use rlp::{Encodable, Decodable, DecoderError, RlpStream, Rlp, UntrustedRlp};
-use util::{H256, U256, HeapSizeOf, RwLock};
+use util::{H256, H2048, U256, HeapSizeOf, RwLock};
 use util::kvdb::{DBTransaction, KeyValueDB};
 
 use cache::Cache;
@@ -125,6 +127,10 @@ fn cht_key(number: u64) -> String {
 	format!("{:08x}_canonical", number)
 }
 
+fn bloom_bits_key(number: u64) -> String {
+	format!("{:08x}_bloom_bits", number)
+}
+
 fn era_key(number: u64) -> String {
 	format!("candidates_{}", number)
 }
@@ -132,6 +138,66 @@ fn era_key(number: u64) -> String {
 /// Pending changes from `insert` to be applied after the database write has finished.
 pub struct PendingChanges {
 	best_block: Option<BlockDescriptor>, // new best block.
+	enacted: Vec<H256>, // blocks which became canonical, ascending.
+	retracted: Vec<H256>, // blocks which are no longer canonical, descending.
+}
+
+impl PendingChanges {
+	/// Blocks which became canonical with this change, in ascending order.
+	pub fn enacted(&self) -> &[H256] {
+		&self.enacted
+	}
+
+	/// Blocks which are no longer canonical after this change, in descending order.
+	pub fn retracted(&self) -> &[H256] {
+		&self.retracted
+	}
+}
+
+// walk back from two blocks to their common ancestor using the candidates'
+// parent links. returns the blocks from the ancestor to `to` in ascending order
+// and those from `from` back to the ancestor in descending order.
+// stops early if the ancestor has already been pruned, which can only happen
+// for reorganizations deeper than the retained history. the route is then
+// partial: listeners won't hear about the pruned blocks.
+fn tree_route(candidates: &BTreeMap<u64, Entry>, from: (u64, H256), to: (u64, H256)) -> (Vec<H256>, Vec<H256>) {
+	let parent = |number: u64, hash: H256| candidates.get(&number)
+		.and_then(|entry| entry.candidates.iter().find(|c| c.hash == hash))
+		.map(|c| (number - 1, c.parent_hash));
+
+	let (start_from, start_to) = (from.0, to.0);
+	let (mut from, mut to) = (from, to);
+	let mut enacted = Vec::new();
+	let mut retracted = Vec::new();
+
+	while from.1 != to.1 {
+		let (step_from, step_to) = (from.0 >= to.0, to.0 >= from.0);
+
+		if step_from {
+			retracted.push(from.1);
+			match parent(from.0, from.1) {
+				Some(p) => from = p,
+				None => {
+					warn!(target: "chain", "Common ancestor of #{} and #{} already pruned; reported route is incomplete", start_from, start_to);
+					break
+				}
+			}
+		}
+
+		if step_to {
+			enacted.push(to.1);
+			match parent(to.0, to.1) {
+				Some(p) => to = p,
+				None => {
+					warn!(target: "chain", "Common ancestor of #{} and #{} already pruned; reported route is incomplete", start_from, start_to);
+					break
+				}
+			}
+		}
+	}
+
+	enacted.reverse();
+	(enacted, retracted)
 }
 
 /// Header chain. See module docs for more details.
@@ -146,7 +212,16 @@ pub struct HeaderChain {
 
 impl HeaderChain {
 	/// Create a new header chain given this genesis block and database to read from.
-	pub fn new(db: Arc<KeyValueDB>, col: Option<u32>, genesis: &[u8], cache: Arc<Mutex<Cache>>) -> Result<Self, String> {
+	///
+	/// If the database is empty and a trusted checkpoint is given, the chain starts
+	/// from the checkpoint rather than the genesis.
+	pub fn new(
+		db: Arc<KeyValueDB>,
+		col: Option<u32>,
+		genesis: &[u8],
+		checkpoint: Option<&TrustedCheckpoint>,
+		cache: Arc<Mutex<Cache>>,
+	) -> Result<Self, String> {
 		use ethcore::views::HeaderView;
 
 		let chain = if let Some(current) = db.get(col, CURRENT_KEY)? {
@@ -194,7 +269,7 @@ impl HeaderChain {
 			}
 		} else {
 			let g_view = HeaderView::new(genesis);
-			HeaderChain {
+			let chain = HeaderChain {
 				genesis_header: encoded::Header::new(genesis.to_owned()),
 				best_block: RwLock::new(BlockDescriptor {
 					hash: g_view.hash(),
@@ -205,12 +280,67 @@ impl HeaderChain {
 				db: db,
 				col: col,
 				cache: cache,
+			};
+
+			if let Some(checkpoint) = checkpoint {
+				chain.insert_checkpoint(checkpoint)?;
 			}
+
+			chain
 		};
 
 		Ok(chain)
 	}
 
+	// write a trusted checkpoint and the CHT roots up to it to the database and
+	// make it the best block. only done for a fresh database.
+	fn insert_checkpoint(&self, checkpoint: &TrustedCheckpoint) -> Result<(), String> {
+		let header = &checkpoint.header;
+		let (hash, number) = (header.hash(), header.number());
+
+		if number == 0 || number != checkpoint.cht_roots.len() as u64 * cht::SIZE {
+			return Err(format!("Trusted checkpoint #{} must be the last block of the last given CHT ({} roots)",
+				number, checkpoint.cht_roots.len()));
+		}
+
+		let mut candidates = SmallVec::new();
+		candidates.push(Candidate {
+			hash: hash,
+			parent_hash: *header.parent_hash(),
+			total_difficulty: checkpoint.total_difficulty,
+		});
+		let entry = Entry {
+			candidates: candidates,
+			canonical_hash: hash,
+		};
+
+		let mut transaction = self.db.transaction();
+		for (cht_num, cht_root) in checkpoint.cht_roots.iter().enumerate() {
+			transaction.put(self.col, cht_key(cht_num as u64).as_bytes(), &::rlp::encode(cht_root));
+		}
+
+		transaction.put(self.col, era_key(number).as_bytes(), &::rlp::encode(&entry));
+		transaction.put(self.col, &hash[..], &::rlp::encode(header));
+		{
+			let mut stream = RlpStream::new_list(2);
+			stream.append(&number).append(&number);
+			transaction.put(self.col, CURRENT_KEY, &stream.out());
+		}
+		self.db.write(transaction)?;
+
+		debug!(target: "chain", "Starting from trusted checkpoint ({}, {}), TD {}",
+			number, hash, checkpoint.total_difficulty);
+
+		self.candidates.write().insert(number, entry);
+		*self.best_block.write() = BlockDescriptor {
+			hash: hash,
+			number: number,
+			total_difficulty: checkpoint.total_difficulty,
+		};
+
+		Ok(())
+	}
+
 	/// Insert a pre-verified header.
 	///
 	/// This blindly trusts that the data given to it is sensible.
@@ -222,6 +352,8 @@ impl HeaderChain {
 		let parent_hash = *header.parent_hash();
 		let mut pending = PendingChanges {
 			best_block: None,
+			enacted: Vec::new(),
+			retracted: Vec::new(),
 		};
 
 		// hold candidates the whole time to guard import order.
@@ -263,12 +395,13 @@ impl HeaderChain {
 		let raw = ::rlp::encode(&header);
 		transaction.put(self.col, &hash[..], &*raw);
 
-		let (best_num, is_new_best) = {
+		let (best_num, is_new_best, prev_best) = {
 			let cur_best = self.best_block.read();
+			let prev_best = (cur_best.number, cur_best.hash);
 			if cur_best.total_difficulty < total_difficulty {
-				(number, true)
+				(number, true, prev_best)
 			} else {
-				(cur_best.number, false)
+				(cur_best.number, false, prev_best)
 			}
 		};
 
@@ -301,6 +434,12 @@ impl HeaderChain {
 				}
 			}
 
+			// record the route from the previous best block. must be done
+			// before producing a CHT prunes the oldest eras.
+			let (enacted, retracted) = tree_route(&*candidates, prev_best, (number, hash));
+			pending.enacted = enacted;
+			pending.retracted = retracted;
+
 			trace!(target: "chain", "New best block: ({}, {}), TD {}", number, hash, total_difficulty);
 			pending.best_block = Some(BlockDescriptor {
 				hash: hash,
@@ -308,12 +447,28 @@ impl HeaderChain {
 				total_difficulty: total_difficulty,
 			});
 
+			let mut earliest_era = *candidates.keys().next().expect("at least one era just created; qed");
+
+			// a trusted checkpoint is the last block of a CHT whose root is already
+			// known. prune it on its own once it's no longer part of the history.
+			if earliest_era % cht::SIZE == 0 && earliest_era + HISTORY < number {
+				let era_entry = candidates.remove(&earliest_era).expect("key just fetched; qed");
+				transaction.delete(self.col, era_key(earliest_era).as_bytes());
+				for ancient in &era_entry.candidates {
+					transaction.delete(self.col, &ancient.hash);
+				}
+
+				earliest_era += 1;
+			}
+
 			// produce next CHT root if it's time.
-			let earliest_era = *candidates.keys().next().expect("at least one era just created; qed");
 			if earliest_era + HISTORY + cht::SIZE <= number {
 				let cht_num = cht::block_to_cht_number(earliest_era)
 					.expect("fails only for number == 0; genesis never imported; qed");
 
+				// log blooms of the canonical headers, read before they're deleted.
+				let mut blooms = Vec::with_capacity(cht::SIZE as usize);
+
 				let cht_root = {
 					let mut i = earliest_era;
 
@@ -331,6 +486,7 @@ impl HeaderChain {
 						}
 
 						let canon = &era_entry.candidates[0];
+						blooms.push(self.stored_log_bloom(&canon.hash));
 						(canon.hash, canon.total_difficulty)
 					};
 					cht::compute_root(cht_num, ::itertools::repeat_call(iter))
@@ -340,6 +496,18 @@ impl HeaderChain {
 				// write the CHT root to the database.
 				debug!(target: "chain", "Produced CHT {} root: {:?}", cht_num, cht_root);
 				transaction.put(self.col, cht_key(cht_num).as_bytes(), &::rlp::encode(&cht_root));
+
+				let bloom_bits_root = blooms.into_iter()
+					.collect::<Option<Vec<_>>>()
+					.and_then(cht::compute_bloom_bits_root);
+
+				match bloom_bits_root {
+					Some(root) => {
+						debug!(target: "chain", "Produced bloom bits trie {} root: {:?}", cht_num, root);
+						transaction.put(self.col, bloom_bits_key(cht_num).as_bytes(), &::rlp::encode(&root));
+					}
+					None => warn!(target: "chain", "Unable to read log blooms for CHT {}; logs in it won't be searchable", cht_num),
+				}
 			}
 		}
 
@@ -484,6 +652,30 @@ impl HeaderChain {
 		}
 	}
 
+	/// Get the root of the nth bloom bits trie, if it's been computed.
+	///
+	/// Bloom bits trie `n` covers the same blocks as CHT `n`.
+	pub fn bloom_bits_root(&self, n: u64) -> Option<H256> {
+		match self.db.get(self.col, bloom_bits_key(n).as_bytes()) {
+			Ok(val) => val.map(|x| ::rlp::decode(&x)),
+			Err(e) => {
+				warn!(target: "chain", "Error reading from database: {}", e);
+				None
+			}
+		}
+	}
+
+	// read the log bloom of a header stored in the database.
+	fn stored_log_bloom(&self, hash: &H256) -> Option<H2048> {
+		match self.db.get(self.col, hash) {
+			Ok(val) => val.map(|x| encoded::Header::new(x.into_vec()).log_bloom()),
+			Err(e) => {
+				warn!(target: "chain", "Error reading from database: {}", e);
+				None
+			}
+		}
+	}
+
 	/// Get the genesis hash.
 	pub fn genesis_hash(&self) -> H256 {
 		::util::Hashable::sha3(&self.genesis_header)
@@ -568,7 +760,7 @@ mod tests {
 
 		let cache = Arc::new(Mutex::new(Cache::new(Default::default(), Duration::hours(6))));
 
-		let chain = HeaderChain::new(db.clone(), None, &::rlp::encode(&genesis_header), cache).unwrap();
+		let chain = HeaderChain::new(db.clone(), None, &::rlp::encode(&genesis_header), None, cache).unwrap();
 
 		let mut parent_hash = genesis_header.hash();
 		let mut rolling_timestamp = genesis_header.timestamp();
@@ -594,6 +786,49 @@ mod tests {
 		assert!(chain.cht_root(3).is_none());
 	}
 
+	#[test]
+	fn bloom_bits_roots() {
+		use util::H2048;
+
+		let spec = Spec::new_test();
+		let genesis_header = spec.genesis_header();
+		let db = make_db();
+
+		let cache = Arc::new(Mutex::new(Cache::new(Default::default(), Duration::hours(6))));
+
+		let chain = HeaderChain::new(db.clone(), None, &::rlp::encode(&genesis_header), None, cache).unwrap();
+
+		let bloom = |num: u64| {
+			let mut bloom = H2048::default();
+			if num % 100 == 0 { bloom[(num / 100) as usize % 256] = 0x01; }
+			bloom
+		};
+
+		let mut parent_hash = genesis_header.hash();
+		let mut rolling_timestamp = genesis_header.timestamp();
+		for i in 1..(::cht::SIZE + super::HISTORY + 1) {
+			let mut header = Header::new();
+			header.set_parent_hash(parent_hash);
+			header.set_number(i);
+			header.set_timestamp(rolling_timestamp);
+			header.set_difficulty(*genesis_header.difficulty() * i.into());
+			header.set_log_bloom(bloom(i));
+			parent_hash = header.hash();
+
+			let mut tx = db.transaction();
+			let pending = chain.insert(&mut tx, header).unwrap();
+			db.write(tx).unwrap();
+			chain.apply_pending(pending);
+
+			rolling_timestamp += 10;
+		}
+
+		let expected = ::cht::compute_bloom_bits_root((1..(::cht::SIZE + 1)).map(bloom));
+		assert!(chain.cht_root(0).is_some());
+		assert_eq!(chain.bloom_bits_root(0), expected);
+		assert!(chain.bloom_bits_root(1).is_none());
+	}
+
 	#[test]
 	fn reorganize() {
 		let spec = Spec::new_test();
@@ -601,7 +836,7 @@ mod tests {
 		let db = make_db();
 		let cache = Arc::new(Mutex::new(Cache::new(Default::default(), Duration::hours(6))));
 
-		let chain = HeaderChain::new(db.clone(), None, &::rlp::encode(&genesis_header), cache).unwrap();
+		let chain = HeaderChain::new(db.clone(), None, &::rlp::encode(&genesis_header), None, cache).unwrap();
 
 		let mut parent_hash = genesis_header.hash();
 		let mut rolling_timestamp = genesis_header.timestamp();
@@ -677,6 +912,49 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn reorganize_reports_route() {
+		let spec = Spec::new_test();
+		let genesis_header = spec.genesis_header();
+		let db = make_db();
+		let cache = Arc::new(Mutex::new(Cache::new(Default::default(), Duration::hours(6))));
+
+		let chain = HeaderChain::new(db.clone(), None, &::rlp::encode(&genesis_header), None, cache).unwrap();
+
+		let import = |parent_hash, number: u64, difficulty: u64, timestamp| {
+			let mut header = Header::new();
+			header.set_parent_hash(parent_hash);
+			header.set_number(number);
+			header.set_timestamp(timestamp);
+			header.set_difficulty(*genesis_header.difficulty() * difficulty.into());
+			let hash = header.hash();
+
+			let mut tx = db.transaction();
+			let pending = chain.insert(&mut tx, header).unwrap();
+			db.write(tx).unwrap();
+			let route = (pending.enacted().to_vec(), pending.retracted().to_vec());
+			chain.apply_pending(pending);
+
+			(hash, route)
+		};
+
+		let (h1, route) = import(genesis_header.hash(), 1, 1, 10);
+		assert_eq!(route, (vec![h1], vec![]));
+
+		let (a2, _) = import(h1, 2, 2, 20);
+		let (a3, route) = import(a2, 3, 3, 30);
+		assert_eq!(route, (vec![a3], vec![]));
+
+		// side chain with less difficulty doesn't change the canonical chain.
+		let (b2, route) = import(h1, 2, 1, 21);
+		assert_eq!(route, (vec![], vec![]));
+
+		// until it overtakes.
+		let (b3, route) = import(b2, 3, 10, 31);
+		assert_eq!(route, (vec![b2, b3], vec![a3, a2]));
+		assert_eq!(chain.best_block().hash, b3);
+	}
+
 	#[test]
 	fn earliest_is_latest() {
 		let spec = Spec::new_test();
@@ -684,7 +962,7 @@ mod tests {
 		let db = make_db();
 		let cache = Arc::new(Mutex::new(Cache::new(Default::default(), Duration::hours(6))));
 
-		let chain = HeaderChain::new(db.clone(), None, &::rlp::encode(&genesis_header), cache).unwrap();
+		let chain = HeaderChain::new(db.clone(), None, &::rlp::encode(&genesis_header), None, cache).unwrap();
 
 
 		assert!(chain.block_header(BlockId::Earliest).is_some());
@@ -700,7 +978,7 @@ mod tests {
 		let cache = Arc::new(Mutex::new(Cache::new(Default::default(), Duration::hours(6))));
 
 		{
-			let chain = HeaderChain::new(db.clone(), None, &::rlp::encode(&genesis_header), cache.clone()).unwrap();
+			let chain = HeaderChain::new(db.clone(), None, &::rlp::encode(&genesis_header), None, cache.clone()).unwrap();
 			let mut parent_hash = genesis_header.hash();
 			let mut rolling_timestamp = genesis_header.timestamp();
 			for i in 1..10000 {
@@ -720,7 +998,7 @@ mod tests {
 			}
 		}
 
-		let chain = HeaderChain::new(db.clone(), None, &::rlp::encode(&genesis_header), cache.clone()).unwrap();
+		let chain = HeaderChain::new(db.clone(), None, &::rlp::encode(&genesis_header), None, cache.clone()).unwrap();
 		assert!(chain.block_header(BlockId::Number(10)).is_none());
 		assert!(chain.block_header(BlockId::Number(9000)).is_some());
 		assert!(chain.cht_root(2).is_some());
@@ -728,6 +1006,70 @@ mod tests {
 		assert_eq!(chain.block_header(BlockId::Latest).unwrap().number(), 9999);
 	}
 
+	#[test]
+	fn start_from_trusted_checkpoint() {
+		use ethcore::spec::TrustedCheckpoint;
+		use util::{H256, U256};
+
+		let spec = Spec::new_test();
+		let genesis_header = spec.genesis_header();
+		let db = make_db();
+		let cache = Arc::new(Mutex::new(Cache::new(Default::default(), Duration::hours(6))));
+
+		let mut checkpoint_header = Header::new();
+		checkpoint_header.set_parent_hash(H256::from(5));
+		checkpoint_header.set_number(::cht::SIZE);
+		checkpoint_header.set_difficulty(*genesis_header.difficulty());
+
+		let checkpoint = TrustedCheckpoint {
+			header: checkpoint_header.clone(),
+			total_difficulty: U256::from(1_000_000),
+			cht_roots: vec![H256::from(10)],
+		};
+
+		// checkpoint must be at the end of the last CHT.
+		let mut bad_checkpoint = checkpoint.clone();
+		bad_checkpoint.cht_roots.push(H256::from(11));
+		assert!(HeaderChain::new(make_db(), None, &::rlp::encode(&genesis_header), Some(&bad_checkpoint), cache.clone()).is_err());
+
+		{
+			let chain = HeaderChain::new(db.clone(), None, &::rlp::encode(&genesis_header), Some(&checkpoint), cache.clone()).unwrap();
+			assert_eq!(chain.best_block().number, ::cht::SIZE);
+			assert_eq!(chain.best_block().hash, checkpoint_header.hash());
+			assert_eq!(chain.best_header().hash(), checkpoint_header.hash());
+			assert_eq!(chain.first_block().unwrap().number, ::cht::SIZE);
+			assert_eq!(chain.cht_root(0), Some(H256::from(10)));
+			assert!(chain.block_header(BlockId::Number(::cht::SIZE - 1)).is_none());
+
+			let mut parent_hash = checkpoint_header.hash();
+			let mut rolling_timestamp = checkpoint_header.timestamp();
+			for i in (::cht::SIZE + 1)..(::cht::SIZE * 2 + super::HISTORY + 2) {
+				let mut header = Header::new();
+				header.set_parent_hash(parent_hash);
+				header.set_number(i);
+				header.set_timestamp(rolling_timestamp);
+				header.set_difficulty(*genesis_header.difficulty());
+				parent_hash = header.hash();
+
+				let mut tx = db.transaction();
+				let pending = chain.insert(&mut tx, header).unwrap();
+				db.write(tx).unwrap();
+				chain.apply_pending(pending);
+
+				rolling_timestamp += 10;
+			}
+
+			// the checkpoint has been pruned and the next CHT produced.
+			assert!(chain.block_header(BlockId::Hash(checkpoint_header.hash())).is_none());
+			assert_eq!(chain.cht_root(0), Some(H256::from(10)));
+			assert!(chain.cht_root(1).is_some());
+		}
+
+		// an existing database ignores the checkpoint.
+		let chain = HeaderChain::new(db.clone(), None, &::rlp::encode(&genesis_header), Some(&checkpoint), cache.clone()).unwrap();
+		assert_eq!(chain.best_block().number, ::cht::SIZE * 2 + super::HISTORY + 1);
+	}
+
 	#[test]
 	fn restore_higher_non_canonical() {
 		let spec = Spec::new_test();
@@ -736,7 +1078,7 @@ mod tests {
 		let cache = Arc::new(Mutex::new(Cache::new(Default::default(), Duration::hours(6))));
 
 		{
-			let chain = HeaderChain::new(db.clone(), None, &::rlp::encode(&genesis_header), cache.clone()).unwrap();
+			let chain = HeaderChain::new(db.clone(), None, &::rlp::encode(&genesis_header), None, cache.clone()).unwrap();
 			let mut parent_hash = genesis_header.hash();
 			let mut rolling_timestamp = genesis_header.timestamp();
 
@@ -778,7 +1120,7 @@ mod tests {
 		}
 
 		// after restoration, non-canonical eras should still be loaded.
-		let chain = HeaderChain::new(db.clone(), None, &::rlp::encode(&genesis_header), cache.clone()).unwrap();
+		let chain = HeaderChain::new(db.clone(), None, &::rlp::encode(&genesis_header), None, cache.clone()).unwrap();
 		assert_eq!(chain.block_header(BlockId::Latest).unwrap().number(), 10);
 		assert!(chain.candidates.read().get(&100).is_some())
 	}
@@ -790,7 +1132,7 @@ mod tests {
 		let db = make_db();
 		let cache = Arc::new(Mutex::new(Cache::new(Default::default(), Duration::hours(6))));
 
-		let chain = HeaderChain::new(db.clone(), None, &::rlp::encode(&genesis_header), cache.clone()).unwrap();
+		let chain = HeaderChain::new(db.clone(), None, &::rlp::encode(&genesis_header), None, cache.clone()).unwrap();
 
 		assert!(chain.block_header(BlockId::Earliest).is_some());
 		assert!(chain.block_header(BlockId::Number(0)).is_some());
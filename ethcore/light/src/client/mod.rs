@@ -125,6 +125,9 @@ pub trait LightChainClient: Send + Sync {
 	/// Get the `i`th CHT root.
 	fn cht_root(&self, i: usize) -> Option<H256>;
 
+	/// Get the root of the `i`th bloom bits trie.
+	fn bloom_bits_root(&self, i: u64) -> Option<H256>;
+
 	/// Get the EIP-86 transition block number.
 	fn eip86_transition(&self) -> u64;
 
@@ -134,8 +137,11 @@ pub trait LightChainClient: Send + Sync {
 
 /// An actor listening to light chain events.
 pub trait LightChainNotify: Send + Sync {
-	/// Notifies about imported headers.
-	fn new_headers(&self, good: &[H256]);
+	/// Notifies about changes to the canonical chain after importing headers.
+	/// `enacted` is in ascending order, `retracted` in descending order.
+	/// Reorganizations reaching past the retained history are only reported
+	/// back to the earliest block still stored.
+	fn new_headers(&self, enacted: &[H256], retracted: &[H256]);
 }
 
 /// Something which can be treated as a `LightChainClient`.
@@ -173,7 +179,7 @@ impl Client {
 		Ok(Client {
 			queue: HeaderQueue::new(config.queue, spec.engine.clone(), io_channel, config.check_seal),
 			engine: spec.engine.clone(),
-			chain: HeaderChain::new(db.clone(), chain_col, &gh, cache)?,
+			chain: HeaderChain::new(db.clone(), chain_col, &gh, spec.trusted_checkpoint.as_ref(), cache)?,
 			report: RwLock::new(ClientReport::default()),
 			import_lock: Mutex::new(()),
 			db: db,
@@ -275,6 +281,11 @@ impl Client {
 		self.chain.cht_root(i)
 	}
 
+	/// Get the root of the `i`th bloom bits trie.
+	pub fn bloom_bits_root(&self, i: u64) -> Option<H256> {
+		self.chain.bloom_bits_root(i)
+	}
+
 	/// Import a set of pre-verified headers from the queue.
 	pub fn import_verified(&self) {
 		const MAX: usize = 256;
@@ -283,6 +294,8 @@ impl Client {
 
 		let mut bad = Vec::new();
 		let mut good = Vec::new();
+		let mut enacted = Vec::new();
+		let mut retracted = Vec::new();
 		for verified_header in self.queue.drain(MAX) {
 			let (num, hash) = (verified_header.number(), verified_header.hash());
 			trace!(target: "client", "importing block {}", num);
@@ -309,6 +322,16 @@ impl Client {
 				}
 			};
 
+			// blocks enacted earlier in this batch and retracted again
+			// were never announced.
+			for hash in pending.retracted() {
+				match enacted.iter().position(|h| h == hash) {
+					Some(pos) => { enacted.remove(pos); }
+					None => retracted.push(*hash),
+				}
+			}
+			enacted.extend_from_slice(pending.enacted());
+
 			self.db.write_buffered(tx);
 			self.chain.apply_pending(pending);
 		}
@@ -320,7 +343,7 @@ impl Client {
 		self.queue.mark_as_bad(&bad);
 		self.queue.mark_as_good(&good);
 
-		self.notify(|listener| listener.new_headers(&good));
+		self.notify(|listener| listener.new_headers(&enacted, &retracted));
 	}
 
 	/// Get a report about blocks imported.
@@ -480,6 +503,10 @@ impl LightChainClient for Client {
 		Client::cht_root(self, i)
 	}
 
+	fn bloom_bits_root(&self, i: u64) -> Option<H256> {
+		Client::bloom_bits_root(self, i)
+	}
+
 	fn eip86_transition(&self) -> u64 {
 		self.engine().params().eip86_transition
 	}
@@ -488,3 +515,80 @@ impl LightChainClient for Client {
 		Client::report(self)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::{Client, Config, LightChainNotify};
+
+	use std::sync::{Arc, Weak};
+
+	use cache::Cache;
+	use ethcore::header::Header;
+	use ethcore::spec::Spec;
+	use io::IoChannel;
+	use time::Duration;
+	use util::{H256, Mutex};
+
+	#[derive(Default)]
+	struct Notifications(Mutex<Vec<(Vec<H256>, Vec<H256>)>>);
+
+	impl LightChainNotify for Notifications {
+		fn new_headers(&self, enacted: &[H256], retracted: &[H256]) {
+			self.0.lock().push((enacted.to_vec(), retracted.to_vec()));
+		}
+	}
+
+	#[test]
+	fn merges_routes_of_import_batch() {
+		let spec = Spec::new_test();
+		let genesis_header = spec.genesis_header();
+		let cache = Arc::new(Mutex::new(Cache::new(Default::default(), Duration::hours(6))));
+		let mut config = Config::default();
+		config.verify_full = false;
+		config.check_seal = false;
+
+		let client = Client::in_memory(config, &spec, IoChannel::disconnected(), cache);
+		let notifications = Arc::new(Notifications::default());
+		client.add_listener(Arc::downgrade(&notifications) as Weak<LightChainNotify>);
+
+		let header = |parent_hash, number: u64, difficulty: u64, timestamp| {
+			let mut header = Header::new();
+			header.set_parent_hash(parent_hash);
+			header.set_number(number);
+			header.set_timestamp(timestamp);
+			header.set_gas_limit(*genesis_header.gas_limit());
+			header.set_difficulty(*genesis_header.difficulty() * difficulty.into());
+			header
+		};
+
+		let import = |headers: Vec<Header>| {
+			for header in headers {
+				client.import_header(header).unwrap();
+			}
+			client.flush_queue();
+			client.import_verified();
+		};
+
+		let h1 = header(genesis_header.hash(), 1, 1, 10);
+		let a2 = header(h1.hash(), 2, 2, 20);
+		let a3 = header(a2.hash(), 3, 3, 30);
+		let b3 = header(a2.hash(), 3, 4, 31);
+		let b4 = header(b3.hash(), 4, 5, 40);
+		let (h1_hash, a2_hash, a3_hash) = (h1.hash(), a2.hash(), a3.hash());
+		import(vec![h1, a2, a3]);
+
+		// a side chain overtaking within one batch: blocks on it are announced
+		// once, in order, and only the previously announced block is retracted.
+		let c2 = header(h1_hash, 2, 3, 21);
+		let c3 = header(c2.hash(), 3, 3, 32);
+		let c4 = header(c3.hash(), 4, 10, 41);
+		let (c2_hash, c3_hash, c4_hash) = (c2.hash(), c3.hash(), c4.hash());
+		import(vec![b3, b4, c2, c3, c4]);
+
+		assert_eq!(client.chain_info().best_block_hash, c4_hash);
+		assert_eq!(*notifications.0.lock(), vec![
+			(vec![h1_hash, a2_hash, a3_hash], vec![]),
+			(vec![c2_hash, c3_hash, c4_hash], vec![a3_hash, a2_hash]),
+		]);
+	}
+}
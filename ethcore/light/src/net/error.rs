@@ -68,6 +68,8 @@ pub enum Error {
 	Overburdened,
 	/// No handler kept the peer.
 	RejectedByHandlers,
+	/// Already serving the maximum number of light peers.
+	TooManyPeers,
 }
 
 impl Error {
@@ -88,6 +90,7 @@ impl Error {
 			Error::BadProtocolVersion => Punishment::Disable,
 			Error::Overburdened => Punishment::None,
 			Error::RejectedByHandlers => Punishment::Disconnect,
+			Error::TooManyPeers => Punishment::Disconnect,
 		}
 	}
 }
@@ -121,6 +124,7 @@ impl fmt::Display for Error {
 			Error::BadProtocolVersion => write!(f, "Bad protocol version in handshake"),
 			Error::Overburdened => write!(f, "Peer overburdened"),
 			Error::RejectedByHandlers => write!(f, "No handler kept this peer"),
+			Error::TooManyPeers => write!(f, "Maximum number of light peers reached"),
 		}
 	}
 }
@@ -62,6 +62,7 @@ fn hardcoded_serve_time(kind: Kind) -> u64 {
 		Kind::Storage => 2_000_000,
 		Kind::Code => 1_500_000,
 		Kind::Execution => 250, // per gas.
+		Kind::BloomBits => 1_000_000,
 	}
 }
 
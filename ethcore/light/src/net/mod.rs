@@ -33,7 +33,7 @@ use std::sync::Arc;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::ops::{BitOr, BitAnd, Not};
 
-use provider::Provider;
+use provider::{Provider, BloomBitsCache};
 use request::{Request, NetworkRequests as Requests, Response};
 
 use self::request_credits::{Credits, FlowParams};
@@ -122,6 +122,7 @@ mod timeout {
 	pub const CONTRACT_CODE: i64 = 100;
 	pub const HEADER_PROOF: i64 = 100;
 	pub const TRANSACTION_PROOF: i64 = 1000; // per gas?
+	pub const BLOOM_BITS: i64 = 100;
 }
 
 /// A request id.
@@ -161,6 +162,7 @@ pub struct Peer {
 	skip_update: bool,
 	local_flow: Arc<FlowParams>,
 	awaiting_acknowledge: Option<(SteadyTime, Arc<FlowParams>)>,
+	serve_stats: ServeStats,
 }
 
 /// Whether or not a peer was kept by a handler
@@ -250,25 +252,59 @@ pub trait Handler: Send + Sync {
 }
 
 /// Configuration.
+#[derive(Debug, Clone, PartialEq)]
 pub struct Config {
 	/// How many stored seconds of credits peers should be able to accumulate.
 	pub max_stored_seconds: u64,
-	/// How much of the total load capacity each peer should be allowed to take.
-	pub load_share: f64,
+	/// Maximum number of light client peers to serve at once, if limited.
+	pub max_peers: Option<usize>,
+	/// How much of the total load capacity to spend serving light clients,
+	/// split evenly between `max_peers` if limited, otherwise between the
+	/// connected light clients. 1.0 is the time of one fully busy thread.
+	pub serve_time_share: f64,
+	/// Amount of credits each peer recharges per second.
+	pub recharge: u64,
+	/// Fixed costs for kinds of requests, taking precedence over the ones
+	/// derived from measured request times.
+	pub cost_overrides: HashMap<::request::Kind, U256>,
+}
+
+impl Config {
+	/// How much of the total load capacity each peer should be allowed to take,
+	/// given the number of connected light client peers.
+	pub fn load_share(&self, light_peers: usize) -> f64 {
+		let split = self.max_peers.unwrap_or(light_peers);
+		self.serve_time_share / ::std::cmp::max(split, 1) as f64
+	}
 }
 
 impl Default for Config {
 	fn default() -> Self {
-		const LOAD_SHARE: f64 = 1.0 / 25.0;
+		const SERVE_TIME_SHARE: f64 = 0.5;
 		const MAX_ACCUMULATED: u64 = 60 * 5; // only charge for 5 minutes.
+		const RECHARGE: u64 = 100_000_000;
 
 		Config {
 			max_stored_seconds: MAX_ACCUMULATED,
-			load_share: LOAD_SHARE,
+			max_peers: None,
+			serve_time_share: SERVE_TIME_SHARE,
+			recharge: RECHARGE,
+			cost_overrides: HashMap::new(),
 		}
 	}
 }
 
+/// Statistics about requests served to a single peer.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ServeStats {
+	/// Number of requests answered.
+	pub requests: u64,
+	/// Total amount of request credits charged.
+	pub credits_charged: U256,
+	/// Total time spent answering requests, in nanoseconds.
+	pub serve_time_ns: u64,
+}
+
 /// Protocol initialization parameters.
 pub struct Params {
 	/// Network id.
@@ -344,7 +380,8 @@ mod id_guard {
 //   on the peers, only one peer may be held at a time.
 pub struct LightProtocol {
 	provider: Arc<Provider>,
-	config: Config,
+	bloom_bits: BloomBitsCache,
+	config: RwLock<Config>,
 	genesis_hash: H256,
 	network_id: u64,
 	pending_peers: RwLock<HashMap<PeerId, PendingPeer>>,
@@ -365,15 +402,12 @@ impl LightProtocol {
 		let genesis_hash = provider.chain_info().genesis_hash;
 		let sample_store = params.sample_store.unwrap_or_else(|| Box::new(NullStore));
 		let load_distribution = LoadDistribution::load(&*sample_store);
-		let flow_params = FlowParams::from_request_times(
-			|kind| load_distribution.expected_time_ns(kind),
-			params.config.load_share,
-			params.config.max_stored_seconds,
-		);
+		let flow_params = compute_flow_params(&params.config, &load_distribution, 0);
 
 		LightProtocol {
 			provider: provider,
-			config: params.config,
+			bloom_bits: BloomBitsCache::new(),
+			config: RwLock::new(params.config),
 			genesis_hash: genesis_hash,
 			network_id: params.network_id,
 			pending_peers: RwLock::new(HashMap::new()),
@@ -393,6 +427,58 @@ impl LightProtocol {
 			.map(|peer| peer.lock().status.clone())
 	}
 
+	/// Get statistics about the requests served to a peer.
+	pub fn peer_serve_stats(&self, peer: &PeerId) -> Option<ServeStats> {
+		self.peers.read().get(&peer)
+			.map(|peer| peer.lock().serve_stats.clone())
+	}
+
+	/// Get the current serving configuration.
+	pub fn config(&self) -> Config {
+		self.config.read().clone()
+	}
+
+	/// Replace the serving configuration.
+	///
+	/// Request credit parameters are recomputed immediately and given to new
+	/// peers. Connected peers only learn about them through `update_flow_params`.
+	/// Lowering the maximum number of peers doesn't disconnect anyone.
+	pub fn set_config(&self, config: Config) {
+		let new_params = compute_flow_params(&config, &self.load_distribution, self.num_light_peers());
+
+		*self.config.write() = config;
+		*self.flow_params.write() = Arc::new(new_params);
+	}
+
+	/// Send the current request credit parameters to all connected peers.
+	pub fn update_flow_params(&self, io: &IoContext) {
+		let new_params = self.flow_params.read().clone();
+		let peers = self.peers.read();
+		let now = SteadyTime::now();
+
+		let packet_body = {
+			let mut stream = RlpStream::new_list(3);
+			stream.append(new_params.limit())
+				.append(new_params.recharge_rate())
+				.append(new_params.cost_table());
+			stream.out()
+		};
+
+		for (peer_id, peer_info) in peers.iter() {
+			let mut peer_info = peer_info.lock();
+
+			io.send(*peer_id, packet::UPDATE_CREDITS, packet_body.clone());
+			peer_info.awaiting_acknowledge = Some((now.clone(), new_params.clone()));
+		}
+	}
+
+	// number of connected peers which are light clients we serve.
+	fn num_light_peers(&self) -> usize {
+		self.peers.read().values()
+			.filter(|p| !p.lock().capabilities.serve_headers)
+			.count()
+	}
+
 	/// Get number of (connected, active) peers.
 	pub fn peer_count(&self) -> (usize, usize) {
 		let num_pending = self.pending_peers.read().len();
@@ -733,6 +819,8 @@ impl LightProtocol {
 			}
 		};
 
+		self.refresh_flow_params(io);
+
 		for handler in &self.handlers {
 			handler.on_disconnect(&Ctx {
 				peer: peer,
@@ -763,32 +851,36 @@ impl LightProtocol {
 
 	fn begin_new_cost_period(&self, io: &IoContext) {
 		self.load_distribution.end_period(&*self.sample_store);
+		self.refresh_flow_params(io);
+	}
 
-		let new_params = Arc::new(FlowParams::from_request_times(
-			|kind| self.load_distribution.expected_time_ns(kind),
-			self.config.load_share,
-			self.config.max_stored_seconds,
-		));
-		*self.flow_params.write() = new_params.clone();
-
-		let peers = self.peers.read();
-		let now = SteadyTime::now();
-
-		let packet_body = {
-			let mut stream = RlpStream::new_list(3);
-			stream.append(new_params.limit())
-				.append(new_params.recharge_rate())
-				.append(new_params.cost_table());
-			stream.out()
-		};
+	// recompute request credit parameters and send them to all peers if they changed.
+	fn refresh_flow_params(&self, io: &IoContext) {
+		let new_params = compute_flow_params(&*self.config.read(), &self.load_distribution, self.num_light_peers());
+		if new_params == **self.flow_params.read() {
+			return;
+		}
 
-		for (peer_id, peer_info) in peers.iter() {
-			let mut peer_info = peer_info.lock();
+		*self.flow_params.write() = Arc::new(new_params);
+		self.update_flow_params(io);
+	}
+}
 
-			io.send(*peer_id, packet::UPDATE_CREDITS, packet_body.clone());
-			peer_info.awaiting_acknowledge = Some((now.clone(), new_params.clone()));
-		}
+// compute request credit parameters from measured request times, applying
+// any cost overrides from the configuration.
+fn compute_flow_params(config: &Config, load_distribution: &LoadDistribution, light_peers: usize) -> FlowParams {
+	let mut flow_params = FlowParams::from_request_times(
+		|kind| load_distribution.expected_time_ns(kind),
+		config.load_share(light_peers),
+		config.max_stored_seconds,
+		config.recharge,
+	);
+
+	for (kind, cost) in &config.cost_overrides {
+		flow_params.set_cost(*kind, *cost);
 	}
+
+	flow_params
 }
 
 impl LightProtocol {
@@ -816,6 +908,16 @@ impl LightProtocol {
 			return Err(Error::BadProtocolVersion);
 		}
 
+		// peers which don't serve headers are light clients we'd be serving.
+		let max_light_peers = self.config.read().max_peers;
+		if let (false, Some(max_peers)) = (capabilities.serve_headers, max_light_peers) {
+			let num_light_peers = self.num_light_peers();
+			if num_light_peers >= max_peers {
+				trace!(target: "pip", "rejecting light peer {}: already serving {} peers", peer, num_light_peers);
+				return Err(Error::TooManyPeers);
+			}
+		}
+
 		let remote_flow = flow_params.map(|params| (params.create_credits(), params));
 		let local_flow = self.flow_params.read().clone();
 
@@ -832,8 +934,12 @@ impl LightProtocol {
 			skip_update: false,
 			local_flow: local_flow,
 			awaiting_acknowledge: None,
+			serve_stats: ServeStats::default(),
 		}));
 
+		// without a peer limit, the serving time is split between the connected light clients.
+		self.refresh_flow_params(io);
+
 		let any_kept = self.handlers.iter().map(
 			|handler| handler.on_connect(
 				&Ctx {
@@ -924,6 +1030,7 @@ impl LightProtocol {
 
 		// deserialize requests, check costs and request validity.
 		peer.local_flow.recharge(&mut peer.local_credits);
+		let credits_before = peer.local_credits.current();
 
 		peer.local_credits.deduct_cost(peer.local_flow.base_cost())?;
 		for request_rlp in raw.at(1)?.iter().take(MAX_REQUESTS) {
@@ -938,21 +1045,27 @@ impl LightProtocol {
 		trace!(target: "pip", "Beginning to respond to requests (id: {}) from peer {}", req_id, peer_id);
 
 		// respond to all requests until one fails.
+		let serve_start = ::time::precise_time_ns();
 		let responses = requests.respond_to_all(|complete_req| {
 			let _timer = self.load_distribution.begin_timer(&complete_req);
 			match complete_req {
 				CompleteRequest::Headers(req) => self.provider.block_headers(req).map(Response::Headers),
 				CompleteRequest::HeaderProof(req) => self.provider.header_proof(req).map(Response::HeaderProof),
-				CompleteRequest::TransactionIndex(_) => None, // don't answer these yet, but leave them in protocol.
+				CompleteRequest::TransactionIndex(req) => self.provider.transaction_index(req).map(Response::TransactionIndex),
 				CompleteRequest::Body(req) => self.provider.block_body(req).map(Response::Body),
 				CompleteRequest::Receipts(req) => self.provider.block_receipts(req).map(Response::Receipts),
 				CompleteRequest::Account(req) => self.provider.account_proof(req).map(Response::Account),
 				CompleteRequest::Storage(req) => self.provider.storage_proof(req).map(Response::Storage),
 				CompleteRequest::Code(req) => self.provider.contract_code(req).map(Response::Code),
 				CompleteRequest::Execution(req) => self.provider.transaction_proof(req).map(Response::Execution),
+				CompleteRequest::BloomBits(req) => self.bloom_bits.proof(&*self.provider, req).map(Response::BloomBits),
 			}
 		});
 
+		peer.serve_stats.requests += responses.len() as u64;
+		peer.serve_stats.credits_charged = peer.serve_stats.credits_charged + (credits_before - peer.local_credits.current());
+		peer.serve_stats.serve_time_ns += ::time::precise_time_ns() - serve_start;
+
 		trace!(target: "pip", "Responded to {}/{} requests in packet {}", responses.len(), num_requests, req_id);
 		trace!(target: "pip", "Peer {} has {} credits remaining.", peer_id, peer.local_credits.current());
 
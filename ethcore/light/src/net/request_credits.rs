@@ -199,19 +199,20 @@ impl FlowParams {
 		}
 	}
 
-	/// Create new flow parameters from ,
+	/// Create new flow parameters from expected request times,
 	/// proportion of total capacity which should be given to a peer,
-	/// and number of seconds of stored capacity a peer can accumulate.
+	/// number of seconds of stored capacity a peer can accumulate,
+	/// and the amount of credits a peer recharges per second.
 	pub fn from_request_times<F: Fn(::request::Kind) -> u64>(
 		request_time_ns: F,
 		load_share: f64,
-		max_stored_seconds: u64
+		max_stored_seconds: u64,
+		recharge: u64,
 	) -> Self {
 		use request::Kind;
 
 		let load_share = load_share.abs();
 
-		let recharge: u64 = 100_000_000;
 		let max = recharge.saturating_mul(max_stored_seconds);
 
 		let cost_for_kind = |kind| {
@@ -268,6 +269,28 @@ impl FlowParams {
 		}
 	}
 
+	/// Override the cost of a kind of request.
+	///
+	/// The cost of `Execution` requests is per unit of gas. `BloomBits`
+	/// requests are priced like header proofs and can't be set separately,
+	/// so overrides for them must be rejected before reaching this.
+	pub fn set_cost(&mut self, kind: request::Kind, cost: U256) {
+		use request::Kind;
+
+		match kind {
+			Kind::Headers => self.costs.headers = cost,
+			Kind::HeaderProof => self.costs.header_proof = cost,
+			Kind::TransactionIndex => self.costs.transaction_index = cost,
+			Kind::Body => self.costs.body = cost,
+			Kind::Receipts => self.costs.receipts = cost,
+			Kind::Account => self.costs.account = cost,
+			Kind::Storage => self.costs.storage = cost,
+			Kind::Code => self.costs.code = cost,
+			Kind::Execution => self.costs.transaction_proof = cost,
+			Kind::BloomBits => {} // no entry of its own in the cost table.
+		}
+	}
+
 	/// Get a reference to the credit limit.
 	pub fn limit(&self) -> &U256 { &self.limit }
 
@@ -293,6 +316,8 @@ impl FlowParams {
 			Request::Storage(_) => self.costs.storage,
 			Request::Code(_) => self.costs.code,
 			Request::Execution(ref req) => self.costs.transaction_proof * req.gas,
+			// priced like header proofs so the cost table stays compatible with older peers.
+			Request::BloomBits(_) => self.costs.header_proof,
 		}
 	}
 
@@ -384,21 +409,47 @@ mod tests {
 			|_| 10_000,
 			0.05,
 			60,
+			100_000_000,
 		);
 
 		let flow_params2 = FlowParams::from_request_times(
 			|_| 10_000,
 			0.1,
 			60,
+			100_000_000,
 		);
 
 		let flow_params3 = FlowParams::from_request_times(
 			|_| 5_000,
 			0.05,
 			60,
+			100_000_000,
 		);
 
 		assert_eq!(flow_params2.costs, flow_params3.costs);
 		assert_eq!(flow_params.costs.headers, flow_params2.costs.headers * 2.into());
 	}
+
+	#[test]
+	fn scale_by_recharge() {
+		let flow_params = FlowParams::from_request_times(|_| 10_000, 0.05, 60, 100_000_000);
+		let flow_params2 = FlowParams::from_request_times(|_| 10_000, 0.05, 60, 200_000_000);
+
+		assert_eq!(*flow_params2.limit(), *flow_params.limit() * 2.into());
+		assert_eq!(*flow_params2.recharge_rate(), *flow_params.recharge_rate() * 2.into());
+		assert_eq!(flow_params2.costs.headers, flow_params.costs.headers * 2.into());
+	}
+
+	#[test]
+	fn cost_overrides() {
+		use request::Kind;
+
+		let mut flow_params = FlowParams::from_request_times(|_| 10_000, 0.05, 60, 100_000_000);
+		let header_proof = flow_params.costs.header_proof;
+
+		flow_params.set_cost(Kind::Execution, 1000.into());
+
+		assert_eq!(flow_params.costs.transaction_proof, 1000.into());
+		assert_eq!(flow_params.costs.header_proof, header_proof);
+	}
 }
@@ -36,6 +36,7 @@ enum Key {
 	ServeChainSince,
 	ServeStateSince,
 	TxRelay,
+	ServeBloomBits,
 	BufferLimit,
 	BufferCostTable,
 	BufferRechargeRate,
@@ -55,6 +56,7 @@ impl Key {
 			Key::ServeChainSince => "serveChainSince",
 			Key::ServeStateSince => "serveStateSince",
 			Key::TxRelay => "txRelay",
+			Key::ServeBloomBits => "serveBloomBits",
 			Key::BufferLimit => "flowControl/BL",
 			Key::BufferCostTable => "flowControl/MRC",
 			Key::BufferRechargeRate => "flowControl/MRR",
@@ -74,6 +76,7 @@ impl Key {
 			"serveChainSince" => Some(Key::ServeChainSince),
 			"serveStateSince" => Some(Key::ServeStateSince),
 			"txRelay" => Some(Key::TxRelay),
+			"serveBloomBits" => Some(Key::ServeBloomBits),
 			"flowControl/BL" => Some(Key::BufferLimit),
 			"flowControl/MRC" => Some(Key::BufferCostTable),
 			"flowControl/MRR" => Some(Key::BufferRechargeRate),
@@ -181,6 +184,8 @@ pub struct Capabilities {
 	pub serve_state_since: Option<u64>,
 	/// Whether it can relay transactions to the eth network.
 	pub tx_relay: bool,
+	/// Whether it can serve bloom bits trie proofs.
+	pub serve_bloom_bits: bool,
 }
 
 impl Default for Capabilities {
@@ -190,6 +195,7 @@ impl Default for Capabilities {
 			serve_chain_since: None,
 			serve_state_since: None,
 			tx_relay: false,
+			serve_bloom_bits: false,
 		}
 	}
 }
@@ -229,6 +235,7 @@ pub fn parse_handshake(rlp: UntrustedRlp) -> Result<(Status, Capabilities, Optio
 		serve_chain_since: parser.expect(Key::ServeChainSince).ok(),
 		serve_state_since: parser.expect(Key::ServeStateSince).ok(),
 		tx_relay: parser.expect_raw(Key::TxRelay).is_ok(),
+		serve_bloom_bits: parser.expect_raw(Key::ServeBloomBits).is_ok(),
 	};
 
 	let flow_params = match (
@@ -265,6 +272,9 @@ pub fn write_handshake(status: &Status, capabilities: &Capabilities, flow_params
 	if capabilities.tx_relay {
 		pairs.push(encode_flag(Key::TxRelay));
 	}
+	if capabilities.serve_bloom_bits {
+		pairs.push(encode_flag(Key::ServeBloomBits));
+	}
 
 	if let Some(flow_params) = flow_params {
 		pairs.push(encode_pair(Key::BufferLimit, flow_params.limit()));
@@ -393,6 +403,7 @@ mod tests {
 			serve_chain_since: Some(5),
 			serve_state_since: Some(8),
 			tx_relay: true,
+			serve_bloom_bits: true,
 		};
 
 		let flow_params = FlowParams::new(
@@ -428,6 +439,7 @@ mod tests {
 			serve_chain_since: Some(5),
 			serve_state_since: None,
 			tx_relay: true,
+			serve_bloom_bits: false,
 		};
 
 		let flow_params = FlowParams::new(
@@ -463,6 +475,7 @@ mod tests {
 			serve_chain_since: Some(5),
 			serve_state_since: None,
 			tx_relay: true,
+			serve_bloom_bits: false,
 		};
 
 		let flow_params = FlowParams::new(
@@ -561,6 +574,7 @@ mod tests {
 			serve_chain_since: Some(5),
 			serve_state_since: Some(8),
 			tx_relay: true,
+			serve_bloom_bits: true,
 		};
 
 		let handshake = write_handshake(&status, &capabilities, None);
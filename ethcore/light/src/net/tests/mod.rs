@@ -26,7 +26,7 @@ use network::{PeerId, NodeId};
 
 use net::context::IoContext;
 use net::status::{Capabilities, Status};
-use net::{LightProtocol, Params, packet, Peer};
+use net::{LightProtocol, Params, Config, packet, Peer};
 use provider::Provider;
 use request;
 use request::*;
@@ -34,6 +34,7 @@ use request::*;
 use rlp::*;
 use util::{Address, H256, U256};
 
+use std::cell::RefCell;
 use std::sync::Arc;
 
 // helper for encoding a single request into a packet.
@@ -114,6 +115,10 @@ impl Provider for TestProvider {
 		self.0.client.block_header(id)
 	}
 
+	fn transaction_index(&self, req: request::CompleteTransactionIndexRequest) -> Option<request::TransactionIndexResponse> {
+		self.0.client.transaction_index(req)
+	}
+
 	fn block_body(&self, req: request::CompleteBodyRequest) -> Option<request::BodyResponse> {
 		self.0.client.block_body(req)
 	}
@@ -167,6 +172,7 @@ fn capabilities() -> Capabilities {
 		serve_chain_since: Some(1),
 		serve_state_since: Some(1),
 		tx_relay: true,
+		serve_bloom_bits: true,
 	}
 }
 
@@ -619,6 +625,7 @@ fn id_guard() {
 		skip_update: false,
 		local_flow: flow_params,
 		awaiting_acknowledge: None,
+		serve_stats: Default::default(),
 	}));
 
 	// first, malformed responses.
@@ -661,3 +668,199 @@ fn id_guard() {
 		assert_eq!(peer_info.failed_requests, &[req_id_1]);
 	}
 }
+
+#[test]
+fn records_serve_stats() {
+	let capabilities = capabilities();
+
+	let (provider, proto) = setup(capabilities.clone());
+	let flow_params = proto.flow_params.read().clone();
+
+	provider.client.add_blocks(100, EachBlockWith::Nothing);
+
+	let cur_status = status(provider.client.chain_info());
+
+	{
+		let packet_body = write_handshake(&cur_status, &capabilities, &proto);
+		proto.on_connect(&1, &Expect::Send(1, packet::STATUS, packet_body.clone()));
+		proto.handle_packet(&Expect::Nothing, &1, packet::STATUS, &packet_body);
+	}
+
+	assert_eq!(proto.peer_serve_stats(&1), Some(Default::default()));
+
+	let requests = encode_single(Request::Headers(IncompleteHeadersRequest {
+		start: HashOrNumber::Number(1).into(),
+		max: 10,
+		skip: 0,
+		reverse: false,
+	}));
+
+	let response = {
+		let headers: Vec<_> = (0..10).map(|i| provider.client.block_header(BlockId::Number(i + 1)).unwrap()).collect();
+		let new_creds = *flow_params.limit() - flow_params.compute_cost_multi(requests.requests());
+
+		let mut stream = RlpStream::new_list(3);
+		stream.append(&111usize).append(&new_creds).append_list(&[Response::Headers(HeadersResponse {
+			headers: headers,
+		})]);
+
+		stream.out()
+	};
+
+	proto.handle_packet(&Expect::Respond(packet::RESPONSE, response), &1, packet::REQUEST, &make_packet(111, &requests));
+
+	let stats = proto.peer_serve_stats(&1).unwrap();
+	assert_eq!(stats.requests, 1);
+	assert_eq!(stats.credits_charged, flow_params.compute_cost_multi(requests.requests()));
+}
+
+#[test]
+fn default_config_does_not_limit_light_peers() {
+	let config = Config::default();
+
+	assert_eq!(config.max_peers, None);
+	assert_eq!(config.load_share(0), 0.5);
+	assert_eq!(config.load_share(1), 0.5);
+	assert_eq!(config.load_share(4), 0.125);
+	assert_eq!(Config { max_peers: Some(25), ..config }.load_share(4), 0.02);
+}
+
+// records sent packets instead of expecting particular ones.
+#[derive(Default)]
+struct Sent(RefCell<Vec<(PeerId, u8)>>);
+
+impl IoContext for Sent {
+	fn send(&self, peer: PeerId, packet_id: u8, _packet_body: Vec<u8>) {
+		self.0.borrow_mut().push((peer, packet_id));
+	}
+
+	fn respond(&self, _packet_id: u8, _packet_body: Vec<u8>) {
+		panic!("unexpected response");
+	}
+
+	fn disconnect_peer(&self, peer: PeerId) {
+		panic!("unexpected disconnect of {}", peer);
+	}
+
+	fn disable_peer(&self, peer: PeerId) {
+		panic!("unexpected disable of {}", peer);
+	}
+
+	fn protocol_version(&self, _peer: PeerId) -> Option<u8> {
+		Some(super::MAX_PROTOCOL_VERSION)
+	}
+
+	fn persistent_peer_id(&self, _peer: PeerId) -> Option<NodeId> {
+		None
+	}
+}
+
+#[test]
+fn serve_time_share_split_between_light_peers() {
+	let capabilities = capabilities();
+	let light_capabilities = Capabilities {
+		serve_headers: false,
+		serve_chain_since: None,
+		serve_state_since: None,
+		tx_relay: false,
+		serve_bloom_bits: false,
+	};
+
+	let (provider, proto) = setup(capabilities.clone());
+	let status = status(provider.client.chain_info());
+	let our_handshake = write_handshake(&status, &capabilities, &proto);
+	let light_handshake = ::net::status::write_handshake(&status, &light_capabilities, None);
+	let whole_share = proto.flow_params.read().clone();
+
+	for peer in 1..3 {
+		proto.on_connect(&peer, &Expect::Send(peer, packet::STATUS, our_handshake.clone()));
+	}
+
+	// a single light client gets the whole share.
+	proto.handle_packet(&Expect::Nothing, &1, packet::STATUS, &light_handshake);
+	assert_eq!(*proto.flow_params.read(), whole_share);
+
+	// the next one halves it for everyone.
+	let sent = Sent::default();
+	proto.handle_packet(&sent, &2, packet::STATUS, &light_handshake);
+	let mut sent = sent.0.into_inner();
+	sent.sort();
+	assert_eq!(sent, vec![(1, packet::UPDATE_CREDITS), (2, packet::UPDATE_CREDITS)]);
+	assert!(*proto.flow_params.read() != whole_share);
+
+	let sent = Sent::default();
+	proto.on_disconnect(2, &sent);
+	assert_eq!(sent.0.into_inner(), vec![(1, packet::UPDATE_CREDITS)]);
+	assert_eq!(*proto.flow_params.read(), whole_share);
+}
+
+#[test]
+fn rejects_light_peers_over_limit() {
+	let capabilities = capabilities();
+	let light_capabilities = Capabilities {
+		serve_headers: false,
+		serve_chain_since: None,
+		serve_state_since: None,
+		tx_relay: false,
+		serve_bloom_bits: false,
+	};
+
+	let (provider, proto) = setup(capabilities.clone());
+	proto.set_config(Config { max_peers: Some(1), ..Default::default() });
+
+	let status = status(provider.client.chain_info());
+	let our_handshake = write_handshake(&status, &capabilities, &proto);
+	let light_handshake = ::net::status::write_handshake(&status, &light_capabilities, None);
+
+	for peer in 1..4 {
+		proto.on_connect(&peer, &Expect::Send(peer, packet::STATUS, our_handshake.clone()));
+	}
+
+	proto.handle_packet(&Expect::Nothing, &1, packet::STATUS, &light_handshake);
+	proto.handle_packet(&Expect::Punish(2), &2, packet::STATUS, &light_handshake);
+
+	// full nodes don't count towards the limit.
+	proto.handle_packet(&Expect::Nothing, &3, packet::STATUS, &our_handshake);
+}
+
+#[test]
+fn cost_overrides_sent_to_peers() {
+	use std::collections::HashMap;
+
+	let capabilities = capabilities();
+
+	let (provider, proto) = setup(capabilities.clone());
+	let status = status(provider.client.chain_info());
+
+	{
+		let packet_body = write_handshake(&status, &capabilities, &proto);
+		proto.on_connect(&1, &Expect::Send(1, packet::STATUS, packet_body.clone()));
+		proto.handle_packet(&Expect::Nothing, &1, packet::STATUS, &packet_body);
+	}
+
+	let mut cost_overrides = HashMap::new();
+	cost_overrides.insert(request::Kind::Execution, U256::from(1_000));
+	proto.set_config(Config { cost_overrides: cost_overrides, ..Default::default() });
+
+	let flow_params = proto.flow_params.read().clone();
+	let execution = Request::Execution(IncompleteExecutionRequest {
+		block_hash: H256::default().into(),
+		from: Address::default(),
+		action: Action::Create,
+		gas: 100.into(),
+		gas_price: 0.into(),
+		value: 0.into(),
+		data: Vec::new(),
+	});
+	assert_eq!(flow_params.compute_cost(&execution), U256::from(100_000));
+
+	let packet_body = {
+		let mut stream = RlpStream::new_list(3);
+		stream.append(flow_params.limit())
+			.append(flow_params.recharge_rate())
+			.append(flow_params.cost_table());
+		stream.out()
+	};
+
+	proto.update_flow_params(&Expect::Send(1, packet::UPDATE_CREDITS, packet_body));
+}
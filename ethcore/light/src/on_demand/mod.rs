@@ -18,7 +18,7 @@
 //! The request service is implemented using Futures. Higher level request handlers
 //! will take the raw data received here and extract meaningful results from it.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::marker::PhantomData;
 use std::sync::Arc;
 
@@ -34,7 +34,7 @@ use net::{
 	Announcement, EventContext, BasicContext, ReqId,
 };
 use cache::Cache;
-use request::{self as basic_request, Request as NetworkRequest};
+use request::{self as basic_request, Field, Request as NetworkRequest};
 use self::request::CheckedRequest;
 
 pub use self::request::{Request, Response, HeaderRef};
@@ -47,6 +47,10 @@ pub mod request;
 /// The result of execution
 pub type ExecutionResult = Result<Executed, ExecutionError>;
 
+// maximum number of peers to ask for the location of a transaction. peers which
+// don't know about a transaction can't be told apart from it not existing.
+const MAX_TRANSACTION_INDEX_PEERS: usize = 5;
+
 // relevant peer info.
 struct Peer {
 	status: Status,
@@ -68,7 +72,8 @@ impl Peer {
 
 		local_caps.serve_headers >= request.serve_headers &&
 		    can_serve_since(request.serve_chain_since, local_caps.serve_chain_since) &&
-		    can_serve_since(request.serve_state_since, local_caps.serve_state_since)
+		    can_serve_since(request.serve_state_since, local_caps.serve_state_since) &&
+		    local_caps.serve_bloom_bits >= request.serve_bloom_bits
 	}
 }
 
@@ -79,6 +84,8 @@ struct Pending {
 	required_capabilities: Capabilities,
 	responses: Vec<Response>,
 	sender: oneshot::Sender<Vec<Response>>,
+	// peers which couldn't locate the transaction of the next transaction index request.
+	unlocated_by: HashSet<PeerId>,
 }
 
 impl Pending {
@@ -147,6 +154,51 @@ impl Pending {
 		self.requests.fill_unanswered();
 	}
 
+	// whether the next request to answer is for a transaction location.
+	fn locating_transaction(&self) -> bool {
+		match self.requests.requests().get(self.requests.num_answered()) {
+			Some(&CheckedRequest::TransactionIndex(_, _)) => true,
+			_ => false,
+		}
+	}
+
+	// peers can't prove transaction locations, so check those answered from `first`
+	// onwards against the bodies of their blocks given along with them.
+	fn check_transaction_locations(&self, first: usize) -> Result<(), self::request::Error> {
+		use self::request::Error;
+
+		for (idx, response) in self.responses.iter().enumerate().skip(first) {
+			let location = match *response {
+				Response::TransactionIndex(ref location) => location,
+				_ => continue,
+			};
+			let tx_hash = match self.requests[idx] {
+				CheckedRequest::TransactionIndex(_, ref req) => match req.hash {
+					Field::Scalar(hash) => hash,
+					Field::BackReference(req, out) => return Err(Error::UnresolvedField(req, out)),
+				},
+				_ => return Err(Error::WrongKind),
+			};
+
+			let bodies = self.responses[idx + 1..].iter().filter_map(|response| match *response {
+				Response::Body(ref block) if block.hash() == location.hash => Some(block),
+				_ => None,
+			});
+
+			for block in bodies {
+				if block.number() != location.num {
+					return Err(Error::WrongNumber(location.num, block.number()));
+				}
+				match block.transaction_hashes().get(location.index as usize) {
+					Some(hash) if hash == &tx_hash => {},
+					other => return Err(Error::WrongHash(tx_hash, other.cloned().unwrap_or_default())),
+				}
+			}
+		}
+
+		Ok(())
+	}
+
 	// update the cached network requests.
 	fn update_net_requests(&mut self) {
 		use request::IncompleteRequest;
@@ -180,6 +232,7 @@ fn guess_capabilities(requests: &[CheckedRequest]) -> Capabilities {
 		serve_chain_since: None,
 		serve_state_since: None,
 		tx_relay: false,
+		serve_bloom_bits: false,
 	};
 
 	let update_since = |current: &mut Option<u64>, new|
@@ -195,6 +248,7 @@ fn guess_capabilities(requests: &[CheckedRequest]) -> Capabilities {
 				caps.serve_headers = true,
 			CheckedRequest::HeaderByHash(_, _) =>
 				caps.serve_headers = true,
+			CheckedRequest::TransactionIndex(_, _) => {}, // hashes yield no info.
 			CheckedRequest::Body(ref req, _) => if let Ok(ref hdr) = req.0.as_ref() {
 				update_since(&mut caps.serve_chain_since, hdr.number());
 			},
@@ -204,12 +258,17 @@ fn guess_capabilities(requests: &[CheckedRequest]) -> Capabilities {
 			CheckedRequest::Account(ref req, _) => if let Ok(ref hdr) = req.header.as_ref() {
 				update_since(&mut caps.serve_state_since, hdr.number());
 			},
+			CheckedRequest::Storage(ref req, _) => if let Ok(ref hdr) = req.header.as_ref() {
+				update_since(&mut caps.serve_state_since, hdr.number());
+			},
 			CheckedRequest::Code(ref req, _) => if let Ok(ref hdr) = req.header.as_ref() {
 				update_since(&mut caps.serve_state_since, hdr.number());
 			},
 			CheckedRequest::Execution(ref req, _) => if let Ok(ref hdr) = req.header.as_ref() {
 				update_since(&mut caps.serve_state_since, hdr.number());
 			},
+			CheckedRequest::BloomBits(_, _) =>
+				caps.serve_bloom_bits = true,
 		}
 	}
 
@@ -314,6 +373,7 @@ impl OnDemand {
 			required_capabilities: capabilities,
 			responses: responses,
 			sender: sender,
+			unlocated_by: HashSet::new(),
 		});
 
 		Ok(receiver)
@@ -382,7 +442,7 @@ impl OnDemand {
 				for (peer_id, peer) in peers.iter() { // .shuffle?
 					// TODO: see which requests can be answered by the cache?
 
-					if !peer.can_fulfill(&pending.required_capabilities) {
+					if !peer.can_fulfill(&pending.required_capabilities) || pending.unlocated_by.contains(peer_id) {
 						continue
 					}
 
@@ -397,6 +457,12 @@ impl OnDemand {
 					}
 				}
 
+				// every connected peer was asked for the transaction location already.
+				if !pending.unlocated_by.is_empty() && peers.keys().all(|id| pending.unlocated_by.contains(id)) {
+					debug!(target: "on_demand", "No more peers to locate transaction, cancelling request");
+					return None
+				}
+
 				// TODO: maximum number of failures _when we have peers_.
 				Some(pending)
 			})
@@ -470,13 +536,15 @@ impl Handler for OnDemand {
 			None => return,
 		};
 
+		let peer = ctx.peer();
+		let (checkpoint, first) = (pending.requests.clone(), pending.responses.len());
+
 		// for each incoming response
 		//   1. ensure verification data filled.
 		//   2. pending.requests.supply_response
 		//   3. if extracted on-demand response, keep it for later.
 		for response in responses {
 			if let Err(e) = pending.supply_response(&*self.cache, response) {
-				let peer = ctx.peer();
 				debug!(target: "on_demand", "Peer {} gave bad response: {:?}", peer, e);
 				ctx.disable_peer(peer);
 
@@ -484,6 +552,23 @@ impl Handler for OnDemand {
 			}
 		}
 
+		if let Err(e) = pending.check_transaction_locations(first) {
+			debug!(target: "on_demand", "Peer {} gave bad transaction location: {:?}", peer, e);
+			ctx.disable_peer(peer);
+
+			pending.requests = checkpoint;
+			pending.responses.truncate(first);
+		}
+
+		// give up on transactions which several peers couldn't locate by dropping the sender.
+		if pending.responses.len() == first && pending.locating_transaction() {
+			pending.unlocated_by.insert(peer);
+			if pending.unlocated_by.len() >= MAX_TRANSACTION_INDEX_PEERS {
+				debug!(target: "on_demand", "{} peers couldn't locate transaction, cancelling request", MAX_TRANSACTION_INDEX_PEERS);
+				return;
+			}
+		}
+
 		pending.fill_unanswered();
 		self.submit_pending(ctx.as_basic(), pending);
 	}
@@ -43,16 +43,22 @@ pub enum Request {
 	HeaderProof(HeaderProof),
 	/// A request for a header by hash.
 	HeaderByHash(HeaderByHash),
+	/// A request for the location of a transaction.
+	TransactionIndex(TransactionIndex),
 	/// A request for block receipts.
 	Receipts(BlockReceipts),
 	/// A request for a block body.
 	Body(Body),
 	/// A request for an account.
 	Account(Account),
+	/// A request for a storage value of an account.
+	Storage(Storage),
 	/// A request for a contract's code.
 	Code(Code),
 	/// A request for proof of execution.
 	Execution(TransactionProof),
+	/// A request for a bloom bits vector.
+	BloomBits(BloomBits),
 }
 
 /// A request argument.
@@ -128,11 +134,14 @@ macro_rules! impl_single {
 // implement traits for each kind of request.
 impl_single!(HeaderProof, HeaderProof, (H256, U256));
 impl_single!(HeaderByHash, HeaderByHash, encoded::Header);
+impl_single!(TransactionIndex, TransactionIndex, net_request::TransactionIndexResponse);
 impl_single!(Receipts, BlockReceipts, Vec<Receipt>);
 impl_single!(Body, Body, encoded::Block);
 impl_single!(Account, Account, Option<BasicAccount>);
+impl_single!(Storage, Storage, H256);
 impl_single!(Code, Code, Bytes);
 impl_single!(Execution, TransactionProof, super::ExecutionResult);
+impl_single!(BloomBits, BloomBits, Bytes);
 
 macro_rules! impl_args {
 	() => {
@@ -236,11 +245,14 @@ impl From<encoded::Header> for HeaderRef {
 pub enum CheckedRequest {
 	HeaderProof(HeaderProof, net_request::IncompleteHeaderProofRequest),
 	HeaderByHash(HeaderByHash, net_request::IncompleteHeadersRequest),
+	TransactionIndex(TransactionIndex, net_request::IncompleteTransactionIndexRequest),
 	Receipts(BlockReceipts, net_request::IncompleteReceiptsRequest),
 	Body(Body, net_request::IncompleteBodyRequest),
 	Account(Account, net_request::IncompleteAccountRequest),
+	Storage(Storage, net_request::IncompleteStorageRequest),
 	Code(Code, net_request::IncompleteCodeRequest),
 	Execution(TransactionProof, net_request::IncompleteExecutionRequest),
+	BloomBits(BloomBits, net_request::IncompleteBloomBitsRequest),
 }
 
 impl From<Request> for CheckedRequest {
@@ -261,6 +273,12 @@ impl From<Request> for CheckedRequest {
 				};
 				CheckedRequest::HeaderProof(req, net_req)
 			}
+			Request::TransactionIndex(req) => {
+				let net_req = net_request::IncompleteTransactionIndexRequest {
+					hash: req.0.clone(),
+				};
+				CheckedRequest::TransactionIndex(req, net_req)
+			}
 			Request::Body(req) =>  {
 				let net_req = net_request::IncompleteBodyRequest {
 					hash: req.0.field(),
@@ -280,6 +298,14 @@ impl From<Request> for CheckedRequest {
 				};
 				CheckedRequest::Account(req, net_req)
 			}
+			Request::Storage(req) => {
+				let net_req = net_request::IncompleteStorageRequest {
+					block_hash: req.header.field(),
+					address_hash: ::util::Hashable::sha3(&req.address).into(),
+					key_hash: ::util::Hashable::sha3(&req.key).into(),
+				};
+				CheckedRequest::Storage(req, net_req)
+			}
 			Request::Code(req) => {
 				let net_req = net_request::IncompleteCodeRequest {
 					block_hash: req.header.field(),
@@ -299,6 +325,13 @@ impl From<Request> for CheckedRequest {
 				};
 				CheckedRequest::Execution(req, net_req)
 			}
+			Request::BloomBits(req) => {
+				let net_req = net_request::IncompleteBloomBitsRequest {
+					section: req.section().into(),
+					bit: req.bit() as u64,
+				};
+				CheckedRequest::BloomBits(req, net_req)
+			}
 		}
 	}
 }
@@ -311,11 +344,14 @@ impl CheckedRequest {
 		match self {
 			CheckedRequest::HeaderProof(_, req) => NetRequest::HeaderProof(req),
 			CheckedRequest::HeaderByHash(_, req) => NetRequest::Headers(req),
+			CheckedRequest::TransactionIndex(_, req) => NetRequest::TransactionIndex(req),
 			CheckedRequest::Receipts(_, req) => NetRequest::Receipts(req),
 			CheckedRequest::Body(_, req) => NetRequest::Body(req),
 			CheckedRequest::Account(_, req) => NetRequest::Account(req),
+			CheckedRequest::Storage(_, req) => NetRequest::Storage(req),
 			CheckedRequest::Code(_, req) => NetRequest::Code(req),
 			CheckedRequest::Execution(_, req) => NetRequest::Execution(req),
+			CheckedRequest::BloomBits(_, req) => NetRequest::BloomBits(req),
 		}
 	}
 
@@ -328,6 +364,7 @@ impl CheckedRequest {
 			CheckedRequest::Receipts(ref x, _) => x.0.needs_header(),
 			CheckedRequest::Body(ref x, _) => x.0.needs_header(),
 			CheckedRequest::Account(ref x, _) => x.header.needs_header(),
+			CheckedRequest::Storage(ref x, _) => x.header.needs_header(),
 			CheckedRequest::Code(ref x, _) => x.header.needs_header(),
 			CheckedRequest::Execution(ref x, _) => x.header.needs_header(),
 			_ => None,
@@ -342,6 +379,7 @@ impl CheckedRequest {
 			CheckedRequest::Receipts(ref mut x, _) => x.0 = HeaderRef::Stored(header),
 			CheckedRequest::Body(ref mut x, _) => x.0 = HeaderRef::Stored(header),
 			CheckedRequest::Account(ref mut x, _) => x.header = HeaderRef::Stored(header),
+			CheckedRequest::Storage(ref mut x, _) => x.header = HeaderRef::Stored(header),
 			CheckedRequest::Code(ref mut x, _) => x.header = HeaderRef::Stored(header),
 			CheckedRequest::Execution(ref mut x, _) => x.header = HeaderRef::Stored(header),
 			_ => {},
@@ -421,6 +459,13 @@ impl CheckedRequest {
 						Response::Body(encoded::Block::new(stream.out()))
 					})
 			}
+			CheckedRequest::Storage(ref check, _) => {
+				// empty storage trie -> all values zero.
+				match check.storage_root {
+					Field::Scalar(ref root) if *root == SHA3_NULL_RLP => Some(Response::Storage(H256::zero())),
+					_ => None,
+				}
+			}
 			CheckedRequest::Code(_, ref req) => {
 				if req.code_hash.as_ref().map_or(false, |&h| h == SHA3_EMPTY) {
 					Some(Response::Code(Vec::new()))
@@ -438,11 +483,14 @@ macro_rules! match_me {
 		match $me {
 			CheckedRequest::HeaderProof($check, $req) => $e,
 			CheckedRequest::HeaderByHash($check, $req) => $e,
+			CheckedRequest::TransactionIndex($check, $req) => $e,
 			CheckedRequest::Receipts($check, $req) => $e,
 			CheckedRequest::Body($check, $req) => $e,
 			CheckedRequest::Account($check, $req) => $e,
+			CheckedRequest::Storage($check, $req) => $e,
 			CheckedRequest::Code($check, $req) => $e,
 			CheckedRequest::Execution($check, $req) => $e,
+			CheckedRequest::BloomBits($check, $req) => $e,
 		}
 	}
 }
@@ -465,11 +513,22 @@ impl IncompleteRequest for CheckedRequest {
 					_ => Ok(()),
 				}
 			}
+			CheckedRequest::TransactionIndex(_, ref req) => req.check_outputs(f),
 			CheckedRequest::Receipts(_, ref req) => req.check_outputs(f),
 			CheckedRequest::Body(_, ref req) => req.check_outputs(f),
 			CheckedRequest::Account(_, ref req) => req.check_outputs(f),
+			CheckedRequest::Storage(ref check, ref req) => {
+				req.check_outputs(&mut f)?;
+
+				// the storage root is needed to verify the proof.
+				match check.storage_root {
+					Field::BackReference(r, idx) => f(r, idx, OutputKind::Hash),
+					_ => Ok(()),
+				}
+			}
 			CheckedRequest::Code(_, ref req) => req.check_outputs(f),
 			CheckedRequest::Execution(_, ref req) => req.check_outputs(f),
+			CheckedRequest::BloomBits(_, ref req) => req.check_outputs(f),
 		}
 	}
 
@@ -478,6 +537,14 @@ impl IncompleteRequest for CheckedRequest {
 	}
 
 	fn fill<F>(&mut self, f: F) where F: Fn(usize, usize) -> Result<Output, net_request::NoSuchOutput> {
+		if let CheckedRequest::Storage(ref mut check, _) = *self {
+			if let Field::BackReference(req, idx) = check.storage_root {
+				if let Ok(Output::Hash(root)) = f(req, idx) {
+					check.storage_root = Field::Scalar(root);
+				}
+			}
+		}
+
 		match_me!(*self, (_, ref mut req) => req.fill(f))
 	}
 
@@ -485,11 +552,14 @@ impl IncompleteRequest for CheckedRequest {
 		match self {
 			CheckedRequest::HeaderProof(_, req) => req.complete().map(CompleteRequest::HeaderProof),
 			CheckedRequest::HeaderByHash(_, req) => req.complete().map(CompleteRequest::Headers),
+			CheckedRequest::TransactionIndex(_, req) => req.complete().map(CompleteRequest::TransactionIndex),
 			CheckedRequest::Receipts(_, req) => req.complete().map(CompleteRequest::Receipts),
 			CheckedRequest::Body(_, req) => req.complete().map(CompleteRequest::Body),
 			CheckedRequest::Account(_, req) => req.complete().map(CompleteRequest::Account),
+			CheckedRequest::Storage(_, req) => req.complete().map(CompleteRequest::Storage),
 			CheckedRequest::Code(_, req) => req.complete().map(CompleteRequest::Code),
 			CheckedRequest::Execution(_, req) => req.complete().map(CompleteRequest::Execution),
+			CheckedRequest::BloomBits(_, req) => req.complete().map(CompleteRequest::BloomBits),
 		}
 	}
 
@@ -526,6 +596,9 @@ impl net_request::CheckedRequest for CheckedRequest {
 			CheckedRequest::HeaderByHash(ref prover, _) =>
 				expect!((&NetResponse::Headers(ref res), &CompleteRequest::Headers(ref req)) =>
 					prover.check_response(cache, &req.start, &res.headers).map(Response::HeaderByHash)),
+			CheckedRequest::TransactionIndex(ref prover, _) =>
+				expect!((&NetResponse::TransactionIndex(ref res), _) =>
+					prover.check_response(cache, res).map(Response::TransactionIndex)),
 			CheckedRequest::Receipts(ref prover, _) =>
 				expect!((&NetResponse::Receipts(ref res), _) =>
 					prover.check_response(cache, &res.receipts).map(Response::Receipts)),
@@ -534,17 +607,44 @@ impl net_request::CheckedRequest for CheckedRequest {
 					prover.check_response(cache, &res.body).map(Response::Body)),
 			CheckedRequest::Account(ref prover, _) =>
 				expect!((&NetResponse::Account(ref res), _) =>
-					prover.check_response(cache, &res.proof).map(Response::Account)),
+					prover.check_response(cache, &res.proof)
+						.and_then(|acc| check_account_outputs(acc, res))
+						.map(Response::Account)),
+			CheckedRequest::Storage(ref prover, _) =>
+				expect!((&NetResponse::Storage(ref res), _) =>
+					prover.check_response(cache, &res.proof).map(Response::Storage)),
 			CheckedRequest::Code(ref prover, _) =>
 				expect!((&NetResponse::Code(ref res), &CompleteRequest::Code(ref req)) =>
 					prover.check_response(cache, &req.code_hash, &res.code).map(Response::Code)),
 			CheckedRequest::Execution(ref prover, _) =>
 				expect!((&NetResponse::Execution(ref res), _) =>
 					prover.check_response(cache, &res.items).map(Response::Execution)),
+			CheckedRequest::BloomBits(ref prover, _) =>
+				expect!((&NetResponse::BloomBits(ref res), _) =>
+					prover.check_response(&res.proof).map(Response::BloomBits)),
 		}
 	 }
 }
 
+// later requests may refer to the code hash and storage root given in an account
+// response, so those must match the proved account.
+fn check_account_outputs(acc: Option<BasicAccount>, res: &net_request::AccountResponse) -> Result<Option<BasicAccount>, Error> {
+	let (code_hash, storage_root) = match acc {
+		Some(ref acc) => (acc.code_hash, acc.storage_root),
+		None => (SHA3_EMPTY, SHA3_NULL_RLP),
+	};
+
+	if code_hash != res.code_hash {
+		return Err(Error::WrongHash(code_hash, res.code_hash));
+	}
+
+	if storage_root != res.storage_root {
+		return Err(Error::WrongTrieRoot(storage_root, res.storage_root));
+	}
+
+	Ok(acc)
+}
+
 /// Responses to on-demand requests.
 /// All of these are checked.
 pub enum Response {
@@ -553,6 +653,8 @@ pub enum Response {
 	HeaderProof((H256, U256)),
 	/// Response to a header-by-hash request.
 	HeaderByHash(encoded::Header),
+	/// Response to a transaction index request.
+	TransactionIndex(net_request::TransactionIndexResponse),
 	/// Response to a receipts request.
 	Receipts(Vec<Receipt>),
 	/// Response to a block body request.
@@ -560,10 +662,15 @@ pub enum Response {
 	/// Response to an Account request.
 	// TODO: `unwrap_or(engine_defaults)`
 	Account(Option<BasicAccount>),
+	/// Response to a storage request.
+	Storage(H256),
 	/// Response to a request for code.
 	Code(Vec<u8>),
 	/// Response to a request for proved execution.
 	Execution(super::ExecutionResult),
+	/// Response to a bloom bits request.
+	/// Returns the bit vector, with one bit per block in the section.
+	BloomBits(Bytes),
 }
 
 impl net_request::ResponseLike for Response {
@@ -578,6 +685,8 @@ impl net_request::ResponseLike for Response {
 				f(0, Output::Hash(acc.code_hash));
 				f(1, Output::Hash(acc.storage_root));
 			}
+			Response::TransactionIndex(ref res) => res.fill_outputs(f),
+			Response::Storage(ref value) => f(0, Output::Hash(*value)),
 			_ => {}
 		}
 	}
@@ -598,6 +707,8 @@ pub enum Error {
 	HeaderByNumber,
 	/// Unresolved header reference.
 	UnresolvedHeader(usize),
+	/// Unresolved back-reference to the output of a prior request.
+	UnresolvedField(usize, usize),
 	/// Wrong header number.
 	WrongNumber(u64, u64),
 	/// Wrong hash.
@@ -666,6 +777,45 @@ impl HeaderProof {
 	}
 }
 
+/// Request for a bloom bits vector of a CHT section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BloomBits {
+	/// The CHT number of the section.
+	section: u64,
+	/// The bloom bit.
+	bit: usize,
+	/// The root of the section's bloom bits trie.
+	root: H256,
+}
+
+impl BloomBits {
+	/// Construct a new bloom bits request. Fails if the given bit is out of range.
+	/// Provide the expected bloom bits trie root to compare against.
+	pub fn new(section: u64, bit: usize, root: H256) -> Option<Self> {
+		if bit >= ::cht::BLOOM_BITS { return None }
+
+		Some(BloomBits {
+			section: section,
+			bit: bit,
+			root: root,
+		})
+	}
+
+	/// Access the CHT number of the section.
+	pub fn section(&self) -> u64 { self.section }
+
+	/// Access the requested bloom bit.
+	pub fn bit(&self) -> usize { self.bit }
+
+	/// Access the expected bloom bits trie root.
+	pub fn root(&self) -> H256 { self.root }
+
+	/// Check a response with a bloom bits trie proof, get the bit vector back.
+	pub fn check_response(&self, proof: &[Bytes]) -> Result<Bytes, Error> {
+		::cht::check_bloom_bits_proof(proof, self.bit, self.root).ok_or(Error::BadProof)
+	}
+}
+
 /// Request for a header by hash.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct HeaderByHash(pub Field<H256>);
@@ -699,6 +849,23 @@ impl HeaderByHash {
 	}
 }
 
+/// Request for the location of a transaction by its hash.
+/// Peers can't prove the location, so it should be checked against the
+/// body of the indicated block before use.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionIndex(pub Field<H256>);
+
+impl TransactionIndex {
+	/// Check a response for the transaction index.
+	pub fn check_response(
+		&self,
+		_: &Mutex<::cache::Cache>,
+		res: &net_request::TransactionIndexResponse
+	) -> Result<net_request::TransactionIndexResponse, Error> {
+		Ok(res.clone())
+	}
+}
+
 /// Request for a block, with header for verification.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Body(pub HeaderRef);
@@ -783,6 +950,41 @@ impl Account {
 	}
 }
 
+/// Request for a storage value of an account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Storage {
+	/// Header for verification.
+	pub header: HeaderRef,
+	/// Address of the account.
+	pub address: Address,
+	/// Storage key.
+	pub key: H256,
+	/// Root of the account's storage trie.
+	/// Usually a back-reference to the output of an `Account` request.
+	pub storage_root: Field<H256>,
+}
+
+impl Storage {
+	/// Check a response with a storage proof against the account's storage root.
+	pub fn check_response(&self, _: &Mutex<::cache::Cache>, proof: &[Bytes]) -> Result<H256, Error> {
+		let storage_root = match self.storage_root {
+			Field::Scalar(root) => root,
+			Field::BackReference(req, idx) => return Err(Error::UnresolvedField(req, idx)),
+		};
+
+		let mut db = MemoryDB::new();
+		for node in proof { db.insert(&node[..]); }
+
+		match TrieDB::new(&db, &storage_root).and_then(|t| t.get(&self.key.sha3()))? {
+			Some(val) => {
+				let value: U256 = UntrustedRlp::new(&val).as_val()?;
+				Ok(value.into())
+			}
+			None => Ok(H256::zero()),
+		}
+	}
+}
+
 /// Request for account code.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Code {
@@ -850,7 +1052,7 @@ impl TransactionProof {
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use util::{MemoryDB, Address, Mutex, H256};
+	use util::{MemoryDB, Address, Mutex, H256, U256};
 	use util::trie::{Trie, TrieMut, SecTrieDB, SecTrieDBMut};
 	use util::trie::recorder::Recorder;
 
@@ -896,6 +1098,26 @@ mod tests {
 		assert!(req.check_response(&cache, &proof[..]).is_ok());
 	}
 
+	#[test]
+	fn check_bloom_bits() {
+		use ::cht;
+		use util::H2048;
+
+		let mut bloom = H2048::default();
+		bloom[0] = 0x40;
+
+		let trie = cht::build_bloom_bits(0, |_| Some(bloom.clone())).unwrap();
+		let proof = trie.prove(1, 0).unwrap().unwrap();
+
+		assert!(BloomBits::new(0, cht::BLOOM_BITS, trie.root()).is_none());
+
+		let req = BloomBits::new(0, 1, trie.root()).unwrap();
+		assert_eq!(req.check_response(&proof[..]).unwrap(), vec![0xff; cht::SIZE as usize / 8]);
+
+		let req = BloomBits::new(0, 1, H256::random()).unwrap();
+		assert_eq!(req.check_response(&proof[..]), Err(Error::BadProof));
+	}
+
 	#[test]
 	fn check_header_by_hash() {
 		let mut header = Header::new();
@@ -995,6 +1217,78 @@ mod tests {
 		assert!(req.check_response(&cache, &proof[..]).is_ok());
 	}
 
+	#[test]
+	fn check_storage_proof() {
+		let mut root = H256::default();
+		let mut db = MemoryDB::new();
+		let key = H256::random();
+		{
+			let mut trie = SecTrieDBMut::new(&mut db, &mut root);
+			for i in 0..100u64 {
+				trie.insert(&*H256::random(), &::rlp::encode(&U256::from(i + 1))).unwrap();
+			}
+
+			trie.insert(&*key, &::rlp::encode(&U256::from(0xdead))).unwrap();
+		}
+
+		let prove = |key: &H256| {
+			let trie = SecTrieDB::new(&db, &root).unwrap();
+			let mut recorder = Recorder::new();
+
+			trie.get_with(&**key, &mut recorder).unwrap();
+
+			recorder.drain().into_iter().map(|r| r.data).collect::<Vec<_>>()
+		};
+
+		let header = encoded::Header::new(::rlp::encode(&Header::new()).into_vec());
+		let mut req = Storage {
+			header: header.into(),
+			address: Address::random(),
+			key: key,
+			storage_root: Field::BackReference(0, 1),
+		};
+
+		let cache = Mutex::new(make_cache());
+		let proof = prove(&key);
+		assert_eq!(req.check_response(&cache, &proof[..]), Err(Error::UnresolvedField(0, 1)));
+
+		req.storage_root = root.into();
+		assert_eq!(req.check_response(&cache, &proof[..]), Ok(H256::from(U256::from(0xdead))));
+
+		let missing = H256::random();
+		req.key = missing;
+		assert_eq!(req.check_response(&cache, &prove(&missing)[..]), Ok(H256::zero()));
+	}
+
+	#[test]
+	fn account_outputs_must_match_proof() {
+		use request::AccountResponse;
+
+		let acc = BasicAccount {
+			nonce: 1.into(),
+			balance: 2.into(),
+			code_hash: H256::random(),
+			storage_root: H256::random(),
+		};
+
+		let mut res = AccountResponse {
+			proof: Vec::new(),
+			nonce: acc.nonce,
+			balance: acc.balance,
+			code_hash: acc.code_hash,
+			storage_root: acc.storage_root,
+		};
+
+		assert!(check_account_outputs(Some(acc.clone()), &res).is_ok());
+
+		res.storage_root = H256::random();
+		assert!(check_account_outputs(Some(acc), &res).is_err());
+
+		res.code_hash = SHA3_EMPTY;
+		res.storage_root = SHA3_NULL_RLP;
+		assert!(check_account_outputs(None, &res).is_ok());
+	}
+
 	#[test]
 	fn check_code() {
 		let code = vec![1u8; 256];
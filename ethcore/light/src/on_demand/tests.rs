@@ -116,6 +116,7 @@ fn dummy_capabilities() -> Capabilities {
 		serve_chain_since: Some(1),
 		serve_state_since: Some(1),
 		tx_relay: true,
+		serve_bloom_bits: true,
 	}
 }
 
@@ -495,3 +496,88 @@ fn fill_from_cache() {
 
 	assert!(recv.wait().is_ok());
 }
+
+#[test]
+fn gives_up_locating_unknown_transaction() {
+	let harness = Harness::create();
+
+	let peer_id = 111;
+	let req_id = ReqId(14426);
+
+	harness.inject_peer(peer_id, Peer {
+		status: dummy_status(),
+		capabilities: dummy_capabilities(),
+	});
+
+	let recv = harness.service.request_raw(
+		&Context::NoOp,
+		vec![request::TransactionIndex(H256::default().into()).into()]
+	).unwrap();
+
+	harness.service.dispatch_pending(&Context::RequestFrom(peer_id, req_id));
+	assert_eq!(harness.service.pending.read().len(), 0);
+
+	// the peer doesn't know about the transaction.
+	harness.service.on_responses(&Context::WithPeer(peer_id), req_id, &[]);
+	assert_eq!(harness.service.pending.read().len(), 1);
+
+	// and there is no other peer to ask.
+	harness.service.dispatch_pending(&Context::NoOp);
+	assert!(harness.service.pending.read().is_empty());
+	assert!(recv.wait().is_err());
+}
+
+#[test]
+fn punishes_bad_transaction_location() {
+	let harness = Harness::create();
+
+	let peer_id = 111;
+	let req_id = ReqId(14426);
+
+	harness.inject_peer(peer_id, Peer {
+		status: dummy_status(),
+		capabilities: dummy_capabilities(),
+	});
+
+	let header = Header::default();
+	let encoded = encoded::Header::new(header.rlp(Seal::With));
+	let body = {
+		let mut stream = ::rlp::RlpStream::new_list(2);
+		stream.begin_list(0);
+		stream.begin_list(0);
+		encoded::Body::new(stream.out())
+	};
+
+	let block_hash = ::request::Field::back_ref(0, 1);
+	let _recv = harness.service.request_raw(
+		&Context::NoOp,
+		vec![
+			request::TransactionIndex(H256::from(5).into()).into(),
+			request::HeaderByHash(block_hash).into(),
+			request::Body(HeaderRef::Unresolved(1, block_hash)).into(),
+		]
+	).unwrap();
+
+	harness.service.dispatch_pending(&Context::RequestFrom(peer_id, req_id));
+	assert_eq!(harness.service.pending.read().len(), 0);
+
+	// the block given has no transactions at all.
+	harness.service.on_responses(
+		&Context::Punish(peer_id),
+		req_id,
+		&[
+			Response::TransactionIndex(basic_request::TransactionIndexResponse {
+				num: header.number(),
+				hash: header.hash(),
+				index: 0,
+			}),
+			Response::Headers(basic_request::HeadersResponse { headers: vec![encoded] }),
+			Response::Body(basic_request::BodyResponse { body: body }),
+		]
+	);
+
+	// none of the responses are kept.
+	let pending = harness.service.pending.read();
+	assert_eq!(pending.len(), 1);
+	assert_eq!(pending[0].responses.len(), 0);
+}
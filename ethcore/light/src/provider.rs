@@ -17,14 +17,15 @@
 //! A provider for the LES protocol. This is typically a full node, who can
 //! give as much data as necessary to its peers.
 
+use std::collections::VecDeque;
 use std::sync::Arc;
 
 use ethcore::blockchain_info::BlockChainInfo;
 use ethcore::client::{BlockChainClient, ProvingBlockChainClient};
 use ethcore::transaction::PendingTransaction;
-use ethcore::ids::BlockId;
+use ethcore::ids::{BlockId, TransactionId};
 use ethcore::encoded;
-use util::{RwLock, H256};
+use util::{Mutex, RwLock, H256, MemoryDB};
 
 use cht::{self, BlockInfo};
 use client::{LightChainClient, AsLightClient};
@@ -101,6 +102,9 @@ pub trait Provider: Send + Sync {
 	/// Get a block header by id.
 	fn block_header(&self, id: BlockId) -> Option<encoded::Header>;
 
+	/// Get the location of a canonical transaction by its hash.
+	fn transaction_index(&self, req: request::CompleteTransactionIndexRequest) -> Option<request::TransactionIndexResponse>;
+
 	/// Fulfill a block body request.
 	fn block_body(&self, req: request::CompleteBodyRequest) -> Option<request::BodyResponse>;
 
@@ -146,6 +150,16 @@ impl<T: ProvingBlockChainClient + ?Sized> Provider for T {
 		BlockChainClient::block_header(self, id)
 	}
 
+	fn transaction_index(&self, req: request::CompleteTransactionIndexRequest) -> Option<request::TransactionIndexResponse> {
+		self.transaction(TransactionId::Hash(req.hash)).map(|tx| {
+			::request::TransactionIndexResponse {
+				num: tx.block_number,
+				hash: tx.block_hash,
+				index: tx.transaction_index as u64,
+			}
+		})
+	}
+
 	fn block_body(&self, req: request::CompleteBodyRequest) -> Option<request::BodyResponse> {
 		BlockChainClient::block_body(self, BlockId::Hash(req.hash))
 			.map(|body| ::request::BodyResponse { body: body })
@@ -301,6 +315,10 @@ impl<L: AsLightClient + Send + Sync> Provider for LightProvider<L> {
 		self.client.as_light_client().block_header(id)
 	}
 
+	fn transaction_index(&self, _req: request::CompleteTransactionIndexRequest) -> Option<request::TransactionIndexResponse> {
+		None
+	}
+
 	fn block_body(&self, _req: request::CompleteBodyRequest) -> Option<request::BodyResponse> {
 		None
 	}
@@ -343,10 +361,91 @@ impl<L: AsLightClient> AsLightClient for LightProvider<L> {
 	}
 }
 
+/// Number of bloom bits tries kept by a `BloomBitsCache`.
+const BLOOM_BITS_CACHE_SIZE: usize = 4;
+
+/// Serves bits vectors of the bloom bits tries, built from the headers of a provider.
+///
+/// Building a trie reads every header in its section, so the tries of recently
+/// requested sections are kept along with the hash of the last block they cover,
+/// which keeps a reorganized section from being served out of the cache.
+pub struct BloomBitsCache {
+	tries: Mutex<VecDeque<(H256, Arc<cht::BloomBitsTrie<MemoryDB>>)>>,
+}
+
+impl BloomBitsCache {
+	/// Create a new, empty cache.
+	pub fn new() -> Self {
+		BloomBitsCache {
+			tries: Mutex::new(VecDeque::with_capacity(BLOOM_BITS_CACHE_SIZE)),
+		}
+	}
+
+	/// Provide a bit vector and its proof from the bloom bits trie of a given section.
+	pub fn proof(&self, provider: &Provider, req: request::CompleteBloomBitsRequest) -> Option<request::BloomBitsResponse> {
+		let bit = req.bit as usize;
+		if bit >= cht::BLOOM_BITS {
+			debug!(target: "pip_provider", "Requested bloom bits proof with invalid bit");
+			return None;
+		}
+
+		let last_hash = match provider.block_header(BlockId::Number(cht::start_number(req.section) + cht::SIZE - 1)) {
+			Some(hdr) => hdr.hash(),
+			None => return None, // incomplete section.
+		};
+
+		let trie = match self.trie(provider, req.section, last_hash) {
+			Some(trie) => trie,
+			None => return None,
+		};
+
+		match trie.prove(bit, 0) {
+			Ok(Some(proof)) => {
+				let bits = cht::check_bloom_bits_proof(&proof, bit, trie.root())
+					.expect("proof generated from a complete trie; qed");
+
+				Some(::request::BloomBitsResponse {
+					proof: proof,
+					bits: bits,
+				})
+			}
+			Ok(None) => None,
+			Err(e) => {
+				debug!(target: "pip_provider", "Error looking up bit in bloom bits trie: {}", e);
+				None
+			}
+		}
+	}
+
+	// get the trie of the section ending with `last_hash`, building it if necessary.
+	fn trie(&self, provider: &Provider, section: u64, last_hash: H256) -> Option<Arc<cht::BloomBitsTrie<MemoryDB>>> {
+		{
+			let tries = self.tries.lock();
+			let cached = tries.iter().find(|&&(ref hash, ref trie)| trie.number() == section && *hash == last_hash);
+			if let Some(&(_, ref trie)) = cached {
+				return Some(trie.clone());
+			}
+		}
+
+		let bloom = |num| provider.block_header(BlockId::Number(num)).map(|hdr| hdr.log_bloom());
+		let trie = match cht::build_bloom_bits(section, bloom) {
+			Some(trie) => Arc::new(trie),
+			None => return None, // incomplete section.
+		};
+
+		let mut tries = self.tries.lock();
+		if tries.len() >= BLOOM_BITS_CACHE_SIZE {
+			tries.pop_front();
+		}
+		tries.push_back((last_hash, trie.clone()));
+		Some(trie)
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use ethcore::client::{EachBlockWith, TestBlockChainClient};
-	use super::Provider;
+	use super::{Provider, BloomBitsCache};
 
 	#[test]
 	fn cht_proof() {
@@ -363,4 +462,24 @@ mod tests {
 
 		assert!(client.header_proof(req.clone()).is_some());
 	}
+	#[test]
+	fn bloom_bits_proof_is_cached() {
+		let client = TestBlockChainClient::new();
+		client.add_blocks(2000, EachBlockWith::Nothing);
+
+		let cache = BloomBitsCache::new();
+		let req = ::request::CompleteBloomBitsRequest {
+			section: 0,
+			bit: 7,
+		};
+
+		assert!(cache.proof(&client, req.clone()).is_none());
+		assert!(cache.tries.lock().is_empty());
+
+		client.add_blocks(48, EachBlockWith::Nothing);
+
+		assert!(cache.proof(&client, req.clone()).is_some());
+		assert!(cache.proof(&client, ::request::CompleteBloomBitsRequest { section: 0, bit: 8 }).is_some());
+		assert_eq!(cache.tries.lock().len(), 1);
+	}
 }
@@ -19,6 +19,8 @@
 use rlp::{Encodable, Decodable, DecoderError, RlpStream, UntrustedRlp};
 use util::H256;
 
+use std::str::FromStr;
+
 mod builder;
 
 // re-exports of request types.
@@ -67,6 +69,11 @@ pub use self::execution::{
 	Incomplete as IncompleteExecutionRequest,
 	Response as ExecutionResponse,
 };
+pub use self::bloom_bits::{
+	Complete as CompleteBloomBitsRequest,
+	Incomplete as IncompleteBloomBitsRequest,
+	Response as BloomBitsResponse,
+};
 
 pub use self::builder::{RequestBuilder, Requests};
 
@@ -261,6 +268,8 @@ pub enum Request {
 	Code(IncompleteCodeRequest),
 	/// A request for proof of execution,
 	Execution(IncompleteExecutionRequest),
+	/// A request for a bloom bits vector (from a bloom bits trie)
+	BloomBits(IncompleteBloomBitsRequest),
 }
 
 /// All request types, in an answerable state.
@@ -284,6 +293,8 @@ pub enum CompleteRequest {
 	Code(CompleteCodeRequest),
 	/// A request for proof of execution,
 	Execution(CompleteExecutionRequest),
+	/// A request for a bloom bits vector (from a bloom bits trie)
+	BloomBits(CompleteBloomBitsRequest),
 }
 
 impl CompleteRequest {
@@ -299,6 +310,7 @@ impl CompleteRequest {
 			CompleteRequest::Storage(_) => Kind::Storage,
 			CompleteRequest::Code(_) => Kind::Code,
 			CompleteRequest::Execution(_) => Kind::Execution,
+			CompleteRequest::BloomBits(_) => Kind::BloomBits,
 		}
 	}
 }
@@ -316,6 +328,7 @@ impl Request {
 			Request::Storage(_) => Kind::Storage,
 			Request::Code(_) => Kind::Code,
 			Request::Execution(_) => Kind::Execution,
+			Request::BloomBits(_) => Kind::BloomBits,
 		}
 	}
 }
@@ -332,6 +345,7 @@ impl Decodable for Request {
 			Kind::Storage => Ok(Request::Storage(rlp.val_at(1)?)),
 			Kind::Code => Ok(Request::Code(rlp.val_at(1)?)),
 			Kind::Execution => Ok(Request::Execution(rlp.val_at(1)?)),
+			Kind::BloomBits => Ok(Request::BloomBits(rlp.val_at(1)?)),
 		}
 	}
 }
@@ -353,6 +367,7 @@ impl Encodable for Request {
 			Request::Storage(ref req) => s.append(req),
 			Request::Code(ref req) => s.append(req),
 			Request::Execution(ref req) => s.append(req),
+			Request::BloomBits(ref req) => s.append(req),
 		};
 	}
 }
@@ -374,6 +389,7 @@ impl IncompleteRequest for Request {
 			Request::Storage(ref req) => req.check_outputs(f),
 			Request::Code(ref req) => req.check_outputs(f),
 			Request::Execution(ref req) => req.check_outputs(f),
+			Request::BloomBits(ref req) => req.check_outputs(f),
 		}
 	}
 
@@ -388,6 +404,7 @@ impl IncompleteRequest for Request {
 			Request::Storage(ref req) => req.note_outputs(f),
 			Request::Code(ref req) => req.note_outputs(f),
 			Request::Execution(ref req) => req.note_outputs(f),
+			Request::BloomBits(ref req) => req.note_outputs(f),
 		}
 	}
 
@@ -402,6 +419,7 @@ impl IncompleteRequest for Request {
 			Request::Storage(ref mut req) => req.fill(oracle),
 			Request::Code(ref mut req) => req.fill(oracle),
 			Request::Execution(ref mut req) => req.fill(oracle),
+			Request::BloomBits(ref mut req) => req.fill(oracle),
 		}
 	}
 
@@ -416,6 +434,7 @@ impl IncompleteRequest for Request {
 			Request::Storage(req) => req.complete().map(CompleteRequest::Storage),
 			Request::Code(req) => req.complete().map(CompleteRequest::Code),
 			Request::Execution(req) => req.complete().map(CompleteRequest::Execution),
+			Request::BloomBits(req) => req.complete().map(CompleteRequest::BloomBits),
 		}
 	}
 
@@ -430,6 +449,7 @@ impl IncompleteRequest for Request {
 			Request::Storage(ref mut req) => req.adjust_refs(mapping),
 			Request::Code(ref mut req) => req.adjust_refs(mapping),
 			Request::Execution(ref mut req) => req.adjust_refs(mapping),
+			Request::BloomBits(ref mut req) => req.adjust_refs(mapping),
 		}
 	}
 }
@@ -471,6 +491,8 @@ pub enum Kind {
 	Code = 7,
 	/// A request for transaction execution + state proof.
 	Execution = 8,
+	/// A request for a bloom bits vector + merkle proof.
+	BloomBits = 9,
 }
 
 impl Decodable for Kind {
@@ -485,6 +507,7 @@ impl Decodable for Kind {
 			6 => Ok(Kind::Storage),
 			7 => Ok(Kind::Code),
 			8 => Ok(Kind::Execution),
+			9 => Ok(Kind::BloomBits),
 			_ => Err(DecoderError::Custom("Unknown PIP request ID.")),
 		}
 	}
@@ -496,6 +519,26 @@ impl Encodable for Kind {
 	}
 }
 
+impl FromStr for Kind {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"headers" => Ok(Kind::Headers),
+			"header_proof" => Ok(Kind::HeaderProof),
+			"transaction_index" => Ok(Kind::TransactionIndex),
+			"receipts" => Ok(Kind::Receipts),
+			"body" => Ok(Kind::Body),
+			"account" => Ok(Kind::Account),
+			"storage" => Ok(Kind::Storage),
+			"code" => Ok(Kind::Code),
+			"execution" => Ok(Kind::Execution),
+			"bloom_bits" => Ok(Kind::BloomBits),
+			_ => Err(format!("Unknown request kind: {}", s)),
+		}
+	}
+}
+
 /// All response types.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Response {
@@ -517,6 +560,8 @@ pub enum Response {
 	Code(CodeResponse),
 	/// A response for proof of execution,
 	Execution(ExecutionResponse),
+	/// A response for a bloom bits vector (from a bloom bits trie)
+	BloomBits(BloomBitsResponse),
 }
 
 impl ResponseLike for Response {
@@ -532,6 +577,7 @@ impl ResponseLike for Response {
 			Response::Storage(ref res) => res.fill_outputs(f),
 			Response::Code(ref res) => res.fill_outputs(f),
 			Response::Execution(ref res) => res.fill_outputs(f),
+			Response::BloomBits(ref res) => res.fill_outputs(f),
 		}
 	}
 }
@@ -549,6 +595,7 @@ impl Response {
 			Response::Storage(_) => Kind::Storage,
 			Response::Code(_) => Kind::Code,
 			Response::Execution(_) => Kind::Execution,
+			Response::BloomBits(_) => Kind::BloomBits,
 		}
 	}
 }
@@ -565,6 +612,7 @@ impl Decodable for Response {
 			Kind::Storage => Ok(Response::Storage(rlp.val_at(1)?)),
 			Kind::Code => Ok(Response::Code(rlp.val_at(1)?)),
 			Kind::Execution => Ok(Response::Execution(rlp.val_at(1)?)),
+			Kind::BloomBits => Ok(Response::BloomBits(rlp.val_at(1)?)),
 		}
 	}
 }
@@ -586,6 +634,7 @@ impl Encodable for Response {
 			Response::Storage(ref res) => s.append(res),
 			Response::Code(ref res) => s.append(res),
 			Response::Execution(ref res) => s.append(res),
+			Response::BloomBits(ref res) => s.append(res),
 		};
 	}
 }
@@ -884,7 +933,7 @@ pub mod transaction_index {
 		fn fill<F>(&mut self, oracle: F) where F: Fn(usize, usize) -> Result<Output, NoSuchOutput> {
 			if let Field::BackReference(req, idx) = self.hash {
 				self.hash = match oracle(req, idx) {
-					Ok(Output::Number(hash)) => Field::Scalar(hash.into()),
+					Ok(Output::Hash(hash)) => Field::Scalar(hash),
 					_ => Field::BackReference(req, idx),
 				}
 			}
@@ -959,7 +1008,7 @@ pub mod block_receipts {
 		fn fill<F>(&mut self, oracle: F) where F: Fn(usize, usize) -> Result<Output, NoSuchOutput> {
 			if let Field::BackReference(req, idx) = self.hash {
 				self.hash = match oracle(req, idx) {
-					Ok(Output::Number(hash)) => Field::Scalar(hash.into()),
+					Ok(Output::Hash(hash)) => Field::Scalar(hash),
 					_ => Field::BackReference(req, idx),
 				}
 			}
@@ -1501,6 +1550,98 @@ pub mod execution {
 	}
 }
 
+/// A request for a bloom bits vector.
+pub mod bloom_bits {
+	use super::{Field, NoSuchOutput, OutputKind, Output};
+	use rlp::{Encodable, Decodable, DecoderError, RlpStream, UntrustedRlp};
+	use util::Bytes;
+
+	/// Potentially incomplete bloom bits request.
+	#[derive(Debug, Clone, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+	pub struct Incomplete {
+		/// The number of the CHT whose section of blocks the bits cover.
+		pub section: Field<u64>,
+		/// The bloom bit to get the vector of.
+		pub bit: u64,
+	}
+
+	impl super::IncompleteRequest for Incomplete {
+		type Complete = Complete;
+		type Response = Response;
+
+		fn check_outputs<F>(&self, mut f: F) -> Result<(), NoSuchOutput>
+			where F: FnMut(usize, usize, OutputKind) -> Result<(), NoSuchOutput>
+		{
+			match self.section {
+				Field::Scalar(_) => Ok(()),
+				Field::BackReference(req, idx) => f(req, idx, OutputKind::Number),
+			}
+		}
+
+		fn note_outputs<F>(&self, _: F) where F: FnMut(usize, OutputKind) {}
+
+		fn fill<F>(&mut self, oracle: F) where F: Fn(usize, usize) -> Result<Output, NoSuchOutput> {
+			if let Field::BackReference(req, idx) = self.section {
+				self.section = match oracle(req, idx) {
+					Ok(Output::Number(num)) => Field::Scalar(num),
+					_ => Field::BackReference(req, idx),
+				}
+			}
+		}
+
+		fn complete(self) -> Result<Self::Complete, NoSuchOutput> {
+			Ok(Complete {
+				section: self.section.into_scalar()?,
+				bit: self.bit,
+			})
+		}
+
+		fn adjust_refs<F>(&mut self, mapping: F) where F: FnMut(usize) -> usize {
+			self.section.adjust_req(mapping)
+		}
+	}
+
+	/// A complete bloom bits request.
+	#[derive(Debug, Clone, PartialEq, Eq)]
+	pub struct Complete {
+		/// The number of the CHT whose section of blocks the bits cover.
+		pub section: u64,
+		/// The bloom bit to get the vector of.
+		pub bit: u64,
+	}
+
+	/// The output of a request for a bloom bits vector.
+	#[derive(Debug, Clone, PartialEq, Eq)]
+	pub struct Response {
+		/// Inclusion proof of the vector in the section's bloom bits trie.
+		pub proof: Vec<Bytes>,
+		/// The bit vector, with one bit per block in the section.
+		pub bits: Bytes,
+	}
+
+	impl super::ResponseLike for Response {
+		/// Fill reusable outputs by providing them to the function.
+		fn fill_outputs<F>(&self, _: F) where F: FnMut(usize, Output) {}
+	}
+
+	impl Decodable for Response {
+		fn decode(rlp: &UntrustedRlp) -> Result<Self, DecoderError> {
+			Ok(Response {
+				proof: rlp.list_at(0)?,
+				bits: rlp.val_at(1)?,
+			})
+		}
+	}
+
+	impl Encodable for Response {
+		fn rlp_append(&self, s: &mut RlpStream) {
+			s.begin_list(2)
+				.append_list::<Vec<u8>,_>(&self.proof[..])
+				.append(&self.bits);
+		}
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -1763,6 +1904,26 @@ mod tests {
 		assert_eq!(rlp.list_at::<Request>(1).unwrap(), reqs);
 	}
 
+	#[test]
+	fn bloom_bits_roundtrip() {
+		let req = IncompleteBloomBitsRequest {
+			section: Field::BackReference(1, 2),
+			bit: 1234,
+		};
+
+		let full_req = Request::BloomBits(req.clone());
+		let res = BloomBitsResponse {
+			proof: vec![vec![1, 2, 3], vec![4, 5, 6]],
+			bits: vec![0x80; 256],
+		};
+		let full_res = Response::BloomBits(res.clone());
+
+		check_roundtrip(req);
+		check_roundtrip(full_req);
+		check_roundtrip(res);
+		check_roundtrip(full_res);
+	}
+
 	#[test]
 	fn responses_vec() {
 		let mut stream = RlpStream::new_list(2);
@@ -1784,9 +1945,17 @@ mod tests {
 			Response::Storage(StorageResponse { proof: vec![], value: H256::default() }),
 			Response::Code(CodeResponse { code: vec![1, 2, 3, 4, 5] }),
 			Response::Execution(ExecutionResponse { items: vec![] }),
+			Response::BloomBits(BloomBitsResponse { proof: vec![], bits: vec![0; 256] }),
 		];
 
 		let raw = ::rlp::encode_list(&reqs);
 		assert_eq!(::rlp::decode_list::<Response>(&raw), reqs);
 	}
+
+	#[test]
+	fn kind_from_str() {
+		assert_eq!("execution".parse::<Kind>(), Ok(Kind::Execution));
+		assert_eq!("header_proof".parse::<Kind>(), Ok(Kind::HeaderProof));
+		assert!("Execution".parse::<Kind>().is_err());
+	}
 }
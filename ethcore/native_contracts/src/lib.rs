@@ -31,6 +31,7 @@ mod secretstore_acl_storage;
 mod validator_set;
 mod validator_report;
 mod peer_set;
+mod block_reward;
 
 pub mod test_contracts;
 
@@ -42,3 +43,4 @@ pub use self::secretstore_acl_storage::SecretStoreAclStorage;
 pub use self::validator_set::ValidatorSet;
 pub use self::validator_report::ValidatorReport;
 pub use self::peer_set::PeerSet;
+pub use self::block_reward::BlockReward;
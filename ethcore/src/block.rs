@@ -92,6 +92,7 @@ pub struct ExecutedBlock {
 	transactions_set: HashSet<H256>,
 	state: State<StateDB>,
 	traces: Option<Vec<Vec<FlatTrace>>>,
+	last_hashes: Arc<LastHashes>,
 }
 
 /// A set of references to `ExecutedBlock` fields that are publicly accessible.
@@ -128,7 +129,7 @@ pub struct BlockRef<'a> {
 
 impl ExecutedBlock {
 	/// Create a new block from the given `state`.
-	fn new(state: State<StateDB>, tracing: bool) -> ExecutedBlock {
+	fn new(state: State<StateDB>, last_hashes: Arc<LastHashes>, tracing: bool) -> ExecutedBlock {
 		ExecutedBlock {
 			header: Default::default(),
 			transactions: Default::default(),
@@ -137,9 +138,15 @@ impl ExecutedBlock {
 			transactions_set: Default::default(),
 			state: state,
 			traces: if tracing {Some(Vec::new())} else {None},
+			last_hashes: last_hashes,
 		}
 	}
 
+	/// Get the hashes of the most recent blocks preceding this one.
+	pub fn last_hashes(&self) -> Arc<LastHashes> {
+		self.last_hashes.clone()
+	}
+
 	/// Get a structure containing individual references to all public fields.
 	pub fn fields_mut(&mut self) -> BlockRefMut {
 		BlockRefMut {
@@ -265,7 +272,7 @@ impl<'x> OpenBlock<'x> {
 		let number = parent.number() + 1;
 		let state = State::from_existing(db, parent.state_root().clone(), engine.account_start_nonce(number), factories)?;
 		let mut r = OpenBlock {
-			block: ExecutedBlock::new(state, tracing),
+			block: ExecutedBlock::new(state, last_hashes.clone(), tracing),
 			engine: engine,
 			last_hashes: last_hashes.clone(),
 		};
@@ -384,6 +391,8 @@ impl<'x> OpenBlock<'x> {
 		self.set_extra_data(header.extra_data().clone()).unwrap_or_else(|e| warn!("Couldn't set extradata: {}. Ignoring.", e));
 		self.set_uncles_hash(header.uncles_hash().clone());
 		self.set_transactions_root(header.transactions_root().clone());
+		// the seal is needed by engines to tell whether the block is being verified on close.
+		self.block.header.set_seal(header.seal().to_vec());
 	}
 
 	/// Turn this into a `ClosedBlock`.
@@ -497,10 +506,14 @@ impl LockedBlock {
 	/// NOTE: This does not check the validity of `seal` with the engine.
 	pub fn seal(self, engine: &Engine, seal: Vec<Bytes>) -> Result<SealedBlock, BlockError> {
 		let mut s = self;
-		if seal.len() != engine.seal_fields() {
-			return Err(BlockError::InvalidSealArity(Mismatch{expected: engine.seal_fields(), found: seal.len()}));
+		if seal.len() != engine.seal_fields(&s.block.header) {
+			return Err(BlockError::InvalidSealArity(Mismatch{expected: engine.seal_fields(&s.block.header), found: seal.len()}));
 		}
 		s.block.header.set_seal(seal);
+		if let Err(e) = engine.on_seal_block(&mut s.block) {
+			warn!("Encountered error on sealing the block: {}", e);
+			return Err(BlockError::InvalidSeal);
+		}
 		Ok(SealedBlock { block: s.block, uncle_bytes: s.uncle_bytes })
 	}
 
@@ -514,6 +527,9 @@ impl LockedBlock {
 	) -> Result<SealedBlock, (Error, LockedBlock)> {
 		let mut s = self;
 		s.block.header.set_seal(seal);
+		if let Err(e) = engine.on_seal_block(&mut s.block) {
+			return Err((e, s));
+		}
 		match engine.verify_block_seal(&s.block.header) {
 			Err(e) => Err((e, s)),
 			_ => Ok(SealedBlock { block: s.block, uncle_bytes: s.uncle_bytes }),
@@ -18,7 +18,7 @@ use std::collections::{HashSet, HashMap, BTreeMap, VecDeque};
 use std::str::FromStr;
 use std::sync::{Arc, Weak};
 use std::sync::atomic::{AtomicUsize, AtomicBool, Ordering as AtomicOrdering};
-use std::time::{Instant};
+use std::time::{Instant, Duration};
 use time::precise_time_ns;
 use itertools::Itertools;
 
@@ -44,7 +44,7 @@ use client::{
 use encoded;
 use engines::{Engine, EpochTransition};
 use error::{ImportError, ExecutionError, CallError, BlockError, ImportResult, Error as EthcoreError};
-use vm::{EnvInfo, LastHashes};
+use vm::{self, EnvInfo, LastHashes};
 use evm::{Factory as EvmFactory, Schedule};
 use executive::{Executive, Executed, TransactOptions, contract_address};
 use factory::Factories;
@@ -64,10 +64,11 @@ use state_db::StateDB;
 use state::{self, State};
 use trace;
 use trace::{TraceDB, ImportRequest as TraceImportRequest, LocalizedTrace, Database as TraceDatabase};
-use trace::FlatTransactionTraces;
+use trace::{FlatTransactionTraces, StructLog, StructLogOptions};
 use transaction::{LocalizedTransaction, UnverifiedTransaction, SignedTransaction, Transaction, PendingTransaction, Action};
 use types::filter::Filter;
 use types::mode::Mode as IpcMode;
+use types::state_override::StateOverride;
 use verification;
 use verification::{PreverifiedBlock, Verifier};
 use verification::queue::BlockQueue;
@@ -82,6 +83,9 @@ pub use verification::queue::QueueInfo as BlockQueueInfo;
 const MAX_TX_QUEUE_SIZE: usize = 4096;
 const MAX_QUEUE_SIZE_TO_SLEEP_ON: usize = 2;
 const MIN_HISTORY_SIZE: u64 = 8;
+const ADDRESS_INDEX_BLOCKS_PER_TICK: u64 = 2000;
+const TRACE_BACKFILL_BLOCKS_PER_TICK: u64 = 20;
+const TRACE_BACKFILL_MS_PER_TICK: u64 = 250;
 
 /// Report on the status of a client.
 #[derive(Default, Clone, Debug, Eq, PartialEq)]
@@ -142,6 +146,8 @@ pub struct Client {
 	mode: Mutex<Mode>,
 	chain: RwLock<Arc<BlockChain>>,
 	tracedb: RwLock<TraceDB<BlockChain>>,
+	/// First block from which all blocks are traced, while the background trace backfill is running.
+	trace_backfill: Mutex<Option<BlockNumber>>,
 	engine: Arc<Engine>,
 	config: ClientConfig,
 	pruning: journaldb::Algorithm,
@@ -202,6 +208,20 @@ impl Client {
 		let gb = spec.genesis_block();
 		let chain = Arc::new(BlockChain::new(config.blockchain.clone(), &gb, db.clone()));
 		let tracedb = RwLock::new(TraceDB::new(config.tracing.clone(), db.clone(), chain.clone()));
+		// an unfinished backfill is resumed even without the flag, since tracing is already
+		// on for the whole database after the first run with it.
+		let trace_backfill = match (config.tracing.enabled, tracedb.read().backfill_cursor()) {
+			(false, _) => None,
+			(true, Some(cursor)) => Some(cursor),
+			(true, None) if config.tracing.backfill => {
+				let cursor = chain.best_block_number() + 1;
+				let mut batch = DBTransaction::new();
+				tracedb.read().set_backfill_cursor(&mut batch, cursor);
+				db.write(batch).map_err(ClientError::Database)?;
+				Some(cursor)
+			},
+			(true, None) => None,
+		};
 
 		trace!("Cleanup journal: DB Earliest = {:?}, Latest = {:?}", state_db.journal_db().earliest_era(), state_db.journal_db().latest_era());
 
@@ -231,6 +251,7 @@ impl Client {
 			mode: Mutex::new(config.mode.clone()),
 			chain: RwLock::new(chain),
 			tracedb: tracedb,
+			trace_backfill: Mutex::new(trace_backfill),
 			engine: engine,
 			pruning: config.pruning.clone(),
 			verifier: verification::new(config.verifier_type.clone()),
@@ -331,6 +352,11 @@ impl Client {
 		&*self.engine
 	}
 
+	/// Returns a handle to the client database.
+	pub fn db(&self) -> Arc<KeyValueDB> {
+		self.db.read().clone()
+	}
+
 	fn notify<F>(&self, f: F) where F: Fn(&ChainNotify) {
 		for np in self.notify.read().iter() {
 			if let Some(n) = np.upgrade() {
@@ -930,6 +956,67 @@ impl Client {
 		})
 	}
 
+	/// Re-executes a canonical block imported while tracing was disabled and stores its traces.
+	/// Returns `false` if the block is already traced or tracing is disabled.
+	pub fn backfill_block_traces(&self, number: BlockNumber) -> Result<bool, CallError> {
+		let hash = self.chain.read().block_hash(number).ok_or(CallError::StatePruned)?;
+		if number == 0 || !self.tracedb.read().tracing_enabled() || self.tracedb.read().has_traces(&hash) {
+			return Ok(false);
+		}
+
+		let is_pruned = self.state_db.lock().is_pruned();
+		if is_pruned && self.pruning_info().earliest_state >= number {
+			return Err(CallError::StatePruned);
+		}
+
+		let block = self.block(BlockId::Hash(hash)).ok_or(CallError::StatePruned)?;
+		let header = block.decode_header();
+		let parent = self.block_header(BlockId::Hash(*header.parent_hash()))
+			.ok_or(CallError::StatePruned)?
+			.decode();
+		let last_hashes = self.build_last_hashes(*header.parent_hash());
+		let is_epoch_begin = self.chain.read().epoch_transition(parent.number(), *header.parent_hash()).is_some();
+		let db = self.state_db.lock().boxed_clone_canon(header.parent_hash());
+
+		const PROOF: &'static str = "Transactions fetched from blockchain; blockchain transactions are valid; qed";
+		let transactions: Vec<_> = block.transactions().into_iter()
+			.map(|t| SignedTransaction::new(t).expect(PROOF))
+			.collect();
+
+		let locked_block = enact(
+			&header,
+			&transactions,
+			&block.uncles(),
+			&*self.engine,
+			true,
+			db,
+			&parent,
+			last_hashes,
+			self.factories.clone(),
+			is_epoch_begin,
+		).map_err(|e| {
+			warn!(target: "client", "Failed to re-execute block #{} ({}): {:?}", number, hash, e);
+			CallError::StateCorrupt
+		})?;
+
+		let traces: Vec<FlatTransactionTraces> = locked_block.traces().clone()
+			.unwrap_or_else(Vec::new)
+			.into_iter()
+			.map(Into::into)
+			.collect();
+
+		let mut batch = DBTransaction::new();
+		self.tracedb.read().import(&mut batch, TraceImportRequest {
+			traces: traces.into(),
+			block_hash: hash,
+			block_number: number,
+			enacted: vec![hash],
+			retracted: 0,
+		});
+		self.db.read().write(batch).expect("DB write failed.");
+		Ok(true)
+	}
+
 	/// Attempt to get a copy of a specific block's beginning state.
 	///
 	/// This will not fail if given BlockId::Latest.
@@ -973,6 +1060,48 @@ impl Client {
 	pub fn tick(&self) {
 		self.check_garbage();
 		self.check_snooze();
+		self.check_address_index();
+		self.check_trace_backfill();
+	}
+
+	/// Trace some blocks imported before tracing was enabled.
+	fn check_trace_backfill(&self) {
+		let mut trace_backfill = self.trace_backfill.lock();
+		let started = match *trace_backfill {
+			Some(cursor) if cursor > 1 => cursor,
+			_ => return,
+		};
+
+		// re-execution runs on the timer thread, so keep each tick short.
+		let deadline = Instant::now() + Duration::from_millis(TRACE_BACKFILL_MS_PER_TICK);
+		let mut cursor = started;
+		while cursor > 1 && started - cursor < TRACE_BACKFILL_BLOCKS_PER_TICK && Instant::now() < deadline {
+			if let Err(e) = self.backfill_block_traces(cursor - 1) {
+				warn!(target: "client", "Trace backfill stopped at block #{}: {}", cursor - 1, e);
+				*trace_backfill = None;
+				break;
+			}
+			cursor -= 1;
+		}
+
+		let mut batch = DBTransaction::new();
+		self.tracedb.read().set_backfill_cursor(&mut batch, cursor);
+		self.db.read().write(batch).expect("DB write failed.");
+
+		if trace_backfill.is_some() {
+			*trace_backfill = Some(cursor);
+			match cursor {
+				1 => info!(target: "client", "Trace backfill completed."),
+				_ => debug!(target: "client", "Trace backfill: {} blocks remaining", cursor - 1),
+			}
+		}
+	}
+
+	/// Index traces of some blocks imported before the address index was enabled.
+	fn check_address_index(&self) {
+		// indexing runs on the timer thread too, so it shares the time cap of the trace backfill.
+		let deadline = Instant::now() + Duration::from_millis(TRACE_BACKFILL_MS_PER_TICK);
+		self.tracedb.read().build_address_index(ADDRESS_INDEX_BLOCKS_PER_TICK, deadline);
 	}
 
 	fn check_garbage(&self) {
@@ -1119,33 +1248,69 @@ impl Client {
 			env_info: &EnvInfo,
 			engine: &E,
 			state_diff: bool,
+			pre_state: bool,
 			transaction: &SignedTransaction,
 			options: TransactOptions<T, V>,
-		) -> Result<Executed, CallError> where
+		) -> Result<Executed<V::Output>, CallError> where
 			E: Engine + ?Sized,
 			T: trace::Tracer,
 			V: trace::VMTracer,
 		{
 			let options = options.dont_check_nonce();
-			let original_state = if state_diff { Some(state.clone()) } else { None };
+			let original_state = if state_diff || pre_state { Some(state.clone()) } else { None };
+			if pre_state {
+				state.start_recording_access();
+			}
 
 			let mut ret = Executive::new(state, env_info, engine).transact_virtual(transaction, options)?;
 
 			if let Some(original) = original_state {
-				ret.state_diff = Some(state.diff_from(original).map_err(ExecutionError::from)?);
+				if pre_state {
+					let accessed = state.take_accessed();
+					ret.pre_state = Some(original.to_pod_accounts(&accessed).map_err(ExecutionError::from)?);
+				}
+				if state_diff {
+					ret.state_diff = Some(state.diff_from(original).map_err(ExecutionError::from)?);
+				}
 			}
 			Ok(ret)
 		}
 
 		let state_diff = analytics.state_diffing;
+		let pre_state = analytics.pre_state_dumping;
 		let engine = &*self.engine;
 
 		match (analytics.transaction_tracing, analytics.vm_tracing) {
-			(true, true) => call(state, env_info, engine, state_diff, t, TransactOptions::with_tracing_and_vm_tracing()),
-			(true, false) => call(state, env_info, engine, state_diff, t, TransactOptions::with_tracing()),
-			(false, true) => call(state, env_info, engine, state_diff, t, TransactOptions::with_vm_tracing()),
-			(false, false) => call(state, env_info, engine, state_diff, t, TransactOptions::with_no_tracing()),
+			(true, true) => call(state, env_info, engine, state_diff, pre_state, t, TransactOptions::with_tracing_and_vm_tracing()),
+			(true, false) => call(state, env_info, engine, state_diff, pre_state, t, TransactOptions::with_tracing()),
+			(false, true) => call(state, env_info, engine, state_diff, pre_state, t, TransactOptions::with_vm_tracing()),
+			(false, false) => call(state, env_info, engine, state_diff, pre_state, t, TransactOptions::with_no_tracing()),
+		}
+	}
+
+	// state right before the given transaction was executed, with the transaction itself.
+	fn state_before_transaction(&self, id: TransactionId) -> Result<(EnvInfo, State<StateDB>, SignedTransaction), CallError> {
+		let address = self.transaction_address(id).ok_or(CallError::TransactionNotFound)?;
+		let mut env_info = self.env_info(BlockId::Hash(address.block_hash)).ok_or(CallError::StatePruned)?;
+		let body = self.block_body(BlockId::Hash(address.block_hash)).ok_or(CallError::StatePruned)?;
+		let mut state = self.state_at_beginning(BlockId::Hash(address.block_hash)).ok_or(CallError::StatePruned)?;
+		let mut txs = body.transactions();
+
+		if address.index >= txs.len() {
+			return Err(CallError::TransactionNotFound);
 		}
+
+		const PROOF: &'static str = "Transactions fetched from blockchain; blockchain transactions are valid; qed";
+		let rest = txs.split_off(address.index);
+		for t in txs {
+			let t = SignedTransaction::new(t).expect(PROOF);
+			let x = Executive::new(&mut state, &env_info, &*self.engine).transact(&t, TransactOptions::with_no_tracing())?;
+			env_info.gas_used = env_info.gas_used + x.gas_used;
+		}
+		let first = rest.into_iter().next().expect("We split off < `address.index`; Length is checked earlier; qed");
+		let t = SignedTransaction::new(first).expect(PROOF);
+
+		Ok((env_info, state, t))
 	}
 }
 
@@ -1171,12 +1336,13 @@ impl snapshot::DatabaseRestore for Client {
 }
 
 impl BlockChainClient for Client {
-	fn call(&self, transaction: &SignedTransaction, analytics: CallAnalytics, block: BlockId) -> Result<Executed, CallError> {
+	fn call(&self, transaction: &SignedTransaction, analytics: CallAnalytics, block: BlockId, state_override: &StateOverride) -> Result<Executed, CallError> {
 		let mut env_info = self.env_info(block).ok_or(CallError::StatePruned)?;
 		env_info.gas_limit = U256::max_value();
 
 		// that's just a copy of the state.
 		let mut state = self.state_at(block).ok_or(CallError::StatePruned)?;
+		state.apply_overrides(state_override).map_err(|_| CallError::StateCorrupt)?;
 
 		self.do_virtual_call(&env_info, &mut state, transaction, analytics)
 	}
@@ -1198,7 +1364,7 @@ impl BlockChainClient for Client {
 		Ok(results)
 	}
 
-	fn estimate_gas(&self, t: &SignedTransaction, block: BlockId) -> Result<U256, CallError> {
+	fn estimate_gas(&self, t: &SignedTransaction, block: BlockId, state_override: &StateOverride) -> Result<U256, CallError> {
 		const UPPER_CEILING: u64 = 1_000_000_000_000u64;
 		let (mut upper, env_info)  = {
 			let mut env_info = self.env_info(block).ok_or(CallError::StatePruned)?;
@@ -1208,28 +1374,33 @@ impl BlockChainClient for Client {
 		};
 
 		// that's just a copy of the state.
-		let original_state = self.state_at(block).ok_or(CallError::StatePruned)?;
+		let mut original_state = self.state_at(block).ok_or(CallError::StatePruned)?;
+		original_state.apply_overrides(state_override).map_err(|_| CallError::StateCorrupt)?;
 		let sender = t.sender();
 		let options = || TransactOptions::with_tracing();
 
-		let cond = |gas| {
+		let exec = |gas| {
 			let mut tx = t.as_unsigned().clone();
 			tx.gas = gas;
 			let tx = tx.fake_sign(sender);
 
 			let mut state = original_state.clone();
-			Ok(Executive::new(&mut state, &env_info, &*self.engine)
+			Executive::new(&mut state, &env_info, &*self.engine)
 				.transact_virtual(&tx, options())
-				.map(|r| r.exception.is_none())
-				.unwrap_or(false))
+				.ok()
 		};
 
+		let cond = |gas| Ok(exec(gas).map(|r| r.exception.is_none()).unwrap_or(false));
+
 		if !cond(upper)? {
 			// impossible at block gas limit - try `UPPER_CEILING` instead.
 			// TODO: consider raising limit by powers of two.
 			upper = UPPER_CEILING.into();
 			if !cond(upper)? {
 				trace!(target: "estimate_gas", "estimate_gas failed with {}", upper);
+				if let Some(Executed { exception: Some(vm::Error::Reverted), output, .. }) = exec(upper) {
+					return Err(CallError::Reverted(output))
+				}
 				let err = ExecutionError::Internal(format!("Requires higher than upper limit of {}", upper));
 				return Err(err.into())
 			}
@@ -1265,27 +1436,28 @@ impl BlockChainClient for Client {
 	}
 
 	fn replay(&self, id: TransactionId, analytics: CallAnalytics) -> Result<Executed, CallError> {
-		let address = self.transaction_address(id).ok_or(CallError::TransactionNotFound)?;
-		let mut env_info = self.env_info(BlockId::Hash(address.block_hash)).ok_or(CallError::StatePruned)?;
-		let body = self.block_body(BlockId::Hash(address.block_hash)).ok_or(CallError::StatePruned)?;
-		let mut state = self.state_at_beginning(BlockId::Hash(address.block_hash)).ok_or(CallError::StatePruned)?;
-		let mut txs = body.transactions();
+		let (env_info, mut state, t) = self.state_before_transaction(id)?;
+		self.do_virtual_call(&env_info, &mut state, &t, analytics)
+	}
 
-		if address.index >= txs.len() {
-			return Err(CallError::TransactionNotFound);
-		}
+	fn debug_trace_transaction(&self, id: TransactionId, options: StructLogOptions) -> Result<Executed<Vec<StructLog>>, CallError> {
+		let (env_info, mut state, t) = self.state_before_transaction(id)?;
+		let options = TransactOptions::new(trace::NoopTracer, trace::StructLogTracer::toplevel(options)).dont_check_nonce();
+		Ok(Executive::new(&mut state, &env_info, &*self.engine).transact_virtual(&t, options)?)
+	}
+
+	fn replay_block_transactions(&self, block: BlockId, analytics: CallAnalytics) -> Result<Vec<(H256, Executed)>, CallError> {
+		let mut env_info = self.env_info(block).ok_or(CallError::StatePruned)?;
+		let body = self.block_body(block).ok_or(CallError::StatePruned)?;
+		let mut state = self.state_at_beginning(block).ok_or(CallError::StatePruned)?;
 
 		const PROOF: &'static str = "Transactions fetched from blockchain; blockchain transactions are valid; qed";
-		let rest = txs.split_off(address.index);
-		for t in txs {
+		body.transactions().into_iter().map(|t| {
 			let t = SignedTransaction::new(t).expect(PROOF);
-			let x = Executive::new(&mut state, &env_info, &*self.engine).transact(&t, TransactOptions::with_no_tracing())?;
-			env_info.gas_used = env_info.gas_used + x.gas_used;
-		}
-		let first = rest.into_iter().next().expect("We split off < `address.index`; Length is checked earlier; qed");
-		let t = SignedTransaction::new(first).expect(PROOF);
-
-		self.do_virtual_call(&env_info, &mut state, &t, analytics)
+			let executed = self.do_virtual_call(&env_info, &mut state, &t, analytics)?;
+			env_info.gas_used = executed.cumulative_gas_used;
+			Ok((t.hash(), executed))
+		}).collect()
 	}
 
 	fn mode(&self) -> IpcMode {
@@ -1699,6 +1871,11 @@ impl BlockChainClient for Client {
 			.and_then(|number| self.tracedb.read().block_traces(number))
 	}
 
+	fn trace_backfill_cursor(&self) -> Option<BlockNumber> {
+		// the stored cursor stays behind when the backfill stops on an error
+		self.tracedb.read().backfill_cursor().and_then(|cursor| if cursor > 1 { Some(cursor) } else { None })
+	}
+
 	fn last_hashes(&self) -> LastHashes {
 		(*self.build_last_hashes(self.chain.read().best_block_hash())).clone()
 	}
@@ -1760,7 +1937,7 @@ impl BlockChainClient for Client {
 	fn call_contract(&self, block_id: BlockId, address: Address, data: Bytes) -> Result<Bytes, String> {
 		let transaction = self.contract_call_tx(block_id, address, data);
 
-		self.call(&transaction, Default::default(), block_id)
+		self.call(&transaction, Default::default(), block_id, &Default::default())
 			.map_err(|e| format!("{:?}", e))
 			.map(|executed| {
 				executed.output
@@ -1989,7 +2166,7 @@ impl Drop for Client {
 
 /// Returns `LocalizedReceipt` given `LocalizedTransaction`
 /// and a vector of receipts from given block up to transaction index.
-fn transaction_receipt(engine: &Engine, mut tx: LocalizedTransaction, mut receipts: Vec<Receipt>) -> LocalizedReceipt {
+pub fn transaction_receipt(engine: &Engine, mut tx: LocalizedTransaction, mut receipts: Vec<Receipt>) -> LocalizedReceipt {
 	assert_eq!(receipts.len(), tx.transaction_index + 1, "All previous receipts are provided.");
 
 	let sender = tx.sender();
@@ -69,6 +69,7 @@ lazy_static! {
 	pub static ref EIP150: spec::Spec = ethereum::new_eip150_test();
 	pub static ref EIP161: spec::Spec = ethereum::new_eip161_test();
 	pub static ref _METROPOLIS: spec::Spec = ethereum::new_metropolis_test();
+	pub static ref CONSTANTINOPLE: spec::Spec = ethereum::new_constantinople_test();
 }
 
 /// Simplified, single-block EVM test client.
@@ -85,7 +86,8 @@ impl<'a> EvmTestClient<'a> {
 			ForkSpec::Homestead => Some(&*HOMESTEAD),
 			ForkSpec::EIP150 => Some(&*EIP150),
 			ForkSpec::EIP158 => Some(&*EIP161),
-			ForkSpec::Metropolis | ForkSpec::Byzantium | ForkSpec::Constantinople => None,
+			ForkSpec::Constantinople => Some(&*CONSTANTINOPLE),
+			ForkSpec::Metropolis | ForkSpec::Byzantium => None,
 		}
 	}
 
@@ -174,7 +176,7 @@ impl<'a> EvmTestClient<'a> {
 		let mut tracer = trace::NoopTracer;
 		let mut output = vec![];
 		let mut executive = executive::Executive::new(&mut self.state, &info, &*self.spec.engine);
-		let (gas_left, _) = executive.call(
+		let evm::FinalizationResult { gas_left, .. } = executive.call(
 			params,
 			&mut substate,
 			util::BytesRef::Flexible(&mut output),
@@ -187,7 +189,7 @@ impl<'a> EvmTestClient<'a> {
 
 	/// Executes a SignedTransaction within context of the provided state and `EnvInfo`.
 	/// Returns the state root, gas left and the output.
-	pub fn transact<T: trace::VMTracer>(
+	pub fn transact<T: trace::VMTracer<Output = trace::VMTrace>>(
 		&mut self,
 		env_info: &client::EnvInfo,
 		transaction: transaction::SignedTransaction,
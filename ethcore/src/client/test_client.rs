@@ -47,12 +47,13 @@ use spec::Spec;
 use types::basic_account::BasicAccount;
 use types::mode::Mode;
 use types::pruning_info::PruningInfo;
+use types::state_override::StateOverride;
 
 use verification::queue::QueueInfo;
 use block::{OpenBlock, SealedBlock, ClosedBlock};
 use executive::Executed;
 use error::CallError;
-use trace::LocalizedTrace;
+use trace::{LocalizedTrace, StructLog, StructLogOptions};
 use state_db::StateDB;
 use encoded;
 
@@ -100,6 +101,8 @@ pub struct TestBlockChainClient {
 	pub first_block: RwLock<Option<(H256, u64)>>,
 	/// Traces to return
 	pub traces: RwLock<Option<Vec<LocalizedTrace>>>,
+	/// Trace backfill cursor to return.
+	pub trace_backfill_cursor: RwLock<Option<BlockNumber>>,
 	/// Pruning history size to report.
 	pub history: RwLock<Option<u64>>,
 }
@@ -167,6 +170,7 @@ impl TestBlockChainClient {
 			ancient_block: RwLock::new(None),
 			first_block: RwLock::new(None),
 			traces: RwLock::new(None),
+			trace_backfill_cursor: RwLock::new(None),
 			history: RwLock::new(None),
 		};
 
@@ -402,19 +406,19 @@ impl MiningBlockChainClient for TestBlockChainClient {
 }
 
 impl BlockChainClient for TestBlockChainClient {
-	fn call(&self, _t: &SignedTransaction, _analytics: CallAnalytics, _block: BlockId) -> Result<Executed, CallError> {
+	fn call(&self, _t: &SignedTransaction, _analytics: CallAnalytics, _block: BlockId, _state_override: &StateOverride) -> Result<Executed, CallError> {
 		self.execution_result.read().clone().unwrap()
 	}
 
 	fn call_many(&self, txs: &[(SignedTransaction, CallAnalytics)], block: BlockId) -> Result<Vec<Executed>, CallError> {
 		let mut res = Vec::with_capacity(txs.len());
 		for &(ref tx, analytics) in txs {
-			res.push(self.call(tx, analytics, block)?);
+			res.push(self.call(tx, analytics, block, &Default::default())?);
 		}
 		Ok(res)
 	}
 
-	fn estimate_gas(&self, _t: &SignedTransaction, _block: BlockId) -> Result<U256, CallError> {
+	fn estimate_gas(&self, _t: &SignedTransaction, _block: BlockId, _state_override: &StateOverride) -> Result<U256, CallError> {
 		Ok(21000.into())
 	}
 
@@ -422,6 +426,32 @@ impl BlockChainClient for TestBlockChainClient {
 		self.execution_result.read().clone().unwrap()
 	}
 
+	fn replay_block_transactions(&self, block: BlockId, _analytics: CallAnalytics) -> Result<Vec<(H256, Executed)>, CallError> {
+		let body = self.block_body(block).ok_or(CallError::StatePruned)?;
+		body.transactions().into_iter().map(|t| {
+			let executed = self.execution_result.read().clone().unwrap()?;
+			Ok((t.hash(), executed))
+		}).collect()
+	}
+
+	fn debug_trace_transaction(&self, _id: TransactionId, _options: StructLogOptions) -> Result<Executed<Vec<StructLog>>, CallError> {
+		let executed = self.execution_result.read().clone().unwrap()?;
+		Ok(Executed {
+			exception: executed.exception,
+			gas: executed.gas,
+			gas_used: executed.gas_used,
+			refunded: executed.refunded,
+			cumulative_gas_used: executed.cumulative_gas_used,
+			logs: executed.logs,
+			contracts_created: executed.contracts_created,
+			output: executed.output,
+			trace: executed.trace,
+			vm_trace: Some(vec![]),
+			state_diff: executed.state_diff,
+			pre_state: executed.pre_state,
+		})
+	}
+
 	fn block_total_difficulty(&self, _id: BlockId) -> Option<U256> {
 		Some(U256::zero())
 	}
@@ -719,6 +749,10 @@ impl BlockChainClient for TestBlockChainClient {
 		self.traces.read().clone()
 	}
 
+	fn trace_backfill_cursor(&self) -> Option<BlockNumber> {
+		self.trace_backfill_cursor.read().clone()
+	}
+
 	fn queue_transactions(&self, transactions: Vec<Bytes>, _peer_id: usize) {
 		// import right here
 		let txs = transactions.into_iter().filter_map(|bytes| UntrustedRlp::new(&bytes).as_val().ok()).collect();
@@ -19,13 +19,13 @@
 use std::sync::atomic::{AtomicUsize, AtomicBool, Ordering as AtomicOrdering};
 use std::sync::{Weak, Arc};
 use std::time::{UNIX_EPOCH, Duration};
-use std::collections::{BTreeMap, HashSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashSet, HashMap};
 use std::cmp;
 
 use account_provider::AccountProvider;
 use block::*;
 use builtin::Builtin;
-use client::{Client, EngineClient};
+use client::{Client, EngineClient, BlockChainClient, BlockId};
 use engines::{Call, Engine, Seal, EngineError, ConstructedVerifier};
 use error::{Error, TransactionError, BlockError};
 use ethjson;
@@ -34,19 +34,24 @@ use spec::CommonParams;
 use state::CleanupMode;
 use transaction::UnverifiedTransaction;
 
+use super::block_reward::{self, BlockRewardContract, RewardKind};
 use super::signer::EngineSigner;
 use super::validator_set::{ValidatorSet, SimpleList, new_validator_set};
 
 use self::finality::RollingFinality;
 
-use ethkey::{verify_address, Signature};
+use ethkey::{self, verify_address, Signature};
 use io::{IoContext, IoHandler, TimerToken, IoService};
 use itertools::{self, Itertools};
-use rlp::{UntrustedRlp, encode};
+use rlp::{UntrustedRlp, RlpStream, Encodable, Decodable, DecoderError, encode};
 use util::*;
 
 mod finality;
 
+/// Maximum number of empty step messages included in a single block seal.
+/// Once reached, an empty block is sealed instead of broadcasting a new empty step.
+const MAX_EMPTY_STEPS: usize = 128;
+
 /// `AuthorityRound` params.
 pub struct AuthorityRoundParams {
 	/// Time to wait before next block or authority switching.
@@ -61,6 +66,10 @@ pub struct AuthorityRoundParams {
 	pub validate_step_transition: u64,
 	/// Immediate transitions.
 	pub immediate_transitions: bool,
+	/// Block reward contract.
+	pub block_reward_contract: Option<BlockRewardContract>,
+	/// Number of first block where empty step messages are used instead of empty blocks.
+	pub empty_steps_transition: u64,
 }
 
 impl From<ethjson::spec::AuthorityRoundParams> for AuthorityRoundParams {
@@ -72,6 +81,9 @@ impl From<ethjson::spec::AuthorityRoundParams> for AuthorityRoundParams {
 			validate_score_transition: p.validate_score_transition.map_or(0, Into::into),
 			validate_step_transition: p.validate_step_transition.map_or(0, Into::into),
 			immediate_transitions: p.immediate_transitions.unwrap_or(false),
+			block_reward_contract: p.block_reward_contract_address.map(|address| BlockRewardContract::new(address.into())),
+			// the genesis block never contains empty steps.
+			empty_steps_transition: p.empty_steps_transition.map_or(u64::max_value(), |n| cmp::max(n.into(), 1)),
 		}
 	}
 }
@@ -215,19 +227,23 @@ pub struct AuthorityRound {
 	validate_step_transition: u64,
 	epoch_manager: Mutex<EpochManager>,
 	immediate_transitions: bool,
+	block_reward_contract: Option<BlockRewardContract>,
+	empty_steps: Mutex<BTreeSet<EmptyStep>>,
+	empty_steps_transition: u64,
 }
 
 // header-chain validator.
 struct EpochVerifier {
 	step: Arc<Step>,
 	subchain_validators: SimpleList,
+	empty_steps_transition: u64,
 }
 
 impl super::EpochVerifier for EpochVerifier {
 	fn verify_light(&self, header: &Header) -> Result<(), Error> {
 		// always check the seal since it's fast.
 		// nothing heavier to do.
-		verify_external(header, &self.subchain_validators, &*self.step, |_| {})
+		verify_external(header, &self.subchain_validators, &*self.step, self.empty_steps_transition, |_| {})
 	}
 
 	fn check_finality_proof(&self, proof: &[u8]) -> Option<Vec<H256>> {
@@ -251,10 +267,11 @@ impl super::EpochVerifier for EpochVerifier {
 			// without panic.
 			//
 			// `verify_external` checks that signature is correct and author == signer.
-			if header.seal().len() != 2 { return None }
-			otry!(verify_external(header, &self.subchain_validators, &*self.step, |_| {}).ok());
+			if header.seal().len() != header_expected_seal_fields(header, self.empty_steps_transition) { return None }
+			otry!(verify_external(header, &self.subchain_validators, &*self.step, self.empty_steps_transition, |_| {}).ok());
 
-			let newly_finalized = otry!(finality_checker.push_hash(header.hash(), header.author().clone()).ok());
+			let signers = otry!(header_signers(header, self.empty_steps_transition).ok());
+			let newly_finalized = otry!(finality_checker.push_hash(header.hash(), signers).ok());
 			finalized.extend(newly_finalized);
 		}
 
@@ -272,6 +289,122 @@ enum Report {
 	Benign(Address, BlockNumber),
 }
 
+/// A message broadcast by authorities when it's their turn to seal a block but there are no
+/// transactions. Other authorities accumulate these messages and later include them in the seal
+/// of the next block as proof.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct EmptyStep {
+	signature: H520,
+	step: usize,
+	parent_hash: H256,
+}
+
+impl PartialOrd for EmptyStep {
+	fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Ord for EmptyStep {
+	fn cmp(&self, other: &Self) -> cmp::Ordering {
+		self.step.cmp(&other.step)
+			.then_with(|| self.parent_hash.cmp(&other.parent_hash))
+			.then_with(|| self.signature.cmp(&other.signature))
+	}
+}
+
+impl EmptyStep {
+	fn from_sealed(sealed_empty_step: SealedEmptyStep, parent_hash: &H256) -> EmptyStep {
+		EmptyStep {
+			signature: sealed_empty_step.signature,
+			step: sealed_empty_step.step,
+			parent_hash: parent_hash.clone(),
+		}
+	}
+
+	fn verify(&self, validators: &ValidatorSet) -> Result<bool, Error> {
+		let message = empty_step_rlp(self.step, &self.parent_hash).sha3();
+		let correct_proposer = step_proposer(validators, &self.parent_hash, self.step);
+
+		verify_address(&correct_proposer, &self.signature.into(), &message).map_err(Into::into)
+	}
+
+	fn author(&self) -> Result<Address, Error> {
+		let message = empty_step_rlp(self.step, &self.parent_hash).sha3();
+		let public = ethkey::recover(&self.signature.into(), &message)?;
+		Ok(ethkey::public_to_address(&public))
+	}
+
+	fn sealed(&self) -> SealedEmptyStep {
+		SealedEmptyStep {
+			signature: self.signature,
+			step: self.step,
+		}
+	}
+}
+
+/// (signature, (step, parent_hash))
+impl Encodable for EmptyStep {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		let empty_step_rlp = empty_step_rlp(self.step, &self.parent_hash);
+		s.begin_list(2)
+			.append(&self.signature)
+			.append_raw(&empty_step_rlp, 1);
+	}
+}
+
+impl Decodable for EmptyStep {
+	fn decode(rlp: &UntrustedRlp) -> Result<Self, DecoderError> {
+		let empty_step_rlp = rlp.at(1)?;
+		Ok(EmptyStep {
+			signature: rlp.val_at(0)?,
+			step: empty_step_rlp.val_at(0)?,
+			parent_hash: empty_step_rlp.val_at(1)?,
+		})
+	}
+}
+
+fn empty_step_rlp(step: usize, parent_hash: &H256) -> Bytes {
+	let mut s = RlpStream::new_list(2);
+	s.append(&step).append(parent_hash);
+	s.out()
+}
+
+/// An empty step message as included in a block seal. The parent hash is left out since it
+/// is the parent hash of the block the seal belongs to.
+struct SealedEmptyStep {
+	signature: H520,
+	step: usize,
+}
+
+/// (signature, step)
+impl Encodable for SealedEmptyStep {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		s.begin_list(2)
+			.append(&self.signature)
+			.append(&self.step);
+	}
+}
+
+impl Decodable for SealedEmptyStep {
+	fn decode(rlp: &UntrustedRlp) -> Result<Self, DecoderError> {
+		Ok(SealedEmptyStep {
+			signature: rlp.val_at(0)?,
+			step: rlp.val_at(1)?,
+		})
+	}
+}
+
+// Chain scoring: total weight is sqrt(U256::max_value())*height - step, with every
+// included empty step adding to the weight.
+fn calculate_score(parent_step: usize, current_step: usize, current_empty_steps: usize) -> U256 {
+	U256::from(U128::max_value()) + parent_step.into() - current_step.into() + current_empty_steps.into()
+}
+
+fn header_expected_seal_fields(header: &Header, empty_steps_transition: u64) -> usize {
+	if header.number() >= empty_steps_transition { 3 } else { 2 }
+}
+
 fn header_step(header: &Header) -> Result<usize, ::rlp::DecoderError> {
 	UntrustedRlp::new(&header.seal().get(0).expect("was either checked with verify_block_basic or is genesis; has 2 fields; qed (Make sure the spec file has a correct genesis seal)")).as_val()
 }
@@ -280,6 +413,41 @@ fn header_signature(header: &Header) -> Result<Signature, ::rlp::DecoderError> {
 	UntrustedRlp::new(&header.seal().get(1).expect("was checked with verify_block_basic; has 2 fields; qed")).as_val::<H520>().map(Into::into)
 }
 
+fn header_empty_steps_raw(header: &Header) -> &[u8] {
+	header.seal().get(2).expect("was checked with verify_block_basic; has 3 fields; qed")
+}
+
+fn header_empty_steps(header: &Header) -> Result<Vec<EmptyStep>, ::rlp::DecoderError> {
+	let empty_steps = UntrustedRlp::new(header_empty_steps_raw(header)).as_list::<SealedEmptyStep>()?;
+	Ok(empty_steps.into_iter().map(|s| EmptyStep::from_sealed(s, header.parent_hash())).collect())
+}
+
+// the hash signed by the block author, which also covers the included empty steps.
+fn header_seal_hash(header: &Header, empty_steps_rlp: Option<&[u8]>) -> H256 {
+	match empty_steps_rlp {
+		Some(empty_steps_rlp) => {
+			let mut message = header.bare_hash().to_vec();
+			message.extend_from_slice(empty_steps_rlp);
+			message.sha3()
+		},
+		None => header.bare_hash(),
+	}
+}
+
+// gets the block author along with the distinct signers of any included empty steps.
+fn header_signers(header: &Header, empty_steps_transition: u64) -> Result<Vec<Address>, Error> {
+	let mut signers = vec![*header.author()];
+	if header.number() >= empty_steps_transition {
+		let empty_step_signers = header_empty_steps(header)?
+			.iter()
+			.map(EmptyStep::author)
+			.collect::<Result<HashSet<_>, _>>()?;
+
+		signers.extend(empty_step_signers.into_iter().filter(|s| s != header.author()));
+	}
+	Ok(signers)
+}
+
 fn step_proposer(validators: &ValidatorSet, bh: &H256, step: usize) -> Address {
 	let proposer = validators.get(bh, step);
 	trace!(target: "engine", "Fetched proposer for step {}: {}", step, proposer);
@@ -290,7 +458,7 @@ fn is_step_proposer(validators: &ValidatorSet, bh: &H256, step: usize, address:
 	step_proposer(validators, bh, step) == *address
 }
 
-fn verify_external<F: Fn(Report)>(header: &Header, validators: &ValidatorSet, step: &Step, report: F)
+fn verify_external<F: Fn(Report)>(header: &Header, validators: &ValidatorSet, step: &Step, empty_steps_transition: u64, report: F)
 	-> Result<(), Error>
 {
 	let header_step = header_step(header)?;
@@ -301,10 +469,16 @@ fn verify_external<F: Fn(Report)>(header: &Header, validators: &ValidatorSet, st
 		report(Report::Benign(*header.author(), header.number()));
 		Err(BlockError::InvalidSeal)?
 	} else {
+		let empty_steps_rlp = if header.number() >= empty_steps_transition {
+			Some(header_empty_steps_raw(header))
+		} else {
+			None
+		};
+
 		let proposer_signature = header_signature(header)?;
 		let correct_proposer = validators.get(header.parent_hash(), header_step);
 		let is_invalid_proposer = *header.author() != correct_proposer ||
-			!verify_address(&correct_proposer, &proposer_signature, &header.bare_hash())?;
+			!verify_address(&correct_proposer, &proposer_signature, &header_seal_hash(header, empty_steps_rlp))?;
 
 		if is_invalid_proposer {
 			trace!(target: "engine", "verify_block_external: bad proposer for step: {}", header_step);
@@ -363,6 +537,9 @@ impl AuthorityRound {
 				validate_step_transition: our_params.validate_step_transition,
 				epoch_manager: Mutex::new(EpochManager::blank()),
 				immediate_transitions: our_params.immediate_transitions,
+				block_reward_contract: our_params.block_reward_contract,
+				empty_steps: Mutex::new(BTreeSet::new()),
+				empty_steps_transition: our_params.empty_steps_transition,
 			});
 
 		// Do not initialize timeouts for tests.
@@ -372,6 +549,54 @@ impl AuthorityRound {
 		}
 		Ok(engine)
 	}
+
+	// fetch empty steps for the given parent hash with a step in the range `(from_step, to_step)`.
+	fn empty_steps(&self, from_step: usize, to_step: usize, parent_hash: H256) -> Vec<EmptyStep> {
+		self.empty_steps.lock()
+			.iter()
+			.filter(|e| e.step > from_step && e.step < to_step && e.parent_hash == parent_hash)
+			.take(MAX_EMPTY_STEPS)
+			.cloned()
+			.collect()
+	}
+
+	// drop all accumulated empty steps up to and including the given step.
+	fn clear_empty_steps(&self, step: usize) {
+		self.empty_steps.lock().retain(|e| e.step > step);
+	}
+
+	// returns whether the empty step wasn't known before.
+	fn handle_empty_step_message(&self, empty_step: EmptyStep) -> bool {
+		self.empty_steps.lock().insert(empty_step)
+	}
+
+	fn generate_empty_step(&self, parent_hash: &H256) {
+		let step = self.step.load();
+		let empty_step_rlp = empty_step_rlp(step, parent_hash);
+
+		if let Ok(signature) = self.sign(empty_step_rlp.sha3()).map(H520::from) {
+			let empty_step = EmptyStep {
+				signature: signature,
+				step: step,
+				parent_hash: *parent_hash,
+			};
+
+			trace!(target: "engine", "generate_empty_step: generated empty step at step={}, parent_hash={}", step, parent_hash);
+			let message = ::rlp::encode(&empty_step).into_vec();
+			self.handle_empty_step_message(empty_step);
+			self.broadcast_message(message);
+		} else {
+			warn!(target: "engine", "generate_empty_step: FAIL: accounts secret key unavailable");
+		}
+	}
+
+	fn broadcast_message(&self, message: Bytes) {
+		if let Some(ref weak) = *self.client.read() {
+			if let Some(c) = weak.upgrade() {
+				c.broadcast_consensus_message(message);
+			}
+		}
+	}
 }
 
 fn unix_now() -> Duration {
@@ -410,8 +635,11 @@ impl Engine for AuthorityRound {
 
 	fn version(&self) -> SemanticVersion { SemanticVersion::new(1, 0, 0) }
 
-	/// Two fields - consensus step and the corresponding proposer signature.
-	fn seal_fields(&self) -> usize { 2 }
+	/// Two fields - consensus step and the corresponding proposer signature, and a third
+	/// one holding the included empty steps after the empty steps transition.
+	fn seal_fields(&self, header: &Header) -> usize {
+		header_expected_seal_fields(header, self.empty_steps_transition)
+	}
 
 	fn params(&self) -> &CommonParams { &self.params }
 
@@ -433,16 +661,33 @@ impl Engine for AuthorityRound {
 
 	/// Additional engine-specific information for the user/developer concerning `header`.
 	fn extra_info(&self, header: &Header) -> BTreeMap<String, String> {
-		map![
+		let mut info = map![
 			"step".into() => header_step(header).as_ref().map(ToString::to_string).unwrap_or("".into()),
 			"signature".into() => header_signature(header).as_ref().map(ToString::to_string).unwrap_or("".into())
-		]
+		];
+
+		if header.number() >= self.empty_steps_transition {
+			let empty_steps = header_empty_steps(header)
+				.map(|empty_steps| empty_steps.iter().map(|e| e.step.to_string()).collect::<Vec<_>>().join(","))
+				.unwrap_or("".into());
+
+			info.insert("emptySteps".into(), empty_steps);
+		}
+
+		info
 	}
 
 	fn populate_from_parent(&self, header: &mut Header, parent: &Header, gas_floor_target: U256, _gas_ceil_target: U256) {
-		// Chain scoring: total weight is sqrt(U256::max_value())*height - step
-		let new_difficulty = U256::from(U128::max_value()) + header_step(parent).expect("Header has been verified; qed").into() - self.step.load().into();
-		header.set_difficulty(new_difficulty);
+		let parent_step = header_step(parent).expect("Header has been verified; qed");
+		let current_step = self.step.load();
+
+		let current_empty_steps_len = if header.number() >= self.empty_steps_transition {
+			self.empty_steps(parent_step, current_step, parent.hash()).len()
+		} else {
+			0
+		};
+
+		header.set_difficulty(calculate_score(parent_step, current_step, current_empty_steps_len));
 		header.set_gas_limit({
 			let gas_limit = parent.gas_limit().clone();
 			let bound_divisor = self.params().gas_limit_bound_divisor;
@@ -462,14 +707,31 @@ impl Engine for AuthorityRound {
 	///
 	/// This operation is synchronous and may (quite reasonably) not be available, in which case
 	/// `Seal::None` will be returned.
-	fn generate_seal(&self, block: &ExecutedBlock) -> Seal {
+	fn generate_seal(&self, block: &ExecutedBlock, parent: &Header) -> Seal {
 		// first check to avoid generating signature most of the time
 		// (but there's still a race to the `compare_and_swap`)
 		if !self.can_propose.load(AtomicOrdering::SeqCst) { return Seal::None; }
 
 		let header = block.header();
+		let parent_step = header_step(parent).expect("Header has been verified; qed");
 		let step = self.step.load();
 
+		// filter messages from old and future steps and different parents
+		let empty_steps = if header.number() >= self.empty_steps_transition {
+			let empty_steps = self.empty_steps(parent_step, step, *header.parent_hash());
+
+			// the block score was computed from the empty steps known at the time the block was opened.
+			let expected_diff = calculate_score(parent_step, step, empty_steps.len());
+			if header.difficulty() != &expected_diff {
+				debug!(target: "engine", "generate_seal: block difficulty doesn't match the known empty steps, not sealing.");
+				return Seal::None;
+			}
+
+			empty_steps
+		} else {
+			Vec::new()
+		};
+
 		// fetch correct validator set for current epoch, taking into account
 		// finality of previous transitions.
 		let active_set;
@@ -496,12 +758,45 @@ impl Engine for AuthorityRound {
 		};
 
 		if is_step_proposer(validators, header.parent_hash(), step, header.author()) {
-			if let Ok(signature) = self.sign(header.bare_hash()) {
+			// if there are no transactions to include in the block, we don't seal and instead broadcast a signed
+			// `EmptyStep(step, parent_hash)` message. once the maximum number of empty steps is reached
+			// we proceed with the seal.
+			if header.number() >= self.empty_steps_transition &&
+				block.transactions().is_empty() &&
+				empty_steps.len() < MAX_EMPTY_STEPS {
+
+				if self.can_propose.compare_and_swap(true, false, AtomicOrdering::SeqCst) {
+					self.generate_empty_step(header.parent_hash());
+				}
+
+				return Seal::None;
+			}
+
+			let empty_steps_rlp = if header.number() >= self.empty_steps_transition {
+				let empty_steps: Vec<_> = empty_steps.iter().map(EmptyStep::sealed).collect();
+				Some(::rlp::encode_list(&empty_steps).into_vec())
+			} else {
+				None
+			};
+
+			if let Ok(signature) = self.sign(header_seal_hash(header, empty_steps_rlp.as_ref().map(|e| &e[..]))) {
 				trace!(target: "engine", "generate_seal: Issuing a block for step {}.", step);
 
 				// only issue the seal if we were the first to reach the compare_and_swap.
 				if self.can_propose.compare_and_swap(true, false, AtomicOrdering::SeqCst) {
-					return Seal::Regular(vec![encode(&step).into_vec(), encode(&(&H520::from(signature) as &[u8])).into_vec()]);
+					// the accumulated empty steps up to this step are no longer needed.
+					self.clear_empty_steps(step);
+
+					let mut fields = vec![
+						encode(&step).into_vec(),
+						encode(&(&H520::from(signature) as &[u8])).into_vec(),
+					];
+
+					if let Some(empty_steps_rlp) = empty_steps_rlp {
+						fields.push(empty_steps_rlp);
+					}
+
+					return Seal::Regular(fields);
 				}
 			} else {
 				warn!(target: "engine", "generate_seal: FAIL: Accounts secret key unavailable.");
@@ -546,15 +841,89 @@ impl Engine for AuthorityRound {
 		self.validators.on_epoch_begin(first, &header, &mut call)
 	}
 
+	fn handle_message(&self, rlp: &[u8]) -> Result<(), Error> {
+		if self.empty_steps_transition == u64::max_value() {
+			return Err(EngineError::UnexpectedMessage.into());
+		}
+
+		let empty_step: EmptyStep = UntrustedRlp::new(rlp).as_val()?;
+
+		if !empty_step.verify(&*self.validators)? {
+			return Err(EngineError::NotAuthorized(empty_step.author()?).into());
+		}
+
+		if self.step.is_future(empty_step.step) {
+			trace!(target: "engine", "handle_message: empty step from the future: {}", empty_step.step);
+			return Err(EngineError::InsufficientProof(format!("empty step from the future: {}", empty_step.step)).into());
+		}
+
+		trace!(target: "engine", "handle_message: received empty step message {:?}", empty_step);
+		if self.handle_empty_step_message(empty_step) {
+			self.broadcast_message(rlp.to_vec());
+		}
+
+		Ok(())
+	}
+
 	/// Apply the block reward on finalisation of the block.
 	fn on_close_block(&self, block: &mut ExecutedBlock) -> Result<(), Error> {
-		let fields = block.fields_mut();
-		// Bestow block reward
-		let reward = self.params().block_reward;
-		let res = fields.state.add_balance(fields.header.author(), &reward, CleanupMode::NoEmpty)
-			.map_err(::error::Error::from)
-			.and_then(|_| fields.state.commit());
+		let mut benefactors = vec![(*block.fields().header.author(), RewardKind::Author)];
+
+		if block.fields().header.number() >= self.empty_steps_transition {
+			let empty_steps = if block.fields().header.seal().is_empty() {
+				// this is a new block, reward the empty steps which will be included in its seal.
+				let client = match self.client.read().as_ref().and_then(|weak| weak.upgrade()) {
+					Some(client) => client,
+					None => {
+						debug!(target: "engine", "Unable to close block: missing client ref.");
+						return Err(EngineError::RequiresClient.into())
+					}
+				};
+
+				let parent_hash = *block.fields().header.parent_hash();
+				let parent = match client.block_header(BlockId::Hash(parent_hash)) {
+					Some(parent) => parent.decode(),
+					None => {
+						debug!(target: "engine", "Unable to close block: parent {} is not in the chain.", parent_hash);
+						return Err(BlockError::UnknownParent(parent_hash).into())
+					}
+				};
+				let parent_step = header_step(&parent)?;
+				self.empty_steps(parent_step, self.step.load(), parent_hash)
+			} else {
+				// we're verifying a block, extract the empty steps from the seal.
+				header_empty_steps(block.fields().header)?
+			};
+
+			for empty_step in empty_steps {
+				benefactors.push((empty_step.author()?, RewardKind::EmptyStep));
+			}
+		}
+
+		let rewards = match self.block_reward_contract {
+			Some(ref contract) => {
+				let last_hashes = block.last_hashes();
+				let mut call = |to, data| {
+					let result = ::engines::common::execute_as_system(
+						block,
+						last_hashes.clone(),
+						self,
+						to,
+						// the contract is part of the chain spec and must run to completion
+						// for all nodes to agree on the rewards, so it is not gas limited.
+						U256::max_value(),
+						Some(data),
+					);
+					result.map_err(|e| format!("{}", e))
+				};
+
+				contract.reward(&benefactors, &mut call)?
+			},
+			None => benefactors.into_iter().map(|(address, _)| (address, self.params().block_reward)).collect(),
+		};
+
 		// Commit state so that we can actually figure out the state root.
+		let res = block_reward::apply_block_rewards(&rewards, block);
 		if let Err(ref e) = res {
 			warn!("Encountered error on closing block: {}", e);
 		}
@@ -563,10 +932,10 @@ impl Engine for AuthorityRound {
 
 	/// Check the number of seal fields.
 	fn verify_block_basic(&self, header: &Header, _block: Option<&[u8]>) -> Result<(), Error> {
-		if header.seal().len() != self.seal_fields() {
+		if header.seal().len() != self.seal_fields(header) {
 			trace!(target: "engine", "verify_block_basic: wrong number of seal fields");
 			Err(From::from(BlockError::InvalidSealArity(
-				Mismatch { expected: self.seal_fields(), found: header.seal().len() }
+				Mismatch { expected: self.seal_fields(header), found: header.seal().len() }
 			)))
 		} else if header.number() >= self.validate_score_transition && *header.difficulty() >= U256::from(U128::max_value()) {
 			Err(From::from(BlockError::DifficultyOutOfBounds(
@@ -600,8 +969,30 @@ impl Engine for AuthorityRound {
 			self.validators.report_malicious(header.author(), header.number(), header.number(), Default::default());
 			Err(EngineError::DoubleVote(header.author().clone()))?;
 		}
-		// Report skipped primaries.
-		if let (true, Some(me)) = (step > parent_step + 1, self.signer.read().address()) {
+		if header.number() >= self.empty_steps_transition {
+			// skipped primaries are expected to have broadcast empty steps, which must all be valid.
+			let empty_steps = header_empty_steps(header)?;
+			let mut prev_empty_step = parent_step;
+			for empty_step in &empty_steps {
+				if empty_step.step <= prev_empty_step || empty_step.step >= step {
+					Err(EngineError::InsufficientProof(
+						format!("empty step proof for invalid or unordered step: {}", empty_step.step)))?;
+				}
+				if !empty_step.verify(&*self.validators).unwrap_or(false) {
+					Err(EngineError::InsufficientProof(format!("invalid empty step proof: {:?}", empty_step)))?;
+				}
+				prev_empty_step = empty_step.step;
+			}
+
+			let expected_score = calculate_score(parent_step, step, empty_steps.len());
+			if header.difficulty() != &expected_score {
+				return Err(From::from(BlockError::InvalidDifficulty(Mismatch { expected: expected_score, found: header.difficulty().clone() })));
+			}
+
+			// these empty steps can't be included in any other block anymore.
+			self.clear_empty_steps(parent_step);
+		} else if let (true, Some(me)) = (step > parent_step + 1, self.signer.read().address()) {
+			// Report skipped primaries.
 			debug!(target: "engine", "Author {} built block with step gap. current step: {}, parent step: {}",
 				header.author(), step, parent_step);
 			let mut reported = HashSet::new();
@@ -662,7 +1053,7 @@ impl Engine for AuthorityRound {
 
 		// verify signature against fixed list, but reports should go to the
 		// contract itself.
-		verify_external(header, validators, &*self.step, report)
+		verify_external(header, validators, &*self.step, self.empty_steps_transition, report)
 	}
 
 	fn genesis_epoch_data(&self, header: &Header, call: &Call) -> Result<Vec<u8>, String> {
@@ -719,6 +1110,8 @@ impl Engine for AuthorityRound {
 			let mut hash = chain_head.parent_hash().clone();
 			let epoch_transition_hash = epoch_manager.epoch_transition_hash;
 
+			let empty_steps_transition = self.empty_steps_transition;
+
 			// walk the chain within current epoch backwards.
 			// author == ec_recover(sig) known since
 			// the blocks are in the DB.
@@ -726,7 +1119,9 @@ impl Engine for AuthorityRound {
 				chain(hash).and_then(|header| {
 					if header.number() == 0 { return None }
 
-					let res = (hash, header.author().clone());
+					let signers = header_signers(&header, empty_steps_transition)
+						.expect("empty steps were verified on import; qed");
+					let res = (hash, signers);
 					trace!(target: "finality", "Ancestry iteration: yielding {:?}", res);
 
 					hash = header.parent_hash().clone();
@@ -742,8 +1137,13 @@ impl Engine for AuthorityRound {
 			}
 		}
 
+		let signers = match header_signers(chain_head, self.empty_steps_transition) {
+			Ok(signers) => signers,
+			Err(_) => return None,
+		};
+
 		{
-			if let Ok(finalized) = epoch_manager.finality_checker.push_hash(chain_head.hash(), *chain_head.author()) {
+			if let Ok(finalized) = epoch_manager.finality_checker.push_hash(chain_head.hash(), signers) {
 				let mut finalized = finalized.into_iter();
 				while let Some(hash) = finalized.next() {
 					if let Some(pending) = transition_store(hash) {
@@ -792,6 +1192,7 @@ impl Engine for AuthorityRound {
 				let verifier = Box::new(EpochVerifier {
 					step: self.step.clone(),
 					subchain_validators: list,
+					empty_steps_transition: self.empty_steps_transition,
 				});
 
 				match finalize {
@@ -850,8 +1251,10 @@ mod tests {
 	use account_provider::AccountProvider;
 	use spec::Spec;
 	use engines::{Seal, Engine};
-	use engines::validator_set::TestSet;
-	use super::{AuthorityRoundParams, AuthorityRound};
+	use engines::validator_set::{TestSet, SimpleList};
+	use ethkey::KeyPair;
+	use transaction::{Transaction, Action};
+	use super::{AuthorityRoundParams, AuthorityRound, EmptyStep, SealedEmptyStep, empty_step_rlp};
 
 	#[test]
 	fn has_valid_metadata() {
@@ -910,17 +1313,17 @@ mod tests {
 		let b2 = b2.close_and_lock();
 
 		engine.set_signer(tap.clone(), addr1, "1".into());
-		if let Seal::Regular(seal) = engine.generate_seal(b1.block()) {
+		if let Seal::Regular(seal) = engine.generate_seal(b1.block(), &genesis_header) {
 			assert!(b1.clone().try_seal(engine, seal).is_ok());
 			// Second proposal is forbidden.
-			assert!(engine.generate_seal(b1.block()) == Seal::None);
+			assert!(engine.generate_seal(b1.block(), &genesis_header) == Seal::None);
 		}
 
 		engine.set_signer(tap, addr2, "2".into());
-		if let Seal::Regular(seal) = engine.generate_seal(b2.block()) {
+		if let Seal::Regular(seal) = engine.generate_seal(b2.block(), &genesis_header) {
 			assert!(b2.clone().try_seal(engine, seal).is_ok());
 			// Second proposal is forbidden.
-			assert!(engine.generate_seal(b2.block()) == Seal::None);
+			assert!(engine.generate_seal(b2.block(), &genesis_header) == Seal::None);
 		}
 	}
 
@@ -1009,6 +1412,8 @@ mod tests {
 			validate_score_transition: 0,
 			validate_step_transition: 0,
 			immediate_transitions: true,
+			block_reward_contract: None,
+			empty_steps_transition: u64::max_value(),
 		};
 
 		let aura = {
@@ -1034,4 +1439,127 @@ mod tests {
 		assert!(aura.verify_block_family(&header, &parent_header, None).is_ok());
 		assert_eq!(last_benign.load(AtomicOrdering::SeqCst), 1);
 	}
+
+	#[test]
+	fn applies_block_reward_contract_rewards() {
+		let spec = Spec::new_test_round_block_reward_contract();
+		let engine = &*spec.engine;
+		let genesis_header = spec.genesis_header();
+		let db = spec.ensure_db_good(get_temp_state_db(), &Default::default()).unwrap();
+		let last_hashes = Arc::new(vec![genesis_header.hash()]);
+		let author: Address = 5.into();
+
+		let b = OpenBlock::new(engine, Default::default(), false, db, &genesis_header, last_hashes, author, (3141562.into(), 31415620.into()), vec![], false).unwrap();
+		let b = b.close_and_lock();
+
+		// the test contract rewards the author with 1000 plus the reward kind (0).
+		assert_eq!(b.state().balance(&author).unwrap(), U256::from(1000));
+	}
+
+	#[test]
+	fn empty_step_roundtrip() {
+		let tap = AccountProvider::transient_provider();
+		let addr = tap.insert_account("0".sha3().into(), "0").unwrap();
+		let parent_hash = H256::random();
+
+		let signature = tap.sign(addr, Some("0".into()), empty_step_rlp(3, &parent_hash).sha3()).unwrap();
+		let empty_step = EmptyStep { signature: signature.into(), step: 3, parent_hash: parent_hash };
+
+		let decoded: EmptyStep = ::rlp::decode(&encode(&empty_step));
+		assert_eq!(decoded, empty_step);
+		assert_eq!(decoded.author().unwrap(), addr);
+
+		// step 3 has the second validator as proposer.
+		let validators = SimpleList::new(vec![Address::random(), addr]);
+		assert!(decoded.verify(&validators).unwrap());
+		let validators = SimpleList::new(vec![addr, Address::random()]);
+		assert!(!decoded.verify(&validators).unwrap());
+	}
+
+	#[test]
+	fn broadcasts_empty_step_instead_of_sealing_empty_block() {
+		let tap = Arc::new(AccountProvider::transient_provider());
+		let addr1 = tap.insert_account("1".sha3().into(), "1").unwrap();
+		let addr2 = tap.insert_account("0".sha3().into(), "0").unwrap();
+
+		let spec = Spec::new_test_round_empty_steps();
+		let engine = &*spec.engine;
+		let genesis_header = spec.genesis_header();
+		let db1 = spec.ensure_db_good(get_temp_state_db(), &Default::default()).unwrap();
+		let db2 = spec.ensure_db_good(get_temp_state_db(), &Default::default()).unwrap();
+		let last_hashes = Arc::new(vec![genesis_header.hash()]);
+
+		// step 2: first validator has no transactions and broadcasts an empty step.
+		engine.set_signer(tap.clone(), addr1, "1".into());
+		let b1 = OpenBlock::new(engine, Default::default(), false, db1, &genesis_header, last_hashes.clone(), addr1, (3141562.into(), 31415620.into()), vec![], false).unwrap();
+		let b1 = b1.close_and_lock();
+		assert!(engine.generate_seal(b1.block(), &genesis_header) == Seal::None);
+
+		// step 3: second validator seals a block with a transaction, including the empty step.
+		engine.step();
+		engine.set_signer(tap.clone(), addr2, "0".into());
+		let mut b2 = OpenBlock::new(engine, Default::default(), false, db2, &genesis_header, last_hashes, addr2, (3141562.into(), 31415620.into()), vec![], false).unwrap();
+		let keypair = KeyPair::from_secret_slice(&"".sha3()).unwrap();
+		let transaction = Transaction {
+			action: Action::Create,
+			value: U256::zero(),
+			data: vec![],
+			gas: U256::from(100_000),
+			gas_price: U256::zero(),
+			nonce: U256::from(1048576),
+		}.sign(keypair.secret(), None);
+		b2.push_transaction(transaction, None).unwrap();
+		let b2 = b2.close_and_lock();
+
+		// one empty step adds to the block score.
+		assert_eq!(*b2.header().difficulty(), U256::from(U128::max_value()) - U256::from(3) + U256::from(1));
+
+		if let Seal::Regular(seal) = engine.generate_seal(b2.block(), &genesis_header) {
+			assert_eq!(seal.len(), 3);
+			let empty_steps = ::rlp::UntrustedRlp::new(&seal[2]).as_list::<SealedEmptyStep>().unwrap();
+			assert_eq!(empty_steps.len(), 1);
+			let empty_step = EmptyStep::from_sealed(empty_steps.into_iter().next().unwrap(), &genesis_header.hash());
+			assert_eq!(empty_step.step, 2);
+			assert_eq!(empty_step.author().unwrap(), addr1);
+
+			let sealed = b2.try_seal(engine, seal).map_err(|(e, _)| e).unwrap();
+			assert!(engine.verify_block_family(sealed.header(), &genesis_header, None).is_ok());
+		} else {
+			panic!("block with transactions should be sealed");
+		}
+	}
+
+	#[test]
+	fn rewards_empty_steps_on_close() {
+		let tap = Arc::new(AccountProvider::transient_provider());
+		let addr1 = tap.insert_account("1".sha3().into(), "1").unwrap();
+		let addr2 = tap.insert_account("0".sha3().into(), "0").unwrap();
+
+		let spec = Spec::new_test_round_empty_steps();
+		let client = generate_dummy_client_with_spec_and_accounts(Spec::new_test_round_empty_steps, None);
+		let engine = client.engine();
+		engine.register_client(Arc::downgrade(&client));
+
+		let genesis_header = spec.genesis_header();
+		let db1 = spec.ensure_db_good(get_temp_state_db(), &Default::default()).unwrap();
+		let db2 = spec.ensure_db_good(get_temp_state_db(), &Default::default()).unwrap();
+		let last_hashes = Arc::new(vec![genesis_header.hash()]);
+
+		// step 2: first validator has no transactions and broadcasts an empty step.
+		engine.set_signer(tap.clone(), addr1, "1".into());
+		let b1 = OpenBlock::new(engine, Default::default(), false, db1, &genesis_header, last_hashes.clone(), addr1, (3141562.into(), 31415620.into()), vec![], false).unwrap();
+		let b1 = b1.close_and_lock();
+		assert!(engine.generate_seal(b1.block(), &genesis_header) == Seal::None);
+
+		// step 3: the block closed by the second validator rewards the empty step author too.
+		engine.step();
+		engine.set_signer(tap.clone(), addr2, "0".into());
+		let b2 = OpenBlock::new(engine, Default::default(), false, db2, &genesis_header, last_hashes, addr2, (3141562.into(), 31415620.into()), vec![], false).unwrap();
+		let b2 = b2.close_and_lock();
+
+		// the spec pays a block reward of 1000 to every benefactor.
+		assert_eq!(b2.state().balance(&addr2).unwrap(), U256::from(1000));
+		assert_eq!(b2.state().balance(&addr1).unwrap(), U256::from(1000));
+	}
+
 }
@@ -0,0 +1,91 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Block reward kinds and a client for the block reward contract.
+
+use futures::Future;
+use native_contracts::BlockReward as Provider;
+
+use block::ExecutedBlock;
+use error::Error;
+use state::CleanupMode;
+use util::*;
+
+use super::EngineError;
+use super::validator_set::SystemCall;
+
+/// The kind of a block reward, as understood by the block reward contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RewardKind {
+	/// Reward attributed to the block author.
+	Author = 0,
+	/// Reward attributed to the author of an uncle.
+	Uncle = 1,
+	/// Reward attributed to the author of an empty step included in the block.
+	EmptyStep = 2,
+}
+
+impl From<RewardKind> for u16 {
+	fn from(kind: RewardKind) -> u16 {
+		kind as u16
+	}
+}
+
+/// A client for the block reward contract.
+///
+/// The contract exposes `reward(address[] benefactors, uint16[] kind)` returning
+/// `(address[] receivers, uint256[] rewards)`, i.e. the balance changes to apply.
+pub struct BlockRewardContract {
+	provider: Provider,
+}
+
+impl BlockRewardContract {
+	/// Create a new block reward contract client targeting the given address.
+	pub fn new(contract_address: Address) -> Self {
+		BlockRewardContract {
+			provider: Provider::new(contract_address),
+		}
+	}
+
+	/// Calls the block reward contract with the given benefactors and returns the resulting
+	/// list of rewards for each address.
+	pub fn reward(&self, benefactors: &[(Address, RewardKind)], caller: &mut SystemCall) -> Result<Vec<(Address, U256)>, Error> {
+		let (addresses, kinds): (Vec<_>, Vec<_>) = benefactors.iter()
+			.map(|&(address, kind)| (address, u16::from(kind)))
+			.unzip();
+
+		let (receivers, rewards) = self.provider.reward(|to, data| caller(to, data), addresses, kinds)
+			.wait()
+			.map_err(EngineError::FailedSystemCall)?;
+
+		if receivers.len() != rewards.len() {
+			return Err(EngineError::FailedSystemCall(
+				"invalid data returned by reward contract: both arrays must have the same size".into()
+			).into());
+		}
+
+		Ok(receivers.into_iter().zip(rewards.into_iter()).collect())
+	}
+}
+
+/// Applies the given block rewards, i.e. adds the given balance to each beneficiary address.
+pub fn apply_block_rewards(rewards: &[(Address, U256)], block: &mut ExecutedBlock) -> Result<(), Error> {
+	let fields = block.fields_mut();
+	for &(ref author, ref block_reward) in rewards {
+		fields.state.add_balance(author, block_reward, CleanupMode::NoEmpty)?;
+	}
+	fields.state.commit()
+}
@@ -0,0 +1,734 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Clique (EIP-225) proof-of-authority consensus engine.
+//!
+//! Blocks are signed by one of the authorized signers; the signature is kept in the
+//! last 65 bytes of the header extra data, after 32 bytes of signer vanity.
+//! Checkpoint blocks (every `epoch` blocks) additionally list the current signers
+//! in the extra data. Signers vote on adding or removing a signer by setting the
+//! header author to the voted address and the nonce to `NONCE_AUTH_VOTE` or `NONCE_DROP_VOTE`.
+
+use std::sync::{Weak, Arc};
+use std::time::{UNIX_EPOCH, Duration};
+use std::collections::BTreeMap;
+use std::cmp;
+
+use account_provider::AccountProvider;
+use block::*;
+use builtin::Builtin;
+use client::{Client, BlockChainClient, EngineClient};
+use engines::{Engine, Seal, EngineError};
+use error::{BlockError, Error};
+use ethjson;
+use header::{Header, BlockNumber};
+use ids::BlockId;
+use spec::CommonParams;
+
+use super::signer::EngineSigner;
+
+use ethkey::{recover, public_to_address, Signature};
+use io::{IoContext, IoHandler, TimerToken, IoService};
+use lru_cache::LruCache;
+use rlp::{UntrustedRlp, encode};
+use util::*;
+
+mod snapshot;
+
+pub use self::snapshot::SignerSnapshot;
+
+/// Fixed number of extra-data prefix bytes reserved for signer vanity.
+pub const VANITY_LENGTH: usize = 32;
+/// Fixed number of extra-data suffix bytes reserved for the signer seal.
+pub const SIGNATURE_LENGTH: usize = 65;
+/// Nonce of a block voting to add the header author to the signers.
+pub const NONCE_AUTH_VOTE: [u8; 8] = [0xff; 8];
+/// Nonce of a block voting to remove the header author from the signers.
+pub const NONCE_DROP_VOTE: [u8; 8] = [0x00; 8];
+/// Default minimum number of seconds between consecutive blocks.
+const DEFAULT_PERIOD: u64 = 15;
+/// Default number of blocks after which votes are reset and a checkpoint is made.
+const DEFAULT_EPOCH: u64 = 30000;
+/// Number of recent signer snapshots to keep in memory.
+const SNAPSHOT_CACHE_ITEMS: usize = 128;
+/// Delay per position away from the in-turn signer before an out-of-turn signer seals.
+const OUT_OF_TURN_DELAY_MS: u64 = 500;
+/// Interval at which sealing is retried.
+const SEALING_INTERVAL_MS: u64 = 1000;
+
+/// `Clique` params.
+#[derive(Debug, PartialEq)]
+pub struct CliqueParams {
+	/// Minimum number of seconds between consecutive blocks.
+	pub period: u64,
+	/// Number of blocks after which votes are reset and a checkpoint is made.
+	pub epoch: u64,
+}
+
+impl From<ethjson::spec::CliqueParams> for CliqueParams {
+	fn from(p: ethjson::spec::CliqueParams) -> Self {
+		CliqueParams {
+			period: p.period.map_or(DEFAULT_PERIOD, Into::into),
+			epoch: p.epoch.map_or(DEFAULT_EPOCH, Into::into),
+		}
+	}
+}
+
+/// Engine using `Clique` proof-of-authority consensus.
+pub struct Clique {
+	params: CommonParams,
+	builtins: BTreeMap<Address, Builtin>,
+	period: u64,
+	epoch: u64,
+	step_service: IoService<()>,
+	client: RwLock<Option<Weak<Client>>>,
+	signer: RwLock<EngineSigner>,
+	snapshots: Mutex<LruCache<H256, SignerSnapshot>>,
+	proposals: RwLock<BTreeMap<Address, bool>>,
+}
+
+impl Clique {
+	/// Create a new instance of Clique engine.
+	pub fn new(params: CommonParams, our_params: CliqueParams, builtins: BTreeMap<Address, Builtin>) -> Result<Arc<Self>, Error> {
+		let engine = Arc::new(
+			Clique {
+				params: params,
+				builtins: builtins,
+				period: our_params.period,
+				// an epoch of zero would make every block a checkpoint
+				epoch: cmp::max(our_params.epoch, 1),
+				step_service: IoService::<()>::start()?,
+				client: RwLock::new(None),
+				signer: Default::default(),
+				snapshots: Mutex::new(LruCache::new(SNAPSHOT_CACHE_ITEMS)),
+				proposals: RwLock::new(BTreeMap::new()),
+			});
+
+		let handler = StepHandler { engine: Arc::downgrade(&engine) };
+		engine.step_service.register_handler(Arc::new(handler))?;
+		Ok(engine)
+	}
+
+	/// Propose to add (`authorize == true`) or remove an address from the signers.
+	/// The vote is cast in blocks sealed by this node until it passes or is discarded.
+	pub fn propose(&self, address: Address, authorize: bool) {
+		self.proposals.write().insert(address, authorize);
+	}
+
+	/// Stop voting on the given address.
+	pub fn discard(&self, address: &Address) {
+		self.proposals.write().remove(address);
+	}
+
+	fn is_checkpoint(&self, number: BlockNumber) -> bool {
+		number % self.epoch == 0
+	}
+
+	fn header_by_hash(&self, hash: &H256) -> Option<Header> {
+		self.client.read()
+			.as_ref()
+			.and_then(Weak::upgrade)
+			.and_then(|c| c.block_header(BlockId::Hash(hash.clone())))
+			.map(|h| h.decode())
+	}
+
+	/// Signer snapshot after the given header has been applied.
+	///
+	/// Walks back through the ancestors until a cached snapshot or a checkpoint is found,
+	/// then applies the headers in between.
+	fn snapshot(&self, header: &Header) -> Result<SignerSnapshot, Error> {
+		let mut headers = Vec::new();
+		let mut current = header.clone();
+
+		let mut snapshot = loop {
+			if let Some(snapshot) = self.snapshots.lock().get_mut(&current.hash()) {
+				break snapshot.clone();
+			}
+
+			if self.is_checkpoint(current.number()) {
+				let mut snapshot = SignerSnapshot::new(current.number(), current.hash(), extract_signers(&current)?);
+
+				// signers of the blocks leading up to the checkpoint are still recent
+				let number = current.number();
+				let limit = snapshot.signer_limit();
+				let mut recent = current.clone();
+				while recent.number() > 0 {
+					snapshot.recents.insert(recent.number(), recover_signer(&recent)?);
+					if recent.number() + limit <= number + 1 {
+						break;
+					}
+					let parent_hash = recent.parent_hash().clone();
+					recent = self.header_by_hash(&parent_hash).ok_or(EngineError::CliqueMissingCheckpoint(parent_hash))?;
+				}
+				break snapshot;
+			}
+
+			let parent_hash = current.parent_hash().clone();
+			headers.push(current);
+			current = self.header_by_hash(&parent_hash).ok_or(EngineError::CliqueMissingCheckpoint(parent_hash))?;
+		};
+
+		for h in headers.into_iter().rev() {
+			snapshot.apply(h.number(), h.hash(), recover_signer(&h)?, header_vote(&h)?, self.epoch)?;
+		}
+
+		self.snapshots.lock().insert(snapshot.hash.clone(), snapshot.clone());
+		Ok(snapshot)
+	}
+
+	/// First pending proposal which would still change the signer set.
+	fn proposal(&self, snapshot: &SignerSnapshot) -> Option<(Address, bool)> {
+		self.proposals.read()
+			.iter()
+			.find(|&(address, authorize)| snapshot.is_valid_vote(address, *authorize))
+			.map(|(address, authorize)| (address.clone(), *authorize))
+	}
+
+	/// Milliseconds an authorized signer should wait past the block timestamp before sealing.
+	fn sealing_delay(&self, snapshot: &SignerSnapshot, number: BlockNumber, signer: &Address) -> u64 {
+		let signers = snapshot.signers();
+		let len = signers.len() as u64;
+		match signers.iter().position(|s| s == signer) {
+			Some(index) => {
+				let in_turn = number % len;
+				let offset = (index as u64 + len - in_turn) % len;
+				offset * OUT_OF_TURN_DELAY_MS
+			},
+			None => 0,
+		}
+	}
+}
+
+fn unix_now() -> Duration {
+	UNIX_EPOCH.elapsed().expect("Valid time has to be set in your system.")
+}
+
+/// Hash signed by the block sealer: the full header with seal fields,
+/// but without the signature in the extra data.
+fn seal_hash(header: &Header) -> Result<H256, Error> {
+	let extra_len = header.extra_data().len();
+	if extra_len < VANITY_LENGTH + SIGNATURE_LENGTH {
+		return Err(EngineError::CliqueMissingSignature.into());
+	}
+
+	let mut unsigned = header.clone();
+	unsigned.set_extra_data(header.extra_data()[..extra_len - SIGNATURE_LENGTH].to_vec());
+	Ok(unsigned.rlp_sha3(::header::Seal::With))
+}
+
+/// Recover the address which sealed the given header.
+fn recover_signer(header: &Header) -> Result<Address, Error> {
+	let extra = header.extra_data();
+	if extra.len() < VANITY_LENGTH {
+		return Err(EngineError::CliqueMissingVanity.into());
+	}
+	if extra.len() < VANITY_LENGTH + SIGNATURE_LENGTH {
+		return Err(EngineError::CliqueMissingSignature.into());
+	}
+
+	let signature = H520::from_slice(&extra[extra.len() - SIGNATURE_LENGTH..]);
+	let public = recover(&signature.into(), &seal_hash(header)?)?;
+	Ok(public_to_address(&public))
+}
+
+/// Signers listed in the extra data of a checkpoint header.
+fn extract_signers(header: &Header) -> Result<Vec<Address>, Error> {
+	let extra = header.extra_data();
+	if extra.len() < VANITY_LENGTH {
+		return Err(EngineError::CliqueMissingVanity.into());
+	}
+	if extra.len() < VANITY_LENGTH + SIGNATURE_LENGTH {
+		return Err(EngineError::CliqueMissingSignature.into());
+	}
+
+	let signers = &extra[VANITY_LENGTH..extra.len() - SIGNATURE_LENGTH];
+	if signers.len() % 20 != 0 {
+		return Err(EngineError::CliqueCheckpointNumberOfSigners(signers.len()).into());
+	}
+	Ok(signers.chunks(20).map(Address::from_slice).collect())
+}
+
+/// Vote carried by the header: the voted address and whether to authorize it.
+fn header_vote(header: &Header) -> Result<Option<(Address, bool)>, Error> {
+	let nonce: H64 = match header.seal().get(1) {
+		Some(nonce) => UntrustedRlp::new(nonce).as_val()?,
+		None => return Err(BlockError::InvalidSealArity(Mismatch { expected: 2, found: header.seal().len() }).into()),
+	};
+
+	let authorize = if &nonce[..] == &NONCE_AUTH_VOTE[..] {
+		true
+	} else if &nonce[..] == &NONCE_DROP_VOTE[..] {
+		false
+	} else {
+		return Err(EngineError::CliqueInvalidNonce(nonce).into());
+	};
+
+	if header.author().is_zero() {
+		Ok(None)
+	} else {
+		Ok(Some((header.author().clone(), authorize)))
+	}
+}
+
+struct StepHandler {
+	engine: Weak<Clique>,
+}
+
+const ENGINE_TIMEOUT_TOKEN: TimerToken = 23;
+
+impl IoHandler<()> for StepHandler {
+	fn initialize(&self, io: &IoContext<()>) {
+		io.register_timer(ENGINE_TIMEOUT_TOKEN, SEALING_INTERVAL_MS)
+			.unwrap_or_else(|e| warn!(target: "engine", "Failed to start Clique sealing timer: {}.", e))
+	}
+
+	fn timeout(&self, _io: &IoContext<()>, timer: TimerToken) {
+		if timer == ENGINE_TIMEOUT_TOKEN {
+			if let Some(engine) = self.engine.upgrade() {
+				engine.step();
+			}
+		}
+	}
+}
+
+impl Engine for Clique {
+	fn name(&self) -> &str { "Clique" }
+
+	fn version(&self) -> SemanticVersion { SemanticVersion::new(1, 0, 0) }
+
+	/// Two fields - mix hash (always zero) and nonce (the vote).
+	fn seal_fields(&self, _header: &Header) -> usize { 2 }
+
+	fn params(&self) -> &CommonParams { &self.params }
+
+	fn builtins(&self) -> &BTreeMap<Address, Builtin> { &self.builtins }
+
+	/// Signer vanity, checkpoint signers and the signature are all kept in the extra data.
+	fn maximum_extra_data_size(&self) -> usize { usize::max_value() }
+
+	fn maximum_uncle_count(&self) -> usize { 0 }
+
+	fn step(&self) {
+		if let Some(ref weak) = *self.client.read() {
+			if let Some(c) = weak.upgrade() {
+				c.update_sealing();
+			}
+		}
+	}
+
+	/// Additional engine-specific information for the user/developer concerning `header`.
+	fn extra_info(&self, header: &Header) -> BTreeMap<String, String> {
+		map![
+			"signer".into() => recover_signer(header).as_ref().map(ToString::to_string).unwrap_or("".into())
+		]
+	}
+
+	fn populate_from_parent(&self, header: &mut Header, parent: &Header, gas_floor_target: U256, _gas_ceil_target: U256) {
+		header.set_gas_limit({
+			let gas_limit = parent.gas_limit().clone();
+			let bound_divisor = self.params().gas_limit_bound_divisor;
+			if gas_limit < gas_floor_target {
+				cmp::min(gas_floor_target, gas_limit + gas_limit / bound_divisor - 1.into())
+			} else {
+				cmp::max(gas_floor_target, gas_limit - gas_limit / bound_divisor + 1.into())
+			}
+		});
+		header.set_timestamp(cmp::max(header.timestamp(), parent.timestamp() + self.period));
+
+		let snapshot = match self.snapshot(parent) {
+			Ok(snapshot) => snapshot,
+			Err(e) => {
+				warn!(target: "engine", "Unable to compute Clique signers at block #{}: {}", parent.number(), e);
+				return;
+			}
+		};
+
+		let number = header.number();
+		let checkpoint = self.is_checkpoint(number);
+
+		// the author is the address voted on, if any
+		let vote = if checkpoint { None } else { self.proposal(&snapshot) };
+		header.set_author(vote.map_or_else(Address::default, |(address, _)| address));
+
+		// the vote travels with the header so that `generate_seal` seals exactly this one
+		let nonce = match vote {
+			Some((_, true)) => NONCE_AUTH_VOTE,
+			_ => NONCE_DROP_VOTE,
+		};
+		header.set_seal(vec![encode(&H256::default()).into_vec(), encode(&H64::from(&nonce[..])).into_vec()]);
+
+		let in_turn = self.signer.read().address().map_or(false, |signer| snapshot.is_in_turn(number, &signer));
+		header.set_difficulty(if in_turn { 2.into() } else { 1.into() });
+
+		let mut extra = header.extra_data().clone();
+		extra.resize(VANITY_LENGTH, 0);
+		if checkpoint {
+			for signer in snapshot.signers() {
+				extra.extend_from_slice(&signer);
+			}
+		}
+		extra.extend_from_slice(&[0u8; SIGNATURE_LENGTH]);
+		header.set_extra_data(extra);
+	}
+
+	fn seals_internally(&self) -> Option<bool> {
+		Some(self.signer.read().is_some())
+	}
+
+	/// Attempt to seal the block internally.
+	///
+	/// Only the seal fields are generated here, the signature is added to the extra data in `on_seal_block`.
+	fn generate_seal(&self, block: &ExecutedBlock, _parent: &Header) -> Seal {
+		let header = block.header();
+		let signer = match self.signer.read().address() {
+			Some(signer) => signer,
+			None => return Seal::None,
+		};
+
+		let snapshot = match self.header_by_hash(header.parent_hash()).map(|parent| self.snapshot(&parent)) {
+			Some(Ok(snapshot)) => snapshot,
+			Some(Err(e)) => {
+				warn!(target: "engine", "generate_seal: unable to compute signers: {}", e);
+				return Seal::None;
+			},
+			None => {
+				trace!(target: "engine", "generate_seal: parent of block #{} unknown", header.number());
+				return Seal::None;
+			},
+		};
+
+		if !snapshot.signers.contains(&signer) {
+			trace!(target: "engine", "generate_seal: {} is not an authorized signer", signer);
+			return Seal::None;
+		}
+		if snapshot.recently_signed(header.number(), &signer) {
+			trace!(target: "engine", "generate_seal: {} signed recently, must wait for others", signer);
+			return Seal::None;
+		}
+
+		let ready_at = header.timestamp() * 1000 + self.sealing_delay(&snapshot, header.number(), &signer);
+		let now = unix_now();
+		if now.as_secs() * 1000 + (now.subsec_nanos() / 1_000_000) as u64 < ready_at {
+			return Seal::None;
+		}
+
+		// the vote was chosen in `populate_from_parent`
+		if header.seal().len() != 2 {
+			warn!(target: "engine", "generate_seal: block #{} was not prepared with a vote", header.number());
+			return Seal::None;
+		}
+		Seal::Regular(header.seal().to_vec())
+	}
+
+	fn on_seal_block(&self, block: &mut ExecutedBlock) -> Result<(), Error> {
+		let header = block.fields_mut().header;
+		let signature = self.sign(seal_hash(header)?)?;
+
+		let mut extra = header.extra_data().clone();
+		let signature_start = extra.len() - SIGNATURE_LENGTH;
+		extra[signature_start..].copy_from_slice(&H520::from(signature));
+		header.set_extra_data(extra);
+		Ok(())
+	}
+
+	fn verify_block_basic(&self, header: &Header, _block: Option<&[u8]>) -> Result<(), Error> {
+		if header.seal().len() != self.seal_fields(header) {
+			return Err(From::from(BlockError::InvalidSealArity(
+				Mismatch { expected: self.seal_fields(header), found: header.seal().len() }
+			)));
+		}
+
+		// genesis is not signed
+		if header.number() == 0 {
+			return Ok(());
+		}
+
+		let extra_len = header.extra_data().len();
+		if extra_len < VANITY_LENGTH {
+			return Err(EngineError::CliqueMissingVanity.into());
+		}
+		if extra_len < VANITY_LENGTH + SIGNATURE_LENGTH {
+			return Err(EngineError::CliqueMissingSignature.into());
+		}
+
+		let checkpoint = self.is_checkpoint(header.number());
+		let signers_len = extra_len - VANITY_LENGTH - SIGNATURE_LENGTH;
+		if (!checkpoint && signers_len != 0) || signers_len % 20 != 0 {
+			return Err(EngineError::CliqueCheckpointNumberOfSigners(signers_len).into());
+		}
+
+		// checkpoints must not carry votes
+		let vote = header_vote(header)?;
+		if checkpoint && vote.is_some() {
+			return Err(EngineError::CliqueWrongAuthorCheckpoint(Mismatch { expected: Address::default(), found: header.author().clone() }).into());
+		}
+		if checkpoint && !UntrustedRlp::new(&header.seal()[1]).as_val::<H64>()?.is_zero() {
+			return Err(BlockError::InvalidSeal.into());
+		}
+
+		if !UntrustedRlp::new(&header.seal()[0]).as_val::<H256>()?.is_zero() {
+			return Err(BlockError::InvalidSeal.into());
+		}
+
+		let difficulty = header.difficulty();
+		if *difficulty != 1.into() && *difficulty != 2.into() {
+			return Err(BlockError::DifficultyOutOfBounds(OutOfBounds { min: Some(1.into()), max: Some(2.into()), found: difficulty.clone() }).into());
+		}
+
+		Ok(())
+	}
+
+	fn verify_block_family(&self, header: &Header, parent: &Header, _block: Option<&[u8]>) -> Result<(), Error> {
+		if header.number() == 0 {
+			return Err(From::from(BlockError::RidiculousNumber(OutOfBounds { min: Some(1), max: None, found: header.number() })));
+		}
+
+		let min_timestamp = parent.timestamp() + self.period;
+		if header.timestamp() < min_timestamp {
+			return Err(From::from(BlockError::InvalidTimestamp(OutOfBounds { min: Some(min_timestamp), max: None, found: header.timestamp() })));
+		}
+
+		let gas_limit_divisor = self.params().gas_limit_bound_divisor;
+		let min_gas = parent.gas_limit().clone() - parent.gas_limit().clone() / gas_limit_divisor;
+		let max_gas = parent.gas_limit().clone() + parent.gas_limit().clone() / gas_limit_divisor;
+		if header.gas_limit() <= &min_gas || header.gas_limit() >= &max_gas {
+			return Err(From::from(BlockError::InvalidGasLimit(OutOfBounds { min: Some(min_gas), max: Some(max_gas), found: header.gas_limit().clone() })));
+		}
+
+		let mut snapshot = self.snapshot(parent)?;
+
+		if self.is_checkpoint(header.number()) {
+			let signers = extract_signers(header)?;
+			if signers != snapshot.signers() {
+				return Err(EngineError::CliqueCheckpointInvalidSigners(signers).into());
+			}
+		}
+
+		let signer = recover_signer(header)?;
+		let expected_difficulty: U256 = if snapshot.is_in_turn(header.number(), &signer) { 2.into() } else { 1.into() };
+		if *header.difficulty() != expected_difficulty {
+			return Err(From::from(BlockError::InvalidDifficulty(Mismatch { expected: expected_difficulty, found: header.difficulty().clone() })));
+		}
+
+		snapshot.apply(header.number(), header.hash(), signer, header_vote(header)?, self.epoch)?;
+		self.snapshots.lock().insert(header.hash(), snapshot);
+		Ok(())
+	}
+
+	fn register_client(&self, client: Weak<Client>) {
+		*self.client.write() = Some(client);
+	}
+
+	fn set_signer(&self, ap: Arc<AccountProvider>, address: Address, password: String) {
+		self.signer.write().set(ap, address, password);
+	}
+
+	fn sign(&self, hash: H256) -> Result<Signature, Error> {
+		self.signer.read().sign(hash).map_err(Into::into)
+	}
+
+	fn stop(&self) {
+		self.step_service.stop()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+	use util::*;
+	use block::*;
+	use error::{BlockError, Error};
+	use tests::helpers::*;
+	use account_provider::AccountProvider;
+	use header::Header;
+	use spec::Spec;
+	use engines::{Engine, Seal, EngineError};
+	use rlp::encode;
+	use super::{Clique, CliqueParams, NONCE_AUTH_VOTE, VANITY_LENGTH, SIGNATURE_LENGTH};
+
+	fn signed_header(tap: &AccountProvider, signer: Address, parent: &Header, difficulty: u64, vote: Option<(Address, bool)>) -> Header {
+		let mut header = Header::default();
+		header.set_parent_hash(parent.hash());
+		header.set_number(parent.number() + 1);
+		header.set_timestamp(parent.timestamp() + 1);
+		header.set_gas_limit(parent.gas_limit().clone());
+		header.set_difficulty(difficulty.into());
+		let nonce = match vote {
+			Some((address, authorize)) => {
+				header.set_author(address);
+				if authorize { NONCE_AUTH_VOTE } else { [0; 8] }
+			},
+			None => [0; 8],
+		};
+		header.set_seal(vec![encode(&H256::default()).into_vec(), encode(&H64::from(&nonce[..])).into_vec()]);
+
+		let mut extra = vec![0u8; VANITY_LENGTH + SIGNATURE_LENGTH];
+		header.set_extra_data(extra.clone());
+		let signature = tap.sign(signer, Some("".into()), super::seal_hash(&header).unwrap()).unwrap();
+		extra[VANITY_LENGTH..].copy_from_slice(&H520::from(signature));
+		header.set_extra_data(extra);
+		header
+	}
+
+	#[test]
+	fn has_valid_metadata() {
+		let engine = Spec::new_test_clique().engine;
+		assert!(!engine.name().is_empty());
+		assert!(engine.version().major >= 1);
+	}
+
+	#[test]
+	fn can_do_seal_verification_fail() {
+		let engine = Spec::new_test_clique().engine;
+		let mut header: Header = Header::default();
+		header.set_number(1);
+
+		match engine.verify_block_basic(&header, None) {
+			Err(Error::Block(BlockError::InvalidSealArity(_))) => {},
+			other => panic!("should be block seal-arity mismatch error (got {:?})", other),
+		}
+
+		header.set_seal(vec![encode(&H256::default()).into_vec(), encode(&H64::default()).into_vec()]);
+		match engine.verify_block_basic(&header, None) {
+			Err(Error::Engine(EngineError::CliqueMissingVanity)) => {},
+			other => panic!("should be missing vanity error (got {:?})", other),
+		}
+	}
+
+	#[test]
+	fn verifies_signed_blocks_in_turn() {
+		let tap = AccountProvider::transient_provider();
+		let v0 = tap.insert_account("0".sha3().into(), "").unwrap();
+		let v1 = tap.insert_account("1".sha3().into(), "").unwrap();
+
+		let spec = Spec::new_test_clique();
+		let engine = &*spec.engine;
+		let genesis = spec.genesis_header();
+
+		// signers are sorted, so `v0` is in turn for odd blocks
+		let header1 = signed_header(&tap, v0, &genesis, 2, None);
+		assert!(engine.verify_block_basic(&header1, None).is_ok());
+		assert!(engine.verify_block_family(&header1, &genesis, None).is_ok());
+
+		// `v0` may not sign again right away
+		let header2 = signed_header(&tap, v0, &header1, 1, None);
+		assert!(engine.verify_block_family(&header2, &header1, None).is_err());
+
+		// wrong difficulty for the in-turn signer
+		let header2 = signed_header(&tap, v1, &header1, 1, None);
+		match engine.verify_block_family(&header2, &header1, None) {
+			Err(Error::Block(BlockError::InvalidDifficulty(_))) => {},
+			other => panic!("should be difficulty mismatch (got {:?})", other),
+		}
+
+		let header2 = signed_header(&tap, v1, &header1, 2, None);
+		assert!(engine.verify_block_family(&header2, &header1, None).is_ok());
+	}
+
+	#[test]
+	fn rejects_unauthorized_signer() {
+		let tap = AccountProvider::transient_provider();
+		let outsider = tap.insert_account("2".sha3().into(), "").unwrap();
+
+		let spec = Spec::new_test_clique();
+		let engine = &*spec.engine;
+		let genesis = spec.genesis_header();
+
+		let header = signed_header(&tap, outsider, &genesis, 1, None);
+		match engine.verify_block_family(&header, &genesis, None) {
+			Err(Error::Engine(EngineError::NotAuthorized(a))) => assert_eq!(a, outsider),
+			other => panic!("should be not authorized error (got {:?})", other),
+		}
+	}
+
+	#[test]
+	fn votes_are_counted() {
+		let tap = AccountProvider::transient_provider();
+		let v0 = tap.insert_account("0".sha3().into(), "").unwrap();
+		let v1 = tap.insert_account("1".sha3().into(), "").unwrap();
+		let candidate = tap.insert_account("2".sha3().into(), "").unwrap();
+
+		let spec = Spec::new_test_clique();
+		let engine = &*spec.engine;
+		let genesis = spec.genesis_header();
+
+		let header1 = signed_header(&tap, v0, &genesis, 2, Some((candidate, true)));
+		engine.verify_block_family(&header1, &genesis, None).unwrap();
+		let header2 = signed_header(&tap, v1, &header1, 2, Some((candidate, true)));
+		engine.verify_block_family(&header2, &header1, None).unwrap();
+
+		// the candidate is now a signer
+		let header3 = signed_header(&tap, candidate, &header2, 1, None);
+		engine.verify_block_family(&header3, &header2, None).unwrap();
+	}
+
+	#[test]
+	fn can_generate_seal() {
+		let tap = Arc::new(AccountProvider::transient_provider());
+		let v0 = tap.insert_account("0".sha3().into(), "").unwrap();
+
+		let spec = Spec::new_test_clique();
+		let engine = &*spec.engine;
+		engine.set_signer(tap.clone(), v0, "".into());
+		let genesis_header = spec.genesis_header();
+		let db = spec.ensure_db_good(get_temp_state_db(), &Default::default()).unwrap();
+		let last_hashes = Arc::new(vec![genesis_header.hash()]);
+		let b = OpenBlock::new(engine, Default::default(), false, db, &genesis_header, last_hashes, v0, (3141562.into(), 31415620.into()), vec![], false).unwrap();
+		assert_eq!(b.block().header().difficulty(), &U256::from(2));
+		assert_eq!(b.block().header().extra_data().len(), VANITY_LENGTH + SIGNATURE_LENGTH);
+
+		let b = b.close_and_lock();
+		// no client registered, parent can't be looked up
+		assert_eq!(engine.generate_seal(b.block(), &genesis_header), Seal::None);
+
+		let seal = vec![encode(&H256::default()).into_vec(), encode(&H64::default()).into_vec()];
+		let sealed = b.try_seal(engine, seal).map_err(|(e, _)| e).unwrap();
+		assert_eq!(super::recover_signer(sealed.header()).unwrap(), v0);
+	}
+
+	#[test]
+	fn prepared_block_carries_vote() {
+		let tap = Arc::new(AccountProvider::transient_provider());
+		let v0 = tap.insert_account("0".sha3().into(), "").unwrap();
+		let candidate = tap.insert_account("2".sha3().into(), "").unwrap();
+
+		let spec = Spec::new_test_clique();
+		let clique = Clique::new(spec.params().clone(), CliqueParams { period: 1, epoch: 30000 }, spec.engine.builtins().clone()).unwrap();
+		clique.set_signer(tap.clone(), v0, "".into());
+		let genesis_header = spec.genesis_header();
+		let db = spec.ensure_db_good(get_temp_state_db(), &Default::default()).unwrap();
+		let last_hashes = Arc::new(vec![genesis_header.hash()]);
+
+		clique.propose(candidate, true);
+		let b = OpenBlock::new(&*clique, Default::default(), false, db, &genesis_header, last_hashes, v0, (3141562.into(), 31415620.into()), vec![], false).unwrap();
+		clique.discard(&candidate);
+
+		let header = b.block().header().clone();
+		assert_eq!(header.author(), &candidate);
+		assert_eq!(super::header_vote(&header).unwrap(), Some((candidate, true)));
+	}
+
+	#[test]
+	fn seals_internally() {
+		let tap = AccountProvider::transient_provider();
+		let authority = tap.insert_account("0".sha3().into(), "").unwrap();
+
+		let engine = Spec::new_test_clique().engine;
+		assert!(!engine.seals_internally().unwrap());
+		engine.set_signer(Arc::new(tap), authority, "".into());
+		assert!(engine.seals_internally().unwrap());
+	}
+}
@@ -0,0 +1,278 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Clique signer set and vote tally at a given block.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use util::*;
+use engines::EngineError;
+use error::Error;
+use header::BlockNumber;
+
+/// A single vote cast by a signer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Vote {
+	/// Signer who cast the vote.
+	pub signer: Address,
+	/// Block at which the vote was cast.
+	pub block: BlockNumber,
+	/// Address being voted on.
+	pub address: Address,
+	/// Whether to authorize or deauthorize the voted address.
+	pub authorize: bool,
+}
+
+/// Running tally of votes for a single address.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tally {
+	/// Whether the vote is about authorizing or kicking someone.
+	pub authorize: bool,
+	/// Number of votes until now wanting to pass the proposal.
+	pub votes: usize,
+}
+
+/// State of the signer set after a given block.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignerSnapshot {
+	/// Number of the block the snapshot was taken at.
+	pub number: BlockNumber,
+	/// Hash of the block the snapshot was taken at.
+	pub hash: H256,
+	/// Set of authorized signers.
+	pub signers: BTreeSet<Address>,
+	/// Recent signers, used for spam protection.
+	pub recents: BTreeMap<BlockNumber, Address>,
+	/// Votes in chronological order.
+	pub votes: Vec<Vote>,
+	/// Current vote tally.
+	pub tally: HashMap<Address, Tally>,
+}
+
+impl SignerSnapshot {
+	/// Create a snapshot with the given signers and no pending votes, e.g. at a checkpoint.
+	pub fn new(number: BlockNumber, hash: H256, signers: Vec<Address>) -> Self {
+		SignerSnapshot {
+			number: number,
+			hash: hash,
+			signers: signers.into_iter().collect(),
+			recents: BTreeMap::new(),
+			votes: Vec::new(),
+			tally: HashMap::new(),
+		}
+	}
+
+	/// Authorized signers in ascending order.
+	pub fn signers(&self) -> Vec<Address> {
+		self.signers.iter().cloned().collect()
+	}
+
+	/// Number of most recent blocks within which a signer may only sign once.
+	pub fn signer_limit(&self) -> u64 {
+		(self.signers.len() / 2 + 1) as u64
+	}
+
+	/// Whether `signer` is the in-turn signer of block `number`.
+	pub fn is_in_turn(&self, number: BlockNumber, signer: &Address) -> bool {
+		if self.signers.is_empty() {
+			return false;
+		}
+		let index = (number % self.signers.len() as u64) as usize;
+		self.signers.iter().nth(index) == Some(signer)
+	}
+
+	/// Whether `signer` has signed too recently to be allowed to sign block `number`.
+	pub fn recently_signed(&self, number: BlockNumber, signer: &Address) -> bool {
+		let limit = self.signer_limit();
+		self.recents.iter().any(|(seen, recent)| recent == signer && (number < limit || *seen > number - limit))
+	}
+
+	/// Whether a vote makes sense in the current state, i.e. it would change the signer set.
+	pub fn is_valid_vote(&self, address: &Address, authorize: bool) -> bool {
+		self.signers.contains(address) != authorize
+	}
+
+	fn cast(&mut self, address: &Address, authorize: bool) -> bool {
+		if !self.is_valid_vote(address, authorize) {
+			return false;
+		}
+		let tally = self.tally.entry(address.clone()).or_insert(Tally { authorize: authorize, votes: 0 });
+		tally.votes += 1;
+		true
+	}
+
+	fn uncast(&mut self, address: &Address, authorize: bool) {
+		let remove = match self.tally.get_mut(address) {
+			Some(tally) if tally.authorize == authorize => {
+				tally.votes -= 1;
+				tally.votes == 0
+			},
+			_ => false,
+		};
+		if remove {
+			self.tally.remove(address);
+		}
+	}
+
+	/// Apply a block signed by `signer` on top of this snapshot.
+	///
+	/// `vote` is the address voted on together with the direction of the vote,
+	/// or `None` if the block carries no vote.
+	pub fn apply(&mut self, number: BlockNumber, hash: H256, signer: Address, vote: Option<(Address, bool)>, epoch: u64) -> Result<(), Error> {
+		// all votes are discarded at checkpoints
+		if number % epoch == 0 {
+			self.votes.clear();
+			self.tally.clear();
+		}
+
+		// let the oldest signer sign again
+		let limit = self.signer_limit();
+		if number >= limit {
+			self.recents.remove(&(number - limit));
+		}
+
+		if !self.signers.contains(&signer) {
+			return Err(EngineError::NotAuthorized(signer).into());
+		}
+		if self.recently_signed(number, &signer) {
+			return Err(EngineError::CliqueTooRecentlySigned(signer).into());
+		}
+		self.recents.insert(number, signer.clone());
+
+		if let Some((address, authorize)) = vote {
+			// discard any previous vote of the signer on the same address
+			if let Some(index) = self.votes.iter().position(|v| v.signer == signer && v.address == address) {
+				let previous = self.votes.remove(index);
+				self.uncast(&previous.address, previous.authorize);
+			}
+
+			if self.cast(&address, authorize) {
+				self.votes.push(Vote {
+					signer: signer,
+					block: number,
+					address: address.clone(),
+					authorize: authorize,
+				});
+			}
+
+			let passed = self.tally.get(&address).map_or(false, |tally| tally.votes > self.signers.len() / 2);
+			if passed {
+				if authorize {
+					self.signers.insert(address.clone());
+				} else {
+					self.signers.remove(&address);
+
+					// signer list shrunk, let the next oldest signer sign again
+					let limit = self.signer_limit();
+					if number >= limit {
+						self.recents.remove(&(number - limit));
+					}
+
+					// discard any votes the deauthorized signer cast
+					let (cast, kept): (Vec<_>, Vec<_>) = self.votes.drain(..).partition(|v| v.signer == address);
+					self.votes = kept;
+					for v in cast {
+						self.uncast(&v.address, v.authorize);
+					}
+				}
+
+				// discard any pending votes around the just changed account
+				self.votes.retain(|v| v.address != address);
+				self.tally.remove(&address);
+			}
+		}
+
+		self.number = number;
+		self.hash = hash;
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use util::*;
+	use super::SignerSnapshot;
+
+	fn addr(n: u64) -> Address {
+		Address::from(n)
+	}
+
+	fn snapshot(signers: &[u64]) -> SignerSnapshot {
+		SignerSnapshot::new(0, H256::default(), signers.iter().cloned().map(addr).collect())
+	}
+
+	#[test]
+	fn in_turn_signer_rotates() {
+		let snap = snapshot(&[1, 2, 3]);
+		assert!(snap.is_in_turn(0, &addr(1)));
+		assert!(snap.is_in_turn(1, &addr(2)));
+		assert!(snap.is_in_turn(5, &addr(3)));
+		assert!(!snap.is_in_turn(5, &addr(1)));
+	}
+
+	#[test]
+	fn rejects_recent_and_unauthorized_signers() {
+		let mut snap = snapshot(&[1, 2, 3]);
+		snap.apply(1, H256::default(), addr(1), None, 30000).unwrap();
+		assert!(snap.apply(2, H256::default(), addr(1), None, 30000).is_err());
+		assert!(snap.apply(2, H256::default(), addr(4), None, 30000).is_err());
+		snap.apply(2, H256::default(), addr(2), None, 30000).unwrap();
+		snap.apply(3, H256::default(), addr(1), None, 30000).unwrap();
+	}
+
+	#[test]
+	fn single_signer_authorizes_new_signer() {
+		let mut snap = snapshot(&[1]);
+		snap.apply(1, H256::default(), addr(1), Some((addr(2), true)), 30000).unwrap();
+		assert_eq!(snap.signers(), vec![addr(1), addr(2)]);
+		assert!(snap.votes.is_empty());
+		assert!(snap.tally.is_empty());
+	}
+
+	#[test]
+	fn majority_is_required_to_pass_vote() {
+		let mut snap = snapshot(&[1, 2, 3]);
+		snap.apply(1, H256::default(), addr(1), Some((addr(4), true)), 30000).unwrap();
+		assert_eq!(snap.signers.len(), 3);
+		// a repeated vote by the same signer is not counted twice
+		snap.apply(3, H256::default(), addr(1), Some((addr(4), true)), 30000).unwrap();
+		assert_eq!(snap.signers.len(), 3);
+		snap.apply(4, H256::default(), addr(2), Some((addr(4), true)), 30000).unwrap();
+		assert_eq!(snap.signers(), vec![addr(1), addr(2), addr(3), addr(4)]);
+	}
+
+	#[test]
+	fn deauthorized_signer_votes_are_discarded() {
+		let mut snap = snapshot(&[1, 2, 3]);
+		// signer 3 votes to add 5
+		snap.apply(1, H256::default(), addr(3), Some((addr(5), true)), 30000).unwrap();
+		// signers 1 and 2 kick signer 3
+		snap.apply(2, H256::default(), addr(1), Some((addr(3), false)), 30000).unwrap();
+		snap.apply(3, H256::default(), addr(2), Some((addr(3), false)), 30000).unwrap();
+		assert_eq!(snap.signers(), vec![addr(1), addr(2)]);
+		assert!(snap.tally.get(&addr(5)).is_none());
+		assert!(snap.votes.is_empty());
+	}
+
+	#[test]
+	fn checkpoint_resets_votes() {
+		let mut snap = snapshot(&[1, 2, 3]);
+		snap.apply(1, H256::default(), addr(1), Some((addr(4), true)), 3).unwrap();
+		assert_eq!(snap.votes.len(), 1);
+		snap.apply(3, H256::default(), addr(2), None, 3).unwrap();
+		assert!(snap.votes.is_empty());
+		assert!(snap.tally.is_empty());
+	}
+}
@@ -18,6 +18,7 @@
 
 mod authority_round;
 mod basic_authority;
+mod clique;
 mod instant_seal;
 mod null_engine;
 mod signer;
@@ -26,10 +27,12 @@ mod transition;
 mod validator_set;
 mod vote_collector;
 
+pub mod block_reward;
 pub mod epoch;
 
 pub use self::authority_round::AuthorityRound;
 pub use self::basic_authority::BasicAuthority;
+pub use self::clique::Clique;
 pub use self::epoch::{EpochVerifier, Transition as EpochTransition};
 pub use self::instant_seal::InstantSeal;
 pub use self::null_engine::NullEngine;
@@ -79,6 +82,22 @@ pub enum EngineError {
 	FailedSystemCall(String),
 	/// Requires client ref, but none registered.
 	RequiresClient,
+	/// Clique: signer snapshot could not be rebuilt, ancestor is missing.
+	CliqueMissingCheckpoint(H256),
+	/// Clique: extra data is missing the signer vanity.
+	CliqueMissingVanity,
+	/// Clique: extra data is missing the signer signature.
+	CliqueMissingSignature,
+	/// Clique: invalid length of the checkpoint signer list.
+	CliqueCheckpointNumberOfSigners(usize),
+	/// Clique: checkpoint signer list does not match the current signers.
+	CliqueCheckpointInvalidSigners(Vec<Address>),
+	/// Clique: checkpoint block casts a vote.
+	CliqueWrongAuthorCheckpoint(Mismatch<Address>),
+	/// Clique: nonce is neither an authorize nor a drop vote.
+	CliqueInvalidNonce(H64),
+	/// Clique: signer signed one of the most recent blocks.
+	CliqueTooRecentlySigned(Address),
 }
 
 impl fmt::Display for EngineError {
@@ -93,6 +112,14 @@ impl fmt::Display for EngineError {
 			InsufficientProof(ref msg) => format!("Insufficient validation proof: {}", msg),
 			FailedSystemCall(ref msg) => format!("Failed to make system call: {}", msg),
 			RequiresClient => format!("Call requires client but none registered"),
+			CliqueMissingCheckpoint(ref hash) => format!("Missing ancestor {} to build Clique signer snapshot", hash),
+			CliqueMissingVanity => format!("Extra data is missing the 32 byte signer vanity"),
+			CliqueMissingSignature => format!("Extra data is missing the 65 byte signer signature"),
+			CliqueCheckpointNumberOfSigners(len) => format!("Checkpoint signer list has invalid length: {} bytes", len),
+			CliqueCheckpointInvalidSigners(ref signers) => format!("Checkpoint signer list does not match current signers: {:?}", signers),
+			CliqueWrongAuthorCheckpoint(ref mis) => format!("Checkpoint block must not vote: {}", mis),
+			CliqueInvalidNonce(ref nonce) => format!("Nonce is not a valid vote: {}", nonce),
+			CliqueTooRecentlySigned(ref address) => format!("Signer {} signed a block too recently", address),
 		};
 
 		f.write_fmt(format_args!("Engine error ({})", msg))
@@ -179,8 +206,8 @@ pub trait Engine : Sync + Send {
 	/// The version of this engine. Should be of the form
 	fn version(&self) -> SemanticVersion { SemanticVersion::new(0, 0, 0) }
 
-	/// The number of additional header fields required for this engine.
-	fn seal_fields(&self) -> usize { 0 }
+	/// The number of additional header fields required for this engine to seal the given header.
+	fn seal_fields(&self, _header: &Header) -> usize { 0 }
 
 	/// Additional engine-specific information for the user/developer concerning `header`.
 	fn extra_info(&self, _header: &Header) -> BTreeMap<String, String> { BTreeMap::new() }
@@ -232,17 +259,23 @@ pub trait Engine : Sync + Send {
 		Ok(())
 	}
 
+	/// Block transformation functions, after the seal fields of a locally sealed block have been set.
+	/// Used by engines which keep the block signature outside of the seal fields.
+	fn on_seal_block(&self, _block: &mut ExecutedBlock) -> Result<(), Error> {
+		Ok(())
+	}
+
 	/// None means that it requires external input (e.g. PoW) to seal a block.
 	/// Some(true) means the engine is currently prime for seal generation (i.e. node is the current validator).
 	/// Some(false) means that the node might seal internally but is not qualified now.
 	fn seals_internally(&self) -> Option<bool> { None }
-	/// Attempt to seal the block internally.
+	/// Attempt to seal the block internally, given the header of its parent.
 	///
 	/// If `Some` is returned, then you get a valid seal.
 	///
 	/// This operation is synchronous and may (quite reasonably) not be available, in which None will
 	/// be returned.
-	fn generate_seal(&self, _block: &ExecutedBlock) -> Seal { Seal::None }
+	fn generate_seal(&self, _block: &ExecutedBlock, _parent: &Header) -> Seal { Seal::None }
 
 	/// Phase 1 quick block verification. Only does checks that are cheap. `block` (the header's full block)
 	/// may be provided for additional checks. Returns either a null `Ok` or a general error detailing the problem with import.
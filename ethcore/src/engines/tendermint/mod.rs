@@ -24,6 +24,7 @@
 
 mod message;
 mod params;
+mod wal;
 
 use std::sync::{Weak, Arc};
 use std::sync::atomic::{AtomicUsize, Ordering as AtomicOrdering};
@@ -48,6 +49,7 @@ use super::transition::TransitionHandler;
 use super::vote_collector::VoteCollector;
 use self::message::*;
 use self::params::TendermintParams;
+use self::wal::{Wal, RoundState};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub enum Step {
@@ -98,6 +100,10 @@ pub struct Tendermint {
 	last_proposed: RwLock<H256>,
 	/// Set used to determine the current validators.
 	validators: Box<ValidatorSet>,
+	/// Messages signed by this validator at the current height.
+	own_messages: RwLock<Vec<ConsensusMessage>>,
+	/// Persistent log of the round state, available once the client is registered.
+	wal: RwLock<Option<Wal>>,
 }
 
 struct EpochVerifier<F>
@@ -179,6 +185,8 @@ impl Tendermint {
 				proposal_parent: Default::default(),
 				last_proposed: Default::default(),
 				validators: our_params.validators,
+				own_messages: RwLock::new(Vec::new()),
+				wal: RwLock::new(None),
 			});
 		let handler = TransitionHandler::new(Arc::downgrade(&engine) as Weak<Engine>, Box::new(our_params.timeouts));
 		engine.step_service.register_handler(Arc::new(handler))?;
@@ -209,15 +217,88 @@ impl Tendermint {
 		}
 	}
 
+	/// Message already signed by this validator at the given step.
+	fn own_message(&self, vote_step: &VoteStep) -> Option<ConsensusMessage> {
+		self.own_messages.read().iter().find(|m| &m.vote_step == vote_step).cloned()
+	}
+
+	/// Snapshot of the current round state.
+	fn round_state(&self) -> RoundState {
+		RoundState {
+			height: self.height.load(AtomicOrdering::SeqCst),
+			view: self.view.load(AtomicOrdering::SeqCst),
+			step: *self.step.read(),
+			last_lock: self.last_lock.load(AtomicOrdering::SeqCst),
+			lock_change: self.lock_change.read().clone(),
+			proposal: self.proposal.read().clone(),
+			proposal_parent: self.proposal_parent.read().clone(),
+			last_proposed: self.last_proposed.read().clone(),
+			own_messages: self.own_messages.read().clone(),
+		}
+	}
+
+	/// Write the round state to the log, if there is one.
+	///
+	/// Nothing signed may leave the engine unless this succeeds.
+	fn persist_round_state(&self) -> Result<(), String> {
+		match *self.wal.read() {
+			Some(ref wal) => wal.write(&self.round_state()).map_err(|e| {
+				warn!(target: "engine", "Could not write round state: {}", e);
+				e
+			}),
+			None => Ok(()),
+		}
+	}
+
+	/// Restore the round state written before the last shutdown.
+	fn restore_round_state(&self, state: RoundState) {
+		let height = self.height.load(AtomicOrdering::SeqCst);
+		// Own messages from the current height onwards must never be contradicted.
+		let own_messages: Vec<_> = state.own_messages.into_iter().filter(|m| m.vote_step.height >= height).collect();
+		for message in &own_messages {
+			match message.verify() {
+				Ok(validator) => { self.votes.vote(message.clone(), &validator); },
+				Err(e) => warn!(target: "engine", "Could not restore own message {:?}: {}", message, e),
+			}
+		}
+		*self.own_messages.write() = own_messages;
+
+		if state.height != height {
+			debug!(target: "engine", "Discarding round state of height {}, current height is {}.", state.height, height);
+			return;
+		}
+		debug!(target: "engine", "Restoring round state at height {} view {} step {:?}.", state.height, state.view, state.step);
+		self.view.store(state.view, AtomicOrdering::SeqCst);
+		self.last_lock.store(state.last_lock, AtomicOrdering::SeqCst);
+		*self.step.write() = state.step;
+		*self.lock_change.write() = state.lock_change;
+		*self.proposal.write() = state.proposal;
+		*self.proposal_parent.write() = state.proposal_parent;
+		*self.last_proposed.write() = state.last_proposed;
+	}
+
 	fn generate_message(&self, block_hash: Option<BlockHash>) -> Option<Bytes> {
 		let h = self.height.load(AtomicOrdering::SeqCst);
 		let r = self.view.load(AtomicOrdering::SeqCst);
 		let s = *self.step.read();
+		// Never sign a second message for the same step, e.g. after a restart.
+		if let Some(message) = self.own_message(&VoteStep::new(h, r, s)) {
+			debug!(target: "engine", "Reusing own {:?}.", message);
+			self.handle_valid_message(&message);
+			return Some(::rlp::encode(&message).into_vec());
+		}
 		let vote_info = message_info_rlp(&VoteStep::new(h, r, s), block_hash);
 		match (self.signer.read().address(), self.sign(vote_info.sha3()).map(Into::into)) {
 			(Some(validator), Ok(signature)) => {
 				let message_rlp = message_full_rlp(&signature, &vote_info);
 				let message = ConsensusMessage::new(signature, h, r, s, block_hash);
+				// Log the message before it can be seen by anyone.
+				self.own_messages.write().push(message.clone());
+				if self.persist_round_state().is_err() {
+					self.own_messages.write().retain(|m| m != &message);
+					warn!(target: "engine", "Not sending {:?}, since it could not be logged.", message);
+					return None;
+				}
 				self.votes.vote(message.clone(), &validator);
 				debug!(target: "engine", "Generated {:?} as {}.", message, validator);
 				self.handle_valid_message(&message);
@@ -256,6 +337,8 @@ impl Tendermint {
 		self.view.store(0, AtomicOrdering::SeqCst);
 		*self.lock_change.write() = None;
 		*self.proposal.write() = None;
+		// Keep messages of the finished height in case its block does not get imported.
+		self.own_messages.write().retain(|m| m.vote_step.height >= height);
 	}
 
 	/// Use via step_service to transition steps.
@@ -264,6 +347,8 @@ impl Tendermint {
 			warn!(target: "engine", "Could not proceed to step {}.", io_err)
 		}
 		*self.step.write() = step;
+		// Signing at the new step persists the state again and refuses to proceed on failure.
+		let _ = self.persist_round_state();
 		match step {
 			Step::Propose => {
 				self.update_sealing()
@@ -384,6 +469,8 @@ impl Tendermint {
 		if lock_change {
 			trace!(target: "engine", "handle_valid_message: Lock change.");
 			*self.lock_change.write() = Some(message.clone());
+			// Any precommit relying on the lock is only sent once the lock is logged with it.
+			let _ = self.persist_round_state();
 		}
 		// Check if it can affect the step transition.
 		if self.is_height(message) {
@@ -438,7 +525,7 @@ impl Engine for Tendermint {
 	fn version(&self) -> SemanticVersion { SemanticVersion::new(1, 0, 0) }
 
 	/// (consensus view, proposal signature, authority signatures)
-	fn seal_fields(&self) -> usize { 3 }
+	fn seal_fields(&self, _header: &Header) -> usize { 3 }
 
 	fn params(&self) -> &CommonParams { &self.params }
 
@@ -487,7 +574,7 @@ impl Engine for Tendermint {
 	///
 	/// This operation is synchronous and may (quite reasonably) not be available, in which case
 	/// `Seal::None` will be returned.
-	fn generate_seal(&self, block: &ExecutedBlock) -> Seal {
+	fn generate_seal(&self, block: &ExecutedBlock, _parent: &Header) -> Seal {
 		let header = block.header();
 		let author = header.author();
 		// Only proposer can generate seal if None was generated.
@@ -497,17 +584,34 @@ impl Engine for Tendermint {
 
 		let height = header.number() as Height;
 		let view = self.view.load(AtomicOrdering::SeqCst);
+		// Do not propose a different block in a view which already has own proposal.
+		if self.own_message(&VoteStep::new(height, view, Step::Propose)).is_some() {
+			return Seal::None;
+		}
 		let bh = Some(header.bare_hash());
 		let vote_info = message_info_rlp(&VoteStep::new(height, view, Step::Propose), bh.clone());
 		if let Ok(signature) = self.sign(vote_info.sha3()).map(Into::into) {
 			// Insert Propose vote.
 			debug!(target: "engine", "Submitting proposal {} at height {} view {}.", header.bare_hash(), height, view);
-			self.votes.vote(ConsensusMessage::new(signature, height, view, Step::Propose, bh), author);
+			let proposal = ConsensusMessage::new(signature, height, view, Step::Propose, bh);
+			let last_proposed = self.last_proposed.read().clone();
+			let proposal_parent = self.proposal_parent.read().clone();
+			self.own_messages.write().push(proposal.clone());
 			// Remember the owned block.
 			*self.last_proposed.write() = header.bare_hash();
 			// Remember proposal for later seal submission.
 			*self.proposal.write() = bh;
 			*self.proposal_parent.write() = header.parent_hash().clone();
+			if self.persist_round_state().is_err() {
+				// Undo the proposal, it must not be sealed unless it is logged.
+				self.own_messages.write().retain(|m| m != &proposal);
+				*self.last_proposed.write() = last_proposed;
+				*self.proposal.write() = None;
+				*self.proposal_parent.write() = proposal_parent;
+				warn!(target: "engine", "generate_seal: proposal could not be logged, not sealing.");
+				return Seal::None;
+			}
+			self.votes.vote(proposal, author);
 			Seal::Proposal(vec![
 				::rlp::encode(&view).into_vec(),
 				::rlp::encode(&signature).into_vec(),
@@ -556,7 +660,7 @@ impl Engine for Tendermint {
 
 	fn verify_block_basic(&self, header: &Header, _block: Option<&[u8]>) -> Result<(), Error> {
 		let seal_length = header.seal().len();
-		if seal_length == self.seal_fields() {
+		if seal_length == self.seal_fields(header) {
 			// Either proposal or commit.
 			if (header.seal()[1] == ::rlp::NULL_RLP)
 				!= (header.seal()[2] == ::rlp::EMPTY_LIST_RLP) {
@@ -567,7 +671,7 @@ impl Engine for Tendermint {
 			}
 		} else {
 			Err(BlockError::InvalidSealArity(
-				Mismatch { expected: self.seal_fields(), found: seal_length }
+				Mismatch { expected: self.seal_fields(header), found: seal_length }
 			).into())
 		}
 	}
@@ -763,6 +867,11 @@ impl Engine for Tendermint {
 		use client::BlockChainClient;
 		if let Some(c) = client.upgrade() {
 			self.height.store(c.chain_info().best_block_number as usize + 1, AtomicOrdering::SeqCst);
+			let wal = Wal::new(c.db());
+			if let Some(state) = wal.read() {
+				self.restore_round_state(state);
+			}
+			*self.wal.write() = Some(wal);
 		}
 		*self.client.write() = Some(client.clone());
 		self.validators.register_contract(client);
@@ -800,7 +909,7 @@ mod tests {
 		let last_hashes = Arc::new(vec![genesis_header.hash()]);
 		let b = OpenBlock::new(spec.engine.as_ref(), Default::default(), false, db.boxed_clone(), &genesis_header, last_hashes, proposer, (3141562.into(), 31415620.into()), vec![], false).unwrap();
 		let b = b.close();
-		if let Seal::Proposal(seal) = spec.engine.generate_seal(b.block()) {
+		if let Seal::Proposal(seal) = spec.engine.generate_seal(b.block(), &genesis_header) {
 			(b, seal)
 		} else {
 			panic!()
@@ -848,6 +957,41 @@ mod tests {
 		}
 	}
 
+	/// Broadcast messages of `signer` at the given step.
+	fn broadcast_votes(notify: &TestNotify, signer: &Address, vote_step: &VoteStep) -> Vec<ConsensusMessage> {
+		notify.messages.read().iter()
+			.map(|m| ::rlp::decode::<ConsensusMessage>(m))
+			.filter(|m| &m.vote_step == vote_step && m.verify().ok().as_ref() == Some(signer))
+			.collect()
+	}
+
+	/// Database on which every write fails.
+	struct FailingDB;
+
+	impl KeyValueDB for FailingDB {
+		fn get(&self, _col: Option<u32>, _key: &[u8]) -> Result<Option<DBValue>, String> { Ok(None) }
+		fn get_by_prefix(&self, _col: Option<u32>, _prefix: &[u8]) -> Option<Box<[u8]>> { None }
+		fn write_buffered(&self, _transaction: DBTransaction) {}
+		fn flush(&self) -> Result<(), String> { Err("disk full".into()) }
+		fn iter<'a>(&'a self, _col: Option<u32>) -> Box<Iterator<Item=(Box<[u8]>, Box<[u8]>)> + 'a> {
+			Box::new(::std::iter::empty())
+		}
+		fn iter_from_prefix<'a>(&'a self, _col: Option<u32>, _prefix: &'a [u8]) -> Box<Iterator<Item=(Box<[u8]>, Box<[u8]>)> + 'a> {
+			Box::new(::std::iter::empty())
+		}
+		fn restore(&self, _new_db: &str) -> Result<(), UtilError> { unimplemented!() }
+	}
+
+	/// Sealed proposal of "1" at height 1, view 0.
+	fn sealed_proposal(tap: &Arc<AccountProvider>) -> Header {
+		let spec = Spec::new_test_tendermint();
+		let proposer = insert_and_register(tap, spec.engine.as_ref(), "1");
+		let (b, seal) = propose_default(&spec, proposer);
+		let sealed = b.lock().seal(spec.engine.as_ref(), seal).unwrap();
+		spec.engine.stop();
+		sealed.header().clone()
+	}
+
 	#[test]
 	fn has_valid_metadata() {
 		let engine = Spec::new_test_tendermint().engine;
@@ -1069,6 +1213,128 @@ mod tests {
 		assert_eq!(client.chain_info().best_block_number, 1);
 	}
 
+	#[test]
+	fn keeps_prevote_after_restart_during_prevote() {
+		let (_, tap) = setup();
+		let v0 = insert_and_unlock(&tap, "0");
+		let proposal = sealed_proposal(&tap);
+		let prevote_step = VoteStep::new(1, 0, Step::Prevote);
+
+		let client = generate_dummy_client(0);
+		let notify = Arc::new(TestNotify::default());
+		client.add_notify(notify.clone());
+
+		let engine = Spec::new_test_tendermint().engine;
+		engine.register_client(Arc::downgrade(&client));
+		engine.set_signer(tap.clone(), v0, "0".into());
+		// Propose timeout without a proposal.
+		engine.step();
+		let prevotes = broadcast_votes(&notify, &v0, &prevote_step);
+		assert_eq!(prevotes.len(), 1);
+		assert_eq!(prevotes[0].block_hash, None);
+		engine.stop();
+
+		// Restart and receive the proposal late.
+		let engine = Spec::new_test_tendermint().engine;
+		engine.register_client(Arc::downgrade(&client));
+		engine.set_signer(tap.clone(), v0, "0".into());
+		assert!(engine.is_proposal(&proposal));
+		engine.step();
+
+		// The same prevote is sent again instead of one for the proposal.
+		let prevotes = broadcast_votes(&notify, &v0, &prevote_step);
+		assert_eq!(prevotes.len(), 2);
+		assert_eq!(prevotes[0], prevotes[1]);
+		engine.stop();
+	}
+
+	#[test]
+	fn keeps_lock_after_restart_during_precommit() {
+		let (_, tap) = setup();
+		let v0 = insert_and_unlock(&tap, "0");
+		let proposal = sealed_proposal(&tap);
+		let v1 = *proposal.author();
+		let bh = Some(proposal.bare_hash());
+
+		let client = generate_dummy_client(0);
+		let notify = Arc::new(TestNotify::default());
+		client.add_notify(notify.clone());
+
+		let engine = Spec::new_test_tendermint().engine;
+		engine.register_client(Arc::downgrade(&client));
+		engine.set_signer(tap.clone(), v0, "0".into());
+		assert!(engine.is_proposal(&proposal));
+		engine.step();
+		// Enough prevotes lock on the proposal and trigger a precommit.
+		vote(engine.as_ref(), |mh| tap.sign(v1, None, mh).map(H520::from), 1, 0, Step::Prevote, bh);
+		let precommits = broadcast_votes(&notify, &v0, &VoteStep::new(1, 0, Step::Precommit));
+		assert_eq!(precommits.len(), 1);
+		assert_eq!(precommits[0].block_hash, bh);
+		engine.stop();
+
+		// Restart without the proposal.
+		let engine = Spec::new_test_tendermint().engine;
+		engine.register_client(Arc::downgrade(&client));
+		engine.set_signer(tap.clone(), v0, "0".into());
+		vote(engine.as_ref(), |mh| tap.sign(v1, None, mh).map(H520::from), 1, 0, Step::Precommit, None);
+		engine.step();
+		vote(engine.as_ref(), |mh| tap.sign(v1, None, mh).map(H520::from), 1, 0, Step::Prevote, bh);
+		let precommits = broadcast_votes(&notify, &v0, &VoteStep::new(1, 0, Step::Precommit));
+		assert_eq!(precommits.len(), 2);
+		assert_eq!(precommits[0], precommits[1]);
+
+		// Precommit timeout moves to the next view, where the lock is still respected.
+		engine.step();
+		engine.step();
+		let prevotes = broadcast_votes(&notify, &v0, &VoteStep::new(1, 1, Step::Prevote));
+		assert_eq!(prevotes.len(), 1);
+		assert_eq!(prevotes[0].block_hash, bh);
+		engine.stop();
+	}
+
+	#[test]
+	fn does_not_sign_when_round_state_is_not_logged() {
+		let (spec, tap) = setup();
+		let v0 = insert_and_unlock(&tap, "0");
+		let v1 = insert_and_unlock(&tap, "1");
+
+		let client = generate_dummy_client(0);
+		let notify = Arc::new(TestNotify::default());
+		client.add_notify(notify.clone());
+
+		let new_engine = || {
+			let params = TendermintParams {
+				validators: Box::new(SimpleList::new(vec![v0, v1])),
+				timeouts: Default::default(),
+			};
+			let engine = Tendermint::new(spec.params().clone(), params, Default::default()).unwrap();
+			engine.register_client(Arc::downgrade(&client));
+			*engine.wal.write() = Some(Wal::new(Arc::new(FailingDB)));
+			engine
+		};
+
+		let engine = new_engine();
+		engine.set_signer(tap.clone(), v0, "0".into());
+
+		// Propose timeout would normally trigger a prevote.
+		engine.step();
+		assert!(broadcast_votes(&notify, &v0, &VoteStep::new(1, 0, Step::Prevote)).is_empty());
+		assert!(engine.own_messages.read().is_empty());
+
+		// Neither is a proposal sealed.
+		engine.stop();
+		let engine = new_engine();
+		engine.set_signer(tap.clone(), v1, "1".into());
+		let genesis_header = spec.genesis_header();
+		let db = spec.ensure_db_good(get_temp_state_db(), &Default::default()).unwrap();
+		let b = OpenBlock::new(&*engine, Default::default(), false, db, &genesis_header, Arc::new(vec![genesis_header.hash()]), v1, (3141562.into(), 31415620.into()), vec![], false).unwrap();
+		let b = b.close();
+		assert!(engine.generate_seal(b.block(), &genesis_header) == Seal::None);
+		assert!(engine.proposal.read().is_none());
+		assert!(engine.own_messages.read().is_empty());
+		engine.stop();
+	}
+
 	#[test]
 	fn epoch_verifier_verify_light() {
 		use ethkey::Error as EthkeyError;
@@ -0,0 +1,177 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Write-ahead log of the Tendermint round state.
+//!
+//! The state of the current round together with all messages signed by the local
+//! validator are written out before they take effect, so that a restarted validator
+//! keeps its lock and does not sign conflicting messages.
+
+use util::*;
+use rlp::{UntrustedRlp, RlpStream, Encodable, Decodable, DecoderError};
+use db::COL_NODE_INFO;
+use super::{Height, View, Step};
+use super::message::ConsensusMessage;
+
+/// Engine data shares the node info column, under keys with a dedicated prefix.
+const ROUND_STATE_KEY: &'static [u8] = b"ENGINE_TENDERMINT_ROUND_STATE";
+
+/// Round state of the local validator.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoundState {
+	/// Blockchain height.
+	pub height: Height,
+	/// Consensus view.
+	pub view: View,
+	/// Consensus step.
+	pub step: Step,
+	/// Last lock view.
+	pub last_lock: View,
+	/// Message for the last PoLC.
+	pub lock_change: Option<ConsensusMessage>,
+	/// Bare hash of the proposed block.
+	pub proposal: Option<H256>,
+	/// Hash of the proposal parent block.
+	pub proposal_parent: H256,
+	/// Last block proposed by this validator.
+	pub last_proposed: H256,
+	/// Messages signed by this validator which may still be relevant.
+	pub own_messages: Vec<ConsensusMessage>,
+}
+
+fn step_number(step: Step) -> u8 {
+	match step {
+		Step::Propose => 0,
+		Step::Prevote => 1,
+		Step::Precommit => 2,
+		Step::Commit => 3,
+	}
+}
+
+impl Encodable for RoundState {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		s.begin_list(9)
+			.append(&self.height)
+			.append(&self.view)
+			.append(&step_number(self.step))
+			.append(&self.last_lock);
+		match self.lock_change {
+			Some(ref lock) => s.begin_list(1).append(lock),
+			None => s.begin_list(0),
+		};
+		s.append(&self.proposal.unwrap_or_else(H256::zero))
+			.append(&self.proposal_parent)
+			.append(&self.last_proposed)
+			.append_list(&self.own_messages);
+	}
+}
+
+impl Decodable for RoundState {
+	fn decode(rlp: &UntrustedRlp) -> Result<Self, DecoderError> {
+		let step = match rlp.val_at::<u8>(2)? {
+			0 => Step::Propose,
+			1 => Step::Prevote,
+			2 => Step::Precommit,
+			3 => Step::Commit,
+			_ => return Err(DecoderError::Custom("Invalid step.")),
+		};
+		let proposal: H256 = rlp.val_at(5)?;
+
+		Ok(RoundState {
+			height: rlp.val_at(0)?,
+			view: rlp.val_at(1)?,
+			step: step,
+			last_lock: rlp.val_at(3)?,
+			lock_change: rlp.list_at(4)?.pop(),
+			proposal: match proposal.is_zero() {
+				true => None,
+				false => Some(proposal),
+			},
+			proposal_parent: rlp.val_at(6)?,
+			last_proposed: rlp.val_at(7)?,
+			own_messages: rlp.list_at(8)?,
+		})
+	}
+}
+
+/// Persistent storage of the round state.
+pub struct Wal {
+	db: Arc<KeyValueDB>,
+}
+
+impl Wal {
+	/// Create a new log backed by the node info column of the given database.
+	pub fn new(db: Arc<KeyValueDB>) -> Self {
+		Wal { db: db }
+	}
+
+	/// Read the last written round state.
+	pub fn read(&self) -> Option<RoundState> {
+		match self.db.get(COL_NODE_INFO, ROUND_STATE_KEY) {
+			Ok(Some(raw)) => match UntrustedRlp::new(&raw).as_val() {
+				Ok(state) => Some(state),
+				Err(e) => {
+					warn!(target: "engine", "Ignoring corrupted round state: {}", e);
+					None
+				},
+			},
+			Ok(None) => None,
+			Err(e) => {
+				warn!(target: "engine", "Could not read round state: {}", e);
+				None
+			},
+		}
+	}
+
+	/// Write out the round state, replacing the previous one.
+	pub fn write(&self, state: &RoundState) -> Result<(), String> {
+		let mut batch = DBTransaction::new();
+		batch.put_vec(COL_NODE_INFO, ROUND_STATE_KEY, ::rlp::encode(state).into_vec());
+		self.db.write(batch)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+	use util::*;
+	use super::super::Step;
+	use super::super::message::ConsensusMessage;
+	use super::*;
+
+	#[test]
+	fn round_state_roundtrip() {
+		let db = Arc::new(::util::kvdb::in_memory(::db::NUM_COLUMNS.unwrap_or(0)));
+		let wal = Wal::new(db.clone());
+		assert_eq!(wal.read(), None);
+
+		let lock = ConsensusMessage::new(H520::from(1), 3, 1, Step::Prevote, Some(H256::from(2)));
+		let state = RoundState {
+			height: 3,
+			view: 2,
+			step: Step::Commit,
+			last_lock: 1,
+			lock_change: Some(lock.clone()),
+			proposal: None,
+			proposal_parent: H256::from(4),
+			last_proposed: H256::from(5),
+			own_messages: vec![lock, ConsensusMessage::new(H520::from(6), 3, 1, Step::Precommit, None)],
+		};
+		wal.write(&state).unwrap();
+
+		assert_eq!(Wal::new(db).read(), Some(state));
+	}
+}
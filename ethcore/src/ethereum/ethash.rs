@@ -71,6 +71,8 @@ pub struct EthashParams {
 	pub difficulty_hardfork_bound_divisor: U256,
 	/// Block on which there is no additional difficulty from the exponential bomb.
 	pub bomb_defuse_transition: u64,
+	/// Delays of the exponential bomb, each applying from the given block onwards.
+	pub difficulty_bomb_delays: BTreeMap<BlockNumber, BlockNumber>,
 	/// Number of first block where EIP-100 rules begin.
 	pub eip100b_transition: u64,
 	/// Number of first block where EIP-150 rules begin.
@@ -87,6 +89,9 @@ pub struct EthashParams {
 	pub ecip1010_continue_transition: u64,
 	/// Total block number for one ECIP-1017 era.
 	pub ecip1017_era_rounds: u64,
+	/// Block rewards, each applying from the given block onwards.
+	/// Blocks before the first entry use the common block reward.
+	pub block_reward: BTreeMap<BlockNumber, U256>,
 	/// Maximum amount of code that can be deploying into a contract.
 	pub max_code_size: u64,
 	/// Number of first block where the max gas limit becomes effective.
@@ -114,6 +119,9 @@ impl From<ethjson::spec::EthashParams> for EthashParams {
 			difficulty_hardfork_transition: p.difficulty_hardfork_transition.map_or(u64::max_value(), Into::into),
 			difficulty_hardfork_bound_divisor: p.difficulty_hardfork_bound_divisor.map_or(p.difficulty_bound_divisor.into(), Into::into),
 			bomb_defuse_transition: p.bomb_defuse_transition.map_or(u64::max_value(), Into::into),
+			difficulty_bomb_delays: p.difficulty_bomb_delays.unwrap_or_else(BTreeMap::new).into_iter()
+				.map(|(block, delay)| (block.into(), delay.into()))
+				.collect(),
 			eip100b_transition: p.eip100b_transition.map_or(u64::max_value(), Into::into),
 			eip150_transition: p.eip150_transition.map_or(0, Into::into),
 			eip160_transition: p.eip160_transition.map_or(0, Into::into),
@@ -122,6 +130,13 @@ impl From<ethjson::spec::EthashParams> for EthashParams {
 			ecip1010_pause_transition: p.ecip1010_pause_transition.map_or(u64::max_value(), Into::into),
 			ecip1010_continue_transition: p.ecip1010_continue_transition.map_or(u64::max_value(), Into::into),
 			ecip1017_era_rounds: p.ecip1017_era_rounds.map_or(u64::max_value(), Into::into),
+			block_reward: match p.block_reward {
+				Some(ethjson::spec::BlockReward::Single(reward)) => map![0 => reward.into()],
+				Some(ethjson::spec::BlockReward::Multi(rewards)) => rewards.into_iter()
+					.map(|(block, reward)| (block.into(), reward.into()))
+					.collect(),
+				None => BTreeMap::new(),
+			},
 			max_code_size: p.max_code_size.map_or(u64::max_value(), Into::into),
 			max_gas_limit_transition: p.max_gas_limit_transition.map_or(u64::max_value(), Into::into),
 			max_gas_limit: p.max_gas_limit.map_or(U256::max_value(), Into::into),
@@ -145,9 +160,10 @@ impl Ethash {
 	pub fn new<T: AsRef<Path>>(
 		cache_dir: T,
 		params: CommonParams,
-		ethash_params: EthashParams,
+		mut ethash_params: EthashParams,
 		builtins: BTreeMap<Address, Builtin>,
 	) -> Arc<Self> {
+		ethash_params.block_reward.entry(0).or_insert(params.block_reward);
 		Arc::new(Ethash {
 			params,
 			ethash_params,
@@ -176,7 +192,7 @@ impl Engine for Arc<Ethash> {
 	fn name(&self) -> &str { "Ethash" }
 	fn version(&self) -> SemanticVersion { SemanticVersion::new(1, 0, 0) }
 	// Two fields - mix
-	fn seal_fields(&self) -> usize { 2 }
+	fn seal_fields(&self, _header: &Header) -> usize { 2 }
 
 	fn params(&self) -> &CommonParams { &self.params }
 	fn additional_params(&self) -> HashMap<String, String> { hash_map!["registrar".to_owned() => self.params().registrar.hex()] }
@@ -187,7 +203,7 @@ impl Engine for Arc<Ethash> {
 
 	/// Additional engine-specific information for the user/developer concerning `header`.
 	fn extra_info(&self, header: &Header) -> BTreeMap<String, String> {
-		if header.seal().len() == self.seal_fields() {
+		if header.seal().len() == self.seal_fields(header) {
 			map![
 				"nonce".to_owned() => format!("0x{}", header.nonce().hex()),
 				"mixHash".to_owned() => format!("0x{}", header.mix_hash().hex())
@@ -285,7 +301,7 @@ impl Engine for Arc<Ethash> {
 	/// Apply the block reward on finalisation of the block.
 	/// This assumes that all uncles are valid uncles (i.e. of at least one generation before the current).
 	fn on_close_block(&self, block: &mut ExecutedBlock) -> Result<(), Error> {
-		let reward = self.params().block_reward;
+		let reward = self.block_reward(block.header().number());
 		let fields = block.fields_mut();
 		let eras_rounds = self.ethash_params.ecip1017_era_rounds;
 		let (eras, reward) = ecip1017_eras_block_reward(eras_rounds, reward, fields.header.number());
@@ -322,9 +338,9 @@ impl Engine for Arc<Ethash> {
 
 	fn verify_block_basic(&self, header: &Header, _block: Option<&[u8]>) -> Result<(), Error> {
 		// check the seal fields.
-		if header.seal().len() != self.seal_fields() {
+		if header.seal().len() != self.seal_fields(header) {
 			return Err(From::from(BlockError::InvalidSealArity(
-				Mismatch { expected: self.seal_fields(), found: header.seal().len() }
+				Mismatch { expected: self.seal_fields(header), found: header.seal().len() }
 			)));
 		}
 		UntrustedRlp::new(&header.seal()[0]).as_val::<H256>()?;
@@ -359,9 +375,9 @@ impl Engine for Arc<Ethash> {
 	}
 
 	fn verify_block_unordered(&self, header: &Header, _block: Option<&[u8]>) -> Result<(), Error> {
-		if header.seal().len() != self.seal_fields() {
+		if header.seal().len() != self.seal_fields(header) {
 			return Err(From::from(BlockError::InvalidSealArity(
-				Mismatch { expected: self.seal_fields(), found: header.seal().len() }
+				Mismatch { expected: self.seal_fields(header), found: header.seal().len() }
 			)));
 		}
 		let result = self.pow.compute_light(header.number() as u64, &header.bare_hash().0, header.nonce().low_u64());
@@ -452,6 +468,19 @@ fn ecip1017_eras_block_reward(era_rounds: u64, mut reward: U256, block_number:u6
 
 #[cfg_attr(feature="dev", allow(wrong_self_convention))]
 impl Ethash {
+	/// Block reward at the given block, before any ECIP-1017 reduction.
+	fn block_reward(&self, number: BlockNumber) -> U256 {
+		self.ethash_params.block_reward.range(..number + 1).next_back()
+			.map_or_else(U256::zero, |(_, reward)| *reward)
+	}
+
+	/// Block number as seen by the exponential bomb, after applying all delays in effect.
+	fn bomb_number(&self, number: BlockNumber) -> BlockNumber {
+		self.ethash_params.difficulty_bomb_delays.iter()
+			.filter(|&(block, _)| number >= *block)
+			.fold(number, |number, (_, delay)| number.saturating_sub(*delay))
+	}
+
 	fn calculate_difficulty(&self, header: &Header, parent: &Header) -> U256 {
 		const EXP_DIFF_PERIOD: u64 = 100000;
 		if header.number() == 0 {
@@ -503,7 +532,7 @@ impl Ethash {
 		target = cmp::max(min_difficulty, target);
 		if header.number() < self.ethash_params.bomb_defuse_transition {
 			if header.number() < self.ethash_params.ecip1010_pause_transition {
-				let period = ((parent.number() + 1) / EXP_DIFF_PERIOD) as usize;
+				let period = (self.bomb_number(parent.number() + 1) / EXP_DIFF_PERIOD) as usize;
 				if period > 1 {
 					target = cmp::max(min_difficulty, target + (U256::from(1) << (period - 2)));
 				}
@@ -513,9 +542,12 @@ impl Ethash {
 				target = cmp::max(min_difficulty, target + (U256::from(1) << fixed_difficulty));
 			}
 			else {
-				let period = ((parent.number() + 1) / EXP_DIFF_PERIOD) as usize;
+				let period = (self.bomb_number(parent.number() + 1) / EXP_DIFF_PERIOD) as usize;
 				let delay = ((self.ethash_params.ecip1010_continue_transition - self.ethash_params.ecip1010_pause_transition) / EXP_DIFF_PERIOD) as usize;
-				target = cmp::max(min_difficulty, target + (U256::from(1) << (period - delay - 2)));
+				// bomb delays may already have pushed the period below the pause.
+				if let Some(exponent) = period.checked_sub(delay + 2) {
+					target = cmp::max(min_difficulty, target + (U256::from(1) << exponent));
+				}
 			}
 		}
 		target
@@ -926,6 +958,108 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn block_reward_schedule() {
+		let spec = new_homestead_test();
+		let common_reward = spec.params().block_reward;
+		let ethparams = EthashParams {
+			block_reward: map![
+				4370000 => U256::from_str("29a2241af62c0000").unwrap(),
+				7280000 => U256::from_str("1bc16d674ec80000").unwrap()
+			],
+			..get_default_ethash_params()
+		};
+		let ethash = Ethash::new(&::std::env::temp_dir(), spec.params().clone(), ethparams, BTreeMap::new());
+
+		assert_eq!(ethash.block_reward(0), common_reward);
+		assert_eq!(ethash.block_reward(4369999), common_reward);
+		assert_eq!(ethash.block_reward(4370000), U256::from_str("29a2241af62c0000").unwrap());
+		assert_eq!(ethash.block_reward(7279999), U256::from_str("29a2241af62c0000").unwrap());
+		assert_eq!(ethash.block_reward(7280000), U256::from_str("1bc16d674ec80000").unwrap());
+	}
+
+	#[test]
+	fn on_close_block_with_reward_schedule() {
+		let spec = new_homestead_test();
+		let ethparams = EthashParams {
+			block_reward: map![1 => U256::from_str("29a2241af62c0000").unwrap()],
+			..get_default_ethash_params()
+		};
+		let ethash = Ethash::new(&::std::env::temp_dir(), spec.params().clone(), ethparams, BTreeMap::new());
+		let genesis_header = spec.genesis_header();
+		let db = spec.ensure_db_good(get_temp_state_db(), &Default::default()).unwrap();
+		let last_hashes = Arc::new(vec![genesis_header.hash()]);
+		let b = OpenBlock::new(&ethash, Default::default(), false, db, &genesis_header, last_hashes, Address::zero(), (3141562.into(), 31415620.into()), vec![], false).unwrap();
+		let b = b.close();
+		assert_eq!(b.state().balance(&Address::zero()).unwrap(), U256::from_str("29a2241af62c0000").unwrap());
+	}
+
+	#[test]
+	fn difficulty_bomb_delays() {
+		let spec = new_homestead_test();
+		let ethparams = EthashParams {
+			difficulty_bomb_delays: map![
+				4370000 => 3000000,
+				7280000 => 2000000
+			],
+			..get_default_ethash_params()
+		};
+		let ethash = Ethash::new(&::std::env::temp_dir(), spec.params().clone(), ethparams, BTreeMap::new());
+		let undelayed = Ethash::new(&::std::env::temp_dir(), spec.params().clone(), get_default_ethash_params(), BTreeMap::new());
+
+		assert_eq!(ethash.bomb_number(4369999), 4369999);
+		assert_eq!(ethash.bomb_number(4370000), 1370000);
+		assert_eq!(ethash.bomb_number(7280000), 2280000);
+
+		let mut parent_header = Header::default();
+		parent_header.set_number(7280000);
+		parent_header.set_difficulty(U256::from_str("2d0ef4a7ad6bd3").unwrap());
+		parent_header.set_timestamp(1551383501);
+		let mut header = Header::default();
+		header.set_number(parent_header.number() + 1);
+		header.set_timestamp(parent_header.timestamp() + 13);
+
+		// The bomb behaves as if the chain was 5M blocks shorter.
+		assert_eq!(
+			*parent_header.difficulty() + (U256::from(1) << 20),
+			ethash.calculate_difficulty(&header, &parent_header)
+		);
+		assert_eq!(
+			*parent_header.difficulty() + (U256::from(1) << 70),
+			undelayed.calculate_difficulty(&header, &parent_header)
+		);
+	}
+
+	#[test]
+	fn difficulty_bomb_continue_with_delays() {
+		let spec = new_homestead_test();
+		let ethparams = EthashParams {
+			ecip1010_pause_transition: 3000000,
+			ecip1010_continue_transition: 5000000,
+			difficulty_bomb_delays: map![5000000 => 3000000],
+			..get_default_ethash_params()
+		};
+		let ethash = Ethash::new(&::std::env::temp_dir(), spec.params().clone(), ethparams, BTreeMap::new());
+		let defused = Ethash::new(&::std::env::temp_dir(), spec.params().clone(), EthashParams {
+			bomb_defuse_transition: 0,
+			..get_default_ethash_params()
+		}, BTreeMap::new());
+
+		let mut parent_header = Header::default();
+		parent_header.set_number(5000102);
+		parent_header.set_difficulty(U256::from_str("14944397EE8B").unwrap());
+		parent_header.set_timestamp(1513175023);
+		let mut header = Header::default();
+		header.set_number(parent_header.number() + 1);
+		header.set_timestamp(parent_header.timestamp() + 6);
+
+		// The delayed bomb period is still below the pause, so there is no bomb term.
+		assert_eq!(
+			defused.calculate_difficulty(&header, &parent_header),
+			ethash.calculate_difficulty(&header, &parent_header)
+		);
+	}
+
 	#[test]
 	fn gas_limit_is_multiple_of_determinant() {
 		let spec = new_homestead_test();
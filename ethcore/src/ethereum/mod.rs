@@ -90,6 +90,9 @@ pub fn new_mainnet_like() -> Spec { load(None, include_bytes!("../../res/ethereu
 /// Create a new Foundation Metropolis era spec.
 pub fn new_metropolis_test() -> Spec { load(None, include_bytes!("../../res/ethereum/metropolis_test.json")) }
 
+/// Create a new Foundation Constantinople era spec.
+pub fn new_constantinople_test() -> Spec { load(None, include_bytes!("../../res/ethereum/constantinople_test.json")) }
+
 #[cfg(test)]
 mod tests {
 	use util::*;
@@ -21,12 +21,13 @@ use vm;
 use trace::{VMTrace, FlatTrace};
 use log_entry::LogEntry;
 use state_diff::StateDiff;
+use pod_state::PodState;
 
 use std::fmt;
 
-/// Transaction execution receipt.
+/// Transaction execution receipt, parametrised by the output of the VM tracer.
 #[derive(Debug, PartialEq, Clone)]
-pub struct Executed {
+pub struct Executed<V = VMTrace> {
 	/// True if the outer call/create resulted in an exceptional exit.
 	pub exception: Option<vm::Error>,
 
@@ -62,9 +63,11 @@ pub struct Executed {
 	/// The trace of this transaction.
 	pub trace: Vec<FlatTrace>,
 	/// The VM trace of this transaction.
-	pub vm_trace: Option<VMTrace>,
+	pub vm_trace: Option<V>,
 	/// The state diff, if we traced it.
 	pub state_diff: Option<StateDiff>,
+	/// The state of all accessed accounts before execution, if we recorded it.
+	pub pre_state: Option<PodState>,
 }
 
 /// Result of executing the transaction.
@@ -157,6 +160,8 @@ pub enum CallError {
 	Exceptional,
 	/// Corrupt state.
 	StateCorrupt,
+	/// Execution was reverted with REVERT; carries the returned data.
+	Reverted(Bytes),
 	/// Error executing.
 	Execution(ExecutionError),
 }
@@ -176,6 +181,7 @@ impl fmt::Display for CallError {
 			StatePruned => "Couldn't find the transaction block's state in the chain".into(),
 			Exceptional => "An exception happened in the execution".into(),
 			StateCorrupt => "Stored state found to be corrupted.".into(),
+			Reverted(_) => "Execution reverted".into(),
 			Execution(ref e) => format!("{}", e),
 		};
 
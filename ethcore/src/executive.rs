@@ -26,7 +26,7 @@ use evm::{CallType, Factory, Finalize, FinalizationResult};
 use vm::{self, Ext, CreateContractAddress, ReturnData, CleanDustMode, ActionParams, ActionValue};
 use wasm;
 use externalities::*;
-use trace::{self, FlatTrace, VMTrace, Tracer, VMTracer};
+use trace::{self, FlatTrace, Tracer, VMTracer};
 use transaction::{Action, SignedTransaction};
 use crossbeam;
 pub use executed::{Executed, ExecutionResult};
@@ -62,6 +62,15 @@ pub fn contract_address(address_scheme: CreateContractAddress, sender: &Address,
 			&mut buffer[20..].copy_from_slice(&code_hash[..]);
 			(From::from((&buffer[..]).sha3()), Some(code_hash))
 		},
+		CreateContractAddress::FromSenderSaltAndCodeHash(salt) => {
+			let code_hash = code.sha3();
+			let mut buffer = [0u8; 1 + 20 + 32 + 32];
+			buffer[0] = 0xff;
+			&mut buffer[1..(1+20)].copy_from_slice(&sender[..]);
+			&mut buffer[(1+20)..(1+20+32)].copy_from_slice(&salt[..]);
+			&mut buffer[(1+20+32)..].copy_from_slice(&code_hash[..]);
+			(From::from((&buffer[..]).sha3()), Some(code_hash))
+		},
 	}
 }
 
@@ -199,7 +208,7 @@ impl<'a, B: 'a + StateBackend, E: Engine + ?Sized> Executive<'a, B, E> {
 
 	/// This function should be used to execute transaction.
 	pub fn transact<T, V>(&'a mut self, t: &SignedTransaction, options: TransactOptions<T, V>)
-		-> Result<Executed, ExecutionError> where T: Tracer, V: VMTracer,
+		-> Result<Executed<V::Output>, ExecutionError> where T: Tracer, V: VMTracer,
 	{
 		self.transact_with_tracer(t, options.check_nonce, options.tracer, options.vm_tracer)
 	}
@@ -208,7 +217,7 @@ impl<'a, B: 'a + StateBackend, E: Engine + ?Sized> Executive<'a, B, E> {
 	/// This will ensure the caller has enough balance to execute the desired transaction.
 	/// Used for extra-block executions for things like consensus contracts and RPCs
 	pub fn transact_virtual<T, V>(&'a mut self, t: &SignedTransaction, options: TransactOptions<T, V>)
-		-> Result<Executed, ExecutionError> where T: Tracer, V: VMTracer,
+		-> Result<Executed<V::Output>, ExecutionError> where T: Tracer, V: VMTracer,
 	{
 		let sender = t.sender();
 		let balance = self.state.balance(&sender)?;
@@ -228,7 +237,7 @@ impl<'a, B: 'a + StateBackend, E: Engine + ?Sized> Executive<'a, B, E> {
 		check_nonce: bool,
 		mut tracer: T,
 		mut vm_tracer: V
-	) -> Result<Executed, ExecutionError> where T: Tracer, V: VMTracer {
+	) -> Result<Executed<V::Output>, ExecutionError> where T: Tracer, V: VMTracer {
 		let sender = t.sender();
 		let nonce = self.state.nonce(&sender)?;
 
@@ -272,6 +281,9 @@ impl<'a, B: 'a + StateBackend, E: Engine + ?Sized> Executive<'a, B, E> {
 
 		let mut substate = Substate::new();
 
+		// storage as it was before this transaction is the original one for EIP-1283
+		self.state.clear_original_storage();
+
 		// NOTE: there can be no invalid transactions from this point.
 		if !t.is_unsigned() {
 			self.state.inc_nonce(&sender)?;
@@ -356,7 +368,7 @@ impl<'a, B: 'a + StateBackend, E: Engine + ?Sized> Executive<'a, B, E> {
 	/// Calls contract function with given contract params.
 	/// NOTE. It does not finalize the transaction (doesn't do refunds, nor suicides).
 	/// Modifies the substate and the output.
-	/// Returns either gas_left or `vm::Error`.
+	/// Returns either the finalization result or `vm::Error`.
 	pub fn call<T, V>(
 		&mut self,
 		params: ActionParams,
@@ -364,7 +376,7 @@ impl<'a, B: 'a + StateBackend, E: Engine + ?Sized> Executive<'a, B, E> {
 		mut output: BytesRef,
 		tracer: &mut T,
 		vm_tracer: &mut V
-	) -> vm::Result<(U256, ReturnData)> where T: Tracer, V: VMTracer {
+	) -> vm::Result<FinalizationResult> where T: Tracer, V: VMTracer {
 
 		trace!("Executive::call(params={:?}) self.env_info={:?}", params, self.info);
 		if (params.call_type == CallType::StaticCall ||
@@ -422,7 +434,11 @@ impl<'a, B: 'a + StateBackend, E: Engine + ?Sized> Executive<'a, B, E> {
 						);
 					}
 
-					Ok((params.gas - cost, ReturnData::empty()))
+					Ok(FinalizationResult {
+						gas_left: params.gas - cost,
+						apply_state: true,
+						return_data: ReturnData::empty(),
+					})
 				}
 			} else {
 				// just drain the whole gas
@@ -469,13 +485,17 @@ impl<'a, B: 'a + StateBackend, E: Engine + ?Sized> Executive<'a, B, E> {
 
 				self.enact_result(&res, substate, unconfirmed_substate);
 				trace!(target: "executive", "enacted: substate={:?}\n", substate);
-				res.map(|r| (r.gas_left, r.return_data))
+				res
 			} else {
 				// otherwise it's just a basic transaction, only do tracing, if necessary.
 				self.state.discard_checkpoint();
 
 				tracer.trace_call(trace_info, U256::zero(), trace_output, vec![]);
-				Ok((params.gas, ReturnData::empty()))
+				Ok(FinalizationResult {
+					gas_left: params.gas,
+					apply_state: true,
+					return_data: ReturnData::empty(),
+				})
 			}
 		}
 	}
@@ -489,10 +509,21 @@ impl<'a, B: 'a + StateBackend, E: Engine + ?Sized> Executive<'a, B, E> {
 		substate: &mut Substate,
 		tracer: &mut T,
 		vm_tracer: &mut V,
-	) -> vm::Result<(U256, ReturnData)> where T: Tracer, V: VMTracer {
+	) -> vm::Result<FinalizationResult> where T: Tracer, V: VMTracer {
 
-		let scheme = self.engine.create_address_scheme(self.info.number);
-		if scheme != CreateContractAddress::FromSenderAndNonce && self.state.exists_and_has_code(&params.address)? {
+		// EIP-684: creating a contract where one already lives (code or non-zero nonce) fails.
+		// before it only contracts at addresses not derived from the sender's nonce are checked.
+		let schedule = self.engine.schedule(self.info.number);
+		let collision = if schedule.eip684 {
+			self.state.exists_and_has_code_or_nonce(&params.address)?
+		} else {
+			let scheme = self.engine.create_address_scheme(self.info.number);
+			scheme != CreateContractAddress::FromSenderAndNonce && self.state.exists_and_has_code(&params.address)?
+		};
+
+		if collision {
+			let trace_info = tracer.prepare_trace_create(&params);
+			tracer.trace_failed_create(trace_info, vec![], vm::Error::OutOfGas.into());
 			return Err(vm::Error::OutOfGas);
 		}
 
@@ -509,7 +540,6 @@ impl<'a, B: 'a + StateBackend, E: Engine + ?Sized> Executive<'a, B, E> {
 		let mut unconfirmed_substate = Substate::new();
 
 		// create contract and transfer value to it if necessary
-		let schedule = self.engine.schedule(self.info.number);
 		let nonce_offset = if schedule.no_empty {1} else {0}.into();
 		let prev_bal = self.state.balance(&params.address)?;
 		if let ActionValue::Transfer(val) = params.value {
@@ -545,29 +575,30 @@ impl<'a, B: 'a + StateBackend, E: Engine + ?Sized> Executive<'a, B, E> {
 		};
 
 		self.enact_result(&res, substate, unconfirmed_substate);
-		res.map(|r| (r.gas_left, r.return_data))
+		res
 	}
 
 	/// Finalizes the transaction (does refunds and suicides).
-	fn finalize(
+	fn finalize<V>(
 		&mut self,
 		t: &SignedTransaction,
 		mut substate: Substate,
-		result: vm::Result<(U256, ReturnData)>,
+		result: vm::Result<FinalizationResult>,
 		output: Bytes,
 		trace: Vec<FlatTrace>,
-		vm_trace: Option<VMTrace>
-	) -> ExecutionResult {
+		vm_trace: Option<V>
+	) -> Result<Executed<V>, ExecutionError> {
 		let schedule = self.engine.schedule(self.info.number);
 
-		// refunds from SSTORE nonzero -> zero
-		let sstore_refunds = U256::from(schedule.sstore_refund_gas) * substate.sstore_clears_count;
+		// refunds from SSTORE
+		assert!(substate.sstore_clears_refund >= 0, "On transaction level, sstore clears refund cannot go below zero.");
+		let sstore_refunds = U256::from(substate.sstore_clears_refund as u64);
 		// refunds from contract suicides
 		let suicide_refunds = U256::from(schedule.suicide_refund_gas) * U256::from(substate.suicides.len());
 		let refunds_bound = sstore_refunds + suicide_refunds;
 
 		// real ammount to refund
-		let gas_left_prerefund = match result { Ok((x, _)) => x, _ => 0.into() };
+		let gas_left_prerefund = match result { Ok(FinalizationResult { gas_left, .. }) => gas_left, _ => 0.into() };
 		let refunded = cmp::min(refunds_bound, (t.gas - gas_left_prerefund) >> 1);
 		let gas_left = gas_left_prerefund + refunded;
 
@@ -609,11 +640,12 @@ impl<'a, B: 'a + StateBackend, E: Engine + ?Sized> Executive<'a, B, E> {
 					trace: trace,
 					vm_trace: vm_trace,
 					state_diff: None,
+					pre_state: None,
 				})
 			},
-			_ => {
+			Ok(r) => {
 				Ok(Executed {
-					exception: None,
+					exception: if r.apply_state { None } else { Some(vm::Error::Reverted) },
 					gas: t.gas,
 					gas_used: gas_used,
 					refunded: refunded,
@@ -624,6 +656,7 @@ impl<'a, B: 'a + StateBackend, E: Engine + ?Sized> Executive<'a, B, E> {
 					trace: trace,
 					vm_trace: vm_trace,
 					state_diff: None,
+					pre_state: None,
 				})
 			},
 		}
@@ -639,6 +672,7 @@ impl<'a, B: 'a + StateBackend, E: Engine + ?Sized> Executive<'a, B, E> {
 				| Err(vm::Error::Wasm {..})
 				| Err(vm::Error::OutOfStack {..})
 				| Err(vm::Error::MutableCallInStaticContext)
+				| Err(vm::Error::Reverted)
 				| Ok(FinalizationResult { apply_state: false, .. }) => {
 					self.state.revert_to_checkpoint();
 			},
@@ -666,7 +700,7 @@ mod tests {
 	use state::{Substate, CleanupMode};
 	use tests::helpers::*;
 	use trace::trace;
-	use trace::{FlatTrace, Tracer, NoopTracer, ExecutiveTracer};
+	use trace::{FlatTrace, Tracer, NoopTracer, ExecutiveTracer, TraceError};
 	use trace::{VMTrace, VMOperation, VMExecutedOperation, MemoryDiff, StorageDiff, VMTracer, NoopVMTracer, ExecutiveVMTracer};
 	use transaction::{Action, Transaction};
 
@@ -677,6 +711,27 @@ mod tests {
 		assert_eq!(expected_address, contract_address(CreateContractAddress::FromSenderAndNonce, &address, &U256::from(88), &[]).0);
 	}
 
+	#[test]
+	fn test_contract_address_create2() {
+		// EIP-1014 test vectors
+		let sender = Address::from_str("0000000000000000000000000000000000000000").unwrap();
+		let expected_address = Address::from_str("4d1a2e2bb4f88f0250f26ffff098b0b30b26bf38").unwrap();
+		assert_eq!(expected_address, contract_address(CreateContractAddress::FromSenderSaltAndCodeHash(H256::zero()), &sender, &U256::zero(), &[0x00]).0);
+
+		let sender = Address::from_str("deadbeef00000000000000000000000000000000").unwrap();
+		let expected_address = Address::from_str("b928f69bb1d91cd65274e3c79d8986362984fda3").unwrap();
+		assert_eq!(expected_address, contract_address(CreateContractAddress::FromSenderSaltAndCodeHash(H256::zero()), &sender, &U256::zero(), &[0x00]).0);
+
+		let sender = Address::from_str("00000000000000000000000000000000deadbeef").unwrap();
+		let salt = H256::from_str("00000000000000000000000000000000000000000000000000000000cafebabe").unwrap();
+		let expected_address = Address::from_str("60f3f640a8508fc6a86d45df051962668e1e8ac7").unwrap();
+		assert_eq!(expected_address, contract_address(CreateContractAddress::FromSenderSaltAndCodeHash(salt), &sender, &U256::zero(), &"deadbeef".from_hex().unwrap()).0);
+
+		let sender = Address::from_str("0000000000000000000000000000000000000000").unwrap();
+		let expected_address = Address::from_str("e33c0c7f7df4809055c3eba6c09cfe4baf1bd9e0").unwrap();
+		assert_eq!(expected_address, contract_address(CreateContractAddress::FromSenderSaltAndCodeHash(H256::zero()), &sender, &U256::zero(), &[]).0);
+	}
+
 	// TODO: replace params with transactions!
 	evm_test!{test_sender_balance: test_sender_balance_jit, test_sender_balance_int}
 	fn test_sender_balance(factory: Factory) {
@@ -694,7 +749,7 @@ mod tests {
 		let engine = TestEngine::new(0);
 		let mut substate = Substate::new();
 
-		let (gas_left, _) = {
+		let FinalizationResult { gas_left, .. } = {
 			let mut ex = Executive::new(&mut state, &info, &engine);
 			ex.create(params, &mut substate, &mut NoopTracer, &mut NoopVMTracer).unwrap()
 		};
@@ -752,7 +807,7 @@ mod tests {
 		let engine = TestEngine::new(0);
 		let mut substate = Substate::new();
 
-		let (gas_left, _) = {
+		let FinalizationResult { gas_left, .. } = {
 			let mut ex = Executive::new(&mut state, &info, &engine);
 			ex.create(params, &mut substate, &mut NoopTracer, &mut NoopVMTracer).unwrap()
 		};
@@ -810,7 +865,7 @@ mod tests {
 		let mut tracer = ExecutiveTracer::default();
 		let mut vm_tracer = ExecutiveVMTracer::toplevel();
 
-		let (gas_left, _) = {
+		let FinalizationResult { gas_left, .. } = {
 			let mut ex = Executive::new(&mut state, &info, &engine);
 			let output = BytesRef::Fixed(&mut[0u8;0]);
 			ex.call(params, &mut substate, output, &mut tracer, &mut vm_tracer).unwrap()
@@ -919,7 +974,7 @@ mod tests {
 		let mut tracer = ExecutiveTracer::default();
 		let mut vm_tracer = ExecutiveVMTracer::toplevel();
 
-		let (gas_left, _) = {
+		let FinalizationResult { gas_left, .. } = {
 			let mut ex = Executive::new(&mut state, &info, &engine);
 			ex.create(params.clone(), &mut substate, &mut tracer, &mut vm_tracer).unwrap()
 		};
@@ -1004,7 +1059,7 @@ mod tests {
 		let engine = TestEngine::new(0);
 		let mut substate = Substate::new();
 
-		let (gas_left, _) = {
+		let FinalizationResult { gas_left, .. } = {
 			let mut ex = Executive::new(&mut state, &info, &engine);
 			ex.create(params, &mut substate, &mut NoopTracer, &mut NoopVMTracer).unwrap()
 		};
@@ -1115,7 +1170,7 @@ mod tests {
 		let engine = TestEngine::new(0);
 		let mut substate = Substate::new();
 
-		let (gas_left, _) = {
+		let FinalizationResult { gas_left, .. } = {
 			let mut ex = Executive::new(&mut state, &info, &engine);
 			ex.call(params, &mut substate, BytesRef::Fixed(&mut []), &mut NoopTracer, &mut NoopVMTracer).unwrap()
 		};
@@ -1159,7 +1214,7 @@ mod tests {
 		let engine = TestEngine::new(0);
 		let mut substate = Substate::new();
 
-		let (gas_left, _) = {
+		let FinalizationResult { gas_left, .. } = {
 			let mut ex = Executive::new(&mut state, &info, &engine);
 			ex.call(params, &mut substate, BytesRef::Fixed(&mut []), &mut NoopTracer, &mut NoopVMTracer).unwrap()
 		};
@@ -1339,6 +1394,113 @@ mod tests {
 		}
 	}
 
+	#[test]
+	// CREATE2 is not supported in JIT
+	fn test_create2_salted_address() {
+		// code:
+		//
+		// 60 2a - push salt 42
+		// 60 01 - push init code size 1
+		// 60 00 - push init code offset 0
+		// 60 00 - push endowment 0
+		// fb - create2
+		// 60 00 - push 0
+		// 55 - sstore
+
+		let code = "602a600160006000fb600055".from_hex().unwrap();
+
+		let sender = Address::from_str("cd1722f3947def4cf144679da39c4c32bdc35681").unwrap();
+		let address = contract_address(CreateContractAddress::FromSenderAndNonce, &sender, &U256::zero(), &[]).0;
+		let expected_address = contract_address(CreateContractAddress::FromSenderSaltAndCodeHash(H256::from(42)), &address, &U256::zero(), &[0x00]).0;
+		let mut params = ActionParams::default();
+		params.address = address.clone();
+		params.code_address = address.clone();
+		params.sender = sender.clone();
+		params.origin = sender.clone();
+		params.gas = U256::from(100_000);
+		params.code = Some(Arc::new(code));
+		params.value = ActionValue::Transfer(U256::zero());
+		let mut state = get_temp_state();
+		let info = EnvInfo::default();
+		let engine = TestEngine::new_constantinople(5);
+		let mut substate = Substate::new();
+
+		{
+			let mut ex = Executive::new(&mut state, &info, &engine);
+			let output = BytesRef::Fixed(&mut[0u8;0]);
+			ex.call(params, &mut substate, output, &mut NoopTracer, &mut NoopVMTracer).unwrap();
+		}
+
+		assert_eq!(substate.contracts_created, vec![expected_address.clone()]);
+		assert_eq!(state.storage_at(&address, &H256::new()).unwrap(), H256::from(expected_address));
+	}
+
+	#[test]
+	// CREATE2 is not supported in JIT
+	fn test_create2_collision_is_traced() {
+		// same code as `test_create2_salted_address`.
+		let code = "602a600160006000fb600055".from_hex().unwrap();
+
+		let sender = Address::from_str("cd1722f3947def4cf144679da39c4c32bdc35681").unwrap();
+		let address = contract_address(CreateContractAddress::FromSenderAndNonce, &sender, &U256::zero(), &[]).0;
+		let expected_address = contract_address(CreateContractAddress::FromSenderSaltAndCodeHash(H256::from(42)), &address, &U256::zero(), &[0x00]).0;
+		let mut params = ActionParams::default();
+		params.address = address.clone();
+		params.code_address = address.clone();
+		params.sender = sender.clone();
+		params.origin = sender.clone();
+		params.gas = U256::from(100_000);
+		params.code = Some(Arc::new(code));
+		params.value = ActionValue::Transfer(U256::zero());
+		let mut state = get_temp_state();
+		// an account without code but with a nonce is a collision too.
+		state.inc_nonce(&expected_address).unwrap();
+		let info = EnvInfo::default();
+		let engine = TestEngine::new_constantinople(5);
+		let mut substate = Substate::new();
+		let mut tracer = ExecutiveTracer::default();
+
+		{
+			let mut ex = Executive::new(&mut state, &info, &engine);
+			let output = BytesRef::Fixed(&mut[0u8;0]);
+			ex.call(params, &mut substate, output, &mut tracer, &mut NoopVMTracer).unwrap();
+		}
+
+		assert!(substate.contracts_created.is_empty());
+		assert_eq!(state.storage_at(&address, &H256::new()).unwrap(), H256::new());
+
+		let traces = tracer.drain();
+		assert_eq!(traces.len(), 2);
+		assert_eq!(traces[1].trace_address.iter().cloned().collect::<Vec<_>>(), vec![0]);
+		assert_eq!(traces[1].result, trace::Res::FailedCreate(TraceError::OutOfGas));
+	}
+
+	#[test]
+	fn test_create_collision_with_nonce_before_and_after_eip684() {
+		let sender = Address::from_str("cd1722f3947def4cf144679da39c4c32bdc35681").unwrap();
+		let address = contract_address(CreateContractAddress::FromSenderSaltAndCodeHash(H256::from(42)), &sender, &U256::zero(), &[]).0;
+		let create = |engine: &TestEngine| {
+			let mut params = ActionParams::default();
+			params.address = address.clone();
+			params.sender = sender.clone();
+			params.origin = sender.clone();
+			params.gas = U256::from(100_000);
+			params.code = Some(Arc::new(vec![]));
+			let mut state = get_temp_state();
+			state.inc_nonce(&address).unwrap();
+			let info = EnvInfo::default();
+			let mut substate = Substate::new();
+
+			let result = Executive::new(&mut state, &info, engine)
+				.create(params, &mut substate, &mut NoopTracer, &mut NoopVMTracer);
+			result.is_ok()
+		};
+
+		// only accounts with code collided before EIP-684.
+		assert!(create(&TestEngine::new_metropolis()));
+		assert!(!create(&TestEngine::new_constantinople(5)));
+	}
+
 	evm_test!{test_revert: test_revert_jit, test_revert_int}
 	fn test_revert(factory: Factory) {
 		let contract_address = Address::from_str("cd1722f3947def4cf144679da39c4c32bdc35681").unwrap();
@@ -1364,13 +1526,48 @@ mod tests {
 		let mut substate = Substate::new();
 
 		let mut output = [0u8; 14];
-		let (result, _) = {
+		let FinalizationResult { gas_left: result, apply_state, .. } = {
 			let mut ex = Executive::new(&mut state, &info, &engine);
 			ex.call(params, &mut substate, BytesRef::Fixed(&mut output), &mut NoopTracer, &mut NoopVMTracer).unwrap()
 		};
 
 		assert_eq!(result, U256::from(1));
+		assert!(!apply_state);
 		assert_eq!(output[..], returns[..]);
 		assert_eq!(state.storage_at(&contract_address, &H256::from(&U256::zero())).unwrap(), H256::from(&U256::from(0)));
 	}
+
+	evm_test!{test_transact_reverted: test_transact_reverted_jit, test_transact_reverted_int}
+	fn test_transact_reverted(factory: Factory) {
+		let contract = Address::from_str("cd1722f3947def4cf144679da39c4c32bdc35681").unwrap();
+		// EIP-140 test case
+		let code = "6c726576657274656420646174616000557f726576657274206d657373616765000000000000000000000000000000000000600052600e6000fd".from_hex().unwrap();
+		let returns = "726576657274206d657373616765".from_hex().unwrap();
+		let keypair = Random.generate().unwrap();
+		let t = Transaction {
+			action: Action::Call(contract.clone()),
+			value: U256::zero(),
+			data: vec![],
+			gas: U256::from(100_000),
+			gas_price: U256::zero(),
+			nonce: U256::zero()
+		}.sign(keypair.secret(), None);
+
+		let mut state = get_temp_state_with_factory(factory);
+		state.init_code(&contract, code).unwrap();
+		let mut info = EnvInfo::default();
+		info.gas_limit = U256::from(100_000);
+		let engine = TestEngine::new_metropolis();
+
+		let executed = {
+			let mut ex = Executive::new(&mut state, &info, &engine);
+			let opts = TransactOptions::with_no_tracing();
+			ex.transact(&t, opts).unwrap()
+		};
+
+		assert_eq!(executed.exception, Some(vm::Error::Reverted));
+		assert_eq!(executed.output, returns);
+		assert!(executed.gas_used < executed.gas);
+		assert_eq!(state.storage_at(&contract, &H256::new()).unwrap(), H256::new());
+	}
 }
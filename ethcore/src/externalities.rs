@@ -21,6 +21,7 @@ use util::*;
 use state::{Backend as StateBackend, State, Substate, CleanupMode};
 use engines::Engine;
 use executive::*;
+use evm::FinalizationResult;
 use vm::{
 	self, ActionParams, ActionValue, EnvInfo, CallType, Schedule,
 	Ext, ContractCreateResult, MessageCallResult, CreateContractAddress,
@@ -116,6 +117,10 @@ impl<'a, T: 'a, V: 'a, B: 'a, E: 'a> Ext for Externalities<'a, T, V, B, E>
 		self.state.storage_at(&self.origin_info.address, key).map_err(Into::into)
 	}
 
+	fn initial_storage_at(&self, key: &H256) -> vm::Result<H256> {
+		self.state.original_storage_at(&self.origin_info.address, key).map_err(Into::into)
+	}
+
 	fn set_storage(&mut self, key: H256, value: H256) -> vm::Result<()> {
 		if self.static_flag {
 			Err(vm::Error::MutableCallInStaticContext)
@@ -219,11 +224,12 @@ impl<'a, T: 'a, V: 'a, B: 'a, E: 'a> Ext for Externalities<'a, T, V, B, E>
 				return ContractCreateResult::Failed
 			}
 		}
+
 		let mut ex = Executive::from_parent(self.state, self.env_info, self.engine, self.depth, self.static_flag);
 
 		// TODO: handle internal error separately
 		match ex.create(params, self.substate, self.tracer, self.vm_tracer) {
-			Ok((gas_left, _)) => {
+			Ok(FinalizationResult { gas_left, .. }) => {
 				self.substate.contracts_created.push(address.clone());
 				ContractCreateResult::Created(address, gas_left)
 			},
@@ -272,7 +278,7 @@ impl<'a, T: 'a, V: 'a, B: 'a, E: 'a> Ext for Externalities<'a, T, V, B, E>
 		let mut ex = Executive::from_parent(self.state, self.env_info, self.engine, self.depth, self.static_flag);
 
 		match ex.call(params, self.substate, BytesRef::Fixed(output), self.tracer, self.vm_tracer) {
-			Ok((gas_left, return_data)) => MessageCallResult::Success(gas_left, return_data),
+			Ok(FinalizationResult { gas_left, return_data, .. }) => MessageCallResult::Success(gas_left, return_data),
 			_ => MessageCallResult::Failed
 		}
 	}
@@ -285,6 +291,10 @@ impl<'a, T: 'a, V: 'a, B: 'a, E: 'a> Ext for Externalities<'a, T, V, B, E>
 		Ok(self.state.code_size(address)?.unwrap_or(0))
 	}
 
+	fn extcodehash(&self, address: &Address) -> vm::Result<H256> {
+		Ok(self.state.code_hash_if_exists(address)?.unwrap_or_else(H256::zero))
+	}
+
 	#[cfg_attr(feature="dev", allow(match_ref_pats))]
 	fn ret(mut self, gas: &U256, data: &ReturnData) -> vm::Result<U256>
 		where Self: Sized {
@@ -378,8 +388,12 @@ impl<'a, T: 'a, V: 'a, B: 'a, E: 'a> Ext for Externalities<'a, T, V, B, E>
 		self.depth
 	}
 
-	fn inc_sstore_clears(&mut self) {
-		self.substate.sstore_clears_count = self.substate.sstore_clears_count + U256::one();
+	fn add_sstore_refund(&mut self, value: usize) {
+		self.substate.sstore_clears_refund += value as i64;
+	}
+
+	fn sub_sstore_refund(&mut self, value: usize) {
+		self.substate.sstore_clears_refund -= value as i64;
 	}
 
 	fn trace_next_instruction(&mut self, pc: usize, instruction: u8) -> bool {
@@ -390,6 +404,10 @@ impl<'a, T: 'a, V: 'a, B: 'a, E: 'a> Ext for Externalities<'a, T, V, B, E>
 		self.vm_tracer.trace_prepare_execute(pc, instruction, gas_cost)
 	}
 
+	fn trace_machine_state(&mut self, gas: U256, stack: &[U256], memory: &[u8]) {
+		self.vm_tracer.trace_machine_state(gas, stack, memory)
+	}
+
 	fn trace_executed(&mut self, gas_used: U256, stack_push: &[U256], mem_diff: Option<(usize, &[u8])>, store_diff: Option<(U256, U256)>) {
 		self.vm_tracer.trace_executed(gas_used, stack_push, mem_diff, store_diff)
 	}
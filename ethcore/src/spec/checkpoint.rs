@@ -0,0 +1,59 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Trusted checkpoint for light clients.
+
+use std::io::Read;
+use rlp::{DecoderError, UntrustedRlp};
+use util::{H256, U256};
+use ethjson;
+use header::Header;
+
+/// A trusted block light clients may start syncing from instead of the genesis.
+///
+/// The block must be the last one covered by a CHT. Headers before it
+/// are only available through proofs against the given CHT roots.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrustedCheckpoint {
+	/// Header of the checkpoint block.
+	pub header: Header,
+	/// Total difficulty of the checkpoint block.
+	pub total_difficulty: U256,
+	/// Roots of all CHTs up to and including the checkpoint block, starting from the first.
+	pub cht_roots: Vec<H256>,
+}
+
+impl TrustedCheckpoint {
+	/// Convert from the json representation, decoding the header.
+	pub fn from_json(c: ethjson::spec::TrustedCheckpoint) -> Result<Self, DecoderError> {
+		let header: Vec<u8> = c.header.into();
+		Ok(TrustedCheckpoint {
+			header: UntrustedRlp::new(&header).as_val()?,
+			total_difficulty: c.total_difficulty.into(),
+			cht_roots: c.cht_roots.into_iter().map(Into::into).collect(),
+		})
+	}
+
+	/// Loads a trusted checkpoint from json.
+	pub fn load<R>(reader: R) -> Result<Self, String> where R: Read {
+		fn fmt<F: ::std::fmt::Display>(f: F) -> String {
+			format!("Trusted checkpoint json is invalid: {}", f)
+		}
+
+		ethjson::spec::TrustedCheckpoint::load(reader).map_err(fmt)
+			.and_then(|x| TrustedCheckpoint::from_json(x).map_err(fmt))
+	}
+}
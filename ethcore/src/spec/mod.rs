@@ -16,9 +16,11 @@
 
 //! Blockchain params.
 
+mod checkpoint;
 mod genesis;
 mod seal;
 pub mod spec;
 
+pub use self::checkpoint::TrustedCheckpoint;
 pub use self::spec::*;
 pub use self::genesis::Genesis;
@@ -23,9 +23,10 @@ use std::sync::Arc;
 use rustc_hex::FromHex;
 use super::genesis::Genesis;
 use super::seal::Generic as GenericSeal;
+use super::checkpoint::TrustedCheckpoint;
 
 use builtin::Builtin;
-use engines::{Engine, NullEngine, InstantSeal, BasicAuthority, AuthorityRound, Tendermint, DEFAULT_BLOCKHASH_CONTRACT};
+use engines::{Engine, NullEngine, InstantSeal, BasicAuthority, AuthorityRound, Tendermint, Clique, DEFAULT_BLOCKHASH_CONTRACT};
 use vm::{EnvInfo, CallType, ActionValue, ActionParams};
 use error::Error;
 use ethereum;
@@ -86,6 +87,14 @@ pub struct CommonParams {
 	pub eip211_transition: BlockNumber,
 	/// Number of first block where EIP-214 rules begin.
 	pub eip214_transition: BlockNumber,
+	/// Number of first block where EIP-145 (Constantinople: bitwise shifting instructions) rules begin.
+	pub eip145_transition: BlockNumber,
+	/// Number of first block where EIP-1014 (Constantinople: CREATE2 salted address scheme) rules begin.
+	pub eip1014_transition: BlockNumber,
+	/// Number of first block where EIP-1052 (Constantinople: EXTCODEHASH instruction) rules begin.
+	pub eip1052_transition: BlockNumber,
+	/// Number of first block where EIP-1283 (Constantinople: net gas metering for SSTORE) rules begin.
+	pub eip1283_transition: BlockNumber,
 	/// Number of first block where dust cleanup rules (EIP-168 and EIP169) begin.
 	pub dust_protection_transition: BlockNumber,
 	/// Nonce cap increase per block. Nonce cap is only checked if dust protection is enabled.
@@ -114,10 +123,14 @@ impl CommonParams {
 
 	/// Apply common spec config parameters to the schedule.
  	pub fn update_schedule(&self, block_number: u64, schedule: &mut ::vm::Schedule) {
-		schedule.have_create2 = block_number >= self.eip86_transition;
+		schedule.have_create2 = block_number >= self.eip86_transition || block_number >= self.eip1014_transition;
 		schedule.have_revert = block_number >= self.eip140_transition;
 		schedule.have_static_call = block_number >= self.eip214_transition;
 		schedule.have_return_data = block_number >= self.eip211_transition;
+		schedule.have_bitwise_shifting = block_number >= self.eip145_transition;
+		schedule.have_extcodehash = block_number >= self.eip1052_transition;
+		schedule.eip1283 = block_number >= self.eip1283_transition;
+		schedule.eip684 = block_number >= self.eip1014_transition;
 		if block_number >= self.eip210_transition {
 			schedule.blockhash_gas = 350;
 		}
@@ -166,6 +179,10 @@ impl From<ethjson::spec::Params> for CommonParams {
 			eip210_contract_gas: p.eip210_contract_gas.map_or(1000000.into(), Into::into),
 			eip211_transition: p.eip211_transition.map_or(BlockNumber::max_value(), Into::into),
 			eip214_transition: p.eip214_transition.map_or(BlockNumber::max_value(), Into::into),
+			eip145_transition: p.eip145_transition.map_or(BlockNumber::max_value(), Into::into),
+			eip1014_transition: p.eip1014_transition.map_or(BlockNumber::max_value(), Into::into),
+			eip1052_transition: p.eip1052_transition.map_or(BlockNumber::max_value(), Into::into),
+			eip1283_transition: p.eip1283_transition.map_or(BlockNumber::max_value(), Into::into),
 			dust_protection_transition: p.dust_protection_transition.map_or(BlockNumber::max_value(), Into::into),
 			nonce_cap_increment: p.nonce_cap_increment.map_or(64, Into::into),
 			remove_dust_contracts: p.remove_dust_contracts.unwrap_or(false),
@@ -191,6 +208,9 @@ pub struct Spec {
 	/// Known nodes on the network in enode format.
 	pub nodes: Vec<String>,
 
+	/// Trusted checkpoint light clients start syncing from, if any.
+	pub trusted_checkpoint: Option<TrustedCheckpoint>,
+
 	/// The genesis block's parent hash field.
 	pub parent_hash: H256,
 	/// The genesis block's author field.
@@ -227,12 +247,17 @@ fn load_from<T: AsRef<Path>>(cache_dir: T, s: ethjson::spec::Spec) -> Result<Spe
 	let g = Genesis::from(s.genesis);
 	let GenericSeal(seal_rlp) = g.seal.into();
 	let params = CommonParams::from(s.params);
+	let trusted_checkpoint = match s.trusted_checkpoint {
+		Some(checkpoint) => Some(TrustedCheckpoint::from_json(checkpoint)?),
+		None => None,
+	};
 
 	let mut s = Spec {
 		name: s.name.clone().into(),
 		engine: Spec::engine(cache_dir, s.engine, params, builtins),
 		data_dir: s.data_dir.unwrap_or(s.name).into(),
 		nodes: s.nodes.unwrap_or_else(Vec::new),
+		trusted_checkpoint: trusted_checkpoint,
 		parent_hash: g.parent_hash,
 		transactions_root: g.transactions_root,
 		receipts_root: g.receipts_root,
@@ -282,6 +307,7 @@ impl Spec {
 			ethjson::spec::Engine::BasicAuthority(basic_authority) => Arc::new(BasicAuthority::new(params, From::from(basic_authority.params), builtins)),
 			ethjson::spec::Engine::AuthorityRound(authority_round) => AuthorityRound::new(params, From::from(authority_round.params), builtins).expect("Failed to start AuthorityRound consensus engine."),
 			ethjson::spec::Engine::Tendermint(tendermint) => Tendermint::new(params, From::from(tendermint.params), builtins).expect("Failed to start the Tendermint consensus engine."),
+			ethjson::spec::Engine::Clique(clique) => Clique::new(params, From::from(clique.params), builtins).expect("Failed to start the Clique consensus engine."),
 		}
 	}
 
@@ -497,10 +523,22 @@ impl Spec {
 	/// Accounts with secrets "0".sha3() and "1".sha3() are the validators.
 	pub fn new_test_round() -> Self { load_bundled!("authority_round") }
 
+	/// Create a new Spec with AuthorityRound consensus which uses a block reward contract at address 0x42.
+	/// The contract rewards each benefactor with 1000 plus the reward kind.
+	pub fn new_test_round_block_reward_contract() -> Self { load_bundled!("authority_round_block_reward_contract") }
+
+	/// Create a new Spec with AuthorityRound consensus using empty step messages from block 1.
+	/// Accounts with secrets "0".sha3() and "1".sha3() are the validators.
+	pub fn new_test_round_empty_steps() -> Self { load_bundled!("authority_round_empty_steps") }
+
 	/// Create a new Spec with Tendermint consensus which does internal sealing (not requiring work).
 	/// Account "0".sha3() and "1".sha3() are a authorities.
 	pub fn new_test_tendermint() -> Self { load_bundled!("tendermint") }
 
+	/// Create a new Spec with Clique consensus which does internal sealing (not requiring work).
+	/// Accounts with secrets "0".sha3() and "1".sha3() are the initial signers.
+	pub fn new_test_clique() -> Self { load_bundled!("clique") }
+
 	/// TestList.sol used in both specs: https://github.com/paritytech/contracts/pull/30/files
 	/// Accounts with secrets "0".sha3() and "1".sha3() are initially the validators.
 	/// Create a new Spec with BasicAuthority which uses a contract at address 5 to determine the current validators using `getValidators`.
@@ -45,6 +45,9 @@ pub struct Account {
 	// Modified storage. Accumulates changes to storage made in `set_storage`
 	// Takes precedence over `storage_cache`.
 	storage_changes: HashMap<H256, H256>,
+	// Storage values as they were at the start of the current transaction.
+	// Recorded on the first modification of a key in `set_storage`.
+	original_storage: HashMap<H256, H256>,
 	// Code hash of the account.
 	code_hash: H256,
 	// Size of the accoun code.
@@ -65,6 +68,7 @@ impl From<BasicAccount> for Account {
 			storage_root: basic.storage_root,
 			storage_cache: Self::empty_storage_cache(),
 			storage_changes: HashMap::new(),
+			original_storage: HashMap::new(),
 			code_hash: basic.code_hash,
 			code_size: None,
 			code_cache: Arc::new(vec![]),
@@ -84,6 +88,7 @@ impl Account {
 			storage_root: SHA3_NULL_RLP,
 			storage_cache: Self::empty_storage_cache(),
 			storage_changes: storage,
+			original_storage: HashMap::new(),
 			code_hash: code.sha3(),
 			code_size: Some(code.len()),
 			code_cache: Arc::new(code),
@@ -104,6 +109,7 @@ impl Account {
 			storage_root: SHA3_NULL_RLP,
 			storage_cache: Self::empty_storage_cache(),
 			storage_changes: pod.storage.into_iter().collect(),
+			original_storage: HashMap::new(),
 			code_hash: pod.code.as_ref().map_or(SHA3_EMPTY, |c| c.sha3()),
 			code_filth: Filth::Dirty,
 			code_size: Some(pod.code.as_ref().map_or(0, |c| c.len())),
@@ -120,6 +126,7 @@ impl Account {
 			storage_root: SHA3_NULL_RLP,
 			storage_cache: Self::empty_storage_cache(),
 			storage_changes: HashMap::new(),
+			original_storage: HashMap::new(),
 			code_hash: SHA3_EMPTY,
 			code_cache: Arc::new(vec![]),
 			code_size: Some(0),
@@ -143,6 +150,7 @@ impl Account {
 			storage_root: SHA3_NULL_RLP,
 			storage_cache: Self::empty_storage_cache(),
 			storage_changes: HashMap::new(),
+			original_storage: HashMap::new(),
 			code_hash: SHA3_EMPTY,
 			code_cache: Arc::new(vec![]),
 			code_size: None,
@@ -165,11 +173,36 @@ impl Account {
 		self.init_code(code);
 	}
 
+	/// Clear the whole storage of this account, including uncommitted changes.
+	pub fn reset_storage(&mut self) {
+		self.storage_root = SHA3_NULL_RLP;
+		self.storage_cache = Self::empty_storage_cache();
+		self.storage_changes.clear();
+		self.original_storage.clear();
+	}
+
 	/// Set (and cache) the contents of the trie's storage at `key` to `value`.
 	pub fn set_storage(&mut self, key: H256, value: H256) {
 		self.storage_changes.insert(key, value);
 	}
 
+	/// Remember `value` as the contents of storage at `key` at the start of the current transaction.
+	/// Does nothing if an original value for `key` has already been noted.
+	pub fn note_original_storage(&mut self, key: H256, value: H256) {
+		self.original_storage.entry(key).or_insert(value);
+	}
+
+	/// Get the contents of storage at `key` as of the start of the current transaction.
+	/// Returns `None` if the key has not been modified since, in which case the current value is the original one.
+	pub fn original_storage_at(&self, key: &H256) -> Option<H256> {
+		self.original_storage.get(key).cloned()
+	}
+
+	/// Forget noted original storage values, so that the current storage becomes the original one.
+	pub fn clear_original_storage(&mut self) {
+		self.original_storage.clear();
+	}
+
 	/// Get (and cache) the contents of the trie's storage at `key`.
 	/// Takes modifed storage into account.
 	pub fn storage_at(&self, db: &HashDB, key: &H256) -> trie::Result<H256> {
@@ -329,6 +362,19 @@ impl Account {
 	/// Return the storage overlay.
 	pub fn storage_changes(&self) -> &HashMap<H256, H256> { &self.storage_changes }
 
+	/// Return the storage root as of the last commit, disregarding the overlay.
+	pub fn base_storage_root(&self) -> &H256 { &self.storage_root }
+
+	/// Set the nonce of the account.
+	pub fn set_nonce(&mut self, nonce: U256) {
+		self.nonce = nonce;
+	}
+
+	/// Set the balance of the account.
+	pub fn set_balance(&mut self, balance: U256) {
+		self.balance = balance;
+	}
+
 	/// Increment the nonce of the account by one.
 	pub fn inc_nonce(&mut self) {
 		self.nonce = self.nonce + U256::from(1u8);
@@ -397,6 +443,7 @@ impl Account {
 			storage_root: self.storage_root.clone(),
 			storage_cache: Self::empty_storage_cache(),
 			storage_changes: HashMap::new(),
+			original_storage: HashMap::new(),
 			code_hash: self.code_hash.clone(),
 			code_size: self.code_size.clone(),
 			code_cache: self.code_cache.clone(),
@@ -409,6 +456,7 @@ impl Account {
 	pub fn clone_dirty(&self) -> Account {
 		let mut account = self.clone_basic();
 		account.storage_changes = self.storage_changes.clone();
+		account.original_storage = self.original_storage.clone();
 		account.code_cache = self.code_cache.clone();
 		account
 	}
@@ -437,6 +485,7 @@ impl Account {
 			cache.insert(k.clone() , v.clone()); //TODO: cloning should not be required here
 		}
 		self.storage_changes = other.storage_changes;
+		self.original_storage = other.original_storage;
 	}
 }
 
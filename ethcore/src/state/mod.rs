@@ -37,6 +37,7 @@ use pod_state::{self, PodState};
 use types::basic_account::BasicAccount;
 use executed::{Executed, ExecutionError};
 use types::state_diff::StateDiff;
+use types::state_override::StateOverride;
 use transaction::SignedTransaction;
 use state_db::StateDB;
 use evm::{Factory as EvmFactory};
@@ -267,6 +268,8 @@ pub struct State<B: Backend> {
 	cache: RefCell<HashMap<Address, AccountEntry>>,
 	// The original account is preserved in
 	checkpoints: RefCell<Vec<HashMap<Address, Option<AccountEntry>>>>,
+	// Accounts and storage slots accessed since recording was started, if it was.
+	accessed: RefCell<Option<HashMap<Address, HashSet<H256>>>>,
 	account_start_nonce: U256,
 	factories: Factories,
 }
@@ -307,6 +310,7 @@ impl<B: Backend> State<B> {
 			root: root,
 			cache: RefCell::new(HashMap::new()),
 			checkpoints: RefCell::new(Vec::new()),
+			accessed: RefCell::new(None),
 			account_start_nonce: account_start_nonce,
 			factories: factories,
 		}
@@ -323,6 +327,7 @@ impl<B: Backend> State<B> {
 			root: root,
 			cache: RefCell::new(HashMap::new()),
 			checkpoints: RefCell::new(Vec::new()),
+			accessed: RefCell::new(None),
 			account_start_nonce: account_start_nonce,
 			factories: factories
 		};
@@ -343,6 +348,7 @@ impl<B: Backend> State<B> {
 			root: self.root,
 			cache: self.cache,
 			checkpoints: self.checkpoints,
+			accessed: self.accessed,
 			account_start_nonce: self.account_start_nonce,
 			factories: self.factories,
 		}
@@ -463,6 +469,12 @@ impl<B: Backend> State<B> {
 		self.ensure_cached(a, RequireCache::CodeSize, false, |a| a.map_or(false, |a| a.code_size().map_or(false, |size| size != 0)))
 	}
 
+	/// Determine whether an account exists and has code or a non-zero nonce.
+	pub fn exists_and_has_code_or_nonce(&self, a: &Address) -> trie::Result<bool> {
+		self.ensure_cached(a, RequireCache::CodeSize, false,
+			|a| a.map_or(false, |a| a.code_size().map_or(false, |size| size != 0) || *a.nonce() != self.account_start_nonce))
+	}
+
 	/// Get the balance of account `a`.
 	pub fn balance(&self, a: &Address) -> trie::Result<U256> {
 		self.ensure_cached(a, RequireCache::None, true,
@@ -481,6 +493,30 @@ impl<B: Backend> State<B> {
 			|a| a.as_ref().and_then(|account| account.storage_root().cloned()))
 	}
 
+	/// Get the value of storage of account `address` at `key` as it was at the start of the current transaction.
+	pub fn original_storage_at(&self, address: &Address, key: &H256) -> trie::Result<H256> {
+		{
+			let local_cache = self.cache.borrow();
+			let original = local_cache.get(address)
+				.and_then(|entry| entry.account.as_ref())
+				.and_then(|account| account.original_storage_at(key));
+			if let Some(value) = original {
+				return Ok(value);
+			}
+		}
+		self.storage_at(address, key)
+	}
+
+	/// Forget original storage values of all cached accounts, making the current
+	/// storage the original one. Called at the start of each transaction.
+	pub fn clear_original_storage(&mut self) {
+		for entry in self.cache.get_mut().values_mut() {
+			if let Some(ref mut account) = entry.account {
+				account.clear_original_storage();
+			}
+		}
+	}
+
 	/// Mutate storage of account `address` so that it is `value` for `key`.
 	pub fn storage_at(&self, address: &Address, key: &H256) -> trie::Result<H256> {
 		// Storage key search and update works like this:
@@ -488,6 +524,8 @@ impl<B: Backend> State<B> {
 		// 2. If there's an entry for the account in the global cache check for the key or load it into that account.
 		// 3. If account is missing in the global cache load it into the local cache and cache the key there.
 
+		self.note_access(address, Some(key));
+
 		// check local cache first without updating
 		{
 			let local_cache = self.cache.borrow_mut();
@@ -505,17 +543,22 @@ impl<B: Backend> State<B> {
 				}
 			}
 			// check the global cache and and cache storage key there if found,
+			// unless the storage of the local account has been replaced since.
+			let local_root = local_account.as_ref()
+				.and_then(|entry| entry.account.as_ref())
+				.map(|account| *account.base_storage_root());
 			let trie_res = self.db.get_cached(address, |acc| match acc {
-				None => Ok(H256::new()),
+				None => Some(Ok(H256::new())),
+				Some(ref a) if local_root.map_or(false, |root| root != *a.base_storage_root()) => None,
 				Some(a) => {
 					let account_db = self.factories.accountdb.readonly(self.db.as_hashdb(), a.address_hash(address));
-					a.storage_at(account_db.as_hashdb(), key)
+					Some(a.storage_at(account_db.as_hashdb(), key))
 				}
 			});
 
 			match trie_res {
-				None => {}
-				Some(res) => return res,
+				None | Some(None) => {}
+				Some(Some(res)) => return res,
 			}
 
 			// otherwise cache the account localy and cache storage key there.
@@ -555,6 +598,13 @@ impl<B: Backend> State<B> {
 			|a| a.as_ref().map_or(SHA3_EMPTY, |a| a.code_hash()))
 	}
 
+	/// Get an account's code hash, or `None` if the account does not exist or is empty.
+	/// Does not load the code itself.
+	pub fn code_hash_if_exists(&self, a: &Address) -> trie::Result<Option<H256>> {
+		self.ensure_cached(a, RequireCache::None, true,
+			|a| a.as_ref().and_then(|a| if a.is_null() { None } else { Some(a.code_hash()) }))
+	}
+
 	/// Get accounts' code size.
 	pub fn code_size(&self, a: &Address) -> trie::Result<Option<usize>> {
 		self.ensure_cached(a, RequireCache::CodeSize, true,
@@ -604,8 +654,11 @@ impl<B: Backend> State<B> {
 	/// Mutate storage of account `a` so that it is `value` for `key`.
 	pub fn set_storage(&mut self, a: &Address, key: H256, value: H256) -> trie::Result<()> {
 		trace!(target: "state", "set_storage({}:{} to {})", a, key.hex(), value.hex());
-		if self.storage_at(a, &key)? != value {
-			self.require(a, false)?.set_storage(key, value)
+		let current = self.storage_at(a, &key)?;
+		if current != value {
+			let mut account = self.require(a, false)?;
+			account.note_original_storage(key, current);
+			account.set_storage(key, value);
 		}
 
 		Ok(())
@@ -624,6 +677,34 @@ impl<B: Backend> State<B> {
 		Ok(())
 	}
 
+	/// Override the given accounts, e.g. to simulate a call against a modified state.
+	/// Changes are made to the local cache only and are not committed.
+	pub fn apply_overrides(&mut self, overrides: &StateOverride) -> trie::Result<()> {
+		for (address, account) in overrides {
+			if let Some(ref code) = account.code {
+				self.reset_code(address, code.clone())?;
+			}
+
+			{
+				let mut acc = self.require(address, false)?;
+				if let Some(balance) = account.balance {
+					acc.set_balance(balance);
+				}
+				if let Some(nonce) = account.nonce {
+					acc.set_nonce(nonce);
+				}
+				if account.state.is_some() {
+					acc.reset_storage();
+				}
+			}
+
+			for (key, value) in account.state.iter().chain(account.state_diff.iter()).flat_map(|s| s.iter()) {
+				self.set_storage(address, *key, *value)?;
+			}
+		}
+		Ok(())
+	}
+
 	/// Execute a given transaction, producing a receipt and an optional trace.
 	/// This will change the state accordingly.
 	pub fn apply(&mut self, env_info: &EnvInfo, engine: &Engine, t: &SignedTransaction, tracing: bool) -> ApplyResult {
@@ -647,7 +728,7 @@ impl<B: Backend> State<B> {
 		vm_tracer: V,
 	) -> ApplyResult where
 		T: trace::Tracer,
-		V: trace::VMTracer,
+		V: trace::VMTracer<Output = VMTrace>,
 	{
 		let options = TransactOptions::new(tracer, vm_tracer);
 		let e = self.execute(env_info, engine, t, options, false)?;
@@ -676,7 +757,7 @@ impl<B: Backend> State<B> {
 	// `virt` signals that we are executing outside of a block set and restrictions like
 	// gas limits and gas costs should be lifted.
 	fn execute<T, V>(&mut self, env_info: &EnvInfo, engine: &Engine, t: &SignedTransaction, options: TransactOptions<T, V>, virt: bool)
-		-> Result<Executed, ExecutionError> where T: trace::Tracer, V: trace::VMTracer,
+		-> Result<Executed<V::Output>, ExecutionError> where T: trace::Tracer, V: trace::VMTracer,
 	{
 		let mut e = Executive::new(self, env_info, engine);
 
@@ -809,6 +890,51 @@ impl<B: Backend> State<B> {
 		Ok(pod_state::diff_pod(&state_pre.to_pod(), &pod_state_post))
 	}
 
+	/// Start recording every account and storage slot accessed from now on.
+	/// Any previous record is discarded.
+	pub fn start_recording_access(&mut self) {
+		*self.accessed.get_mut() = Some(HashMap::new());
+	}
+
+	/// Stop recording accesses and return the accessed accounts together with the storage
+	/// slots accessed within each of them.
+	pub fn take_accessed(&mut self) -> HashMap<Address, HashSet<H256>> {
+		self.accessed.get_mut().take().unwrap_or_default()
+	}
+
+	/// Returns the given accounts as found in this state, limiting their storage to the
+	/// given slots. Accounts which don't exist are skipped.
+	pub fn to_pod_accounts(&self, accounts: &HashMap<Address, HashSet<H256>>) -> trie::Result<PodState> {
+		let mut pod = BTreeMap::new();
+		for (address, keys) in accounts {
+			if !self.exists(address)? {
+				continue;
+			}
+
+			let mut storage = BTreeMap::new();
+			for key in keys {
+				storage.insert(key.clone(), self.storage_at(address, key)?);
+			}
+
+			pod.insert(address.clone(), PodAccount {
+				balance: self.balance(address)?,
+				nonce: self.nonce(address)?,
+				code: self.code(address)?.map(|code| (*code).clone()),
+				storage: storage,
+			});
+		}
+		Ok(PodState::from(pod))
+	}
+
+	fn note_access(&self, a: &Address, key: Option<&H256>) {
+		if let Some(ref mut accessed) = *self.accessed.borrow_mut() {
+			let keys = accessed.entry(a.clone()).or_insert_with(HashSet::new);
+			if let Some(key) = key {
+				keys.insert(key.clone());
+			}
+		}
+	}
+
 	// load required account data from the databases.
 	fn update_account_cache(require: RequireCache, account: &mut Account, state_db: &B, db: &HashDB) {
 		match (account.is_cached(), require) {
@@ -842,6 +968,8 @@ impl<B: Backend> State<B> {
 	/// Populates local cache if nothing found.
 	fn ensure_cached<F, U>(&self, a: &Address, require: RequireCache, check_null: bool, f: F) -> trie::Result<U>
 		where F: Fn(Option<&Account>) -> U {
+		self.note_access(a, None);
+
 		// check local cache first
 		if let Some(ref mut maybe_acc) = self.cache.borrow_mut().get_mut(a) {
 			if let Some(ref mut account) = maybe_acc.account {
@@ -889,6 +1017,8 @@ impl<B: Backend> State<B> {
 	fn require_or_from<'a, F, G>(&'a self, a: &Address, require_code: bool, default: F, not_default: G) -> trie::Result<RefMut<'a, Account>>
 		where F: FnOnce() -> Account, G: FnOnce(&mut Account),
 	{
+		self.note_access(a, None);
+
 		let contains_key = self.cache.borrow().contains_key(a);
 		if !contains_key {
 			match self.db.get_cached_account(a) {
@@ -1000,6 +1130,7 @@ impl Clone for State<StateDB> {
 			root: self.root.clone(),
 			cache: RefCell::new(cache),
 			checkpoints: RefCell::new(Vec::new()),
+			accessed: RefCell::new(None),
 			account_start_nonce: self.account_start_nonce.clone(),
 			factories: self.factories.clone(),
 		}
@@ -1022,6 +1153,7 @@ mod tests {
 	use ethcore_logger::init_log;
 	use trace::{FlatTrace, TraceError, trace};
 	use evm::CallType;
+	use types::state_override::AccountOverride;
 
 	fn secret() -> Secret {
 		"".sha3().into()
@@ -1870,6 +2002,23 @@ mod tests {
 		assert_eq!(state.code(&a).unwrap(), Some(Arc::new(vec![1u8, 2, 3])));
 	}
 
+	#[test]
+	fn code_hash_if_exists_from_database() {
+		let a = Address::zero();
+		let b = 1u64.into();
+		let (root, db) = {
+			let mut state = get_temp_state();
+			state.require_or_from(&a, false, ||Account::new_contract(42.into(), 0.into()), |_|{}).unwrap();
+			state.init_code(&a, vec![1, 2, 3]).unwrap();
+			state.commit().unwrap();
+			state.drop()
+		};
+
+		let state = State::from_existing(db, root, U256::from(0u8), Default::default()).unwrap();
+		assert_eq!(state.code_hash_if_exists(&a).unwrap(), Some(vec![1u8, 2, 3].sha3()));
+		assert_eq!(state.code_hash_if_exists(&b).unwrap(), None);
+	}
+
 	#[test]
 	fn storage_at_from_database() {
 		let a = Address::zero();
@@ -1884,6 +2033,99 @@ mod tests {
 		assert_eq!(s.storage_at(&a, &H256::from(&U256::from(1u64))).unwrap(), H256::from(&U256::from(69u64)));
 	}
 
+	#[test]
+	fn apply_overrides() {
+		let a = Address::from(1);
+		let b = Address::from(2);
+		let key = |k: u64| H256::from(U256::from(k));
+		let (root, db) = {
+			let mut state = get_temp_state();
+			state.add_balance(&a, &U256::from(100u64), CleanupMode::NoEmpty).unwrap();
+			state.set_storage(&a, key(1), key(69)).unwrap();
+			state.set_storage(&a, key(2), key(70)).unwrap();
+			state.set_storage(&b, key(1), key(69)).unwrap();
+			state.set_storage(&b, key(2), key(70)).unwrap();
+			state.commit().unwrap();
+			state.drop()
+		};
+
+		let mut overrides = StateOverride::new();
+		overrides.insert(a, AccountOverride {
+			balance: Some(5.into()),
+			nonce: Some(7.into()),
+			code: Some(vec![0x60, 0x00]),
+			state: Some(vec![(key(1), key(3))].into_iter().collect()),
+			state_diff: None,
+		});
+		overrides.insert(b, AccountOverride {
+			state_diff: Some(vec![(key(1), key(4))].into_iter().collect()),
+			..Default::default()
+		});
+
+		let mut state = State::from_existing(db, root, U256::from(0u8), Default::default()).unwrap();
+		state.apply_overrides(&overrides).unwrap();
+
+		assert_eq!(state.balance(&a).unwrap(), 5.into());
+		assert_eq!(state.nonce(&a).unwrap(), 7.into());
+		assert_eq!(state.code(&a).unwrap(), Some(Arc::new(vec![0x60, 0x00])));
+		assert_eq!(state.storage_at(&a, &key(1)).unwrap(), key(3));
+		assert_eq!(state.storage_at(&a, &key(2)).unwrap(), H256::zero());
+		assert_eq!(state.storage_at(&b, &key(1)).unwrap(), key(4));
+		assert_eq!(state.storage_at(&b, &key(2)).unwrap(), key(70));
+	}
+
+	#[test]
+	fn records_accessed_accounts() {
+		let a = Address::from(1);
+		let b = Address::from(2);
+		let key = |k: u64| H256::from(U256::from(k));
+		let (root, db) = {
+			let mut state = get_temp_state();
+			state.add_balance(&a, &U256::from(100u64), CleanupMode::NoEmpty).unwrap();
+			state.set_storage(&a, key(1), key(69)).unwrap();
+			state.set_storage(&a, key(2), key(70)).unwrap();
+			state.commit().unwrap();
+			state.drop()
+		};
+
+		let mut state = State::from_existing(db, root, U256::from(0u8), Default::default()).unwrap();
+		let original = state.clone();
+		state.start_recording_access();
+		state.set_storage(&a, key(1), key(3)).unwrap();
+		state.add_balance(&b, &U256::from(1u64), CleanupMode::NoEmpty).unwrap();
+		let accessed = state.take_accessed();
+		state.storage_at(&a, &key(2)).unwrap();
+		assert_eq!(accessed.len(), 2);
+		assert!(state.take_accessed().is_empty());
+
+		let pre = original.to_pod_accounts(&accessed).unwrap();
+		assert_eq!(pre.get().len(), 1);
+		let account = &pre.get()[&a];
+		assert_eq!(account.balance, 100.into());
+		assert_eq!(account.storage, vec![(key(1), key(69))].into_iter().collect());
+	}
+
+	#[test]
+	fn original_storage_at() {
+		let a = Address::zero();
+		let k = H256::from(&U256::from(1u64));
+		let mut state = get_temp_state();
+		state.set_storage(&a, k.clone(), H256::from(&U256::from(69u64))).unwrap();
+		assert_eq!(state.original_storage_at(&a, &k).unwrap(), H256::new());
+
+		state.checkpoint();
+		state.set_storage(&a, k.clone(), H256::from(&U256::from(70u64))).unwrap();
+		assert_eq!(state.original_storage_at(&a, &k).unwrap(), H256::new());
+		state.revert_to_checkpoint();
+		assert_eq!(state.original_storage_at(&a, &k).unwrap(), H256::new());
+
+		state.clear_original_storage();
+		assert_eq!(state.original_storage_at(&a, &k).unwrap(), H256::from(&U256::from(69u64)));
+		state.set_storage(&a, k.clone(), H256::from(&U256::from(71u64))).unwrap();
+		assert_eq!(state.original_storage_at(&a, &k).unwrap(), H256::from(&U256::from(69u64)));
+		assert_eq!(state.storage_at(&a, &k).unwrap(), H256::from(&U256::from(71u64)));
+	}
+
 	#[test]
 	fn get_from_database() {
 		let a = Address::zero();
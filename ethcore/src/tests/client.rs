@@ -17,7 +17,7 @@
 use std::str::FromStr;
 use std::sync::Arc;
 use io::IoChannel;
-use client::{BlockChainClient, MiningBlockChainClient, Client, ClientConfig, BlockId};
+use client::{BlockChainClient, MiningBlockChainClient, Client, ClientConfig, BlockId, TransactionId};
 use state::{self, State, CleanupMode};
 use executive::{Executive, TransactOptions};
 use ethereum;
@@ -328,6 +328,131 @@ fn does_not_propagate_delayed_transactions() {
 	assert_eq!(2, client.miner().pending_transactions().len());
 }
 
+#[test]
+fn replays_block_transactions() {
+	use client::CallAnalytics;
+
+	let client = generate_dummy_client_with_data(2, 2, &[1.into()]);
+	let analytics = CallAnalytics { transaction_tracing: true, vm_tracing: false, state_diffing: true, pre_state_dumping: false };
+	let body = client.block_body(BlockId::Number(2)).unwrap();
+	let hashes: Vec<_> = body.transactions().iter().map(|t| t.hash()).collect();
+
+	let replayed = client.replay_block_transactions(BlockId::Number(2), analytics).unwrap();
+	assert_eq!(replayed.iter().map(|&(hash, _)| hash).collect::<Vec<_>>(), hashes);
+	assert_eq!(replayed[1].1.cumulative_gas_used, replayed[0].1.gas_used + replayed[1].1.gas_used);
+	assert!(replayed.iter().all(|&(_, ref executed)| executed.trace.len() == 1 && executed.state_diff.is_some()));
+
+	let (_, ref last) = replayed[1];
+	let single = client.replay(TransactionId::Hash(hashes[1]), analytics).unwrap();
+	assert_eq!(single.state_diff, last.state_diff);
+}
+
+#[test]
+fn replays_transaction_with_pre_state() {
+	use client::CallAnalytics;
+	use transaction::SignedTransaction;
+
+	let client = generate_dummy_client_with_data(2, 2, &[1.into()]);
+	let body = client.block_body(BlockId::Number(2)).unwrap();
+	let t = SignedTransaction::new(body.transactions()[1].clone()).unwrap();
+
+	let analytics = CallAnalytics { pre_state_dumping: true, ..Default::default() };
+	let replayed = client.replay(TransactionId::Hash(t.hash()), analytics).unwrap();
+	let pre_state = replayed.pre_state.unwrap();
+	assert_eq!(pre_state.get()[&t.sender()].nonce, t.nonce);
+	assert!(replayed.state_diff.is_none());
+}
+
+#[test]
+fn traces_transaction_struct_logs() {
+	let client = generate_dummy_client_with_data(2, 2, &[1.into()]);
+	let body = client.block_body(BlockId::Number(2)).unwrap();
+	let hash = body.transactions()[1].hash();
+
+	let traced = client.debug_trace_transaction(TransactionId::Hash(hash), Default::default()).unwrap();
+	let replayed = client.replay(TransactionId::Hash(hash), Default::default()).unwrap();
+	assert_eq!(traced.gas_used, replayed.gas_used);
+	// plain value transfer, no code is executed
+	assert_eq!(traced.vm_trace, Some(vec![]));
+}
+
+#[test]
+fn backfills_traces() {
+	let spec = get_test_spec();
+	let client_db: Arc<::util::kvdb::KeyValueDB> = Arc::new(::util::kvdb::in_memory(::db::NUM_COLUMNS.unwrap_or(0)));
+	{
+		let client = Client::new(
+			ClientConfig::default(),
+			&spec,
+			client_db.clone(),
+			Arc::new(Miner::with_spec(&spec)),
+			IoChannel::disconnected(),
+		).unwrap();
+		for block in get_good_dummy_block_seq(3) {
+			client.import_block(block).unwrap();
+		}
+		client.flush_queue();
+		client.import_verified_blocks();
+	}
+
+	let mut config = ClientConfig::default();
+	config.tracing.enabled = true;
+	config.tracing.backfill = true;
+	let client = Client::new(
+		config,
+		&spec,
+		client_db,
+		Arc::new(Miner::with_spec(&spec)),
+		IoChannel::disconnected(),
+	).unwrap();
+
+	assert_eq!(client.block_traces(BlockId::Number(2)), None);
+	assert_eq!(client.backfill_block_traces(2), Ok(true));
+	assert_eq!(client.backfill_block_traces(2), Ok(false));
+	assert_eq!(client.block_traces(BlockId::Number(2)), Some(vec![]));
+
+	// the rest is traced in the background
+	client.tick();
+	assert_eq!(client.block_traces(BlockId::Number(1)), Some(vec![]));
+	assert_eq!(client.block_traces(BlockId::Number(4)), Some(vec![]));
+}
+
+#[test]
+fn resumes_trace_backfill() {
+	let spec = get_test_spec();
+	let client_db: Arc<::util::kvdb::KeyValueDB> = Arc::new(::util::kvdb::in_memory(::db::NUM_COLUMNS.unwrap_or(0)));
+	let new_client = |tracing: bool, backfill: bool| {
+		let mut config = ClientConfig::default();
+		config.tracing.enabled = tracing;
+		config.tracing.backfill = backfill;
+		Client::new(
+			config,
+			&spec,
+			client_db.clone(),
+			Arc::new(Miner::with_spec(&spec)),
+			IoChannel::disconnected(),
+		).unwrap()
+	};
+
+	{
+		let client = new_client(false, false);
+		for block in get_good_dummy_block_seq(3) {
+			client.import_block(block).unwrap();
+		}
+		client.flush_queue();
+		client.import_verified_blocks();
+	}
+
+	// stopped before the first tick.
+	drop(new_client(true, true));
+
+	let client = new_client(true, false);
+	assert_eq!(client.block_traces(BlockId::Number(1)), None);
+	client.tick();
+	assert_eq!(client.block_traces(BlockId::Number(1)), Some(vec![]));
+	assert_eq!(client.block_traces(BlockId::Number(3)), Some(vec![]));
+}
+
 #[test]
 fn transaction_proof() {
 	use ::client::ProvingBlockChainClient;
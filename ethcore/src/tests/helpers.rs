@@ -66,6 +66,13 @@ impl TestEngine {
 			max_depth: 0,
 		}
 	}
+
+	pub fn new_constantinople(max_depth: usize) -> TestEngine {
+		TestEngine {
+			engine: ethereum::new_constantinople_test().engine,
+			max_depth: max_depth,
+		}
+	}
 }
 
 impl Engine for TestEngine {
@@ -410,6 +417,7 @@ pub fn get_default_ethash_params() -> EthashParams {
 		difficulty_hardfork_transition: u64::max_value(),
 		difficulty_hardfork_bound_divisor: U256::from(0),
 		bomb_defuse_transition: u64::max_value(),
+		difficulty_bomb_delays: BTreeMap::new(),
 		eip100b_transition: u64::max_value(),
 		eip150_transition: u64::max_value(),
 		eip160_transition: u64::max_value(),
@@ -418,6 +426,7 @@ pub fn get_default_ethash_params() -> EthashParams {
 		ecip1010_pause_transition: u64::max_value(),
 		ecip1010_continue_transition: u64::max_value(),
 		ecip1017_era_rounds: u64::max_value(),
+		block_reward: BTreeMap::new(),
 		max_code_size: u64::max_value(),
 		max_gas_limit_transition: u64::max_value(),
 		max_gas_limit: U256::max_value(),
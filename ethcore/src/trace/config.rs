@@ -23,6 +23,10 @@ pub struct Config {
 	/// Indicates if tracing should be enabled or not.
 	/// If it's None, it will be automatically configured.
 	pub enabled: bool,
+	/// Indicates if traces should also be indexed by address.
+	pub address_index: bool,
+	/// Indicates if blocks imported before tracing was enabled should be traced in the background.
+	pub backfill: bool,
 	/// Traces blooms configuration.
 	pub blooms: BloomConfig,
 	/// Preferef cache-size.
@@ -35,6 +39,8 @@ impl Default for Config {
 	fn default() -> Self {
 		Config {
 			enabled: false,
+			address_index: false,
+			backfill: false,
 			blooms: BloomConfig {
 				levels: 3,
 				elements_per_index: 16,
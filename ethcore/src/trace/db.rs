@@ -15,20 +15,26 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 //! Trace database.
-use std::ops::Deref;
-use std::collections::{HashMap, VecDeque};
+use std::ops::{Deref, Range};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
 use std::sync::Arc;
-use bloomchain::{Number, Config as BloomConfig};
+use std::time::Instant;
+use bloomchain::{Number, Config as BloomConfig, Bloom, Filter as BloomFilter};
 use bloomchain::group::{BloomGroupDatabase, BloomGroupChain, GroupPosition, BloomGroup};
-use util::{H256, H264, KeyValueDB, DBTransaction, RwLock, HeapSizeOf};
+use rlp::{self, Encodable, Decodable, RlpStream, UntrustedRlp, DecoderError};
+use util::{Address, H256, H264, KeyValueDB, DBTransaction, RwLock, HeapSizeOf};
 use header::BlockNumber;
-use trace::{LocalizedTrace, Config, Filter, Database as TraceDatabase, ImportRequest, DatabaseExtras};
+use trace::{LocalizedTrace, Config, Filter, TraceSide, Database as TraceDatabase, ImportRequest, DatabaseExtras};
 use db::{self, Key, Writable, Readable, CacheUpdatePolicy};
 use blooms;
 use super::flat::{FlatTrace, FlatBlockTraces, FlatTransactionTraces};
 use cache_manager::CacheManager;
 
 const TRACE_DB_VER: &'static [u8] = b"1.0";
+/// Key of the first block number from which all blocks are present in the address index.
+const ADDRESS_INDEX_FROM_KEY: &'static [u8] = b"address_index_from";
+/// Key of the first block number from which all blocks are traced, stored by the background backfill.
+const BACKFILL_CURSOR_KEY: &'static [u8] = b"backfill_cursor";
 
 #[derive(Debug, Copy, Clone)]
 #[cfg_attr(feature="dev", allow(enum_variant_names))]
@@ -37,6 +43,8 @@ enum TraceDBIndex {
 	BlockTraces = 0,
 	/// Trace bloom group index.
 	BloomGroups = 1,
+	/// Address index.
+	Addresses = 2,
 }
 
 impl Key<FlatBlockTraces> for H256 {
@@ -93,6 +101,82 @@ impl Key<blooms::BloomGroup> for TraceGroupPosition {
 	}
 }
 
+/// Positions of traces with an address on one side within a single block.
+#[derive(Debug, PartialEq, Clone)]
+struct AddressIndexEntry {
+	/// Hash of the indexed block, used to skip entries of retracted blocks.
+	block_hash: H256,
+	/// Transaction number and trace address of each trace.
+	positions: Vec<(usize, Vec<usize>)>,
+}
+
+impl Encodable for AddressIndexEntry {
+	fn rlp_append(&self, s: &mut RlpStream) {
+		s.begin_list(2);
+		s.append(&self.block_hash);
+		s.begin_list(self.positions.len());
+		for &(ref tx_number, ref trace_address) in &self.positions {
+			s.begin_list(2);
+			s.append(tx_number);
+			s.append_list::<usize, usize>(trace_address);
+		}
+	}
+}
+
+impl Decodable for AddressIndexEntry {
+	fn decode(d: &UntrustedRlp) -> Result<Self, DecoderError> {
+		let positions = d.at(1)?.iter()
+			.map(|p| -> Result<(usize, Vec<usize>), DecoderError> { Ok((p.val_at(0)?, p.list_at(1)?)) })
+			.collect::<Result<Vec<_>, _>>()?;
+
+		Ok(AddressIndexEntry {
+			block_hash: d.val_at(0)?,
+			positions: positions,
+		})
+	}
+}
+
+/// Prefix of all address index keys of given address on given side.
+fn address_index_prefix(side: TraceSide, address: &Address) -> Vec<u8> {
+	let mut key = Vec::with_capacity(30);
+	key.push(TraceDBIndex::Addresses as u8);
+	key.push(match side {
+		TraceSide::From => 0,
+		TraceSide::To => 1,
+	});
+	key.extend_from_slice(address);
+	key
+}
+
+/// Address index key. Block number is stored big-endian, so keys of a single address are ordered by block.
+fn address_index_key(side: TraceSide, address: &Address, block_number: BlockNumber) -> Vec<u8> {
+	let mut key = address_index_prefix(side, address);
+	for i in 0..8 {
+		key.push((block_number >> (56 - 8 * i)) as u8);
+	}
+	key
+}
+
+fn address_index_block_number(key: &[u8]) -> BlockNumber {
+	key[key.len() - 8..].iter().fold(0, |number, byte| (number << 8) | *byte as BlockNumber)
+}
+
+/// Trace filter restricted to a part of its block range.
+struct RangeFilter<'a> {
+	filter: &'a Filter,
+	range: Range<Number>,
+}
+
+impl<'a> BloomFilter for RangeFilter<'a> {
+	fn bloom_possibilities(&self) -> Vec<Bloom> {
+		BloomFilter::bloom_possibilities(self.filter)
+	}
+
+	fn range(&self) -> Range<Number> {
+		self.range.clone()
+	}
+}
+
 #[derive(Debug, Hash, Eq, PartialEq)]
 enum CacheId {
 	Trace(H256),
@@ -111,6 +195,10 @@ pub struct TraceDB<T> where T: DatabaseExtras {
 	bloom_config: BloomConfig,
 	// tracing enabled
 	enabled: bool,
+	// address index enabled
+	address_index: bool,
+	// first block from which all blocks are indexed by address
+	address_index_from: RwLock<Option<BlockNumber>>,
 	// extras
 	extras: Arc<T>,
 }
@@ -132,6 +220,19 @@ impl<T> TraceDB<T> where T: DatabaseExtras {
 			.expect("Genesis block is always inserted upon extras db creation qed");
 		batch.write(db::COL_TRACE, &genesis, &FlatBlockTraces::default());
 		batch.put(db::COL_TRACE, b"version", TRACE_DB_VER);
+
+		// blocks imported while the address index is disabled won't be indexed,
+		// so it has to be built from scratch once it's enabled again.
+		let address_index = config.enabled && config.address_index;
+		let address_index_from = match address_index {
+			true => tracesdb.get(db::COL_TRACE, ADDRESS_INDEX_FROM_KEY)
+				.expect("Low-level database error. Some issue with your hard disk?")
+				.map(|from| rlp::decode(&from)),
+			false => {
+				batch.delete(db::COL_TRACE, ADDRESS_INDEX_FROM_KEY);
+				None
+			},
+		};
 		tracesdb.write(batch).expect("failed to update version");
 
 		TraceDB {
@@ -141,10 +242,105 @@ impl<T> TraceDB<T> where T: DatabaseExtras {
 			tracesdb: tracesdb,
 			bloom_config: config.blooms,
 			enabled: config.enabled,
+			address_index: address_index,
+			address_index_from: RwLock::new(address_index_from),
 			extras: extras,
 		}
 	}
 
+	/// Returns true if traces of the block with given hash are in the database.
+	pub fn has_traces(&self, block_hash: &H256) -> bool {
+		self.tracesdb.exists_with_cache::<_, FlatBlockTraces, _, _>(db::COL_TRACE, &self.traces, block_hash)
+	}
+
+	/// Returns the first block from which all blocks are traced, as stored by the background backfill.
+	pub fn backfill_cursor(&self) -> Option<BlockNumber> {
+		self.tracesdb.get(db::COL_TRACE, BACKFILL_CURSOR_KEY)
+			.expect("Low-level database error. Some issue with your hard disk?")
+			.map(|cursor| rlp::decode(&cursor))
+	}
+
+	/// Inserts the first block from which all blocks are traced into the batch.
+	pub fn set_backfill_cursor(&self, batch: &mut DBTransaction, cursor: BlockNumber) {
+		batch.put(db::COL_TRACE, BACKFILL_CURSOR_KEY, &rlp::encode(&cursor));
+	}
+
+	/// Indexes by address up to `max_blocks` blocks imported before the address index
+	/// was enabled, going backwards from the first indexed block.
+	/// At least one block is indexed, further ones only until `deadline` has passed.
+	/// Stops at the first block missing from the database, e.g. below a warp-restored snapshot.
+	/// Returns the number of blocks which are still not indexed and can be.
+	pub fn build_address_index(&self, max_blocks: BlockNumber, deadline: Instant) -> BlockNumber {
+		let from = match *self.address_index_from.read() {
+			Some(from) if self.address_index => from,
+			_ => return 0,
+		};
+
+		let mut start = from;
+		let mut complete = true;
+		let mut batch = DBTransaction::new();
+		for number in (from.saturating_sub(max_blocks)..from).rev() {
+			let hash = match self.extras.block_hash(number) {
+				Some(hash) => hash,
+				None => {
+					complete = false;
+					break;
+				},
+			};
+			// blocks imported before tracing was enabled have no traces, and nothing to index.
+			if let Some(traces) = self.tracesdb.read::<FlatBlockTraces, _>(db::COL_TRACE, &hash) {
+				Self::index_block(&mut batch, number, &hash, traces);
+			}
+			start = number;
+			if Instant::now() >= deadline {
+				break;
+			}
+		}
+
+		if start != from {
+			batch.put(db::COL_TRACE, ADDRESS_INDEX_FROM_KEY, &rlp::encode(&start));
+			self.tracesdb.write(batch).expect("Low-level database error. Some issue with your hard disk?");
+			*self.address_index_from.write() = Some(start);
+
+			match (start, complete) {
+				(0, _) => info!(target: "trace", "Address index of traces built."),
+				(_, false) => info!(target: "trace", "Address index of traces built down to block #{}, older blocks are missing.", start),
+				_ => debug!(target: "trace", "Indexed traces of blocks #{}..#{} by address.", start, from),
+			}
+		}
+
+		match complete {
+			true => start,
+			false => 0,
+		}
+	}
+
+	/// Inserts address index entries of given block traces into the batch.
+	fn index_block(batch: &mut DBTransaction, block_number: BlockNumber, block_hash: &H256, traces: FlatBlockTraces) {
+		let mut entries: HashMap<(TraceSide, Address), Vec<(usize, Vec<usize>)>> = HashMap::new();
+		let tx_traces: Vec<FlatTransactionTraces> = traces.into();
+		for (tx_number, tx_traces) in tx_traces.into_iter().enumerate() {
+			let flat_traces: Vec<FlatTrace> = tx_traces.into();
+			for trace in flat_traces {
+				for side in &[TraceSide::From, TraceSide::To] {
+					if let Some(address) = side.address(&trace) {
+						entries.entry((*side, address))
+							.or_insert_with(Vec::new)
+							.push((tx_number, trace.trace_address.iter().cloned().collect()));
+					}
+				}
+			}
+		}
+
+		for ((side, address), positions) in entries {
+			let entry = AddressIndexEntry {
+				block_hash: block_hash.clone(),
+				positions: positions,
+			};
+			batch.put(db::COL_TRACE, &address_index_key(side, &address, block_number), &rlp::encode(&entry));
+		}
+	}
+
 	fn cache_size(&self) -> usize {
 		let traces = self.traces.read().heap_size_of_children();
 		let blooms = self.blooms.read().heap_size_of_children();
@@ -293,6 +489,24 @@ impl<T> TraceDatabase for TraceDB<T> where T: DatabaseExtras {
 			}
 		}
 
+		// index traces of enacted blocks by address
+		if self.address_index && !request.enacted.is_empty() {
+			let first = request.block_number + 1 - request.enacted.len() as BlockNumber;
+			for (offset, block_hash) in request.enacted.iter().enumerate() {
+				let traces = match block_hash == &request.block_hash {
+					true => request.traces.clone(),
+					false => self.traces(block_hash).expect("Traces database is incomplete."),
+				};
+				Self::index_block(batch, first + offset as BlockNumber, block_hash, traces);
+			}
+
+			let mut address_index_from = self.address_index_from.write();
+			if address_index_from.is_none() {
+				batch.put(db::COL_TRACE, ADDRESS_INDEX_FROM_KEY, &rlp::encode(&first));
+				*address_index_from = Some(first);
+			}
+		}
+
 		// insert new block traces into the cache and the database
 		{
 			let mut traces = self.traces.write();
@@ -385,8 +599,31 @@ impl<T> TraceDatabase for TraceDB<T> where T: DatabaseExtras {
 	}
 
 	fn filter(&self, filter: &Filter) -> Vec<LocalizedTrace> {
+		let index_from = match *self.address_index_from.read() {
+			Some(from) if self.address_index && from as Number <= filter.range.end => from as Number,
+			_ => return self.filter_with_blooms(filter, filter.range.clone()),
+		};
+
+		match filter.indexed_side() {
+			Some((side, addresses)) => {
+				let mut traces = match filter.range.start < index_from {
+					true => self.filter_with_blooms(filter, filter.range.start..(index_from - 1)),
+					false => vec![],
+				};
+				let start = ::std::cmp::max(filter.range.start, index_from);
+				traces.extend(self.filter_with_index(filter, side, addresses, start..filter.range.end));
+				traces
+			},
+			None => self.filter_with_blooms(filter, filter.range.clone()),
+		}
+	}
+}
+
+impl<T> TraceDB<T> where T: DatabaseExtras {
+	/// Filters traces in given inclusive block range using bloom groups.
+	fn filter_with_blooms(&self, filter: &Filter, range: Range<Number>) -> Vec<LocalizedTrace> {
 		let chain = BloomGroupChain::new(self.bloom_config, self);
-		let numbers = chain.filter(filter);
+		let numbers = chain.filter(&RangeFilter { filter: filter, range: range });
 		numbers.into_iter()
 			.flat_map(|n| {
 				let number = n as BlockNumber;
@@ -398,12 +635,51 @@ impl<T> TraceDatabase for TraceDB<T> where T: DatabaseExtras {
 			})
 			.collect()
 	}
+
+	/// Filters traces in given inclusive block range using the address index.
+	fn filter_with_index(&self, filter: &Filter, side: TraceSide, addresses: &[Address], range: Range<Number>) -> Vec<LocalizedTrace> {
+		// transactions with matching traces, by block
+		let mut blocks: BTreeMap<BlockNumber, (H256, BTreeSet<usize>)> = BTreeMap::new();
+		for address in addresses {
+			let prefix = address_index_prefix(side, address);
+			let entries = self.tracesdb.iter_from_prefix(db::COL_TRACE, &prefix)
+				.take_while(|&(ref key, _)| key.starts_with(&prefix))
+				.map(|(key, value)| (address_index_block_number(&key), value))
+				.skip_while(|&(number, _)| number < range.start as BlockNumber)
+				.take_while(|&(number, _)| number <= range.end as BlockNumber);
+
+			for (number, value) in entries {
+				let AddressIndexEntry { block_hash, positions } = rlp::decode(&value);
+				// entries of retracted blocks are not removed
+				if self.extras.block_hash(number).as_ref() != Some(&block_hash) {
+					continue;
+				}
+
+				let tx_numbers = &mut blocks.entry(number).or_insert_with(|| (block_hash, BTreeSet::new())).1;
+				tx_numbers.extend(positions.into_iter().map(|(tx_number, _)| tx_number));
+			}
+		}
+
+		blocks.into_iter()
+			.flat_map(|(number, (hash, tx_numbers))| {
+				let traces: Vec<FlatTransactionTraces> = self.traces(&hash)
+					.expect("Expected to find a trace. Db is probably corrupted.")
+					.into();
+				traces.into_iter()
+					.enumerate()
+					.filter(|&(tx_number, _)| tx_numbers.contains(&tx_number))
+					.flat_map(|(tx_number, tx_traces)| self.matching_transaction_traces(filter, tx_traces, hash.clone(), number, tx_number))
+					.collect::<Vec<_>>()
+			})
+			.collect()
+	}
 }
 
 #[cfg(test)]
 mod tests {
 	use std::collections::HashMap;
 	use std::sync::Arc;
+	use std::time::{Instant, Duration};
 	use util::{Address, U256, H256, DBTransaction};
 	use header::BlockNumber;
 	use trace::{Config, TraceDB, Database as TraceDatabase, DatabaseExtras, ImportRequest};
@@ -708,4 +984,140 @@ mod tests {
 
 		assert_eq!(traces.len(), 0);
 	}
+
+	#[test]
+	fn filter_with_address_index() {
+		let db = new_db();
+		let mut config = Config::default();
+		config.enabled = true;
+		config.address_index = true;
+		let block_1 = H256::from(0xa1);
+		let block_2 = H256::from(0xa2);
+		let tx_1 = H256::from(0xff);
+		let tx_2 = H256::from(0xaf);
+
+		let mut extras = Extras::default();
+		extras.block_hashes.insert(0, H256::default());
+		extras.block_hashes.insert(1, block_1.clone());
+		extras.block_hashes.insert(2, block_2.clone());
+		extras.transaction_hashes.insert(1, vec![tx_1.clone()]);
+		extras.transaction_hashes.insert(2, vec![tx_2.clone()]);
+
+		let tracedb = TraceDB::new(config, db.clone(), Arc::new(extras));
+
+		for &(number, ref hash) in &[(1, block_1.clone()), (2, block_2.clone())] {
+			let mut batch = DBTransaction::new();
+			tracedb.import(&mut batch, create_simple_import_request(number, hash.clone()));
+			db.write(batch).unwrap();
+		}
+		assert_eq!(*tracedb.address_index_from.read(), Some(1));
+
+		let filter = Filter {
+			range: (1..2),
+			from_address: AddressesFilter::from(vec![Address::from(1)]),
+			to_address: AddressesFilter::from(vec![]),
+		};
+		let traces = tracedb.filter(&filter);
+		assert_eq!(traces, vec![
+			create_simple_localized_trace(1, block_1.clone(), tx_1.clone()),
+			create_simple_localized_trace(2, block_2.clone(), tx_2.clone()),
+		]);
+
+		let filter = Filter {
+			range: (2..2),
+			from_address: AddressesFilter::from(vec![]),
+			to_address: AddressesFilter::from(vec![Address::from(2)]),
+		};
+		assert_eq!(tracedb.filter(&filter), vec![create_simple_localized_trace(2, block_2, tx_2)]);
+
+		let filter = Filter {
+			range: (1..2),
+			from_address: AddressesFilter::from(vec![Address::from(2)]),
+			to_address: AddressesFilter::from(vec![]),
+		};
+		assert_eq!(tracedb.filter(&filter), vec![]);
+	}
+
+	fn far_deadline() -> Instant {
+		Instant::now() + Duration::from_secs(60)
+	}
+
+	#[test]
+	fn build_address_index_of_existing_blocks() {
+		let db = new_db();
+		let mut config = Config::default();
+		config.enabled = true;
+		let mut extras = Extras::default();
+		extras.block_hashes.insert(0, H256::default());
+		for number in 1..4 {
+			extras.block_hashes.insert(number, H256::from(0xa0 + number));
+			extras.transaction_hashes.insert(number, vec![H256::from(0xf0 + number)]);
+		}
+		let expected: Vec<_> = (1..4)
+			.map(|number| create_simple_localized_trace(number, H256::from(0xa0 + number), H256::from(0xf0 + number)))
+			.collect();
+		let filter = Filter {
+			range: (1..3),
+			from_address: AddressesFilter::from(vec![Address::from(1)]),
+			to_address: AddressesFilter::from(vec![]),
+		};
+
+		{
+			let tracedb = TraceDB::new(config.clone(), db.clone(), Arc::new(extras.clone()));
+			for number in 1..3 {
+				let mut batch = DBTransaction::new();
+				tracedb.import(&mut batch, create_simple_import_request(number, H256::from(0xa0 + number)));
+				db.write(batch).unwrap();
+			}
+		}
+
+		config.address_index = true;
+		let tracedb = TraceDB::new(config, db.clone(), Arc::new(extras));
+		assert_eq!(tracedb.build_address_index(10, far_deadline()), 0);
+
+		let mut batch = DBTransaction::new();
+		tracedb.import(&mut batch, create_simple_import_request(3, H256::from(0xa3)));
+		db.write(batch).unwrap();
+		assert_eq!(*tracedb.address_index_from.read(), Some(3));
+		assert_eq!(tracedb.filter(&filter), expected);
+
+		assert_eq!(tracedb.build_address_index(1, far_deadline()), 2);
+		// a passed deadline still lets one block through
+		assert_eq!(tracedb.build_address_index(10, Instant::now()), 1);
+		assert_eq!(tracedb.build_address_index(10, far_deadline()), 0);
+		assert_eq!(*tracedb.address_index_from.read(), Some(0));
+		assert_eq!(tracedb.filter(&filter), expected);
+	}
+
+	#[test]
+	fn build_address_index_stops_at_missing_blocks() {
+		let db = new_db();
+		let mut config = Config::default();
+		config.enabled = true;
+		// warp-restored database, blocks below #2 are missing.
+		let mut extras = Extras::default();
+		for number in 2..4 {
+			extras.block_hashes.insert(number, H256::from(0xa0 + number));
+			extras.transaction_hashes.insert(number, vec![H256::from(0xf0 + number)]);
+		}
+
+		{
+			let tracedb = TraceDB::new(config.clone(), db.clone(), Arc::new(extras.clone()));
+			let mut batch = DBTransaction::new();
+			tracedb.import(&mut batch, create_simple_import_request(2, H256::from(0xa2)));
+			db.write(batch).unwrap();
+		}
+
+		config.address_index = true;
+		let tracedb = TraceDB::new(config, db.clone(), Arc::new(extras));
+		let mut batch = DBTransaction::new();
+		tracedb.import(&mut batch, create_simple_import_request(3, H256::from(0xa3)));
+		db.write(batch).unwrap();
+		assert_eq!(*tracedb.address_index_from.read(), Some(3));
+
+		assert_eq!(tracedb.build_address_index(10, far_deadline()), 0);
+		assert_eq!(*tracedb.address_index_from.read(), Some(2));
+		assert_eq!(tracedb.build_address_index(10, far_deadline()), 0);
+		assert_eq!(*tracedb.address_index_from.read(), Some(2));
+	}
 }
@@ -22,12 +22,14 @@ mod db;
 mod executive_tracer;
 mod import;
 mod noop_tracer;
+mod struct_log_tracer;
 mod types;
 
 pub use self::config::Config;
 pub use self::db::TraceDB;
 pub use self::noop_tracer::{NoopTracer, NoopVMTracer};
 pub use self::executive_tracer::{ExecutiveTracer, ExecutiveVMTracer};
+pub use self::struct_log_tracer::{StructLogTracer, StructLog, StructLogOptions};
 pub use self::import::ImportRequest;
 pub use self::localized::LocalizedTrace;
 
@@ -35,7 +37,7 @@ pub use self::types::{filter, flat, localized, trace};
 pub use self::types::error::Error as TraceError;
 pub use self::types::trace::{VMTrace, VMOperation, VMExecutedOperation, MemoryDiff, StorageDiff};
 pub use self::types::flat::{FlatTrace, FlatTransactionTraces, FlatBlockTraces};
-pub use self::types::filter::{Filter, AddressesFilter};
+pub use self::types::filter::{Filter, AddressesFilter, TraceSide};
 
 use util::{Bytes, Address, U256, H256, DBTransaction};
 use self::trace::{Call, Create};
@@ -90,6 +92,8 @@ pub trait Tracer: Send {
 
 /// Used by executive to build VM traces.
 pub trait VMTracer: Send {
+	/// Data produced by the tracer.
+	type Output;
 
 	/// Trace the progression of interpreter to next instruction.
 	/// If tracer returns `false` it won't be called again.
@@ -99,6 +103,9 @@ pub trait VMTracer: Send {
 	/// Trace the preparation to execute a single valid instruction.
 	fn trace_prepare_execute(&mut self, _pc: usize, _instruction: u8, _gas_cost: U256) {}
 
+	/// Trace the gas left, stack and memory right before the prepared instruction is executed.
+	fn trace_machine_state(&mut self, _gas: U256, _stack: &[U256], _memory: &[u8]) {}
+
 	/// Trace the finalised execution of a single valid instruction.
 	fn trace_executed(&mut self, _gas_used: U256, _stack_push: &[U256], _mem_diff: Option<(usize, &[u8])>, _store_diff: Option<(U256, U256)>) {}
 
@@ -109,7 +116,7 @@ pub trait VMTracer: Send {
 	fn done_subtrace(&mut self, sub: Self) where Self: Sized;
 
 	/// Consumes self and returns the VM trace.
-	fn drain(self) -> Option<VMTrace>;
+	fn drain(self) -> Option<Self::Output>;
 }
 
 /// `DbExtras` provides an interface to query extra data which is not stored in tracesdb,
@@ -0,0 +1,215 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Geth-style struct log tracer.
+
+use std::collections::BTreeMap;
+use util::{Bytes, U256};
+use evm;
+use trace::VMTracer;
+
+const SLOAD: u8 = 0x54;
+
+/// Parts of the machine state which struct logs should not capture.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct StructLogOptions {
+	/// Don't capture memory.
+	pub disable_memory: bool,
+	/// Don't capture the stack.
+	pub disable_stack: bool,
+	/// Don't capture storage.
+	pub disable_storage: bool,
+}
+
+/// Machine state right before executing a single instruction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructLog {
+	/// Program counter.
+	pub pc: usize,
+	/// Executed instruction.
+	pub instruction: u8,
+	/// Gas left before executing the instruction.
+	pub gas: U256,
+	/// Gas cost of the instruction.
+	pub gas_cost: U256,
+	/// Call depth, starting at 1 for the transaction itself.
+	pub depth: usize,
+	/// Stack, bottom first.
+	pub stack: Option<Vec<U256>>,
+	/// Memory.
+	pub memory: Option<Bytes>,
+	/// Storage slots of the executing contract accessed in this call so far.
+	pub storage: Option<BTreeMap<U256, U256>>,
+}
+
+impl StructLog {
+	/// Mnemonic of the executed instruction.
+	pub fn op_name(&self) -> &'static str {
+		evm::INSTRUCTIONS[self.instruction as usize].name
+	}
+}
+
+/// VM tracer recording a `StructLog` for every executed instruction.
+pub struct StructLogTracer {
+	options: StructLogOptions,
+	depth: usize,
+	logs: Vec<StructLog>,
+	storage: BTreeMap<U256, U256>,
+	sload_key: Option<U256>,
+}
+
+impl StructLogTracer {
+	/// Create a new top-level instance.
+	pub fn toplevel(options: StructLogOptions) -> Self {
+		StructLogTracer {
+			options: options,
+			depth: 0,
+			logs: vec![],
+			storage: BTreeMap::new(),
+			sload_key: None,
+		}
+	}
+}
+
+impl VMTracer for StructLogTracer {
+	type Output = Vec<StructLog>;
+
+	fn trace_next_instruction(&mut self, _pc: usize, _instruction: u8) -> bool { true }
+
+	fn trace_prepare_execute(&mut self, pc: usize, instruction: u8, gas_cost: U256) {
+		self.logs.push(StructLog {
+			pc: pc,
+			instruction: instruction,
+			gas: U256::zero(),
+			gas_cost: gas_cost,
+			depth: self.depth,
+			stack: None,
+			memory: None,
+			storage: None,
+		});
+	}
+
+	fn trace_machine_state(&mut self, gas: U256, stack: &[U256], memory: &[u8]) {
+		let options = self.options;
+		let log = self.logs.last_mut().expect("trace_machine_state is always called after a trace_prepare_execute");
+		log.gas = gas;
+		if !options.disable_stack {
+			log.stack = Some(stack.to_vec());
+		}
+		if !options.disable_memory {
+			log.memory = Some(memory.to_vec());
+		}
+		if !options.disable_storage {
+			log.storage = Some(self.storage.clone());
+			if log.instruction == SLOAD {
+				self.sload_key = stack.last().cloned();
+			}
+		}
+	}
+
+	fn trace_executed(&mut self, _gas_used: U256, stack_push: &[U256], _mem_diff: Option<(usize, &[u8])>, store_diff: Option<(U256, U256)>) {
+		if self.options.disable_storage {
+			return;
+		}
+
+		let loaded = match (self.sload_key.take(), stack_push.first()) {
+			(Some(key), Some(value)) => Some((key, *value)),
+			_ => None,
+		};
+
+		if let Some((key, value)) = store_diff.or(loaded) {
+			self.storage.insert(key, value);
+			let log = self.logs.last_mut().expect("trace_executed is always called after a trace_prepare_execute");
+			log.storage = Some(self.storage.clone());
+		}
+	}
+
+	fn prepare_subtrace(&self, _code: &[u8]) -> Self {
+		StructLogTracer {
+			options: self.options,
+			depth: self.depth + 1,
+			logs: vec![],
+			storage: BTreeMap::new(),
+			sload_key: None,
+		}
+	}
+
+	fn done_subtrace(&mut self, sub: Self) {
+		self.logs.extend(sub.logs);
+	}
+
+	fn drain(self) -> Option<Vec<StructLog>> { Some(self.logs) }
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::BTreeMap;
+	use util::U256;
+	use trace::VMTracer;
+	use super::{StructLogTracer, StructLogOptions};
+
+	fn step(tracer: &mut StructLogTracer, pc: usize, instruction: u8, stack: &[U256], stack_push: &[U256], store_diff: Option<(U256, U256)>) {
+		assert!(tracer.trace_next_instruction(pc, instruction));
+		tracer.trace_prepare_execute(pc, instruction, 3.into());
+		tracer.trace_machine_state(100.into(), stack, &[0u8; 32]);
+		tracer.trace_executed(97.into(), stack_push, None, store_diff);
+	}
+
+	#[test]
+	fn records_steps_in_execution_order() {
+		let mut tracer = StructLogTracer::toplevel(Default::default());
+		let mut frame = tracer.prepare_subtrace(&[]);
+		// SLOAD, then SSTORE
+		step(&mut frame, 0, 0x54, &[1.into()], &[5.into()], None);
+		{
+			let mut sub = frame.prepare_subtrace(&[]);
+			step(&mut sub, 0, 0x00, &[], &[], None);
+			frame.done_subtrace(sub);
+		}
+		step(&mut frame, 1, 0x55, &[6.into(), 2.into()], &[], Some((2.into(), 6.into())));
+		tracer.done_subtrace(frame);
+
+		let logs = tracer.drain().unwrap();
+		assert_eq!(logs.len(), 3);
+		assert_eq!(logs.iter().map(|l| l.depth).collect::<Vec<_>>(), vec![1, 2, 1]);
+		assert_eq!(logs[0].op_name(), "SLOAD");
+		assert_eq!(logs[0].gas, 100.into());
+		assert_eq!(logs[0].stack, Some(vec![1.into()]));
+		assert_eq!(logs[0].memory, Some(vec![0u8; 32]));
+
+		let mut storage = BTreeMap::new();
+		storage.insert(U256::from(1), U256::from(5));
+		assert_eq!(logs[0].storage, Some(storage.clone()));
+		assert_eq!(logs[1].storage, Some(BTreeMap::new()));
+		storage.insert(U256::from(2), U256::from(6));
+		assert_eq!(logs[2].storage, Some(storage));
+	}
+
+	#[test]
+	fn respects_disabled_captures() {
+		let options = StructLogOptions { disable_memory: true, disable_stack: true, disable_storage: true };
+		let mut tracer = StructLogTracer::toplevel(options);
+		let mut frame = tracer.prepare_subtrace(&[]);
+		step(&mut frame, 0, 0x55, &[6.into(), 2.into()], &[], Some((2.into(), 6.into())));
+		tracer.done_subtrace(frame);
+
+		let logs = tracer.drain().unwrap();
+		assert_eq!(logs[0].op_name(), "SSTORE");
+		assert_eq!(logs[0].stack, None);
+		assert_eq!(logs[0].memory, None);
+		assert_eq!(logs[0].storage, None);
+	}
+}
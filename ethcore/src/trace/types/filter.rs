@@ -74,6 +74,33 @@ impl AddressesFilter {
 	}
 }
 
+/// Side of a trace an address is filtered on.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum TraceSide {
+	/// Caller, creator or suicided contract.
+	From,
+	/// Callee, created contract or refund address.
+	To,
+}
+
+impl TraceSide {
+	/// Returns address on this side of the trace.
+	/// Failed contract creations have no address on the `To` side.
+	pub fn address(&self, trace: &FlatTrace) -> Option<Address> {
+		match (*self, &trace.action) {
+			(TraceSide::From, &Action::Call(ref call)) => Some(call.from.clone()),
+			(TraceSide::From, &Action::Create(ref create)) => Some(create.from.clone()),
+			(TraceSide::From, &Action::Suicide(ref suicide)) => Some(suicide.address.clone()),
+			(TraceSide::To, &Action::Call(ref call)) => Some(call.to.clone()),
+			(TraceSide::To, &Action::Create(_)) => match trace.result {
+				Res::Create(ref create_result) => Some(create_result.address.clone()),
+				_ => None,
+			},
+			(TraceSide::To, &Action::Suicide(ref suicide)) => Some(suicide.refund_address.clone()),
+		}
+	}
+}
+
 #[derive(Debug)]
 /// Traces filter.
 pub struct Filter {
@@ -108,27 +135,22 @@ impl Filter {
 
 	/// Returns true if given trace matches the filter.
 	pub fn matches(&self, trace: &FlatTrace) -> bool {
-		match trace.action {
-			Action::Call(ref call) => {
-				let from_matches = self.from_address.matches(&call.from);
-				let to_matches = self.to_address.matches(&call.to);
-				from_matches && to_matches
-			}
-			Action::Create(ref create) => {
-				let from_matches = self.from_address.matches(&create.from);
-
-				let to_matches = match trace.result {
-					Res::Create(ref create_result) => self.to_address.matches(&create_result.address),
-					_ => false
-				};
-
-				from_matches && to_matches
+		let from_matches = TraceSide::From.address(trace).map_or(false, |address| self.from_address.matches(&address));
+		let to_matches = TraceSide::To.address(trace).map_or(false, |address| self.to_address.matches(&address));
+		from_matches && to_matches
+	}
+
+	/// Returns the side of the filter with the fewest addresses, which should be
+	/// looked up in the address index. `None` if the filter matches all addresses.
+	pub fn indexed_side(&self) -> Option<(TraceSide, &[Address])> {
+		match (self.from_address.matches_all(), self.to_address.matches_all()) {
+			(true, true) => None,
+			(false, true) => Some((TraceSide::From, &self.from_address.list[..])),
+			(true, false) => Some((TraceSide::To, &self.to_address.list[..])),
+			(false, false) => match self.from_address.list.len() <= self.to_address.list.len() {
+				true => Some((TraceSide::From, &self.from_address.list[..])),
+				false => Some((TraceSide::To, &self.to_address.list[..])),
 			},
-			Action::Suicide(ref suicide) => {
-				let from_matches = self.from_address.matches(&suicide.address);
-				let to_matches = self.to_address.matches(&suicide.refund_address);
-				from_matches && to_matches
-			}
 		}
 	}
 }
@@ -140,7 +162,7 @@ mod tests {
 	use bloomable::Bloomable;
 	use trace::trace::{Action, Call, Res, Create, CreateResult, Suicide};
 	use trace::flat::FlatTrace;
-	use trace::{Filter, AddressesFilter, TraceError};
+	use trace::{Filter, AddressesFilter, TraceError, TraceSide};
 	use evm::CallType;
 
 	#[test]
@@ -342,4 +364,46 @@ mod tests {
 		assert!(f5.matches(&trace));
 		assert!(!f6.matches(&trace));
 	}
+
+	#[test]
+	fn filter_indexed_side() {
+		let filter = Filter {
+			range: (0..0),
+			from_address: AddressesFilter::from(vec![]),
+			to_address: AddressesFilter::from(vec![]),
+		};
+		assert_eq!(filter.indexed_side(), None);
+
+		let filter = Filter {
+			range: (0..0),
+			from_address: AddressesFilter::from(vec![Address::from(1), Address::from(2)]),
+			to_address: AddressesFilter::from(vec![Address::from(3)]),
+		};
+		assert_eq!(filter.indexed_side(), Some((TraceSide::To, &[Address::from(3)][..])));
+
+		let filter = Filter {
+			range: (0..0),
+			from_address: AddressesFilter::from(vec![Address::from(1)]),
+			to_address: AddressesFilter::from(vec![]),
+		};
+		assert_eq!(filter.indexed_side(), Some((TraceSide::From, &[Address::from(1)][..])));
+	}
+
+	#[test]
+	fn trace_side_of_failed_create() {
+		let trace = FlatTrace {
+			action: Action::Create(Create {
+				from: 1.into(),
+				value: 3.into(),
+				gas: 4.into(),
+				init: vec![0x5],
+			}),
+			result: Res::FailedCreate(TraceError::OutOfGas),
+			trace_address: vec![].into_iter().collect(),
+			subtraces: 0,
+		};
+
+		assert_eq!(TraceSide::From.address(&trace), Some(Address::from(1)));
+		assert_eq!(TraceSide::To.address(&trace), None);
+	}
 }
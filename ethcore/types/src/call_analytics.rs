@@ -25,4 +25,6 @@ pub struct CallAnalytics {
 	pub vm_tracing: bool,
 	/// Make a diff.
 	pub state_diffing: bool,
+	/// Dump the state of all accessed accounts before execution.
+	pub pre_state_dumping: bool,
 }
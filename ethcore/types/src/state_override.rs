@@ -0,0 +1,38 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! State overrides for virtual calls.
+
+use std::collections::BTreeMap;
+use util::{U256, H256, Address, Bytes};
+
+/// Account fields to override before executing a call.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AccountOverride {
+	/// Replaces the balance.
+	pub balance: Option<U256>,
+	/// Replaces the nonce.
+	pub nonce: Option<U256>,
+	/// Replaces the code.
+	pub code: Option<Bytes>,
+	/// Replaces the whole storage; slots not listed are cleared.
+	pub state: Option<BTreeMap<H256, H256>>,
+	/// Replaces individual storage slots, leaving the others intact.
+	pub state_diff: Option<BTreeMap<H256, H256>>,
+}
+
+/// Overridden accounts by address.
+pub type StateOverride = BTreeMap<Address, AccountOverride>;
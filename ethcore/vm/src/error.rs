@@ -65,6 +65,8 @@ pub enum Error {
 	Internal(String),
 	/// Wasm runtime error
 	Wasm(String),
+	/// Execution has been reverted with REVERT.
+	Reverted,
 }
 
 
@@ -93,6 +95,7 @@ impl fmt::Display for Error {
 			Internal(ref msg) => write!(f, "Internal error: {}", msg),
 			MutableCallInStaticContext => write!(f, "Mutable call in static context"),
 			Wasm(ref msg) => write!(f, "Internal error: {}", msg),
+			Reverted => write!(f, "Reverted"),
 		}
 	}
 }
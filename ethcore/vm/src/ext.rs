@@ -51,8 +51,10 @@ pub enum CreateContractAddress {
 	FromSenderAndNonce,
 	/// Address is calculated from code hash. Default since EIP-86
 	FromCodeHash,
-	/// Address is calculated from code hash and sender. Used by CREATE_P2SH instruction.
+	/// Address is calculated from code hash and sender. Used by pwasm create ext.
 	FromSenderAndCodeHash,
+	/// Address is calculated from sender, salt and code hash. EIP-1014 CREATE2 scheme.
+	FromSenderSaltAndCodeHash(H256),
 }
 
 /// Externalities interface for EVMs
@@ -60,6 +62,9 @@ pub trait Ext {
 	/// Returns a value for given key.
 	fn storage_at(&self, key: &H256) -> Result<H256>;
 
+	/// Returns the storage value for a given key as it was at the start of the current transaction.
+	fn initial_storage_at(&self, key: &H256) -> Result<H256>;
+
 	/// Stores a value for given key.
 	fn set_storage(&mut self, key: H256, value: H256) -> Result<()>;
 
@@ -106,6 +111,9 @@ pub trait Ext {
 	/// Returns code size at given address
 	fn extcodesize(&self, address: &Address) -> Result<usize>;
 
+	/// Returns code hash at given address, or zero if the account does not exist or is empty.
+	fn extcodehash(&self, address: &Address) -> Result<H256>;
+
 	/// Creates log entry with given topics and data
 	fn log(&mut self, topics: Vec<H256>, data: &[u8]) -> Result<()>;
 
@@ -129,8 +137,11 @@ pub trait Ext {
 	/// then A depth is 0, B is 1, C is 2 and so on.
 	fn depth(&self) -> usize;
 
-	/// Increments sstore refunds count by 1.
-	fn inc_sstore_clears(&mut self);
+	/// Increments sstore refunds counter by `value`.
+	fn add_sstore_refund(&mut self, value: usize);
+
+	/// Decrements sstore refunds counter by `value`.
+	fn sub_sstore_refund(&mut self, value: usize);
 
 	/// Decide if any more operations should be traced. Passthrough for the VM trace.
 	fn trace_next_instruction(&mut self, _pc: usize, _instruction: u8) -> bool { false }
@@ -138,6 +149,9 @@ pub trait Ext {
 	/// Prepare to trace an operation. Passthrough for the VM trace.
 	fn trace_prepare_execute(&mut self, _pc: usize, _instruction: u8, _gas_cost: U256) {}
 
+	/// Trace the machine state before the prepared operation is executed. Passthrough for the VM trace.
+	fn trace_machine_state(&mut self, _gas: U256, _stack: &[U256], _memory: &[u8]) {}
+
 	/// Trace the finalised execution of a single instruction.
 	fn trace_executed(&mut self, _gas_used: U256, _stack_push: &[U256], _mem_diff: Option<(usize, &[u8])>, _store_diff: Option<(U256, U256)>) {}
 }
@@ -22,10 +22,14 @@ pub struct Schedule {
 	pub exceptional_failed_code_deposit: bool,
 	/// Does it have a delegate cal
 	pub have_delegate_call: bool,
-	/// Does it have a CREATE_P2SH instruction
+	/// Does it have a CREATE2 instruction
 	pub have_create2: bool,
 	/// Does it have a REVERT instruction
 	pub have_revert: bool,
+	/// Does it have SHL/SHR/SAR bitwise shifting instructions
+	pub have_bitwise_shifting: bool,
+	/// Does it have an EXTCODEHASH instruction
+	pub have_extcodehash: bool,
 	/// VM stack limit
 	pub stack_limit: usize,
 	/// Max number of nested calls/creates
@@ -90,6 +94,8 @@ pub struct Schedule {
 	pub extcodesize_gas: usize,
 	/// Base price of EXTCODECOPY
 	pub extcodecopy_base_gas: usize,
+	/// Price of EXTCODEHASH
+	pub extcodehash_gas: usize,
 	/// Price of BALANCE
 	pub balance_gas: usize,
 	/// Price of SUICIDE
@@ -111,6 +117,10 @@ pub struct Schedule {
 	pub have_return_data: bool,
 	/// Kill basic accounts below this balance if touched.
 	pub kill_dust: CleanDustMode,
+	/// Enable EIP-1283 net gas metering for `SSTORE`.
+	pub eip1283: bool,
+	/// Fail contract creation at addresses with code or a non-zero nonce (EIP-684).
+	pub eip684: bool,
 }
 
 /// Dust accounts cleanup mode.
@@ -143,6 +153,8 @@ impl Schedule {
 			have_create2: false,
 			have_revert: false,
 			have_return_data: false,
+			have_bitwise_shifting: false,
+			have_extcodehash: false,
 			stack_limit: 1024,
 			max_depth: 1024,
 			tier_step_gas: [0, 2, 3, 5, 8, 10, 20, 0],
@@ -175,6 +187,7 @@ impl Schedule {
 			copy_gas: 3,
 			extcodesize_gas: 700,
 			extcodecopy_base_gas: 700,
+			extcodehash_gas: 400,
 			balance_gas: 400,
 			suicide_gas: 5000,
 			suicide_to_new_account_cost: 25000,
@@ -184,6 +197,8 @@ impl Schedule {
 			blockhash_gas: 20,
 			have_static_call: false,
 			kill_dust: CleanDustMode::Off,
+			eip1283: false,
+			eip684: false,
 		}
 	}
 
@@ -198,6 +213,17 @@ impl Schedule {
 		schedule
 	}
 
+	/// Schedule for the Constantinople fork of the Ethereum main net.
+	pub fn new_constantinople() -> Schedule {
+		let mut schedule = Self::new_metropolis();
+		schedule.have_bitwise_shifting = true;
+		schedule.have_create2 = true;
+		schedule.have_extcodehash = true;
+		schedule.eip1283 = true;
+		schedule.eip684 = true;
+		schedule
+	}
+
 	fn new(efcd: bool, hdc: bool, tcg: usize) -> Schedule {
 		Schedule {
 			exceptional_failed_code_deposit: efcd,
@@ -205,6 +231,8 @@ impl Schedule {
 			have_create2: false,
 			have_revert: false,
 			have_return_data: false,
+			have_bitwise_shifting: false,
+			have_extcodehash: false,
 			stack_limit: 1024,
 			max_depth: 1024,
 			tier_step_gas: [0, 2, 3, 5, 8, 10, 20, 0],
@@ -237,6 +265,7 @@ impl Schedule {
 			copy_gas: 3,
 			extcodesize_gas: 20,
 			extcodecopy_base_gas: 20,
+			extcodehash_gas: 400,
 			balance_gas: 20,
 			suicide_gas: 0,
 			suicide_to_new_account_cost: 0,
@@ -246,6 +275,8 @@ impl Schedule {
 			blockhash_gas: 20,
 			have_static_call: false,
 			kill_dust: CleanDustMode::Off,
+			eip1283: false,
+			eip684: false,
 		}
 	}
 }
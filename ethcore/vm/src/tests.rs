@@ -17,7 +17,7 @@
 use std::sync::Arc;
 use std::collections::{HashMap, HashSet};
 
-use util::{H256, U256, Address, Bytes};
+use util::{H256, U256, Address, Bytes, Hashable};
 use {
 	CallType, Schedule, EnvInfo,
 	ReturnData, Ext, ContractCreateResult, MessageCallResult,
@@ -51,9 +51,10 @@ pub struct FakeCall {
 #[derive(Default)]
 pub struct FakeExt {
 	pub store: HashMap<H256, H256>,
+	pub initial_store: HashMap<H256, H256>,
 	pub suicides: HashSet<Address>,
 	pub calls: HashSet<FakeCall>,
-	pub sstore_clears: usize,
+	pub sstore_clears: i64,
 	pub depth: usize,
 	pub blockhashes: HashMap<U256, H256>,
 	pub codes: HashMap<Address, Arc<Bytes>>,
@@ -77,6 +78,12 @@ impl FakeExt {
 	pub fn new() -> Self {
 		FakeExt::default()
 	}
+
+	pub fn new_constantinople() -> Self {
+		let mut ext = FakeExt::default();
+		ext.schedule = Schedule::new_constantinople();
+		ext
+	}
 }
 
 impl Ext for FakeExt {
@@ -84,6 +91,10 @@ impl Ext for FakeExt {
 		Ok(self.store.get(key).unwrap_or(&H256::new()).clone())
 	}
 
+	fn initial_storage_at(&self, key: &H256) -> Result<H256> {
+		Ok(self.initial_store.get(key).unwrap_or(&H256::new()).clone())
+	}
+
 	fn set_storage(&mut self, key: H256, value: H256) -> Result<()> {
 		self.store.insert(key, value);
 		Ok(())
@@ -153,6 +164,10 @@ impl Ext for FakeExt {
 		Ok(self.codes.get(address).map_or(0, |c| c.len()))
 	}
 
+	fn extcodehash(&self, address: &Address) -> Result<H256> {
+		Ok(self.codes.get(address).map_or(H256::zero(), |c| c.sha3()))
+	}
+
 	fn log(&mut self, topics: Vec<H256>, data: &[u8]) -> Result<()> {
 		self.logs.push(FakeLogEntry {
 			topics: topics,
@@ -182,8 +197,12 @@ impl Ext for FakeExt {
 		self.depth
 	}
 
-	fn inc_sstore_clears(&mut self) {
-		self.sstore_clears += 1;
+	fn add_sstore_refund(&mut self, value: usize) {
+		self.sstore_clears += value as i64;
+	}
+
+	fn sub_sstore_refund(&mut self, value: usize) {
+		self.sstore_clears -= value as i64;
 	}
 
 	fn trace_next_instruction(&mut self, _pc: usize, _instruction: u8) -> bool {
@@ -62,6 +62,11 @@ pub const SIGNATURES: &'static [UserFunctionDescriptor] = &[
 		&[I32; 4],
 		Some(I32),
 	),
+	Static(
+		"_extcodehash",
+		&[I32; 2],
+		None,
+	),
 	Static(
 		"_ccall",
 		&[I32; 6],
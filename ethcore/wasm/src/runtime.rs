@@ -147,6 +147,27 @@ impl<'a, 'b> Runtime<'a, 'b> {
 		Ok(None)
 	}
 
+	/// Write code hash of the given account to wasm memory
+	pub fn extcodehash(&mut self, context: interpreter::CallerContext)
+		-> Result<Option<interpreter::RuntimeValue>, interpreter::Error>
+	{
+		//
+		// method signature:
+		//   fn extcodehash(address: *const u8, result_ptr: *mut u8);
+		//
+
+		let mut context = context;
+		let result_ptr = context.value_stack.pop_as::<i32>()? as u32;
+		let address = self.pop_address(&mut context)?;
+
+		let hash = self.ext.extcodehash(&address)
+			.map_err(|_| interpreter::Error::Trap("Code hash read error".to_owned()))?;
+
+		self.memory.set(result_ptr, &*hash)?;
+
+		Ok(None)
+	}
+
 	/// Invoke create in the state runtime
 	pub fn create(&mut self, context: interpreter::CallerContext)
 		-> Result<Option<interpreter::RuntimeValue>, interpreter::Error>
@@ -528,6 +549,9 @@ impl<'a, 'b> interpreter::UserFunctionExecutor for Runtime<'a, 'b> {
 			"_create" => {
 				self.create(context)
 			},
+			"_extcodehash" => {
+				self.extcodehash(context)
+			},
 			"_ccall" => {
 				self.call(context)
 			},
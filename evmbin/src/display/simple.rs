@@ -47,6 +47,8 @@ impl vm::Informant for Informant {
 }
 
 impl trace::VMTracer for Informant {
+	type Output = trace::VMTrace;
+
 	fn prepare_subtrace(&self, _code: &[u8]) -> Self where Self: Sized { Default::default() }
 	fn done_subtrace(&mut self, _sub: Self) {}
 	fn drain(self) -> Option<trace::VMTrace> { None }
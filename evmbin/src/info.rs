@@ -23,7 +23,7 @@ use ethcore::client::{self, EvmTestClient, EvmTestError, TransactResult};
 use ethjson;
 
 /// VM execution informant
-pub trait Informant: trace::VMTracer {
+pub trait Informant: trace::VMTracer<Output = trace::VMTrace> {
 	/// Display a single run init message
 	fn before_test(&self, test: &str, action: &str);
 	/// Set initial gas.
@@ -16,6 +16,7 @@
 
 //! Authority params deserialization.
 
+use hash::Address;
 use uint::Uint;
 use super::ValidatorSet;
 
@@ -40,6 +41,12 @@ pub struct AuthorityRoundParams {
 	/// Whether transitions should be immediate.
 	#[serde(rename="immediateTransitions")]
 	pub immediate_transitions: Option<bool>,
+	/// Reward contract address.
+	#[serde(rename="blockRewardContractAddress")]
+	pub block_reward_contract_address: Option<Address>,
+	/// Block at which empty step messages should start.
+	#[serde(rename="emptyStepsTransition")]
+	pub empty_steps_transition: Option<Uint>,
 }
 
 /// Authority engine deserialization.
@@ -67,7 +74,9 @@ mod tests {
 					"list" : ["0xc6d9d2cd449a754c494264e1809c50e34d64562b"]
 				},
 				"startStep" : 24,
-				"validateStepTransition": 150
+				"validateStepTransition": 150,
+				"blockRewardContractAddress": "0x2000000000000000000000000000000000000002",
+				"emptyStepsTransition": 10
 			}
 		}"#;
 
@@ -76,5 +85,7 @@ mod tests {
 		assert_eq!(deserialized.params.validators, ValidatorSet::List(vec![Address(H160::from("0xc6d9d2cd449a754c494264e1809c50e34d64562b"))]));
 		assert_eq!(deserialized.params.start_step, Some(Uint(U256::from(24))));
 		assert_eq!(deserialized.params.immediate_transitions, None);
+		assert_eq!(deserialized.params.block_reward_contract_address, Some(Address(H160::from("0x2000000000000000000000000000000000000002"))));
+		assert_eq!(deserialized.params.empty_steps_transition, Some(Uint(U256::from(10))));
 	}
 }
@@ -0,0 +1,69 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Spec trusted checkpoint deserialization.
+
+use std::io::Read;
+use serde_json;
+use serde_json::Error;
+use uint::Uint;
+use hash::H256;
+use bytes::Bytes;
+
+/// Trusted checkpoint light clients may start syncing from.
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct TrustedCheckpoint {
+	/// RLP of the checkpoint block header. Must be the last block of a CHT.
+	pub header: Bytes,
+	/// Total difficulty of the checkpoint block.
+	#[serde(rename="totalDifficulty")]
+	pub total_difficulty: Uint,
+	/// Roots of all CHTs up to and including the checkpoint block, starting from the first.
+	#[serde(rename="chtRoots")]
+	pub cht_roots: Vec<H256>,
+}
+
+impl TrustedCheckpoint {
+	/// Loads a checkpoint from json.
+	pub fn load<R>(reader: R) -> Result<Self, Error> where R: Read {
+		serde_json::from_reader(reader)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use bytes::Bytes;
+	use uint::Uint;
+	use bigint::prelude::{U256, H256 as Eth256};
+	use hash::H256;
+	use spec::checkpoint::TrustedCheckpoint;
+
+	#[test]
+	fn trusted_checkpoint_deserialization() {
+		let s = r#"{
+			"header": "0xc0",
+			"totalDifficulty": "0x400",
+			"chtRoots": ["0x0000000000000000000000000000000000000000000000000000000000000001"]
+		}"#;
+		let deserialized: TrustedCheckpoint = serde_json::from_str(s).unwrap();
+		assert_eq!(deserialized, TrustedCheckpoint {
+			header: Bytes::new(vec![0xc0]),
+			total_difficulty: Uint(U256::from(0x400)),
+			cht_roots: vec![H256(Eth256::from("0x0000000000000000000000000000000000000000000000000000000000000001"))],
+		});
+	}
+}
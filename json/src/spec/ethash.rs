@@ -16,9 +16,20 @@
 
 //! Ethash params deserialization.
 
+use std::collections::BTreeMap;
 use uint::Uint;
 use hash::Address;
 
+/// Block reward, either a single value or a map of starting blocks to rewards.
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum BlockReward {
+	/// Reward for all blocks.
+	Single(Uint),
+	/// Reward starting from each of the given blocks.
+	Multi(BTreeMap<Uint, Uint>),
+}
+
 /// Deserializable doppelganger of EthashParams.
 #[derive(Debug, PartialEq, Deserialize)]
 pub struct EthashParams {
@@ -61,6 +72,9 @@ pub struct EthashParams {
 	/// See main EthashParams docs.
 	#[serde(rename="bombDefuseTransition")]
 	pub bomb_defuse_transition: Option<Uint>,
+	/// See main EthashParams docs.
+	#[serde(rename="difficultyBombDelays")]
+	pub difficulty_bomb_delays: Option<BTreeMap<Uint, Uint>>,
 
 	/// See main EthashParams docs.
 	#[serde(rename="eip100bTransition")]
@@ -92,6 +106,10 @@ pub struct EthashParams {
 	#[serde(rename="ecip1017EraRounds")]
 	pub ecip1017_era_rounds: Option<Uint>,
 
+	/// See main EthashParams docs.
+	#[serde(rename="blockReward")]
+	pub block_reward: Option<BlockReward>,
+
 	/// See main EthashParams docs.
 	#[serde(rename="maxCodeSize")]
 	pub max_code_size: Option<Uint>,
@@ -126,7 +144,7 @@ mod tests {
 	use uint::Uint;
 	use bigint::prelude::{H160, U256};
 	use hash::Address;
-	use spec::ethash::{Ethash, EthashParams};
+	use spec::ethash::{Ethash, EthashParams, BlockReward};
 
 	#[test]
 	fn ethash_deserialization() {
@@ -163,11 +181,19 @@ mod tests {
 				"difficultyHardforkTransition": "0x59d9",
 				"difficultyHardforkBoundDivisor": "0x0200",
 				"bombDefuseTransition": "0x41",
+				"difficultyBombDelays": {
+					"4370000": 3000000,
+					"0x6b8d70": "5000000"
+				},
 				"eip100bTransition": "0x42",
 				"eip150Transition": "0x43",
 				"eip160Transition": "0x45",
 				"eip161abcTransition": "0x46",
-				"eip161dTransition": "0x47"
+				"eip161dTransition": "0x47",
+				"blockReward": {
+					"0": "0x4563918244f40000",
+					"4370000": "0x29a2241af62c0000"
+				}
 			}
 		}"#;
 
@@ -208,6 +234,10 @@ mod tests {
 				difficulty_hardfork_transition: Some(Uint(U256::from(0x59d9))),
 				difficulty_hardfork_bound_divisor: Some(Uint(U256::from(0x0200))),
 				bomb_defuse_transition: Some(Uint(U256::from(0x41))),
+				difficulty_bomb_delays: Some(vec![
+					(Uint(U256::from(4370000)), Uint(U256::from(3000000))),
+					(Uint(U256::from(0x6b8d70)), Uint(U256::from(5000000))),
+				].into_iter().collect()),
 				eip100b_transition: Some(Uint(U256::from(0x42))),
 				eip150_transition: Some(Uint(U256::from(0x43))),
 				eip160_transition: Some(Uint(U256::from(0x45))),
@@ -216,6 +246,10 @@ mod tests {
 				ecip1010_pause_transition: None,
 				ecip1010_continue_transition: None,
 				ecip1017_era_rounds: None,
+				block_reward: Some(BlockReward::Multi(vec![
+					(Uint(U256::from(0)), Uint(U256::from(0x4563918244f40000u64))),
+					(Uint(U256::from(4370000)), Uint(U256::from(0x29a2241af62c0000u64))),
+				].into_iter().collect())),
 				max_code_size: None,
 				max_gas_limit_transition: None,
 				max_gas_limit: None,
@@ -225,6 +259,20 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn block_reward_deserialization() {
+		let s = r#"["0x4563918244f40000", { "0": "0x4563918244f40000", "0x42": 10 }]"#;
+
+		let deserialized: Vec<BlockReward> = serde_json::from_str(s).unwrap();
+		assert_eq!(deserialized, vec![
+			BlockReward::Single(Uint(U256::from(0x4563918244f40000u64))),
+			BlockReward::Multi(vec![
+				(Uint(U256::from(0)), Uint(U256::from(0x4563918244f40000u64))),
+				(Uint(U256::from(0x42)), Uint(U256::from(10))),
+			].into_iter().collect()),
+		]);
+	}
+
 	#[test]
 	fn ethash_deserialization_missing_optionals() {
 		let s = r#"{
@@ -249,6 +297,7 @@ mod tests {
 				difficulty_hardfork_transition: None,
 				difficulty_hardfork_bound_divisor: None,
 				bomb_defuse_transition: None,
+				difficulty_bomb_delays: None,
 				eip100b_transition: None,
 				eip150_transition: None,
 				eip160_transition: None,
@@ -257,6 +306,7 @@ mod tests {
 				ecip1010_pause_transition: None,
 				ecip1010_continue_transition: None,
 				ecip1017_era_rounds: None,
+				block_reward: None,
 				max_code_size: None,
 				max_gas_limit_transition: None,
 				max_gas_limit: None,
@@ -28,6 +28,7 @@ use ethcore::client::{Mode, DatabaseCompactionProfile, VMType, BlockImportError,
 use ethcore::error::ImportError;
 use ethcore::miner::Miner;
 use ethcore::verification::queue::VerifierSettings;
+use util::journaldb::Algorithm;
 use cache::CacheConfig;
 use informant::{Informant, FullNodeInformantData, MillisecondDuration};
 use params::{SpecType, Pruning, Switch, tracing_switch_to_bool, fatdb_switch_to_bool};
@@ -66,6 +67,7 @@ pub enum BlockchainCmd {
 	Import(ImportBlockchain),
 	Export(ExportBlockchain),
 	ExportState(ExportState),
+	TraceBackfill(TraceBackfill),
 }
 
 #[derive(Debug, PartialEq)]
@@ -136,9 +138,25 @@ pub struct ExportState {
 	pub max_balance: Option<U256>,
 }
 
+#[derive(Debug, PartialEq)]
+pub struct TraceBackfill {
+	pub spec: SpecType,
+	pub cache_config: CacheConfig,
+	pub dirs: Directories,
+	pub pruning: Pruning,
+	pub pruning_history: u64,
+	pub pruning_memory: usize,
+	pub compaction: DatabaseCompactionProfile,
+	pub wal: bool,
+	pub fat_db: Switch,
+	pub from_block: BlockId,
+	pub to_block: BlockId,
+}
+
 pub fn execute(cmd: BlockchainCmd) -> Result<(), String> {
 	match cmd {
 		BlockchainCmd::Kill(kill_cmd) => kill_db(kill_cmd),
+		BlockchainCmd::TraceBackfill(backfill_cmd) => execute_trace_backfill(backfill_cmd),
 		BlockchainCmd::Import(import_cmd) => {
 			if import_cmd.light {
 				execute_import_light(import_cmd)
@@ -481,6 +499,7 @@ fn start_client(
 	wal: bool,
 	cache_config: CacheConfig,
 	require_fat_db: bool,
+	force_tracing: bool,
 ) -> Result<ClientService, String> {
 
 	// load spec file
@@ -504,7 +523,10 @@ fn start_client(
 	let algorithm = pruning.to_algorithm(&user_defaults);
 
 	// check if tracing is on
-	let tracing = tracing_switch_to_bool(tracing, &user_defaults)?;
+	let tracing = match force_tracing {
+		true => true,
+		false => tracing_switch_to_bool(tracing, &user_defaults)?,
+	};
 
 	// check if fatdb is on
 	let fat_db = fatdb_switch_to_bool(fat_db, &user_defaults, algorithm)?;
@@ -565,6 +587,7 @@ fn execute_export(cmd: ExportBlockchain) -> Result<(), String> {
 		cmd.wal,
 		cmd.cache_config,
 		false,
+		false,
 	)?;
 	let format = cmd.format.unwrap_or_default();
 
@@ -605,7 +628,8 @@ fn execute_export_state(cmd: ExportState) -> Result<(), String> {
 		cmd.compaction,
 		cmd.wal,
 		cmd.cache_config,
-		true
+		true,
+		false,
 	)?;
 
 	let client = service.client();
@@ -680,6 +704,67 @@ fn execute_export_state(cmd: ExportState) -> Result<(), String> {
 	Ok(())
 }
 
+fn execute_trace_backfill(cmd: TraceBackfill) -> Result<(), String> {
+	let user_defaults_path = {
+		let spec = cmd.spec.spec(&cmd.dirs.cache)?;
+		let genesis_hash = spec.genesis_header().hash();
+		cmd.dirs.database(genesis_hash, None, spec.data_dir).user_defaults_path()
+	};
+
+	// backfill re-executes old blocks, so their state must still be there
+	let algorithm = cmd.pruning.to_algorithm(&UserDefaults::load(&user_defaults_path)?);
+	if algorithm != Algorithm::Archive {
+		return Err("Trace backfill requires the state of old blocks. Please rerun with `--pruning=archive`".into());
+	}
+
+	let service = start_client(
+		cmd.dirs,
+		cmd.spec,
+		cmd.pruning,
+		cmd.pruning_history,
+		cmd.pruning_memory,
+		Switch::On,
+		cmd.fat_db,
+		cmd.compaction,
+		cmd.wal,
+		cmd.cache_config,
+		false,
+		true,
+	)?;
+
+	let client = service.client();
+
+	let from = client.block_number(cmd.from_block).ok_or("From block could not be found")?;
+	let to = client.block_number(cmd.to_block).ok_or("To block could not be found")?;
+
+	// blocks which already have traces are skipped, so an interrupted backfill
+	// resumes where it stopped when run again.
+	let mut traced = 0;
+	let mut last_report = Instant::now();
+	for number in from..(to + 1) {
+		match client.backfill_block_traces(number) {
+			Ok(true) => traced += 1,
+			Ok(false) => {},
+			Err(e) => return Err(format!("Cannot trace block #{}: {}. Blocks up to #{} are traced.", number, e, number.saturating_sub(1))),
+		}
+
+		if last_report.elapsed() >= Duration::from_secs(5) {
+			info!("#{} of {}, {} blocks traced", number, to, traced);
+			last_report = Instant::now();
+		}
+	}
+
+	// the whole chain is traced now, so tracing can be enabled for regular runs.
+	if from <= 1 && to >= client.chain_info().best_block_number {
+		let mut user_defaults = UserDefaults::load(&user_defaults_path)?;
+		user_defaults.tracing = true;
+		user_defaults.save(&user_defaults_path)?;
+	}
+
+	info!("Trace backfill completed, {} blocks traced.", traced);
+	Ok(())
+}
+
 pub fn kill_db(cmd: KillBlockchain) -> Result<(), String> {
 	let spec = cmd.spec.spec(&cmd.dirs.cache)?;
 	let genesis_hash = spec.genesis_header().hash();
@@ -43,6 +43,7 @@ usage! {
 		cmd_hash: bool,
 		cmd_kill: bool,
 		cmd_db: bool,
+		cmd_trace_backfill: bool,
 
 		// Arguments
 		arg_pid_file: String,
@@ -96,6 +97,8 @@ usage! {
 		flag_keys_path: String = "$BASE/keys", or |c: &Config| otry!(c.parity).keys_path.clone(),
 		flag_identity: String = "", or |c: &Config| otry!(c.parity).identity.clone(),
 		flag_light: bool = false, or |c: &Config| otry!(c.parity).light,
+		flag_trusted_checkpoint: Option<String> = None,
+			or |c: &Config| otry!(c.parity).trusted_checkpoint.clone().map(Some),
 		flag_no_persistent_txqueue: bool = false,
 			or |c: &Config| otry!(c.parity).no_persistent_txqueue,
 
@@ -166,6 +169,14 @@ usage! {
 		flag_no_ancient_blocks: bool = false, or |_| None,
 		flag_no_serve_light: bool = false,
 			or |c: &Config| otry!(c.network).no_serve_light.clone(),
+		flag_light_serve_max_peers: Option<usize> = None,
+			or |c: &Config| otry!(c.network).light_serve_max_peers.clone().map(Some),
+		flag_light_serve_time_share: String = "0.5",
+			or |c: &Config| otry!(c.network).light_serve_time_share.clone(),
+		flag_light_serve_recharge: u64 = 100_000_000u64,
+			or |c: &Config| otry!(c.network).light_serve_recharge.clone(),
+		flag_light_serve_costs: Option<String> = None,
+			or |c: &Config| otry!(c.network).light_serve_costs.clone().map(Some),
 
 		// -- API and Console Options
 		// RPC
@@ -316,6 +327,10 @@ usage! {
 		// -- Footprint Options
 		flag_tracing: String = "auto",
 			or |c: &Config| otry!(c.footprint).tracing.clone(),
+		flag_trace_address_index: bool = false,
+			or |c: &Config| otry!(c.footprint).trace_address_index.clone(),
+		flag_trace_backfill: bool = false,
+			or |c: &Config| otry!(c.footprint).trace_backfill.clone(),
 		flag_pruning: String = "auto",
 			or |c: &Config| otry!(c.footprint).pruning.clone(),
 		flag_pruning_history: u64 = 64u64,
@@ -439,6 +454,7 @@ struct Operating {
 	keys_path: Option<String>,
 	identity: Option<String>,
 	light: Option<bool>,
+	trusted_checkpoint: Option<String>,
 	no_persistent_txqueue: Option<bool>,
 }
 
@@ -478,6 +494,10 @@ struct Network {
 	reserved_peers: Option<String>,
 	reserved_only: Option<bool>,
 	no_serve_light: Option<bool>,
+	light_serve_max_peers: Option<usize>,
+	light_serve_time_share: Option<String>,
+	light_serve_recharge: Option<u64>,
+	light_serve_costs: Option<String>,
 }
 
 #[derive(Default, Debug, PartialEq, Deserialize)]
@@ -585,6 +605,8 @@ struct Stratum {
 #[derive(Default, Debug, PartialEq, Deserialize)]
 struct Footprint {
 	tracing: Option<String>,
+	trace_address_index: Option<bool>,
+	trace_backfill: Option<bool>,
 	pruning: Option<String>,
 	pruning_history: Option<u64>,
 	pruning_memory: Option<usize>,
@@ -711,6 +733,7 @@ mod tests {
 			cmd_hash: false,
 			cmd_db: false,
 			cmd_kill: false,
+			cmd_trace_backfill: false,
 
 			// Arguments
 			arg_pid_file: "".into(),
@@ -733,6 +756,7 @@ mod tests {
 			flag_keys_path: "$HOME/.parity/keys".into(),
 			flag_identity: "".into(),
 			flag_light: false,
+			flag_trusted_checkpoint: None,
 			flag_no_persistent_txqueue: false,
 
 			// -- Convenience Options
@@ -772,6 +796,10 @@ mod tests {
 			flag_reserved_only: false,
 			flag_no_ancient_blocks: false,
 			flag_no_serve_light: false,
+			flag_light_serve_max_peers: Some(10usize),
+			flag_light_serve_time_share: "0.25".into(),
+			flag_light_serve_recharge: 100_000_000u64,
+			flag_light_serve_costs: Some("execution=100".into()),
 
 			// -- API and Console Options
 			// RPC
@@ -855,6 +883,8 @@ mod tests {
 
 			// -- Footprint Options
 			flag_tracing: "auto".into(),
+			flag_trace_address_index: false,
+			flag_trace_backfill: false,
 			flag_pruning: "auto".into(),
 			flag_pruning_history: 64u64,
 			flag_pruning_memory: 500usize,
@@ -970,6 +1000,7 @@ mod tests {
 				keys_path: None,
 				identity: None,
 				light: None,
+				trusted_checkpoint: None,
 				no_persistent_txqueue: None,
 			}),
 			account: Some(Account {
@@ -1003,6 +1034,10 @@ mod tests {
 				reserved_peers: Some("./path/to/reserved_peers".into()),
 				reserved_only: Some(true),
 				no_serve_light: None,
+				light_serve_max_peers: None,
+				light_serve_time_share: None,
+				light_serve_recharge: None,
+				light_serve_costs: None,
 			}),
 			websockets: Some(Ws {
 				disable: Some(true),
@@ -1087,6 +1122,8 @@ mod tests {
 			}),
 			footprint: Some(Footprint {
 				tracing: Some("on".into()),
+				trace_address_index: None,
+				trace_backfill: None,
 				pruning: Some("fast".into()),
 				pruning_history: Some(64),
 				pruning_memory: None,
@@ -36,7 +36,7 @@ use rpc_apis::ApiSet;
 use parity_rpc::NetworkSettings;
 use cache::CacheConfig;
 use helpers::{to_duration, to_mode, to_block_id, to_u256, to_pending_set, to_price, replace_home, replace_home_and_local,
-geth_ipc_path, parity_ipc_path, to_bootnodes, to_addresses, to_address, to_gas_limit, to_queue_strategy};
+geth_ipc_path, parity_ipc_path, to_bootnodes, to_addresses, to_address, to_gas_limit, to_queue_strategy, to_light_serve_costs};
 use params::{ResealPolicy, AccountsConfig, GasPricerConfig, MinerExtras, Pruning, Switch};
 use ethcore_logger::Config as LogConfig;
 use dir::{self, Directories, default_hypervisor_path, default_local_path, default_data_path};
@@ -45,7 +45,7 @@ use ipfs::Configuration as IpfsConfiguration;
 use secretstore::{Configuration as SecretStoreConfiguration, NodeSecretKey};
 use updater::{UpdatePolicy, UpdateFilter, ReleaseTrack};
 use run::RunCmd;
-use blockchain::{BlockchainCmd, ImportBlockchain, ExportBlockchain, KillBlockchain, ExportState, DataFormat};
+use blockchain::{BlockchainCmd, ImportBlockchain, ExportBlockchain, KillBlockchain, ExportState, TraceBackfill, DataFormat};
 use presale::ImportWallet;
 use account::{AccountCmd, NewAccount, ListAccounts, ImportAccounts, ImportFromGethAccounts};
 use snapshot::{self, SnapshotCommand};
@@ -183,6 +183,20 @@ impl Configuration {
 				dirs: dirs,
 				pruning: pruning,
 			}))
+		} else if self.args.cmd_db && self.args.cmd_trace_backfill {
+			Cmd::Blockchain(BlockchainCmd::TraceBackfill(TraceBackfill {
+				spec: spec,
+				cache_config: cache_config,
+				dirs: dirs,
+				pruning: pruning,
+				pruning_history: pruning_history,
+				pruning_memory: self.args.flag_pruning_memory,
+				compaction: compaction,
+				wal: wal,
+				fat_db: fat_db,
+				from_block: to_block_id(&self.args.flag_from)?,
+				to_block: to_block_id(&self.args.flag_to)?,
+			}))
 		} else if self.args.cmd_account {
 			let account_cmd = if self.args.cmd_new {
 				let new_acc = NewAccount {
@@ -359,6 +373,8 @@ impl Configuration {
 				update_policy: update_policy,
 				mode: mode,
 				tracing: tracing,
+				trace_address_index: self.args.flag_trace_address_index,
+				trace_backfill: self.args.flag_trace_backfill,
 				fat_db: fat_db,
 				compaction: compaction,
 				wal: wal,
@@ -380,7 +396,9 @@ impl Configuration {
 				download_old_blocks: !self.args.flag_no_ancient_blocks,
 				verifier_settings: verifier_settings,
 				serve_light: !self.args.flag_no_serve_light,
+				light_serve: self.light_serve_config()?,
 				light: self.args.flag_light,
+				trusted_checkpoint: self.args.flag_trusted_checkpoint.clone(),
 				no_persistent_txqueue: self.args.flag_no_persistent_txqueue,
 				whisper: whisper_config,
 			};
@@ -1102,6 +1120,27 @@ impl Configuration {
 		settings
 	}
 
+	fn light_serve_config(&self) -> Result<::light::net::Config, String> {
+		let serve_time_share = self.args.flag_light_serve_time_share.parse::<f64>()
+			.map_err(|_| format!("Invalid light serve time share '{}' given. Must be a positive decimal number.", self.args.flag_light_serve_time_share))?;
+		if !serve_time_share.is_finite() || serve_time_share <= 0.0 {
+			return Err(format!("Invalid light serve time share '{}' given. Must be a positive decimal number.", self.args.flag_light_serve_time_share));
+		}
+
+		let cost_overrides = match self.args.flag_light_serve_costs {
+			Some(ref costs) => to_light_serve_costs(costs)?,
+			None => Default::default(),
+		};
+
+		Ok(::light::net::Config {
+			max_peers: self.args.flag_light_serve_max_peers,
+			serve_time_share: serve_time_share,
+			recharge: self.args.flag_light_serve_recharge,
+			cost_overrides: cost_overrides,
+			..Default::default()
+		})
+	}
+
 	fn whisper_config(&self) -> ::whisper::Config {
 		::whisper::Config {
 			enabled: self.args.flag_whisper,
@@ -1123,7 +1162,7 @@ mod tests {
 	use updater::{UpdatePolicy, UpdateFilter, ReleaseTrack};
 
 	use account::{AccountCmd, NewAccount, ImportAccounts, ListAccounts};
-	use blockchain::{BlockchainCmd, ImportBlockchain, ExportBlockchain, DataFormat, ExportState};
+	use blockchain::{BlockchainCmd, ImportBlockchain, ExportBlockchain, DataFormat, ExportState, TraceBackfill};
 	use cli::Args;
 	use dir::{Directories, default_hypervisor_path};
 	use helpers::{default_network_config};
@@ -1252,6 +1291,25 @@ mod tests {
 		})));
 	}
 
+	#[test]
+	fn test_command_trace_backfill() {
+		let args = vec!["parity", "db", "trace-backfill", "--from", "100", "--to", "200"];
+		let conf = parse(&args);
+		assert_eq!(conf.into_command().unwrap().cmd, Cmd::Blockchain(BlockchainCmd::TraceBackfill(TraceBackfill {
+			spec: Default::default(),
+			cache_config: Default::default(),
+			dirs: Default::default(),
+			pruning: Default::default(),
+			pruning_history: 64,
+			pruning_memory: 32,
+			compaction: Default::default(),
+			wal: true,
+			fat_db: Default::default(),
+			from_block: BlockId::Number(100),
+			to_block: BlockId::Number(200),
+		})));
+	}
+
 	#[test]
 	fn test_command_state_export() {
 		let args = vec!["parity", "export", "state", "state.json"];
@@ -1360,6 +1418,8 @@ mod tests {
 			update_policy: UpdatePolicy { enable_downloading: true, require_consensus: true, filter: UpdateFilter::Critical, track: ReleaseTrack::Unknown, path: default_hypervisor_path() },
 			mode: Default::default(),
 			tracing: Default::default(),
+			trace_address_index: false,
+			trace_backfill: false,
 			compaction: Default::default(),
 			wal: true,
 			vm_type: Default::default(),
@@ -1380,7 +1440,9 @@ mod tests {
 			download_old_blocks: true,
 			verifier_settings: Default::default(),
 			serve_light: true,
+			light_serve: Default::default(),
 			light: false,
+			trusted_checkpoint: None,
 			no_persistent_txqueue: false,
 			whisper: Default::default(),
 		};
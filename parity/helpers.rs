@@ -18,10 +18,12 @@ use std::{io, env};
 use std::io::{Write, BufReader, BufRead};
 use std::time::Duration;
 use std::fs::File;
+use std::collections::HashMap;
 use util::{clean_0x, U256, Address, CompactionProfile};
 use util::journaldb::Algorithm;
 use ethcore::client::{Mode, BlockId, VMType, DatabaseCompactionProfile, ClientConfig, VerifierType};
 use ethcore::miner::{PendingSet, GasLimit, PrioritizationStrategy};
+use light::request::Kind as RequestKind;
 use cache::CacheConfig;
 use dir::DatabaseDirectories;
 use upgrade::{upgrade, upgrade_data_paths};
@@ -132,6 +134,22 @@ pub fn to_price(s: &str) -> Result<f32, String> {
 	s.parse::<f32>().map_err(|_| format!("Invalid transaciton price 's' given. Must be a decimal number."))
 }
 
+/// Parses a comma-delimited list of light client request costs in the format `KIND=COST`.
+pub fn to_light_serve_costs(s: &str) -> Result<HashMap<RequestKind, U256>, String> {
+	let mut costs: HashMap<RequestKind, U256> = HashMap::new();
+	for entry in s.split(',').filter(|entry| !entry.is_empty()) {
+		let mut parts = entry.splitn(2, '=');
+		let kind = parts.next().expect("splitn always yields at least one item; qed");
+		let cost = parts.next().ok_or_else(|| format!("Invalid light serve cost '{}'. Expected KIND=COST.", entry))?;
+		let kind: RequestKind = kind.trim().parse()?;
+		if kind == RequestKind::BloomBits {
+			return Err("The cost of bloom_bits requests follows header_proof and can't be set.".into());
+		}
+		costs.insert(kind, to_u256(cost.trim())?);
+	}
+	Ok(costs)
+}
+
 /// Replaces `$HOME` str with home directory path.
 pub fn replace_home(base: &str, arg: &str) -> String {
 	// the $HOME directory on mac os should be `~/Library` or `~/Library/Application Support`
@@ -344,7 +362,7 @@ mod tests {
 	use util::{U256};
 	use ethcore::client::{Mode, BlockId};
 	use ethcore::miner::PendingSet;
-	use super::{to_duration, to_mode, to_block_id, to_u256, to_pending_set, to_address, to_addresses, to_price, geth_ipc_path, to_bootnodes, password_from_file};
+	use super::{to_duration, to_mode, to_block_id, to_u256, to_pending_set, to_address, to_addresses, to_price, geth_ipc_path, to_bootnodes, password_from_file, to_light_serve_costs};
 
 	#[test]
 	fn test_to_duration() {
@@ -456,6 +474,21 @@ but the first password is trimmed
 		assert_eq!(to_price("2.33").unwrap(), 2.33);
 	}
 
+	#[test]
+	fn test_to_light_serve_costs() {
+		use light::request::Kind;
+
+		let costs = to_light_serve_costs("execution=100, account=0x10").unwrap();
+		assert_eq!(costs.len(), 2);
+		assert_eq!(costs[&Kind::Execution], U256::from(100));
+		assert_eq!(costs[&Kind::Account], U256::from(16));
+
+		assert!(to_light_serve_costs("").unwrap().is_empty());
+		assert!(to_light_serve_costs("execution").is_err());
+		assert!(to_light_serve_costs("transactions=1").is_err());
+		assert!(to_light_serve_costs("bloom_bits=1").is_err());
+	}
+
 	#[test]
 	#[cfg(windows)]
 	fn test_geth_ipc_path() {
@@ -22,6 +22,7 @@ use hypervisor::Hypervisor;
 use ethsync::{AttachedProtocol, SyncConfig, NetworkConfiguration, NetworkError, Params, ConnectionFilter};
 use ethcore::snapshot::SnapshotService;
 use light::Provider;
+use light::net::Config as LightServeConfig;
 
 #[cfg(not(feature="ipc"))]
 use self::no_ipc_deps::*;
@@ -147,6 +148,7 @@ pub fn sync(
 	hypervisor_ref: &mut Option<Hypervisor>,
 	sync_cfg: SyncConfig,
 	net_cfg: NetworkConfiguration,
+	_light_serve_cfg: LightServeConfig,
 	_client: Arc<BlockChainClient>,
 	_snapshot_service: Arc<SnapshotService>,
 	_provider: Arc<Provider>,
@@ -178,6 +180,7 @@ pub fn sync(
 	_hypervisor: &mut Option<Hypervisor>,
 	sync_cfg: SyncConfig,
 	net_cfg: NetworkConfiguration,
+	light_serve_cfg: LightServeConfig,
 	client: Arc<BlockChainClient>,
 	snapshot_service: Arc<SnapshotService>,
 	provider: Arc<Provider>,
@@ -191,6 +194,7 @@ pub fn sync(
 		provider: provider,
 		snapshot_service: snapshot_service,
 		network_config: net_cfg,
+		light_config: light_serve_cfg,
 		attached_protos: attached_protos,
 	},
 	connection_filter)?;
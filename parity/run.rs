@@ -15,6 +15,7 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::fmt;
+use std::fs;
 use std::sync::{Arc, Weak};
 use std::net::{TcpListener};
 
@@ -27,6 +28,7 @@ use ethcore::miner::{Miner, MinerService, ExternalMiner, MinerOptions};
 use ethcore::miner::{StratumOptions, Stratum};
 use ethcore::service::ClientService;
 use ethcore::snapshot;
+use ethcore::spec::TrustedCheckpoint;
 use ethcore::verification::queue::VerifierSettings;
 use ethsync::{self, SyncConfig};
 use fdlimit::raise_fd_limit;
@@ -38,6 +40,7 @@ use parity_reactor::EventLoop;
 use parity_rpc::{NetworkSettings, informant, is_major_importing};
 use updater::{UpdatePolicy, Updater};
 use util::{Colour, version, Mutex, Condvar};
+use util::journaldb::Algorithm;
 use node_filter::NodeFilter;
 
 use params::{
@@ -97,6 +100,8 @@ pub struct RunCmd {
 	pub update_policy: UpdatePolicy,
 	pub mode: Option<Mode>,
 	pub tracing: Switch,
+	pub trace_address_index: bool,
+	pub trace_backfill: bool,
 	pub fat_db: Switch,
 	pub compaction: DatabaseCompactionProfile,
 	pub wal: bool,
@@ -117,7 +122,10 @@ pub struct RunCmd {
 	pub download_old_blocks: bool,
 	pub verifier_settings: VerifierSettings,
 	pub serve_light: bool,
+	pub light_serve: ::light::net::Config,
 	pub light: bool,
+	/// Path to a trusted checkpoint for the light client to start from.
+	pub trusted_checkpoint: Option<String>,
 	pub no_persistent_txqueue: bool,
 	pub whisper: ::whisper::Config
 }
@@ -173,7 +181,13 @@ fn execute_light(cmd: RunCmd, can_restart: bool, logger: Arc<RotatingLogger>) ->
 	use util::RwLock;
 
 	// load spec
-	let spec = cmd.spec.spec(&cmd.dirs.cache)?;
+	let mut spec = cmd.spec.spec(&cmd.dirs.cache)?;
+
+	// override the spec's trusted checkpoint.
+	if let Some(ref path) = cmd.trusted_checkpoint {
+		let file = fs::File::open(path).map_err(|e| format!("Unable to open trusted checkpoint file {}: {}", path, e))?;
+		spec.trusted_checkpoint = Some(TrustedCheckpoint::load(file)?);
+	}
 
 	// load genesis hash
 	let genesis_hash = spec.genesis_header().hash();
@@ -425,8 +439,17 @@ pub fn execute(cmd: RunCmd, can_restart: bool, logger: Arc<RotatingLogger>) -> R
 	// select pruning algorithm
 	let algorithm = cmd.pruning.to_algorithm(&user_defaults);
 
-	// check if tracing is on
-	let tracing = tracing_switch_to_bool(cmd.tracing, &user_defaults)?;
+	// backfill re-executes old blocks, so their state must still be there
+	if cmd.trace_backfill && algorithm != Algorithm::Archive {
+		return Err("Trace backfill requires the state of old blocks. Please rerun with `--pruning=archive`".into());
+	}
+
+	// check if tracing is on, backfill enables it on databases synced without tracing.
+	// the client keeps backfilling on later runs until all old blocks are traced.
+	let tracing = match cmd.trace_backfill {
+		true => true,
+		false => tracing_switch_to_bool(cmd.tracing, &user_defaults)?,
+	};
 
 	// check if fatdb is on
 	let fat_db = fatdb_switch_to_bool(cmd.fat_db, &user_defaults, algorithm)?;
@@ -547,6 +570,8 @@ pub fn execute(cmd: RunCmd, can_restart: bool, logger: Arc<RotatingLogger>) -> R
 	);
 
 	client_config.queue.verifier_settings = cmd.verifier_settings;
+	client_config.tracing.address_index = cmd.trace_address_index;
+	client_config.tracing.backfill = cmd.trace_backfill;
 
 	// set up bootnodes
 	let mut net_conf = cmd.net_conf;
@@ -643,6 +668,7 @@ pub fn execute(cmd: RunCmd, can_restart: bool, logger: Arc<RotatingLogger>) -> R
 		&mut hypervisor,
 		sync_config,
 		net_conf.clone().into(),
+		cmd.light_serve.clone(),
 		client.clone(),
 		snapshot_service.clone(),
 		client.clone(),
@@ -57,6 +57,7 @@ pub fn main() {
 		snapshot_service: remote_snapshot.service().clone(),
 		provider: remote_provider.service().clone(),
 		network_config: service_config.net
+		light_config: Default::default(),
 		attached_protos: Vec::new(),
 	}).unwrap();
 
@@ -40,13 +40,14 @@ use ethcore::account_provider::AccountProvider;
 use crypto::DEFAULT_MAC;
 
 use jsonrpc_core::Error;
-use v1::helpers::{errors, TransactionRequest, FilledTransactionRequest, ConfirmationPayload};
+use v1::helpers::{errors, eip712, TransactionRequest, FilledTransactionRequest, ConfirmationPayload};
 use v1::types::{
 	H256 as RpcH256, H520 as RpcH520, Bytes as RpcBytes,
 	RichRawTransaction as RpcRichRawTransaction,
 	ConfirmationPayload as RpcConfirmationPayload,
 	ConfirmationResponse,
 	SignRequest as RpcSignRequest,
+	EIP712SignRequest as RpcEIP712SignRequest,
 	DecryptRequest as RpcDecryptRequest,
 };
 
@@ -525,6 +526,20 @@ pub fn execute<D: Dispatcher + 'static>(
 				);
 			future::done(res).boxed()
 		},
+		ConfirmationPayload::EIP712SignMessage(address, data) => {
+			if accounts.is_hardware_address(address) {
+				return future::err(errors::unsupported("Signing via hardware wallets is not supported.", None)).boxed();
+			}
+
+			let hash = try_bf!(eip712::hash_structured_data(&data));
+			let res = signature(&accounts, address, hash, pass)
+				.map(|result| result
+					.map(|rsv| H520(rsv.into_electrum()))
+					.map(RpcH520::from)
+					.map(ConfirmationResponse::Signature)
+				);
+			future::done(res).boxed()
+		},
 		ConfirmationPayload::Decrypt(address, data) => {
 			if accounts.is_hardware_address(address) {
 				return future::err(errors::unsupported("Decrypting via hardware wallets is not supported.", None)).boxed();
@@ -613,5 +628,10 @@ pub fn from_rpc<D>(payload: RpcConfirmationPayload, default_account: Address, di
 		RpcConfirmationPayload::EthSignMessage(RpcSignRequest { address, data }) => {
 			future::ok(ConfirmationPayload::EthSignMessage(address.into(), data.into())).boxed()
 		},
+		RpcConfirmationPayload::EIP712SignMessage(RpcEIP712SignRequest { address, data }) => {
+			// reject malformed data before it reaches the signer queue
+			try_bf!(eip712::hash_structured_data(&data));
+			future::ok(ConfirmationPayload::EIP712SignMessage(address.into(), data)).boxed()
+		},
 	}
 }
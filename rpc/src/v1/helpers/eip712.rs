@@ -0,0 +1,352 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! EIP-712 typed structured data hashing.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::str::FromStr;
+
+use rustc_hex::FromHex;
+use serde_json::Value;
+use util::{Address, H256, U256};
+use util::sha3::Hashable;
+
+use jsonrpc_core::Error;
+use v1::helpers::errors;
+use v1::types::{EIP712, EIP712Member};
+
+/// Name of the domain struct type.
+const DOMAIN_TYPE: &'static str = "EIP712Domain";
+
+type Types = BTreeMap<String, Vec<EIP712Member>>;
+
+fn invalid<T: Into<String>>(details: T) -> Error {
+	errors::invalid_params("Invalid EIP-712 typed data", details.into())
+}
+
+/// Splits an array type into the item type and the fixed length (if any).
+fn parse_array(ty: &str) -> Result<Option<(&str, Option<usize>)>, Error> {
+	if !ty.ends_with(']') {
+		return Ok(None);
+	}
+
+	let open = ty.rfind('[').ok_or_else(|| invalid(format!("Invalid type: {}", ty)))?;
+	let len = &ty[open + 1..ty.len() - 1];
+	let len = match len.is_empty() {
+		true => None,
+		false => Some(len.parse().map_err(|_| invalid(format!("Invalid array length: {}", ty)))?),
+	};
+	Ok(Some((&ty[..open], len)))
+}
+
+/// Returns the size of a sized atomic type, e.g. `256` for `uint256`.
+fn type_size(ty: &str, prefix: &str) -> Option<usize> {
+	match ty.starts_with(prefix) {
+		true => ty[prefix.len()..].parse().ok(),
+		false => None,
+	}
+}
+
+fn check_type(types: &Types, ty: &str) -> Result<(), Error> {
+	if let Some((item, _)) = parse_array(ty)? {
+		return check_type(types, item);
+	}
+
+	let is_int = |size: Option<usize>| size.map_or(false, |n| n > 0 && n <= 256 && n % 8 == 0);
+	let valid = types.contains_key(ty) || match ty {
+		"address" | "bool" | "string" | "bytes" => true,
+		_ => type_size(ty, "bytes").map_or(false, |n| n > 0 && n <= 32)
+			|| is_int(type_size(ty, "uint"))
+			|| is_int(type_size(ty, "int")),
+	};
+
+	match valid {
+		true => Ok(()),
+		false => Err(invalid(format!("Unknown type: {}", ty))),
+	}
+}
+
+fn validate(data: &EIP712) -> Result<(), Error> {
+	if !data.types.contains_key(DOMAIN_TYPE) {
+		return Err(invalid(format!("Missing {} type", DOMAIN_TYPE)));
+	}
+	if !data.types.contains_key(&data.primary_type) {
+		return Err(invalid(format!("Missing primary type: {}", data.primary_type)));
+	}
+	for members in data.types.values() {
+		for member in members {
+			check_type(&data.types, &member.member_type)?;
+		}
+	}
+	Ok(())
+}
+
+fn collect_dependencies<'a>(types: &'a Types, ty: &'a str, deps: &mut BTreeSet<&'a str>) {
+	let name = match ty.find('[') {
+		Some(pos) => &ty[..pos],
+		None => ty,
+	};
+	if deps.contains(name) {
+		return;
+	}
+	if let Some(members) = types.get(name) {
+		deps.insert(name);
+		for member in members {
+			collect_dependencies(types, &member.member_type, deps);
+		}
+	}
+}
+
+/// Encodes the struct type together with all referenced struct types, e.g.
+/// `Mail(Person from,Person to,string contents)Person(string name,address wallet)`.
+fn encode_type(types: &Types, name: &str) -> String {
+	let mut deps = BTreeSet::new();
+	collect_dependencies(types, name, &mut deps);
+	deps.remove(name);
+
+	::std::iter::once(name).chain(deps.into_iter())
+		.map(|name| {
+			let members = types[name].iter()
+				.map(|member| format!("{} {}", member.member_type, member.name))
+				.collect::<Vec<_>>();
+			format!("{}({})", name, members.join(","))
+		})
+		.collect()
+}
+
+fn hash_struct(types: &Types, name: &str, value: &Value) -> Result<H256, Error> {
+	let fields = value.as_object().ok_or_else(|| invalid(format!("Expected object of type {}", name)))?;
+
+	let mut encoded = encode_type(types, name).as_bytes().sha3().to_vec();
+	for member in &types[name] {
+		let field = fields.get(&member.name)
+			.ok_or_else(|| invalid(format!("Missing field {}.{}", name, member.name)))?;
+		encoded.extend_from_slice(&encode_value(types, &member.member_type, field)?);
+	}
+	Ok(encoded.sha3())
+}
+
+fn parse_bytes(value: &Value) -> Result<Vec<u8>, Error> {
+	value.as_str()
+		.and_then(|s| match s.starts_with("0x") {
+			true => s[2..].from_hex().ok(),
+			false => None,
+		})
+		.ok_or_else(|| invalid(format!("Expected hex-encoded bytes, got {}", value)))
+}
+
+/// Parses an integer given as a JSON number, a decimal or a hex string.
+/// Returns the magnitude and whether it's negative.
+fn parse_int(value: &Value) -> Result<(U256, bool), Error> {
+	let err = || invalid(format!("Expected integer, got {}", value));
+	match *value {
+		Value::Number(ref n) => match (n.as_u64(), n.as_i64()) {
+			(Some(n), _) => Ok((n.into(), false)),
+			(None, Some(n)) => Ok(((n as u64).wrapping_neg().into(), true)),
+			_ => Err(err()),
+		},
+		Value::String(ref s) => {
+			let (s, negative) = match s.starts_with('-') {
+				true => (&s[1..], true),
+				false => (&s[..], false),
+			};
+			let magnitude = match s.starts_with("0x") {
+				true => U256::from_str(&s[2..]).map_err(|_| err())?,
+				false => U256::from_dec_str(s).map_err(|_| err())?,
+			};
+			Ok((magnitude, negative))
+		},
+		_ => Err(err()),
+	}
+}
+
+fn encode_value(types: &Types, ty: &str, value: &Value) -> Result<H256, Error> {
+	if let Some((item, len)) = parse_array(ty)? {
+		let items = value.as_array().ok_or_else(|| invalid(format!("Expected array of type {}", ty)))?;
+		if let Some(len) = len {
+			if len != items.len() {
+				return Err(invalid(format!("Expected {} items of type {}", len, item)));
+			}
+		}
+
+		let mut encoded = Vec::with_capacity(items.len() * 32);
+		for item_value in items {
+			encoded.extend_from_slice(&encode_value(types, item, item_value)?);
+		}
+		return Ok(encoded.sha3());
+	}
+
+	if types.contains_key(ty) {
+		return hash_struct(types, ty, value);
+	}
+
+	match ty {
+		"string" => value.as_str()
+			.map(|s| s.as_bytes().sha3())
+			.ok_or_else(|| invalid(format!("Expected string, got {}", value))),
+		"bytes" => parse_bytes(value).map(|bytes| bytes.sha3()),
+		"bool" => value.as_bool()
+			.map(|b| H256::from(U256::from(b as u8)))
+			.ok_or_else(|| invalid(format!("Expected bool, got {}", value))),
+		"address" => value.as_str()
+			.and_then(|s| match s.starts_with("0x") {
+				true => Address::from_str(&s[2..]).ok(),
+				false => None,
+			})
+			.map(H256::from)
+			.ok_or_else(|| invalid(format!("Expected address, got {}", value))),
+		_ => {
+			if let Some(size) = type_size(ty, "bytes") {
+				let bytes = parse_bytes(value)?;
+				if bytes.len() != size {
+					return Err(invalid(format!("Expected {} bytes, got {}", size, value)));
+				}
+				let mut encoded = H256::new();
+				encoded.0[..size].copy_from_slice(&bytes);
+				return Ok(encoded);
+			}
+
+			let (signed, size) = match (type_size(ty, "uint"), type_size(ty, "int")) {
+				(Some(size), _) => (false, size),
+				(None, Some(size)) => (true, size),
+				_ => return Err(invalid(format!("Unknown type: {}", ty))),
+			};
+
+			let (magnitude, negative) = parse_int(value)?;
+			let out_of_range = match (signed, negative) {
+				(false, true) => !magnitude.is_zero(),
+				(false, false) => magnitude.bits() > size,
+				// allow -2^(size - 1)
+				(true, true) => magnitude.bits() > size || (magnitude.bits() == size && magnitude != U256::one() << (size - 1)),
+				(true, false) => magnitude.bits() >= size,
+			};
+			if out_of_range {
+				return Err(invalid(format!("Value {} out of range for {}", value, ty)));
+			}
+
+			// two's complement for negative values
+			let encoded = match negative {
+				true => (!magnitude).overflowing_add(U256::one()).0,
+				false => magnitude,
+			};
+			Ok(encoded.into())
+		},
+	}
+}
+
+/// Computes the hash of typed structured data to be signed:
+/// `keccak256("\x19\x01" ‖ domainSeparator ‖ hashStruct(message))`.
+pub fn hash_structured_data(data: &EIP712) -> Result<H256, Error> {
+	validate(data)?;
+
+	let domain_separator = hash_struct(&data.types, DOMAIN_TYPE, &data.domain)?;
+	let message = hash_struct(&data.types, &data.primary_type, &data.message)?;
+
+	let mut encoded = vec![0x19, 0x01];
+	encoded.extend_from_slice(&domain_separator);
+	encoded.extend_from_slice(&message);
+	Ok(encoded.sha3())
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json::{self, Value};
+	use util::H256;
+	use v1::types::EIP712;
+	use super::{hash_structured_data, encode_type, encode_value};
+
+	const MAIL: &'static str = r#"{
+		"types": {
+			"EIP712Domain": [
+				{ "name": "name", "type": "string" },
+				{ "name": "version", "type": "string" },
+				{ "name": "chainId", "type": "uint256" },
+				{ "name": "verifyingContract", "type": "address" }
+			],
+			"Person": [
+				{ "name": "name", "type": "string" },
+				{ "name": "wallet", "type": "address" }
+			],
+			"Mail": [
+				{ "name": "from", "type": "Person" },
+				{ "name": "to", "type": "Person" },
+				{ "name": "contents", "type": "string" }
+			]
+		},
+		"primaryType": "Mail",
+		"domain": {
+			"name": "Ether Mail",
+			"version": "1",
+			"chainId": 1,
+			"verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+		},
+		"message": {
+			"from": { "name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826" },
+			"to": { "name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB" },
+			"contents": "Hello, Bob!"
+		}
+	}"#;
+
+	fn mail() -> EIP712 {
+		serde_json::from_str(MAIL).unwrap()
+	}
+
+	#[test]
+	fn should_encode_type_with_dependencies() {
+		assert_eq!(
+			encode_type(&mail().types, "Mail"),
+			"Mail(Person from,Person to,string contents)Person(string name,address wallet)"
+		);
+	}
+
+	#[test]
+	fn should_hash_structured_data() {
+		assert_eq!(
+			hash_structured_data(&mail()).unwrap(),
+			"be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2".parse::<H256>().unwrap()
+		);
+	}
+
+	#[test]
+	fn should_reject_invalid_data() {
+		let mut data = mail();
+		data.types.remove("EIP712Domain");
+		assert!(hash_structured_data(&data).is_err());
+
+		let mut data = mail();
+		data.types.get_mut("Person").unwrap()[1].member_type = "addr".into();
+		assert!(hash_structured_data(&data).is_err());
+
+		let mut data = mail();
+		data.message["to"] = Value::Null;
+		assert!(hash_structured_data(&data).is_err());
+	}
+
+	#[test]
+	fn should_encode_integers() {
+		let types = Default::default();
+		let encode = |ty: &str, value: &str| encode_value(&types, ty, &serde_json::from_str(value).unwrap());
+
+		assert_eq!(encode("uint8", "255").unwrap(), H256::from(255));
+		assert_eq!(encode("uint256", r#""0x100""#).unwrap(), H256::from(256));
+		assert_eq!(encode("int8", "-1").unwrap(), H256::from([0xff; 32]));
+		assert_eq!(encode("int8", r#""-128""#).unwrap(), "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff80".parse::<H256>().unwrap());
+		assert!(encode("uint8", "256").is_err());
+		assert!(encode("uint8", "-1").is_err());
+		assert!(encode("int8", "128").is_err());
+		assert!(encode("int8", "-129").is_err());
+	}
+}
@@ -18,6 +18,8 @@
 
 use std::fmt;
 use rlp::DecoderError;
+use rustc_hex::ToHex;
+use util::U256;
 use ethcore::error::{Error as EthcoreError, CallError, TransactionError};
 use ethcore::account_provider::{SignError as AccountError};
 use jsonrpc_core::{Error, ErrorCode, Value};
@@ -146,6 +148,14 @@ pub fn state_pruned() -> Error {
 	}
 }
 
+pub fn traces_not_backfilled(cursor: u64) -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::UNSUPPORTED_REQUEST),
+		message: "Traces of these blocks are still being backfilled. Try again later or restrict the range to recent blocks.".into(),
+		data: Some(Value::String(format!("Blocks before #{} are not traced yet", cursor))),
+	}
+}
+
 pub fn state_corrupt() -> Error {
 	internal("State corrupt", "")
 }
@@ -158,6 +168,58 @@ pub fn exceptional() -> Error {
 	}
 }
 
+/// Selector of `Error(string)`, used by Solidity to encode revert reasons.
+const REVERT_REASON_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+/// Reads an ABI-encoded word at `at` as an offset or length bounded by the data size.
+fn abi_word(data: &[u8], at: usize) -> Option<usize> {
+	if data.len() < 32 || at > data.len() - 32 {
+		return None;
+	}
+
+	let word = U256::from(&data[at..at + 32]);
+	match word > U256::from(data.len()) {
+		true => None,
+		false => Some(word.low_u64() as usize),
+	}
+}
+
+/// Decodes the message of an `Error(string)` revert reason.
+pub fn revert_reason(data: &[u8]) -> Option<String> {
+	if data.len() < 4 || data[..4] != REVERT_REASON_SELECTOR {
+		return None;
+	}
+
+	let data = &data[4..];
+	let offset = match abi_word(data, 0) {
+		Some(offset) => offset,
+		None => return None,
+	};
+	let len = match abi_word(data, offset) {
+		Some(len) => len,
+		None => return None,
+	};
+	let start = offset + 32;
+	if len > data.len() - start {
+		return None;
+	}
+
+	String::from_utf8(data[start..start + len].to_vec()).ok()
+}
+
+pub fn reverted(data: &[u8]) -> Error {
+	let message = match revert_reason(data) {
+		Some(reason) => format!("Transaction execution reverted: {}", reason),
+		None => "Transaction execution reverted.".into(),
+	};
+
+	Error {
+		code: ErrorCode::ServerError(codes::EXECUTION_ERROR),
+		message: message,
+		data: Some(Value::String(format!("0x{}", data.to_hex()))),
+	}
+}
+
 pub fn no_work() -> Error {
 	Error {
 		code: ErrorCode::ServerError(codes::NO_WORK),
@@ -222,6 +284,30 @@ pub fn dapps_disabled() -> Error {
 	}
 }
 
+pub fn light_serve_disabled() -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::UNSUPPORTED_REQUEST),
+		message: "Serving light clients is disabled. Remove --no-serve-light to enable.".into(),
+		data: None,
+	}
+}
+
+pub fn light_filter_unbounded() -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::UNSUPPORTED_REQUEST),
+		message: "Filters without an address or topics only match blocks still stored by the light client. Restrict the filter or its block range.".into(),
+		data: None,
+	}
+}
+
+pub fn light_logs_unavailable(first: u64, last: u64) -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::UNSUPPORTED_REQUEST),
+		message: "Logs of these blocks can't be searched by the light client. Restrict the block range to recent blocks.".into(),
+		data: Some(Value::String(format!("No bloom bits known for blocks #{}..#{}", first, last))),
+	}
+}
+
 pub fn ws_disabled() -> Error {
 	Error {
 		code: ErrorCode::ServerError(codes::UNSUPPORTED_REQUEST),
@@ -348,6 +434,7 @@ pub fn call(error: CallError) -> Error {
 		CallError::StatePruned => state_pruned(),
 		CallError::StateCorrupt => state_corrupt(),
 		CallError::Exceptional => exceptional(),
+		CallError::Reverted(data) => reverted(&data),
 		CallError::Execution(e) => execution(e),
 		CallError::TransactionNotFound => internal("{}, this should not be the case with eth_call, most likely a bug.", CallError::TransactionNotFound),
 	}
@@ -381,3 +468,38 @@ pub fn deprecated<T: Into<Option<String>>>(message: T) -> Error {
 pub fn on_demand_cancel(_cancel: ::futures::sync::oneshot::Canceled) -> Error {
 	internal("on-demand sender cancelled", "")
 }
+
+#[cfg(test)]
+mod tests {
+	use rustc_hex::FromHex;
+	use super::{revert_reason, reverted};
+
+	// `revert("Not enough Ether provided.")`
+	const REVERT_DATA: &'static str = "08c379a0\
+		0000000000000000000000000000000000000000000000000000000000000020\
+		000000000000000000000000000000000000000000000000000000000000001a\
+		4e6f7420656e6f7567682045746865722070726f76696465642e000000000000";
+
+	#[test]
+	fn should_decode_revert_reason() {
+		let data = REVERT_DATA.from_hex().unwrap();
+		assert_eq!(revert_reason(&data), Some("Not enough Ether provided.".into()));
+
+		let error = reverted(&data);
+		assert_eq!(error.message, "Transaction execution reverted: Not enough Ether provided.");
+		assert_eq!(error.data, Some(format!("0x{}", REVERT_DATA).into()));
+	}
+
+	#[test]
+	fn should_not_decode_malformed_revert_reason() {
+		let data = REVERT_DATA.from_hex().unwrap();
+		assert_eq!(revert_reason(&[]), None);
+		assert_eq!(revert_reason(&data[..40]), None);
+		assert_eq!(revert_reason(&data[..100]), None);
+		assert_eq!(revert_reason(&[0x12, 0x34]), None);
+
+		let error = reverted(&[0x12, 0x34]);
+		assert_eq!(error.message, "Transaction execution reverted.");
+		assert_eq!(error.data, Some("0x1234".into()));
+	}
+}
@@ -23,10 +23,12 @@ use ethcore::encoded;
 use ethcore::executed::{Executed, ExecutionError};
 use ethcore::ids::BlockId;
 use ethcore::filter::Filter as EthcoreFilter;
+use ethcore::log_entry::LocalizedLogEntry;
+use ethcore::receipt::LocalizedReceipt;
 use ethcore::transaction::{Action, Transaction as EthTransaction};
 
 use futures::{future, Future, BoxFuture};
-use futures::future::Either;
+use futures::future::{Either, Loop};
 use jsonrpc_core::Error;
 use jsonrpc_macros::Trailing;
 
@@ -37,13 +39,16 @@ use light::on_demand::{request, OnDemand, HeaderRef, Request as OnDemandRequest,
 use light::request::Field;
 
 use ethsync::LightSync;
-use util::{Address, Mutex, U256};
+use util::{Address, Mutex, H256, H2048, U256};
 
 use v1::helpers::{CallRequest as CallRequestHelper, errors, dispatch};
-use v1::types::{BlockNumber, CallRequest, Log};
+use v1::types::{BlockNumber, CallRequest, Log, Transaction};
+
+const NO_INVALID_BACK_REFS: &'static str = "Fails only on invalid back-references; back-references here known to be valid; qed";
 
 /// Helper for fetching blockchain data either from the light client or the network
 /// as necessary.
+#[derive(Clone)]
 pub struct LightFetch {
 	/// The light client.
 	pub client: Arc<LightChainClient>,
@@ -58,6 +63,12 @@ pub struct LightFetch {
 /// Type alias for convenience.
 pub type ExecutionResult = Result<Executed, ExecutionError>;
 
+/// Extract the transaction at the given index from a block.
+pub fn extract_transaction_at_index(block: encoded::Block, index: usize, eip86_transition: u64) -> Option<Transaction> {
+	block.view().localized_transaction_at(index)
+		.map(|tx| Transaction::from_localized(tx, eip86_transition))
+}
+
 // extract the header indicated by the given `HeaderRef` from the given responses.
 // fails only if they do not correspond.
 fn extract_header(res: &[OnDemandResponse], header: HeaderRef) -> Option<encoded::Header> {
@@ -158,6 +169,42 @@ impl LightFetch {
 		}
 	}
 
+	/// helper for getting a storage value of an account at a given block.
+	/// Storage of accounts which don't exist is all zero.
+	pub fn storage_at(&self, address: Address, key: H256, id: BlockId) -> BoxFuture<H256, Error> {
+		let mut reqs = Vec::new();
+		let header_ref = match self.make_header_requests(id, &mut reqs) {
+			Ok(r) => r,
+			Err(e) => return future::err(e).boxed(),
+		};
+
+		// the storage proof is checked against the root from the account proof.
+		let account_idx = reqs.len();
+		reqs.push(request::Account { header: header_ref.clone(), address: address }.into());
+		reqs.push(request::Storage {
+			header: header_ref,
+			address: address,
+			key: key,
+			storage_root: Field::back_ref(account_idx, 1),
+		}.into());
+
+		let maybe_future = self.sync.with_context(move |ctx| {
+			self.on_demand.request_raw(ctx, reqs)
+				.expect("all back-references known to be valid; qed")
+				.map(|mut res| match res.pop() {
+					Some(OnDemandResponse::Storage(value)) => value,
+					_ => panic!("responses correspond directly with requests in amount and type; qed"),
+				})
+				.map_err(errors::on_demand_cancel)
+				.boxed()
+		});
+
+		match maybe_future {
+			Some(recv) => recv,
+			None => future::err(errors::network_disabled()).boxed()
+		}
+	}
+
 	/// helper for getting proved execution.
 	pub fn proved_execution(&self, req: CallRequest, num: Trailing<BlockNumber>) -> BoxFuture<ExecutionResult, Error> {
 		const DEFAULT_GAS_PRICE: u64 = 21_000;
@@ -262,14 +309,163 @@ impl LightFetch {
 		}
 	}
 
+	// get a block along with its receipts. fails on unknown block ID.
+	fn block_and_receipts(&self, id: BlockId) -> BoxFuture<(encoded::Block, Vec<::ethcore::receipt::Receipt>), Error> {
+		let mut reqs = Vec::new();
+		let header_ref = match self.make_header_requests(id, &mut reqs) {
+			Ok(r) => r,
+			Err(e) => return future::err(e).boxed(),
+		};
+
+		reqs.push(request::BlockReceipts(header_ref.clone()).into());
+		reqs.push(request::Body(header_ref).into());
+
+		let maybe_future = self.sync.with_context(move |ctx| {
+			self.on_demand.request_raw(ctx, reqs)
+				.expect("all back-references known to be valid; qed")
+				.map(|mut res| match (res.pop(), res.pop()) {
+					(Some(OnDemandResponse::Body(b)), Some(OnDemandResponse::Receipts(r))) => (b, r),
+					_ => panic!("responses correspond directly with requests in amount and type; qed"),
+				})
+				.map_err(errors::on_demand_cancel)
+				.boxed()
+		});
+
+		match maybe_future {
+			Some(recv) => recv,
+			None => future::err(errors::network_disabled()).boxed()
+		}
+	}
+
+	/// get the receipts of a block.
+	pub fn receipts(&self, header: encoded::Header) -> BoxFuture<Vec<::ethcore::receipt::Receipt>, Error> {
+		let on_demand = &self.on_demand;
+		let maybe_future = self.sync.with_context(move |ctx| {
+			on_demand.request(ctx, request::BlockReceipts(header.into()))
+				.expect(NO_INVALID_BACK_REFS)
+				.map_err(errors::on_demand_cancel)
+				.boxed()
+		});
+
+		match maybe_future {
+			Some(recv) => recv,
+			None => future::err(errors::network_disabled()).boxed()
+		}
+	}
+
+	/// get the canonical block containing a transaction along with the transaction's
+	/// index within it. `None` indicates the transaction isn't in the canonical chain
+	/// or no peer could locate it.
+	///
+	/// Peers can't prove transaction locations, so the block body is requested along
+	/// with the location and peers giving a location it contradicts are disabled.
+	/// The request is repeated if the block turns out not to be canonical.
+	pub fn transaction_location(&self, tx_hash: H256) -> BoxFuture<Option<(encoded::Block, usize)>, Error> {
+		// number of bad locations to tolerate before giving up.
+		const MAX_ATTEMPTS: usize = 3;
+
+		let fetcher = self.clone();
+		future::loop_fn(0, move |attempt| {
+			let maybe_future = fetcher.sync.with_context(|ctx| {
+				let block_hash = Field::back_ref(0, 1);
+				fetcher.on_demand.request_raw(ctx, vec![
+					request::TransactionIndex(tx_hash.into()).into(),
+					request::HeaderByHash(block_hash).into(),
+					request::Body(HeaderRef::Unresolved(1, block_hash)).into(),
+				]).expect(NO_INVALID_BACK_REFS)
+			});
+
+			// the request is cancelled once enough peers couldn't locate the transaction.
+			let location_future = match maybe_future {
+				Some(fut) => fut.then(|res| Ok::<_, Error>(res.ok())),
+				None => return future::err(errors::network_disabled()).boxed(),
+			};
+
+			let fetcher = fetcher.clone();
+			location_future.and_then(move |responses| {
+				let location = responses.and_then(|responses| {
+					let mut responses = responses.into_iter();
+					match (responses.next(), responses.nth(1)) {
+						(Some(OnDemandResponse::TransactionIndex(index)), Some(OnDemandResponse::Body(block))) => Some((index, block)),
+						_ => None,
+					}
+				});
+
+				let (index, block) = match location {
+					Some(location) => location,
+					None => return future::ok(Loop::Break(None)).boxed(),
+				};
+
+				// the block is beyond the chain we know.
+				if index.num > fetcher.client.chain_info().best_block_number {
+					return future::ok(Loop::Break(None)).boxed();
+				}
+
+				// the header fetched by number decides whether the block is canonical.
+				fetcher.header(BlockId::Number(index.num)).map(move |header| {
+					let tx_index = index.index as usize;
+					let matches = header.hash() == block.hash()
+						&& block.view().transaction_hashes().get(tx_index) == Some(&tx_hash);
+
+					if matches {
+						Loop::Break(Some((block, tx_index)))
+					} else if attempt + 1 < MAX_ATTEMPTS {
+						trace!(target: "light_fetch", "Bad location given for transaction {:?}", tx_hash);
+						Loop::Continue(attempt + 1)
+					} else {
+						Loop::Break(None)
+					}
+				}).boxed()
+			}).boxed()
+		}).boxed()
+	}
+
+	/// get a transaction by hash. `None` indicates the transaction isn't in the canonical chain.
+	pub fn transaction_by_hash(&self, tx_hash: H256, eip86_transition: u64) -> BoxFuture<Option<Transaction>, Error> {
+		self.transaction_location(tx_hash)
+			.map(move |loc| loc.and_then(|(block, index)| extract_transaction_at_index(block, index, eip86_transition)))
+			.boxed()
+	}
+
+	/// get the receipt of a transaction by hash. `None` indicates the transaction isn't in
+	/// the canonical chain.
+	pub fn transaction_receipt(&self, tx_hash: H256) -> BoxFuture<Option<LocalizedReceipt>, Error> {
+		let fetcher = self.clone();
+		self.transaction_location(tx_hash).and_then(move |loc| {
+			let (block, index) = match loc {
+				Some(loc) => loc,
+				None => return future::ok(None).boxed(),
+			};
+
+			let engine = fetcher.client.engine().clone();
+			fetcher.receipts(block.header()).map(move |mut receipts| {
+				let tx = match block.view().localized_transaction_at(index) {
+					Some(tx) => tx,
+					None => return None,
+				};
+
+				// receipts are checked against the header, so this only fails
+				// if the header and body disagree.
+				if receipts.len() <= index { return None }
+				receipts.truncate(index + 1);
+
+				Some(::ethcore::client::transaction_receipt(&**engine, tx, receipts))
+			}).boxed()
+		}).boxed()
+	}
+
 	/// get transaction logs
+	///
+	/// Headers still stored by the light client are checked against the filter directly.
+	/// Older blocks are narrowed down with the bloom bits tries of their CHTs before
+	/// their receipts are fetched. Searching older blocks fails for filters without an
+	/// address or topics, and for blocks whose bloom bits aren't known.
 	pub fn logs(&self, filter: EthcoreFilter) -> BoxFuture<Vec<Log>, Error> {
+		use std::cmp;
 		use std::collections::BTreeMap;
 
 		use futures::stream::{self, Stream};
 
-		const NO_INVALID_BACK_REFS: &'static str = "Fails only on invalid back-references; back-references here known to be valid; qed";
-
 		// early exit for "to" block before "from" block.
 		let best_number = self.client.chain_info().best_block_number;
 		let block_number = |id| match id {
@@ -279,44 +475,180 @@ impl LightFetch {
 			BlockId::Number(x) => Some(x),
 		};
 
-		match (block_number(filter.to_block), block_number(filter.from_block)) {
+		let (from_number, to_number) = match (block_number(filter.to_block), block_number(filter.from_block)) {
 			(Some(to), Some(from)) if to < from => return future::ok(Vec::new()).boxed(),
-			(Some(_), Some(_)) => {},
+			(Some(to), Some(from)) => (from, to),
 			_ => return future::err(errors::unknown_block()).boxed(),
-		}
+		};
+
+		// blocks past the best block can't hold any logs yet.
+		let (to_block, to_number) = if to_number > best_number {
+			if from_number > best_number { return future::ok(Vec::new()).boxed() }
+			(BlockId::Latest, best_number)
+		} else {
+			(filter.to_block, to_number)
+		};
+
+		let bit_combos = filter.bloom_possibilities();
+
+		// all headers in range (inclusive) which are still stored locally.
+		let local_headers: Vec<_> = self.client.ancestry_iter(to_block)
+			.take_while(|hdr| hdr.number() >= from_number)
+			.collect();
+
+		// the rest of the range is searched using bloom bits, and the receipts and
+		// body of each candidate block are fetched along with its header.
+		let lowest_local = local_headers.last().map_or(to_number + 1, |hdr| hdr.number());
+		let remote_start = cmp::max(from_number, 1);
+		let remote_blocks = if remote_start < lowest_local {
+			let fetcher = self.clone();
+			self.bloom_bits_candidates(remote_start, lowest_local - 1, &bit_combos)
+				.and_then(move |nums| {
+					let blocks: Vec<_> = nums.into_iter()
+						.map(|num| fetcher.block_and_receipts(BlockId::Number(num)))
+						.collect();
+
+					future::join_all(blocks)
+				})
+				.boxed()
+		} else {
+			future::ok(Vec::new()).boxed()
+		};
 
 		let maybe_future = self.sync.with_context(move |ctx| {
-			// find all headers which match the filter, and fetch the receipts for each one.
-			// match them with their numbers for easy sorting later.
-			let bit_combos = filter.bloom_possibilities();
-			let receipts_futures: Vec<_> = self.client.ancestry_iter(filter.to_block)
-				.take_while(|ref hdr| BlockId::Number(hdr.number()) != filter.from_block)
-				.take_while(|ref hdr| BlockId::Hash(hdr.hash()) != filter.from_block)
+			// fetch the receipts and body for each local header which matches the filter.
+			// the body provides the transaction hashes.
+			let local_blocks: Vec<_> = local_headers.into_iter()
 				.filter(|ref hdr| {
 					let hdr_bloom = hdr.log_bloom();
 					bit_combos.iter().find(|&bloom| hdr_bloom & *bloom == *bloom).is_some()
 				})
-				.map(|hdr| (hdr.number(), request::BlockReceipts(hdr.into())))
-				.map(|(num, req)| self.on_demand.request(ctx, req).expect(NO_INVALID_BACK_REFS).map(move |x| (num, x)))
+				.map(|hdr| (request::Body(hdr.clone().into()), request::BlockReceipts(hdr.into())))
+				.map(|req| self.on_demand.request(ctx, req).expect(NO_INVALID_BACK_REFS))
+				.collect();
+
+			stream::futures_unordered(local_blocks)
+				.collect()
+				.map_err(errors::on_demand_cancel)
+		});
+
+		let local_blocks = match maybe_future {
+			Some(fut) => fut,
+			None => return future::err(errors::network_disabled()).boxed(),
+		};
+
+		// find logs within the blocks which match the filter. insert them into a
+		// BTreeMap to maintain order by number and block index.
+		local_blocks.join(remote_blocks)
+			.map(move |(local, remote)| {
+				let mut matches = BTreeMap::new();
+				for (block, receipts) in local.into_iter().chain(remote) {
+					let (num, hash) = (block.number(), block.hash());
+					let tx_hashes = block.transaction_hashes();
+					let mut block_index = 0;
+					for (tx_index, (receipt, tx_hash)) in receipts.into_iter().zip(tx_hashes).enumerate() {
+						for (tx_log_index, log) in receipt.logs.into_iter().enumerate() {
+							if filter.matches(&log) {
+								matches.insert((num, block_index), LocalizedLogEntry {
+									entry: log,
+									block_hash: hash,
+									block_number: num,
+									transaction_hash: tx_hash,
+									transaction_index: tx_index,
+									log_index: block_index,
+									transaction_log_index: tx_log_index,
+								}.into());
+							}
+							block_index += 1;
+						}
+					}
+				}
+
+				// and then collect them into a vector.
+				matches.into_iter().map(|(_, v)| v).collect()
+			})
+			.boxed()
+	}
+
+	// find the numbers of the blocks in `start..=end` whose log blooms may contain
+	// any of the given blooms, using the bloom bits tries of the CHTs covering them.
+	// fails if a bloom would match every block, or if a CHT in the range has no known
+	// bloom bits trie root, rather than fetching every block or skipping some.
+	fn bloom_bits_candidates(&self, start: u64, end: u64, blooms: &[H2048]) -> BoxFuture<Vec<u64>, Error> {
+		use std::collections::BTreeSet;
+
+		let sections = match (cht::block_to_cht_number(start), cht::block_to_cht_number(end)) {
+			(Some(first), Some(last)) => first..(last + 1),
+			_ => return future::ok(Vec::new()).boxed(),
+		};
+
+		// the bits set in each bloom, and every bit needed to check them.
+		let combos: Vec<Vec<usize>> = blooms.iter()
+			.map(|bloom| (0..cht::BLOOM_BITS).filter(|&bit| cht::bloom_bit(bloom, bit)).collect())
+			.collect();
+		if combos.iter().any(|combo| combo.is_empty()) {
+			return future::err(errors::light_filter_unbounded()).boxed();
+		}
+
+		let mut roots = Vec::new();
+		for section in sections {
+			match self.client.bloom_bits_root(section) {
+				Some(root) => roots.push((section, root)),
+				None => {
+					let first = ::std::cmp::max(start, cht::start_number(section));
+					let last = ::std::cmp::min(end, cht::start_number(section) + cht::SIZE - 1);
+					return future::err(errors::light_logs_unavailable(first, last)).boxed();
+				}
+			}
+		}
+
+		let needed: Vec<usize> = combos.iter()
+			.flat_map(|combo| combo.iter().cloned())
+			.collect::<BTreeSet<_>>()
+			.into_iter()
+			.collect();
+
+		let maybe_future = self.sync.with_context(move |ctx| {
+			let section_futures: Vec<_> = roots.into_iter()
+				.map(|(section, root)| {
+					let reqs: Vec<_> = needed.iter()
+						.map(|&bit| request::BloomBits::new(section, bit, root)
+							.expect("all bits are below `BLOOM_BITS`; qed"))
+						.collect();
+
+					self.on_demand.request(ctx, reqs)
+						.expect(NO_INVALID_BACK_REFS)
+						.map(move |vectors| (section, vectors))
+				})
 				.collect();
 
-			// as the receipts come in, find logs within them which match the filter.
-			// insert them into a BTreeMap to maintain order by number and block index.
-			stream::futures_unordered(receipts_futures)
-				.fold(BTreeMap::new(), move |mut matches, (num, receipts)| {
-					for (block_index, log) in receipts.into_iter().flat_map(|r| r.logs).enumerate() {
-						if filter.matches(&log) {
-							matches.insert((num, block_index), log.into());
+			future::join_all(section_futures)
+				.map(move |sections| {
+					let mut candidates = Vec::new();
+					for (section, vectors) in sections {
+						// `vectors` holds the vector of each needed bit, in order.
+						let vector = |bit| &vectors[needed.binary_search(&bit).expect("all needed bits requested; qed")];
+						let section_start = cht::start_number(section);
+						let nums = (section_start..(section_start + cht::SIZE))
+							.filter(|&num| num >= start && num <= end);
+
+						for num in nums {
+							let idx = (num - section_start) as usize;
+							let matches = combos.iter()
+								.any(|combo| combo.iter().all(|&bit| cht::bit_vector_contains(vector(bit), idx)));
+
+							if matches { candidates.push(num) }
 						}
 					}
-					future::ok(matches)
-				}) // and then collect them into a vector.
-				.map(|matches| matches.into_iter().map(|(_, v)| v).collect())
+
+					candidates
+				})
 				.map_err(errors::on_demand_cancel)
+				.boxed()
 		});
 
 		match maybe_future {
-			Some(fut) => fut.boxed(),
+			Some(fut) => fut,
 			None => future::err(errors::network_disabled()).boxed(),
 		}
 	}
@@ -343,7 +675,7 @@ fn execute_tx(gas_known: bool, params: ExecuteParams) -> BoxFuture<ExecutionResu
 					Ok(executed) => {
 						// TODO: how to distinguish between actual OOG and
 						// exception?
-						if executed.exception.is_some() {
+						if executed.exception.is_some() && executed.exception != Some(::vm::Error::Reverted) {
 							let old_gas = params.tx.gas;
 							params.tx.gas = params.tx.gas * 2.into();
 							if params.tx.gas > params.hdr.gas_limit() {
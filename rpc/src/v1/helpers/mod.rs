@@ -21,6 +21,7 @@ pub mod accounts;
 pub mod block_import;
 pub mod dapps;
 pub mod dispatch;
+pub mod eip712;
 pub mod fake_sign;
 pub mod light_fetch;
 pub mod oneshot;
@@ -0,0 +1,63 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+//! Debug api implementation.
+
+use std::sync::Arc;
+
+use ethcore::client::{BlockChainClient, CallAnalytics, TransactionId};
+
+use jsonrpc_core::Error;
+use jsonrpc_macros::Trailing;
+use v1::traits::Debug;
+use v1::helpers::errors;
+use v1::types::{H256, DebugTrace, DebugTraceResult, DebugTraceOptions};
+
+/// Debug api implementation.
+pub struct DebugClient<C> {
+	client: Arc<C>,
+}
+
+impl<C> DebugClient<C> {
+	/// Creates new Debug client.
+	pub fn new(client: &Arc<C>) -> Self {
+		DebugClient {
+			client: client.clone(),
+		}
+	}
+}
+
+impl<C> Debug for DebugClient<C> where C: BlockChainClient + 'static {
+	fn trace_transaction(&self, transaction_hash: H256, options: Trailing<DebugTraceOptions>) -> Result<DebugTraceResult, Error> {
+		let options = options.unwrap_or_default();
+		let id = TransactionId::Hash(transaction_hash.into());
+
+		let result = match options.tracer.clone() {
+			None => self.client.debug_trace_transaction(id, options.into())
+				.map(|executed| DebugTraceResult::StructLogs(DebugTrace::from(executed))),
+			Some(ref tracer) if tracer == "callTracer" => {
+				let analytics = CallAnalytics { transaction_tracing: true, ..Default::default() };
+				self.client.replay(id, analytics).map(DebugTraceResult::call_tracer)
+			},
+			Some(ref tracer) if tracer == "prestateTracer" => {
+				let analytics = CallAnalytics { pre_state_dumping: true, ..Default::default() };
+				self.client.replay(id, analytics).map(DebugTraceResult::prestate_tracer)
+			},
+			Some(tracer) => return Err(errors::invalid_params("tracer", format!("unknown tracer {}", tracer))),
+		};
+
+		result.map_err(errors::call)
+	}
+}
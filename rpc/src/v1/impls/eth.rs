@@ -30,7 +30,7 @@ use util::Mutex;
 use ethash::SeedHashCompute;
 use ethcore::account_provider::{AccountProvider, DappId};
 use ethcore::block::IsBlock;
-use ethcore::client::{MiningBlockChainClient, BlockId, TransactionId, UncleId};
+use ethcore::client::{MiningBlockChainClient, ProvingBlockChainClient, BlockId, TransactionId, UncleId};
 use ethcore::ethereum::Ethash;
 use ethcore::filter::Filter as EthcoreFilter;
 use ethcore::header::{Header as BlockHeader, BlockNumber as EthBlockNumber};
@@ -39,6 +39,7 @@ use ethcore::miner::{MinerService, ExternalMinerService};
 use ethcore::transaction::SignedTransaction;
 use ethcore::snapshot::SnapshotService;
 use ethsync::{SyncProvider};
+use vm;
 
 use jsonrpc_core::Error;
 use jsonrpc_macros::Trailing;
@@ -50,7 +51,8 @@ use v1::helpers::accounts::unwrap_provider;
 use v1::traits::Eth;
 use v1::types::{
 	RichBlock, Block, BlockTransactions, BlockNumber, Bytes, SyncStatus, SyncInfo,
-	Transaction, CallRequest, Index, Filter, Log, Receipt, Work,
+	Transaction, CallRequest, Index, Filter, Log, Receipt, Work, EthAccount, StorageProof,
+	StateOverride, into_state_override,
 	H64 as RpcH64, H256 as RpcH256, H160 as RpcH160, U256 as RpcU256,
 };
 use v1::metadata::Metadata;
@@ -272,7 +274,7 @@ fn check_known<C>(client: &C, number: BlockNumber) -> Result<(), Error> where C:
 const MAX_QUEUE_SIZE_TO_MINE_ON: usize = 4;	// because uncles go back 6.
 
 impl<C, SN: ?Sized, S: ?Sized, M, EM> Eth for EthClient<C, SN, S, M, EM> where
-	C: MiningBlockChainClient + 'static,
+	C: MiningBlockChainClient + ProvingBlockChainClient + 'static,
 	SN: SnapshotService + 'static,
 	S: SyncProvider + 'static,
 	M: MinerService + 'static,
@@ -458,6 +460,44 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM> Eth for EthClient<C, SN, S, M, EM> where
 		future::done(res).boxed()
 	}
 
+	fn proof(&self, address: RpcH160, keys: Vec<RpcU256>, num: Trailing<BlockNumber>) -> BoxFuture<EthAccount, Error> {
+		let address: Address = RpcH160::into(address);
+
+		let id = num.unwrap_or_default();
+		try_bf!(check_known(&*self.client, id.clone()));
+
+		let id: BlockId = id.into();
+		let address_hash = address.sha3();
+		let (account_proof, account) = match self.client.prove_account(address_hash, id) {
+			Some(proof) => proof,
+			None => return future::err(errors::state_pruned()).boxed(),
+		};
+
+		let storage_proof = keys.into_iter().map(|key| {
+			let key: U256 = key.into();
+			self.client.prove_storage(address_hash, H256::from(key).sha3(), id).map(|(proof, value)| StorageProof {
+				key: key.into(),
+				value: U256::from(value).into(),
+				proof: proof.into_iter().map(Bytes::new).collect(),
+			})
+		}).collect::<Option<Vec<_>>>();
+
+		let res = match storage_proof {
+			Some(storage_proof) => Ok(EthAccount {
+				address: address.into(),
+				balance: account.balance.into(),
+				nonce: account.nonce.into(),
+				code_hash: account.code_hash.into(),
+				storage_hash: account.storage_root.into(),
+				account_proof: account_proof.into_iter().map(Bytes::new).collect(),
+				storage_proof: storage_proof,
+			}),
+			None => Err(errors::state_pruned()),
+		};
+
+		future::done(res).boxed()
+	}
+
 	fn block_by_hash(&self, hash: RpcH256, include_txs: bool) -> BoxFuture<Option<RichBlock>, Error> {
 		future::done(self.block(BlockId::Hash(hash.into()), include_txs)).boxed()
 	}
@@ -466,38 +506,53 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM> Eth for EthClient<C, SN, S, M, EM> where
 		future::done(self.block(num.into(), include_txs)).boxed()
 	}
 
-	fn transaction_by_hash(&self, hash: RpcH256) -> Result<Option<Transaction>, Error> {
+	fn transaction_by_hash(&self, hash: RpcH256) -> BoxFuture<Option<Transaction>, Error> {
 		let hash: H256 = hash.into();
 		let block_number = self.client.chain_info().best_block_number;
-		Ok(self.transaction(TransactionId::Hash(hash))?.or_else(|| self.miner.transaction(block_number, &hash).map(|t| Transaction::from_pending(t, block_number, self.eip86_transition))))
+		let tx = try_bf!(self.transaction(TransactionId::Hash(hash))).or_else(|| {
+			self.miner.transaction(block_number, &hash)
+				.map(|t| Transaction::from_pending(t, block_number, self.eip86_transition))
+		});
+
+		future::ok(tx).boxed()
 	}
 
-	fn transaction_by_block_hash_and_index(&self, hash: RpcH256, index: Index) -> Result<Option<Transaction>, Error> {
-		self.transaction(TransactionId::Location(BlockId::Hash(hash.into()), index.value()))
+	fn transaction_by_block_hash_and_index(&self, hash: RpcH256, index: Index) -> BoxFuture<Option<Transaction>, Error> {
+		future::done(
+			self.transaction(TransactionId::Location(BlockId::Hash(hash.into()), index.value()))
+		).boxed()
 	}
 
-	fn transaction_by_block_number_and_index(&self, num: BlockNumber, index: Index) -> Result<Option<Transaction>, Error> {
-		self.transaction(TransactionId::Location(num.into(), index.value()))
+	fn transaction_by_block_number_and_index(&self, num: BlockNumber, index: Index) -> BoxFuture<Option<Transaction>, Error> {
+		future::done(
+			self.transaction(TransactionId::Location(num.into(), index.value()))
+		).boxed()
 	}
 
-	fn transaction_receipt(&self, hash: RpcH256) -> Result<Option<Receipt>, Error> {
+	fn transaction_receipt(&self, hash: RpcH256) -> BoxFuture<Option<Receipt>, Error> {
 		let best_block = self.client.chain_info().best_block_number;
 		let hash: H256 = hash.into();
 		match (self.miner.pending_receipt(best_block, &hash), self.options.allow_pending_receipt_query) {
-			(Some(receipt), true) => Ok(Some(receipt.into())),
+			(Some(receipt), true) => future::ok(Some(receipt.into())).boxed(),
 			_ => {
 				let receipt = self.client.transaction_receipt(TransactionId::Hash(hash));
-				Ok(receipt.map(Into::into))
+				future::ok(receipt.map(Into::into)).boxed()
 			}
 		}
 	}
 
-	fn uncle_by_block_hash_and_index(&self, hash: RpcH256, index: Index) -> Result<Option<RichBlock>, Error> {
-		self.uncle(UncleId { block: BlockId::Hash(hash.into()), position: index.value() })
+	fn uncle_by_block_hash_and_index(&self, hash: RpcH256, index: Index) -> BoxFuture<Option<RichBlock>, Error> {
+		future::done(self.uncle(UncleId {
+			block: BlockId::Hash(hash.into()),
+			position: index.value(),
+		})).boxed()
 	}
 
-	fn uncle_by_block_number_and_index(&self, num: BlockNumber, index: Index) -> Result<Option<RichBlock>, Error> {
-		self.uncle(UncleId { block: num.into(), position: index.value() })
+	fn uncle_by_block_number_and_index(&self, num: BlockNumber, index: Index) -> BoxFuture<Option<RichBlock>, Error> {
+		future::done(self.uncle(UncleId {
+			block: num.into(),
+			position: index.value(),
+		})).boxed()
 	}
 
 	fn compilers(&self) -> Result<Vec<String>, Error> {
@@ -613,28 +668,41 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM> Eth for EthClient<C, SN, S, M, EM> where
 	}
 
 	fn call(&self, meta: Self::Metadata, request: CallRequest, num: Trailing<BlockNumber>) -> BoxFuture<Bytes, Error> {
+		self.call_with_overrides(meta, request, Default::default(), num)
+	}
+
+	fn call_with_overrides(&self, meta: Self::Metadata, request: CallRequest, state_override: StateOverride, num: Trailing<BlockNumber>) -> BoxFuture<Bytes, Error> {
 		let request = CallRequest::into(request);
 		let signed = match fake_sign::sign_call(&self.client, &self.miner, request, meta.is_dapp()) {
 			Ok(signed) => signed,
 			Err(e) => return future::err(e).boxed(),
 		};
+		let state_override = try_bf!(into_state_override(state_override));
 
 		let num = num.unwrap_or_default();
-		let result = self.client.call(&signed, Default::default(), num.into());
+		let result = self.client.call(&signed, Default::default(), num.into(), &state_override);
 
 		future::done(result
-			.map(|b| b.output.into())
 			.map_err(errors::call)
+			.and_then(|executed| match executed.exception {
+				Some(vm::Error::Reverted) => Err(errors::reverted(&executed.output)),
+				_ => Ok(executed.output.into()),
+			})
 		).boxed()
 	}
 
 	fn estimate_gas(&self, meta: Self::Metadata, request: CallRequest, num: Trailing<BlockNumber>) -> BoxFuture<RpcU256, Error> {
+		self.estimate_gas_with_overrides(meta, request, Default::default(), num)
+	}
+
+	fn estimate_gas_with_overrides(&self, meta: Self::Metadata, request: CallRequest, state_override: StateOverride, num: Trailing<BlockNumber>) -> BoxFuture<RpcU256, Error> {
 		let request = CallRequest::into(request);
 		let signed = match fake_sign::sign_call(&self.client, &self.miner, request, meta.is_dapp()) {
 			Ok(signed) => signed,
 			Err(e) => return future::err(e).boxed(),
 		};
-		future::done(self.client.estimate_gas(&signed, num.unwrap_or_default().into())
+		let state_override = try_bf!(into_state_override(state_override));
+		future::done(self.client.estimate_gas(&signed, num.unwrap_or_default().into(), &state_override)
 			.map(Into::into)
 			.map_err(errors::call)
 		).boxed()
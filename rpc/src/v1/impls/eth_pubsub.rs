@@ -160,6 +160,13 @@ impl<C> ChainNotificationHandler<C> {
 	}
 }
 
+/// Marks a log as removed from the canonical chain.
+fn removed_log(mut log: Log) -> Log {
+	log.log_type = "removed".into();
+	log.removed = true;
+	log
+}
+
 /// A light client wrapper struct.
 pub trait LightClient: Send + Sync {
 	/// Get a recent block header.
@@ -183,6 +190,7 @@ impl<C: LightClient> LightChainNotify for ChainNotificationHandler<C> {
 	fn new_headers(
 		&self,
 		enacted: &[H256],
+		retracted: &[H256],
 	) {
 		let headers = enacted
 			.iter()
@@ -191,7 +199,14 @@ impl<C: LightClient> LightChainNotify for ChainNotificationHandler<C> {
 			.collect::<Vec<_>>();
 
 		self.notify_heads(&headers);
-		self.notify_logs(&enacted, |filter| self.client.logs(filter))
+
+		// Retracted logs
+		self.notify_logs(retracted, |filter| {
+			self.client.logs(filter).map(|logs| logs.into_iter().map(removed_log).collect()).boxed()
+		});
+
+		// Enacted logs
+		self.notify_logs(enacted, |filter| self.client.logs(filter))
 	}
 }
 
@@ -227,10 +242,7 @@ impl<C: BlockChainClient> ChainNotify for ChainNotificationHandler<C> {
 
 		// Retracted logs
 		self.notify_logs(&retracted, |filter| {
-			future::ok(self.client.logs(filter).into_iter().map(Into::into).map(|mut log: Log| {
-				log.log_type = "removed".into();
-				log
-			}).collect()).boxed()
+			future::ok(self.client.logs(filter).into_iter().map(Into::into).map(removed_log).collect()).boxed()
 		});
 	}
 }
@@ -0,0 +1,32 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+//! Debug api implementation.
+
+use jsonrpc_core::Error;
+use jsonrpc_macros::Trailing;
+use v1::traits::Debug;
+use v1::helpers::errors;
+use v1::types::{H256, DebugTraceResult, DebugTraceOptions};
+
+/// Debug api implementation.
+// TODO: requires proved remote execution of transactions.
+pub struct DebugClient;
+
+impl Debug for DebugClient {
+	fn trace_transaction(&self, _transaction_hash: H256, _options: Trailing<DebugTraceOptions>) -> Result<DebugTraceResult, Error> {
+		Err(errors::light_unimplemented(None))
+	}
+}
@@ -37,9 +37,10 @@ use ethcore::ids::BlockId;
 use ethcore::filter::Filter as EthcoreFilter;
 use ethcore::transaction::{Action, SignedTransaction, Transaction as EthTransaction};
 use ethsync::LightSync;
+use vm;
 use rlp::UntrustedRlp;
 use util::sha3::{SHA3_NULL_RLP, SHA3_EMPTY_LIST_RLP};
-use util::{RwLock, Mutex, U256};
+use util::{RwLock, Mutex, H256, U256};
 
 use futures::{future, Future, BoxFuture, IntoFuture};
 use futures::sync::oneshot;
@@ -48,11 +49,11 @@ use v1::impls::eth_filter::Filterable;
 use v1::helpers::{CallRequest as CRequest, errors, limit_logs, dispatch};
 use v1::helpers::{PollFilter, PollManager};
 use v1::helpers::block_import::is_major_importing;
-use v1::helpers::light_fetch::LightFetch;
+use v1::helpers::light_fetch::{self, LightFetch};
 use v1::traits::Eth;
 use v1::types::{
 	RichBlock, Block, BlockTransactions, BlockNumber, Bytes, SyncStatus, SyncInfo,
-	Transaction, CallRequest, Index, Filter, Log, Receipt, Work,
+	Transaction, CallRequest, Index, Filter, Log, Receipt, Work, EthAccount, StateOverride,
 	H64 as RpcH64, H256 as RpcH256, H160 as RpcH160, U256 as RpcU256,
 };
 use v1::metadata::Metadata;
@@ -206,6 +207,47 @@ impl EthClient {
 			}
 		}).boxed()
 	}
+
+	// get an uncle by its position within a block. Fails on unknown block.
+	fn uncle(&self, id: BlockId, index: usize) -> BoxFuture<Option<RichBlock>, Error> {
+		let (client, engine) = (self.client.clone(), self.client.engine().clone());
+
+		self.fetcher().block(id).map(move |block| {
+			let uncle = match block.view().uncle_at(index) {
+				Some(uncle) => uncle,
+				None => return None,
+			};
+
+			let extra_info = engine.extra_info(&uncle);
+			let parent_score = client.score(BlockId::Hash(uncle.parent_hash().clone()));
+
+			Some(RichBlock {
+				inner: Block {
+					hash: Some(uncle.hash().into()),
+					size: None,
+					parent_hash: uncle.parent_hash().clone().into(),
+					uncles_hash: uncle.uncles_hash().clone().into(),
+					author: uncle.author().clone().into(),
+					miner: uncle.author().clone().into(),
+					state_root: uncle.state_root().clone().into(),
+					transactions_root: uncle.transactions_root().clone().into(),
+					number: Some(uncle.number().into()),
+					gas_used: uncle.gas_used().clone().into(),
+					gas_limit: uncle.gas_limit().clone().into(),
+					logs_bloom: uncle.log_bloom().clone().into(),
+					timestamp: uncle.timestamp().into(),
+					difficulty: uncle.difficulty().clone().into(),
+					total_difficulty: parent_score.map(|score| (score + *uncle.difficulty()).into()),
+					receipts_root: uncle.receipts_root().clone().into(),
+					extra_data: uncle.extra_data().clone().into(),
+					seal_fields: uncle.seal().into_iter().cloned().map(Into::into).collect(),
+					uncles: vec![],
+					transactions: BlockTransactions::Hashes(vec![]),
+				},
+				extra_info: extra_info,
+			})
+		}).boxed()
+	}
 }
 
 impl Eth for EthClient {
@@ -274,8 +316,10 @@ impl Eth for EthClient {
 			.map(|acc| acc.map_or(0.into(), |a| a.balance).into()).boxed()
 	}
 
-	fn storage_at(&self, _address: RpcH160, _key: RpcU256, _num: Trailing<BlockNumber>) -> BoxFuture<RpcH256, Error> {
-		future::err(errors::unimplemented(None)).boxed()
+	fn storage_at(&self, address: RpcH160, key: RpcU256, num: Trailing<BlockNumber>) -> BoxFuture<RpcH256, Error> {
+		let key: U256 = key.into();
+		self.fetcher().storage_at(address.into(), H256::from(key), num.unwrap_or_default().into())
+			.map(Into::into).boxed()
 	}
 
 	fn block_by_hash(&self, hash: RpcH256, include_txs: bool) -> BoxFuture<Option<RichBlock>, Error> {
@@ -359,6 +403,10 @@ impl Eth for EthClient {
 		future::err(errors::unimplemented(None)).boxed()
 	}
 
+	fn proof(&self, _address: RpcH160, _keys: Vec<RpcU256>, _num: Trailing<BlockNumber>) -> BoxFuture<EthAccount, Error> {
+		future::err(errors::light_unimplemented(None)).boxed()
+	}
+
 	fn send_raw_transaction(&self, raw: Bytes) -> Result<RpcH256, Error> {
 		let best_header = self.client.best_block_header().decode();
 
@@ -386,12 +434,17 @@ impl Eth for EthClient {
 	fn call(&self, _meta: Self::Metadata, req: CallRequest, num: Trailing<BlockNumber>) -> BoxFuture<Bytes, Error> {
 		self.fetcher().proved_execution(req, num).and_then(|res| {
 			match res {
+				Ok(ref exec) if exec.exception == Some(vm::Error::Reverted) => Err(errors::reverted(&exec.output)),
 				Ok(exec) => Ok(exec.output.into()),
 				Err(e) => Err(errors::execution(e)),
 			}
 		}).boxed()
 	}
 
+	fn call_with_overrides(&self, _meta: Self::Metadata, _req: CallRequest, _state_override: StateOverride, _num: Trailing<BlockNumber>) -> BoxFuture<Bytes, Error> {
+		future::err(errors::light_unimplemented(Some("State overrides are not supported by the light client.".into()))).boxed()
+	}
+
 	fn estimate_gas(&self, _meta: Self::Metadata, req: CallRequest, num: Trailing<BlockNumber>) -> BoxFuture<RpcU256, Error> {
 		// TODO: binary chop for more accurate estimates.
 		self.fetcher().proved_execution(req, num).and_then(|res| {
@@ -402,28 +455,48 @@ impl Eth for EthClient {
 		}).boxed()
 	}
 
-	fn transaction_by_hash(&self, hash: RpcH256) -> Result<Option<Transaction>, Error> {
-		Err(errors::unimplemented(None))
+	fn estimate_gas_with_overrides(&self, _meta: Self::Metadata, _req: CallRequest, _state_override: StateOverride, _num: Trailing<BlockNumber>) -> BoxFuture<RpcU256, Error> {
+		future::err(errors::light_unimplemented(Some("State overrides are not supported by the light client.".into()))).boxed()
 	}
 
-	fn transaction_by_block_hash_and_index(&self, hash: RpcH256, idx: Index) -> Result<Option<Transaction>, Error> {
-		Err(errors::unimplemented(None))
+	fn transaction_by_hash(&self, hash: RpcH256) -> BoxFuture<Option<Transaction>, Error> {
+		let hash: H256 = hash.into();
+		let eip86_transition = self.client.eip86_transition();
+
+		if let Some(tx) = self.transaction_queue.read().transaction(&hash) {
+			let best_block = self.client.chain_info().best_block_number;
+			return future::ok(Some(Transaction::from_signed(tx, best_block, eip86_transition))).boxed();
+		}
+
+		self.fetcher().transaction_by_hash(hash, eip86_transition)
 	}
 
-	fn transaction_by_block_number_and_index(&self, num: BlockNumber, idx: Index) -> Result<Option<Transaction>, Error> {
-		Err(errors::unimplemented(None))
+	fn transaction_by_block_hash_and_index(&self, hash: RpcH256, idx: Index) -> BoxFuture<Option<Transaction>, Error> {
+		let eip86_transition = self.client.eip86_transition();
+		self.fetcher().block(BlockId::Hash(hash.into()))
+			.map(move |block| light_fetch::extract_transaction_at_index(block, idx.value(), eip86_transition))
+			.boxed()
+	}
+
+	fn transaction_by_block_number_and_index(&self, num: BlockNumber, idx: Index) -> BoxFuture<Option<Transaction>, Error> {
+		let eip86_transition = self.client.eip86_transition();
+		self.fetcher().block(num.into())
+			.map(move |block| light_fetch::extract_transaction_at_index(block, idx.value(), eip86_transition))
+			.boxed()
 	}
 
-	fn transaction_receipt(&self, hash: RpcH256) -> Result<Option<Receipt>, Error> {
-		Err(errors::unimplemented(None))
+	fn transaction_receipt(&self, hash: RpcH256) -> BoxFuture<Option<Receipt>, Error> {
+		self.fetcher().transaction_receipt(hash.into())
+			.map(|receipt| receipt.map(Into::into))
+			.boxed()
 	}
 
-	fn uncle_by_block_hash_and_index(&self, hash: RpcH256, idx: Index) -> Result<Option<RichBlock>, Error> {
-		Err(errors::unimplemented(None))
+	fn uncle_by_block_hash_and_index(&self, hash: RpcH256, idx: Index) -> BoxFuture<Option<RichBlock>, Error> {
+		self.uncle(BlockId::Hash(hash.into()), idx.value())
 	}
 
-	fn uncle_by_block_number_and_index(&self, num: BlockNumber, idx: Index) -> Result<Option<RichBlock>, Error> {
-		Err(errors::unimplemented(None))
+	fn uncle_by_block_number_and_index(&self, num: BlockNumber, idx: Index) -> BoxFuture<Option<RichBlock>, Error> {
+		self.uncle(num.into(), idx.value())
 	}
 
 	fn compilers(&self) -> Result<Vec<String>, Error> {
@@ -19,14 +19,18 @@
 //! This doesn't re-implement all of the RPC APIs, just those which aren't
 //! significantly generic to be reused.
 
+pub mod debug;
 pub mod eth;
 pub mod parity;
 pub mod parity_set;
 pub mod trace;
+pub mod txpool;
 pub mod net;
 
+pub use self::debug::DebugClient;
 pub use self::eth::EthClient;
 pub use self::parity::ParityClient;
 pub use self::parity_set::ParitySetClient;
 pub use self::net::NetClient;
 pub use self::trace::TracesClient;
+pub use self::txpool::TxPoolClient;
@@ -44,7 +44,7 @@ use v1::types::{
 	TransactionStats, LocalTransactionStatus,
 	BlockNumber, ConsensusCapability, VersionInfo,
 	OperationsInfo, DappId, ChainStatus,
-	AccountInfo, HwAccountInfo, Header, RichHeader,
+	AccountInfo, HwAccountInfo, Header, RichHeader, Receipt,
 };
 
 /// Parity implementation for light client.
@@ -397,6 +397,10 @@ impl Parity for ParityClient {
 		future::err(errors::light_unimplemented(None)).boxed()
 	}
 
+	fn transaction_receipt(&self, _hash: H256, _with_reason: Trailing<bool>) -> Result<Option<Receipt>, Error> {
+		Err(errors::light_unimplemented(None))
+	}
+
 	fn node_health(&self) -> BoxFuture<Health, Error> {
 		self.health.health()
 			.map_err(|err| errors::internal("Health API failure.", err))
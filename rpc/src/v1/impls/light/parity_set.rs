@@ -29,7 +29,7 @@ use jsonrpc_core::Error;
 use v1::helpers::dapps::DappsService;
 use v1::helpers::errors;
 use v1::traits::ParitySet;
-use v1::types::{Bytes, H160, H256, U256, ReleaseInfo, Transaction, LocalDapp};
+use v1::types::{Bytes, H160, H256, U256, ReleaseInfo, Transaction, LocalDapp, LightServeParams};
 
 /// Parity-specific rpc interface for operations altering the settings.
 pub struct ParitySetClient<F> {
@@ -120,6 +120,10 @@ impl<F: Fetch> ParitySet for ParitySetClient<F> {
 		Err(errors::light_unimplemented(None))
 	}
 
+	fn set_light_serve_params(&self, _params: LightServeParams) -> Result<bool, Error> {
+		Err(errors::light_unimplemented(None))
+	}
+
 	fn set_spec_name(&self, _spec_name: String) -> Result<bool, Error> {
 		Err(errors::light_unimplemented(None))
 	}
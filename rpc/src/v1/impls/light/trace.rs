@@ -22,7 +22,7 @@ use jsonrpc_macros::Trailing;
 use v1::Metadata;
 use v1::traits::Traces;
 use v1::helpers::errors;
-use v1::types::{TraceFilter, LocalizedTrace, BlockNumber, Index, CallRequest, Bytes, TraceResults, TraceOptions, H256};
+use v1::types::{TraceFilter, LocalizedTrace, BlockNumber, Index, CallRequest, Bytes, TraceResults, TraceResultsWithTransactionHash, TraceOptions, H256, StateOverride};
 
 /// Traces api implementation.
 // TODO: all calling APIs should be possible w. proved remote TX execution.
@@ -51,6 +51,10 @@ impl Traces for TracesClient {
 		future::err(errors::light_unimplemented(None)).boxed()
 	}
 
+	fn call_with_overrides(&self, _meta: Self::Metadata, _request: CallRequest, _flags: TraceOptions, _state_override: StateOverride, _block: Trailing<BlockNumber>) -> BoxFuture<TraceResults, Error> {
+		future::err(errors::light_unimplemented(None)).boxed()
+	}
+
 	fn call_many(&self, _meta: Self::Metadata, _request: Vec<(CallRequest, TraceOptions)>, _block: Trailing<BlockNumber>) -> BoxFuture<Vec<TraceResults>, Error> {
 		future::err(errors::light_unimplemented(None)).boxed()
 	}
@@ -62,4 +66,8 @@ impl Traces for TracesClient {
 	fn replay_transaction(&self, _transaction_hash: H256, _flags: TraceOptions) -> Result<TraceResults, Error> {
 		Err(errors::light_unimplemented(None))
 	}
+
+	fn replay_block_transactions(&self, _block_number: BlockNumber, _flags: TraceOptions) -> Result<Vec<TraceResultsWithTransactionHash>, Error> {
+		Err(errors::light_unimplemented(None))
+	}
 }
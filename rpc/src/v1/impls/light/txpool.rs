@@ -0,0 +1,81 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Transaction pool api implementation for the light client.
+
+use std::sync::Arc;
+
+use light::client::LightChainClient;
+use light::TransactionQueue;
+use util::RwLock;
+
+use jsonrpc_core::Error;
+use v1::traits::TxPool;
+use v1::types::{Transaction, TxPoolContent, TxPoolInspect, TxPoolStatus};
+
+/// Transaction pool api implementation for the light client.
+pub struct TxPoolClient {
+	client: Arc<LightChainClient>,
+	transaction_queue: Arc<RwLock<TransactionQueue>>,
+}
+
+impl TxPoolClient {
+	/// Creates new TxPool client.
+	pub fn new(client: Arc<LightChainClient>, transaction_queue: Arc<RwLock<TransactionQueue>>) -> Self {
+		TxPoolClient {
+			client,
+			transaction_queue,
+		}
+	}
+
+	/// Returns pending and queued transactions.
+	fn transactions(&self) -> (Vec<Transaction>, Vec<Transaction>) {
+		let txq = self.transaction_queue.read();
+		let chain_info = self.client.chain_info();
+		let eip86_transition = self.client.eip86_transition();
+		let convert = |t| Transaction::from_pending(t, chain_info.best_block_number, eip86_transition);
+
+		let pending = txq.ready_transactions(chain_info.best_block_number, chain_info.best_block_timestamp)
+			.into_iter()
+			.map(&convert)
+			.collect();
+		let queued = txq.future_transactions(chain_info.best_block_number, chain_info.best_block_timestamp)
+			.into_iter()
+			.map(&convert)
+			.collect();
+		(pending, queued)
+	}
+}
+
+impl TxPool for TxPoolClient {
+	fn content(&self) -> Result<TxPoolContent, Error> {
+		let (pending, queued) = self.transactions();
+		Ok(TxPoolContent::new(pending, queued))
+	}
+
+	fn inspect(&self) -> Result<TxPoolInspect, Error> {
+		let (pending, queued) = self.transactions();
+		Ok(TxPoolInspect::new(pending, queued))
+	}
+
+	fn status(&self) -> Result<TxPoolStatus, Error> {
+		let (pending, queued) = self.transactions();
+		Ok(TxPoolStatus {
+			pending: pending.len().into(),
+			queued: queued.len().into(),
+		})
+	}
+}
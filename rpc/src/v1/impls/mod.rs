@@ -16,6 +16,7 @@
 
 //! Ethereum rpc interface implementation.
 
+mod debug;
 mod eth;
 mod eth_filter;
 mod eth_pubsub;
@@ -31,13 +32,15 @@ mod signing_unsafe;
 mod rpc;
 mod secretstore;
 mod traces;
+mod txpool;
 mod web3;
 
 pub mod light;
 
+pub use self::debug::DebugClient;
 pub use self::eth::{EthClient, EthClientOptions};
 pub use self::eth_filter::EthFilterClient;
-pub use self::eth_pubsub::EthPubSubClient;
+pub use self::eth_pubsub::{EthPubSubClient, LightClient};
 pub use self::net::NetClient;
 pub use self::parity::ParityClient;
 pub use self::parity_accounts::ParityAccountsClient;
@@ -48,6 +51,7 @@ pub use self::signer::SignerClient;
 pub use self::signing::SigningQueueClient;
 pub use self::signing_unsafe::SigningUnsafeClient;
 pub use self::traces::TracesClient;
+pub use self::txpool::TxPoolClient;
 pub use self::web3::Web3Client;
 pub use self::rpc::RpcClient;
 pub use self::secretstore::SecretStoreClient;
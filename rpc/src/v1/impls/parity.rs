@@ -28,14 +28,16 @@ use ethkey::{Brain, Generator};
 use ethstore::random_phrase;
 use ethsync::{SyncProvider, ManageNetwork};
 use ethcore::account_provider::AccountProvider;
-use ethcore::client::{MiningBlockChainClient};
-use ethcore::ids::BlockId;
+use ethcore::client::{MiningBlockChainClient, Executed};
+use ethcore::error::CallError;
+use ethcore::ids::{BlockId, TransactionId};
 use ethcore::miner::MinerService;
 use ethcore::mode::Mode;
 use ethcore::transaction::SignedTransaction;
 use ethcore_logger::RotatingLogger;
 use node_health::{NodeHealth, Health};
 use updater::{Service as UpdateService};
+use vm;
 
 use jsonrpc_core::Error;
 use jsonrpc_macros::Trailing;
@@ -49,9 +51,18 @@ use v1::types::{
 	TransactionStats, LocalTransactionStatus,
 	BlockNumber, ConsensusCapability, VersionInfo,
 	OperationsInfo, DappId, ChainStatus,
-	AccountInfo, HwAccountInfo, RichHeader
+	AccountInfo, HwAccountInfo, RichHeader, Receipt,
 };
 
+/// Describes why the given execution failed, if it did.
+fn failure_reason(executed: &Executed) -> Option<String> {
+	match executed.exception {
+		Some(vm::Error::Reverted) => Some(errors::revert_reason(&executed.output).unwrap_or_else(|| "Reverted".into())),
+		Some(ref e) => Some(e.to_string()),
+		None => None,
+	}
+}
+
 /// Parity implementation.
 pub struct ParityClient<C, M, U>  {
 	client: Arc<C>,
@@ -425,6 +436,28 @@ impl<C, M, U> Parity for ParityClient<C, M, U> where
 		future::done(result).boxed()
 	}
 
+	fn transaction_receipt(&self, hash: H256, with_reason: Trailing<bool>) -> Result<Option<Receipt>, Error> {
+		let hash = hash.into();
+		let id = TransactionId::Hash(hash);
+		let mut receipt = match self.client.transaction_receipt(id.clone()) {
+			Some(receipt) => Receipt::from(receipt),
+			None => return Ok(None),
+		};
+
+		if with_reason.unwrap_or_default() {
+			receipt.revert_reason = match self.client.replay(id, Default::default()) {
+				Ok(executed) => Some(failure_reason(&executed)),
+				Err(CallError::StatePruned) => {
+					debug!(target: "rpc", "Cannot re-execute transaction {:?} for its revert reason: state is pruned.", hash);
+					Some(None)
+				},
+				Err(e) => return Err(errors::call(e)),
+			};
+		}
+
+		Ok(Some(receipt))
+	}
+
 	fn node_health(&self) -> BoxFuture<Health, Error> {
 		self.health.health()
 			.map_err(|err| errors::internal("Health API failure.", err))
@@ -31,7 +31,7 @@ use jsonrpc_core::Error;
 use v1::helpers::dapps::DappsService;
 use v1::helpers::errors;
 use v1::traits::ParitySet;
-use v1::types::{Bytes, H160, H256, U256, ReleaseInfo, Transaction, LocalDapp};
+use v1::types::{Bytes, H160, H256, U256, ReleaseInfo, Transaction, LocalDapp, LightServeParams};
 
 /// Parity-specific rpc interface for operations altering the settings.
 pub struct ParitySetClient<C, M, U, F = fetch::Client> {
@@ -160,6 +160,13 @@ impl<C, M, U, F> ParitySet for ParitySetClient<C, M, U, F> where
 		Ok(true)
 	}
 
+	fn set_light_serve_params(&self, params: LightServeParams) -> Result<bool, Error> {
+		let mut config = self.net.light_serve_config().ok_or_else(errors::light_serve_disabled)?;
+		params.apply_to(&mut config).map_err(|e| errors::invalid_params("Light serve params", e))?;
+		self.net.set_light_serve_config(config).map_err(|_| errors::light_serve_disabled())?;
+		Ok(true)
+	}
+
 	fn set_spec_name(&self, spec_name: String) -> Result<bool, Error> {
 		self.client.set_spec_name(spec_name);
 		Ok(true)
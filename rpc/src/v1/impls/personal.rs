@@ -19,31 +19,38 @@ use std::sync::Arc;
 
 use ethcore::account_provider::AccountProvider;
 use ethcore::transaction::PendingTransaction;
+use ethkey::{self, public_to_address, Signature};
 
 use util::{Address, U128, ToPretty};
 
 use futures::{future, Future, BoxFuture};
 use jsonrpc_core::Error;
-use v1::helpers::errors;
-use v1::helpers::dispatch::{Dispatcher, SignWith};
+use v1::helpers::{errors, oneshot, ConfirmationPayload, SigningQueue, SignerService};
+use v1::helpers::dispatch::{self, eth_data_hash, Dispatcher, SignWith};
 use v1::helpers::accounts::unwrap_provider;
 use v1::traits::Personal;
-use v1::types::{H160 as RpcH160, H256 as RpcH256, U128 as RpcU128, TransactionRequest};
+use v1::types::{
+	H160 as RpcH160, H256 as RpcH256, H520 as RpcH520, U128 as RpcU128, Bytes as RpcBytes,
+	ConfirmationResponse as RpcConfirmationResponse,
+	TransactionRequest,
+};
 use v1::metadata::Metadata;
 
 /// Account management (personal) rpc implementation.
 pub struct PersonalClient<D: Dispatcher> {
 	accounts: Option<Arc<AccountProvider>>,
 	dispatcher: D,
+	signer: Option<Arc<SignerService>>,
 	allow_perm_unlock: bool,
 }
 
 impl<D: Dispatcher> PersonalClient<D> {
 	/// Creates new PersonalClient
-	pub fn new(store: &Option<Arc<AccountProvider>>, dispatcher: D, allow_perm_unlock: bool) -> Self {
+	pub fn new(store: &Option<Arc<AccountProvider>>, dispatcher: D, signer: Option<Arc<SignerService>>, allow_perm_unlock: bool) -> Self {
 		PersonalClient {
 			accounts: store.clone(),
 			dispatcher: dispatcher,
+			signer: signer,
 			allow_perm_unlock: allow_perm_unlock,
 		}
 	}
@@ -99,6 +106,51 @@ impl<D: Dispatcher + 'static> Personal for PersonalClient<D> {
 		}
 	}
 
+	fn sign(&self, meta: Metadata, data: RpcBytes, account: RpcH160, password: String) -> BoxFuture<RpcH520, Error> {
+		let accounts = try_bf!(self.account_provider());
+		let account: Address = account.into();
+		let payload = ConfirmationPayload::EthSignMessage(account, data.into());
+
+		if !accounts.is_hardware_address(account) {
+			return dispatch::execute(self.dispatcher.clone(), accounts, payload, SignWith::Password(password))
+				.map(|v| v.into_value())
+				.then(|res| match res {
+					Ok(RpcConfirmationResponse::Signature(signature)) => Ok(signature),
+					Err(e) => Err(e),
+					e => Err(errors::internal("Unexpected result.", e)),
+				})
+				.boxed();
+		}
+
+		// Hardware wallets can't be unlocked with a password, the request
+		// has to be confirmed by the Trusted Signer instead.
+		let signer = match self.signer {
+			Some(ref signer) => signer.clone(),
+			None => return future::err(errors::unsupported("Signing via hardware wallets requires Trusted Signer to be enabled.", None)).boxed(),
+		};
+		let promise = try_bf!(signer.add_request(payload, meta.origin).map_err(|_| errors::request_rejected_limit()));
+
+		let (ready, p) = oneshot::oneshot();
+		promise.wait_for_result(move |result| {
+			match result.unwrap_or_else(|| Err(errors::request_rejected())) {
+				Ok(RpcConfirmationResponse::Signature(signature)) => ready.send(Ok(signature)),
+				Err(e) => ready.send(Err(e)),
+				e => ready.send(Err(errors::internal("Unexpected result.", e))),
+			}
+		});
+		p.boxed()
+	}
+
+	fn ec_recover(&self, data: RpcBytes, signature: RpcH520) -> Result<RpcH160, Error> {
+		let signature: ::util::H520 = signature.into();
+		let signature = Signature::from_electrum(&signature);
+		let hash = eth_data_hash(data.into());
+
+		ethkey::recover(&signature, &hash)
+			.map(|public| public_to_address(&public).into())
+			.map_err(errors::encryption)
+	}
+
 	fn send_transaction(&self, meta: Metadata, request: TransactionRequest, password: String) -> BoxFuture<RpcH256, Error> {
 		let dispatcher = self.dispatcher.clone();
 		let accounts = try_bf!(self.account_provider());
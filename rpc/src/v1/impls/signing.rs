@@ -35,6 +35,7 @@ use v1::metadata::Metadata;
 use v1::traits::{EthSigning, ParitySigning};
 use v1::types::{
 	H160 as RpcH160, H256 as RpcH256, U256 as RpcU256, Bytes as RpcBytes, H520 as RpcH520,
+	EIP712 as RpcEIP712,
 	Either as RpcEither,
 	RichRawTransaction as RpcRichRawTransaction,
 	TransactionRequest as RpcTransactionRequest,
@@ -242,6 +243,28 @@ impl<D: Dispatcher + 'static> EthSigning for SigningQueueClient<D> {
 		}).boxed()
 	}
 
+	fn sign_typed_data(&self, meta: Metadata, address: RpcH160, data: RpcEIP712) -> BoxFuture<RpcH520, Error> {
+		let res = self.dispatch(
+			RpcConfirmationPayload::EIP712SignMessage((address.clone(), data).into()),
+			address.into(),
+			meta.origin,
+		);
+
+		let (ready, p) = oneshot::oneshot();
+
+		res.then(move |res| {
+			handle_dispatch(res, move |response| {
+				match response {
+					Ok(RpcConfirmationResponse::Signature(sig)) => ready.send(Ok(sig)),
+					Err(e) => ready.send(Err(e)),
+					e => ready.send(Err(errors::internal("Unexpected result.", e))),
+				}
+			});
+
+			p
+		}).boxed()
+	}
+
 	fn send_transaction(&self, meta: Metadata, request: RpcTransactionRequest) -> BoxFuture<RpcH256, Error> {
 		let res = self.dispatch(
 			RpcConfirmationPayload::SendTransaction(request),
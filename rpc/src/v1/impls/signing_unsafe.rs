@@ -30,6 +30,7 @@ use v1::traits::{EthSigning, ParitySigning};
 use v1::types::{
 	U256 as RpcU256,
 	H160 as RpcH160, H256 as RpcH256, H520 as RpcH520, Bytes as RpcBytes,
+	EIP712 as RpcEIP712,
 	Either as RpcEither,
 	RichRawTransaction as RpcRichRawTransaction,
 	TransactionRequest as RpcTransactionRequest,
@@ -87,6 +88,16 @@ impl<D: Dispatcher + 'static> EthSigning for SigningUnsafeClient<D>
 			.boxed()
 	}
 
+	fn sign_typed_data(&self, _: Metadata, address: RpcH160, data: RpcEIP712) -> BoxFuture<RpcH520, Error> {
+		self.handle(RpcConfirmationPayload::EIP712SignMessage((address.clone(), data).into()), address.into())
+			.then(|res| match res {
+				Ok(RpcConfirmationResponse::Signature(signature)) => Ok(signature),
+				Err(e) => Err(e),
+				e => Err(errors::internal("Unexpected result", e)),
+			})
+			.boxed()
+	}
+
 	fn send_transaction(&self, meta: Metadata, request: RpcTransactionRequest) -> BoxFuture<RpcH256, Error> {
 		self.handle(RpcConfirmationPayload::SendTransaction(request), meta.dapp_id().into())
 			.then(|res| match res {
@@ -16,9 +16,10 @@
 
 //! Traces api implementation.
 
+use std::cmp;
 use std::sync::Arc;
 
-use ethcore::client::{MiningBlockChainClient, CallAnalytics, TransactionId, TraceId};
+use ethcore::client::{MiningBlockChainClient, CallAnalytics, BlockId, TransactionId, TraceId, TraceFilter as ClientTraceFilter};
 use ethcore::miner::MinerService;
 use ethcore::transaction::SignedTransaction;
 use rlp::UntrustedRlp;
@@ -29,13 +30,14 @@ use jsonrpc_macros::Trailing;
 use v1::Metadata;
 use v1::traits::Traces;
 use v1::helpers::{errors, fake_sign};
-use v1::types::{TraceFilter, LocalizedTrace, BlockNumber, Index, CallRequest, Bytes, TraceResults, TraceOptions, H256};
+use v1::types::{TraceFilter, LocalizedTrace, BlockNumber, Index, CallRequest, Bytes, TraceResults, TraceResultsWithTransactionHash, TraceOptions, H256, StateOverride, into_state_override};
 
 fn to_call_analytics(flags: TraceOptions) -> CallAnalytics {
 	CallAnalytics {
 		transaction_tracing: flags.contains(&("trace".to_owned())),
 		vm_tracing: flags.contains(&("vmTrace".to_owned())),
 		state_diffing: flags.contains(&("stateDiff".to_owned())),
+		pre_state_dumping: false,
 	}
 }
 
@@ -55,16 +57,39 @@ impl<C, M> TracesClient<C, M> {
 	}
 }
 
+impl<C, M> TracesClient<C, M> where C: MiningBlockChainClient {
+	/// Fails if blocks from `from` onwards are not all traced yet because the trace backfill is still running.
+	fn ensure_traced(&self, from: BlockId) -> Result<(), Error> {
+		let cursor = match self.client.trace_backfill_cursor() {
+			Some(cursor) => cursor,
+			None => return Ok(()),
+		};
+		let number = match from {
+			BlockId::Number(number) => Some(number),
+			other => self.client.block_number(other),
+		};
+		match number {
+			// the genesis block has no traces
+			Some(number) if cmp::max(number, 1) < cursor => Err(errors::traces_not_backfilled(cursor)),
+			_ => Ok(()),
+		}
+	}
+}
+
 impl<C, M> Traces for TracesClient<C, M> where C: MiningBlockChainClient + 'static, M: MinerService + 'static {
 	type Metadata = Metadata;
 
 	fn filter(&self, filter: TraceFilter) -> Result<Option<Vec<LocalizedTrace>>, Error> {
-		Ok(self.client.filter_traces(filter.into())
+		let filter: ClientTraceFilter = filter.into();
+		self.ensure_traced(filter.range.start)?;
+		Ok(self.client.filter_traces(filter)
 			.map(|traces| traces.into_iter().map(LocalizedTrace::from).collect()))
 	}
 
 	fn block_traces(&self, block_number: BlockNumber) -> Result<Option<Vec<LocalizedTrace>>, Error> {
-		Ok(self.client.block_traces(block_number.into())
+		let block: BlockId = block_number.into();
+		self.ensure_traced(block)?;
+		Ok(self.client.block_traces(block)
 			.map(|traces| traces.into_iter().map(LocalizedTrace::from).collect()))
 	}
 
@@ -84,12 +109,17 @@ impl<C, M> Traces for TracesClient<C, M> where C: MiningBlockChainClient + 'stat
 	}
 
 	fn call(&self, meta: Self::Metadata, request: CallRequest, flags: TraceOptions, block: Trailing<BlockNumber>) -> BoxFuture<TraceResults, Error> {
+		self.call_with_overrides(meta, request, flags, Default::default(), block)
+	}
+
+	fn call_with_overrides(&self, meta: Self::Metadata, request: CallRequest, flags: TraceOptions, state_override: StateOverride, block: Trailing<BlockNumber>) -> BoxFuture<TraceResults, Error> {
 		let block = block.unwrap_or_default();
 
 		let request = CallRequest::into(request);
 		let signed = try_bf!(fake_sign::sign_call(&self.client, &self.miner, request, meta.is_dapp()));
+		let state_override = try_bf!(into_state_override(state_override));
 
-		let res = self.client.call(&signed, to_call_analytics(flags), block.into())
+		let res = self.client.call(&signed, to_call_analytics(flags), block.into(), &state_override)
 			.map(TraceResults::from)
 			.map_err(errors::call);
 
@@ -120,7 +150,7 @@ impl<C, M> Traces for TracesClient<C, M> where C: MiningBlockChainClient + 'stat
 		let tx = UntrustedRlp::new(&raw_transaction.into_vec()).as_val().map_err(|e| errors::invalid_params("Transaction is not valid RLP", e))?;
 		let signed = SignedTransaction::new(tx).map_err(errors::transaction)?;
 
-		self.client.call(&signed, to_call_analytics(flags), block.into())
+		self.client.call(&signed, to_call_analytics(flags), block.into(), &Default::default())
 			.map(TraceResults::from)
 			.map_err(errors::call)
 	}
@@ -130,4 +160,10 @@ impl<C, M> Traces for TracesClient<C, M> where C: MiningBlockChainClient + 'stat
 			.map(TraceResults::from)
 			.map_err(errors::call)
 	}
+
+	fn replay_block_transactions(&self, block_number: BlockNumber, flags: TraceOptions) -> Result<Vec<TraceResultsWithTransactionHash>, Error> {
+		self.client.replay_block_transactions(block_number.into(), to_call_analytics(flags))
+			.map(|results| results.into_iter().map(TraceResultsWithTransactionHash::from).collect())
+			.map_err(errors::call)
+	}
 }
@@ -0,0 +1,100 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Transaction pool api implementation.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use ethcore::client::MiningBlockChainClient;
+use ethcore::miner::MinerService;
+use ethcore::transaction::Condition;
+
+use jsonrpc_core::Error;
+use v1::traits::TxPool;
+use v1::types::{Transaction, TxPoolContent, TxPoolInspect, TxPoolStatus};
+
+/// Transaction pool api implementation.
+pub struct TxPoolClient<C, M> {
+	client: Arc<C>,
+	miner: Arc<M>,
+}
+
+impl<C, M> TxPoolClient<C, M> {
+	/// Creates new TxPool client.
+	pub fn new(client: &Arc<C>, miner: &Arc<M>) -> Self {
+		TxPoolClient {
+			client: client.clone(),
+			miner: miner.clone(),
+		}
+	}
+}
+
+impl<C, M> TxPoolClient<C, M> where C: MiningBlockChainClient, M: MinerService {
+	/// Returns pending and queued transactions.
+	///
+	/// Transactions with a condition not yet met at the best block are queued together
+	/// with all later transactions of their sender, the same way the light client reports them.
+	fn transactions(&self) -> (Vec<Transaction>, Vec<Transaction>) {
+		let chain_info = self.client.chain_info();
+		let (best_block, best_timestamp) = (chain_info.best_block_number, chain_info.best_block_timestamp);
+		let eip86_transition = self.client.eip86_transition();
+		let convert = |t| Transaction::from_pending(t, best_block, eip86_transition);
+
+		let mut current = self.miner.pending_transactions();
+		current.sort_by_key(|t| (t.sender(), t.nonce));
+
+		let mut delayed = HashSet::new();
+		let (ready, waiting): (Vec<_>, Vec<_>) = current.into_iter().partition(|t| {
+			let ready = !delayed.contains(&t.sender()) && match t.condition {
+				Some(Condition::Number(number)) => number <= best_block,
+				Some(Condition::Timestamp(timestamp)) => timestamp <= best_timestamp,
+				None => true,
+			};
+			if !ready {
+				delayed.insert(t.sender());
+			}
+			ready
+		});
+
+		let pending = ready.into_iter().map(&convert).collect();
+		let queued = waiting.into_iter()
+			.chain(self.miner.future_transactions())
+			.map(&convert)
+			.collect();
+		(pending, queued)
+	}
+}
+
+impl<C, M> TxPool for TxPoolClient<C, M> where C: MiningBlockChainClient + 'static, M: MinerService + 'static {
+	fn content(&self) -> Result<TxPoolContent, Error> {
+		let (pending, queued) = self.transactions();
+		Ok(TxPoolContent::new(pending, queued))
+	}
+
+	fn inspect(&self) -> Result<TxPoolInspect, Error> {
+		let (pending, queued) = self.transactions();
+		Ok(TxPoolInspect::new(pending, queued))
+	}
+
+	fn status(&self) -> Result<TxPoolStatus, Error> {
+		let (pending, queued) = self.transactions();
+		Ok(TxPoolStatus {
+			pending: pending.len().into(),
+			queued: queued.len().into(),
+		})
+	}
+}
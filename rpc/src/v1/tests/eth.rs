@@ -196,6 +196,37 @@ fn eth_get_balance() {
 	assert_eq!(tester.handler.handle_request_sync(req_new_acc).unwrap(), res_new_acc);
 }
 
+#[test]
+fn eth_get_proof() {
+	use serde_json::{self, Value};
+	use util::{MemoryDB, HashDB, TrieDB, Trie};
+	use v1::types::Bytes;
+
+	let chain = extract_chain!("BlockchainTests/bcWalletTest", "wallet2outOf3txs");
+	let tester = EthTester::from_chain(&chain);
+	let req = r#"{
+		"jsonrpc": "2.0",
+		"method": "eth_getProof",
+		"params": ["0xaaaf5374fce5edbc8e2a8697c15331677e6ebaaa", ["0x0"], "latest"],
+		"id": 1
+	}"#;
+	let res: Value = serde_json::from_str(&tester.handler.handle_request_sync(req).unwrap()).unwrap();
+	let result = &res["result"];
+	assert_eq!(result["balance"], "0x9");
+	assert_eq!(result["storageProof"][0]["key"], "0x0");
+
+	// the account proof checks out against the latest state root
+	let state_root = tester.client.block_header(BlockId::Latest).unwrap().state_root();
+	let account_proof: Vec<Bytes> = serde_json::from_value(result["accountProof"].clone()).unwrap();
+	let mut db = MemoryDB::new();
+	for node in account_proof {
+		db.insert(&node.into_vec());
+	}
+	let address: Address = "aaaf5374fce5edbc8e2a8697c15331677e6ebaaa".into();
+	let trie = TrieDB::new(&db, &state_root).unwrap();
+	assert!(trie.get(&address.sha3()).unwrap().is_some());
+}
+
 #[test]
 fn eth_block_number() {
 	let chain = extract_chain!("BlockchainTests/bcRPC_API_Test");
@@ -23,7 +23,7 @@ use ethcore::error::Error;
 use ethcore::client::MiningBlockChainClient;
 use ethcore::block::ClosedBlock;
 use ethcore::header::BlockNumber;
-use ethcore::transaction::{UnverifiedTransaction, SignedTransaction, PendingTransaction};
+use ethcore::transaction::{UnverifiedTransaction, SignedTransaction, PendingTransaction, Condition};
 use ethcore::receipt::{Receipt, RichReceipt};
 use ethcore::miner::{MinerService, MinerStatus, TransactionImportResult, LocalTransactionStatus};
 use ethcore::account_provider::SignError as AccountError;
@@ -36,6 +36,8 @@ pub struct TestMinerService {
 	pub latest_closed_block: Mutex<Option<ClosedBlock>>,
 	/// Pre-existed pending transactions
 	pub pending_transactions: Mutex<HashMap<H256, SignedTransaction>>,
+	/// Conditions of pre-existed pending transactions
+	pub pending_conditions: Mutex<HashMap<H256, Condition>>,
 	/// Pre-existed local transactions
 	pub local_transactions: Mutex<BTreeMap<H256, LocalTransactionStatus>>,
 	/// Pre-existed pending receipts
@@ -59,6 +61,7 @@ impl Default for TestMinerService {
 			imported_transactions: Mutex::new(Vec::new()),
 			latest_closed_block: Mutex::new(None),
 			pending_transactions: Mutex::new(HashMap::new()),
+			pending_conditions: Mutex::new(HashMap::new()),
 			local_transactions: Mutex::new(BTreeMap::new()),
 			pending_receipts: Mutex::new(BTreeMap::new()),
 			last_nonces: RwLock::new(HashMap::new()),
@@ -231,7 +234,13 @@ impl MinerService for TestMinerService {
 	}
 
 	fn pending_transactions(&self) -> Vec<PendingTransaction> {
-		self.pending_transactions.lock().values().cloned().map(Into::into).collect()
+		let conditions = self.pending_conditions.lock();
+		self.pending_transactions.lock().values().cloned()
+			.map(|tx| {
+				let condition = conditions.get(&tx.hash()).cloned();
+				PendingTransaction::new(tx, condition)
+			})
+			.collect()
 	}
 
 	fn local_transactions(&self) -> BTreeMap<H256, LocalTransactionStatus> {
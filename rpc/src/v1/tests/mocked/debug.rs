@@ -0,0 +1,146 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+use std::sync::Arc;
+
+use ethcore::executed::{Executed, CallError};
+use ethcore::client::TestBlockChainClient;
+use ethcore::trace::FlatTrace;
+use ethcore::trace::trace::{Action, Res, Call, CallResult};
+use vm::{self, CallType};
+
+use jsonrpc_core::IoHandler;
+use v1::{Debug, DebugClient};
+
+fn io(client: &Arc<TestBlockChainClient>) -> IoHandler {
+	let mut io = IoHandler::default();
+	io.extend_with(DebugClient::new(client).to_delegate());
+	io
+}
+
+#[test]
+fn rpc_debug_trace_transaction() {
+	let client = Arc::new(TestBlockChainClient::new());
+	*client.execution_result.write() = Some(Ok(executed(vec![])));
+	let io = io(&client);
+
+	let request = r#"{"jsonrpc":"2.0","method":"debug_traceTransaction","params":["0x0000000000000000000000000000000000000000000000000000000000000005", {"disableStack":true}],"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"failed":false,"gas":10000,"returnValue":"010203","structLogs":[]},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_debug_trace_transaction_state_pruned() {
+	let client = Arc::new(TestBlockChainClient::new());
+	*client.execution_result.write() = Some(Err(CallError::StatePruned));
+	let io = io(&client);
+
+	let request = r#"{"jsonrpc":"2.0","method":"debug_traceTransaction","params":["0x0000000000000000000000000000000000000000000000000000000000000005"],"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"This request is not supported because your node is running with state pruning. Run with --pruning=archive."},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+fn executed(trace: Vec<FlatTrace>) -> Executed {
+	Executed {
+		exception: None,
+		gas: 20_000.into(),
+		gas_used: 10_000.into(),
+		refunded: 0.into(),
+		cumulative_gas_used: 10_000.into(),
+		logs: vec![],
+		contracts_created: vec![],
+		output: vec![1, 2, 3],
+		trace: trace,
+		vm_trace: None,
+		state_diff: None,
+		pre_state: None,
+	}
+}
+
+#[test]
+fn rpc_debug_trace_transaction_call_tracer() {
+	let client = Arc::new(TestBlockChainClient::new());
+	*client.execution_result.write() = Some(Ok(executed(vec![FlatTrace {
+		action: Action::Call(Call {
+			from: 4.into(),
+			to: 5.into(),
+			value: 6.into(),
+			gas: 7.into(),
+			input: vec![0x12, 0x34],
+			call_type: CallType::Call,
+		}),
+		result: Res::Call(CallResult { gas_used: 8.into(), output: vec![0x56] }),
+		subtraces: 0,
+		trace_address: Default::default(),
+	}])));
+	let io = io(&client);
+
+	let request = r#"{"jsonrpc":"2.0","method":"debug_traceTransaction","params":["0x0000000000000000000000000000000000000000000000000000000000000005", {"tracer":"callTracer"}],"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"from":"0x0000000000000000000000000000000000000004","gas":"0x7","gasUsed":"0x8","input":"0x1234","output":"0x56","to":"0x0000000000000000000000000000000000000005","type":"CALL","value":"0x6"},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_debug_trace_transaction_call_tracer_reverted() {
+	let client = Arc::new(TestBlockChainClient::new());
+	let mut reverted = executed(vec![FlatTrace {
+		action: Action::Call(Call {
+			from: 4.into(),
+			to: 5.into(),
+			value: 6.into(),
+			gas: 7.into(),
+			input: vec![],
+			call_type: CallType::Call,
+		}),
+		result: Res::Call(CallResult { gas_used: 3.into(), output: vec![] }),
+		subtraces: 0,
+		trace_address: Default::default(),
+	}]);
+	reverted.exception = Some(vm::Error::Reverted);
+	*client.execution_result.write() = Some(Ok(reverted));
+	let io = io(&client);
+
+	let request = r#"{"jsonrpc":"2.0","method":"debug_traceTransaction","params":["0x0000000000000000000000000000000000000000000000000000000000000005", {"tracer":"callTracer"}],"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"error":"execution reverted","from":"0x0000000000000000000000000000000000000004","gas":"0x7","gasUsed":"0x3","input":"0x","output":"0x","to":"0x0000000000000000000000000000000000000005","type":"CALL","value":"0x6"},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_debug_trace_transaction_prestate_tracer() {
+	let client = Arc::new(TestBlockChainClient::new());
+	*client.execution_result.write() = Some(Ok(executed(vec![])));
+	let io = io(&client);
+
+	let request = r#"{"jsonrpc":"2.0","method":"debug_traceTransaction","params":["0x0000000000000000000000000000000000000000000000000000000000000005", {"tracer":"prestateTracer"}],"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_debug_trace_transaction_unknown_tracer() {
+	let client = Arc::new(TestBlockChainClient::new());
+	*client.execution_result.write() = Some(Ok(executed(vec![])));
+	let io = io(&client);
+
+	let request = r#"{"jsonrpc":"2.0","method":"debug_traceTransaction","params":["0x0000000000000000000000000000000000000000000000000000000000000005", {"tracer":"4byteTracer"}],"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32602,"message":"Couldn't parse parameters: tracer","data":"\"unknown tracer 4byteTracer\""},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
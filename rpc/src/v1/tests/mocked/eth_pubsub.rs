@@ -127,7 +127,7 @@ fn should_subscribe_to_logs() {
 	// Check notifications (enacted)
 	handler.new_blocks(vec![], vec![], vec![h1], vec![], vec![], vec![], 0);
 	let (res, receiver) = receiver.into_future().wait().unwrap();
-	let response = r#"{"jsonrpc":"2.0","method":"eth_subscription","params":{"result":[{"address":"0x0000000000000000000000000000000000000005","blockHash":"0x3457d2fa2e3dd33c78ac681cf542e429becf718859053448748383af67e23218","blockNumber":"0x1","data":"0x","logIndex":"0x0","topics":["0x0000000000000000000000000000000000000000000000000000000000000001","0x0000000000000000000000000000000000000000000000000000000000000002","0x0000000000000000000000000000000000000000000000000000000000000000","0x0000000000000000000000000000000000000000000000000000000000000000"],"transactionHash":""#.to_owned()
+	let response = r#"{"jsonrpc":"2.0","method":"eth_subscription","params":{"result":[{"address":"0x0000000000000000000000000000000000000005","blockHash":"0x3457d2fa2e3dd33c78ac681cf542e429becf718859053448748383af67e23218","blockNumber":"0x1","data":"0x","logIndex":"0x0","removed":false,"topics":["0x0000000000000000000000000000000000000000000000000000000000000001","0x0000000000000000000000000000000000000000000000000000000000000002","0x0000000000000000000000000000000000000000000000000000000000000000","0x0000000000000000000000000000000000000000000000000000000000000000"],"transactionHash":""#.to_owned()
 		+ &format!("0x{:?}", tx_hash)
 		+ r#"","transactionIndex":"0x0","transactionLogIndex":"0x0","type":"mined"}],"subscription":"0x416d77337e24399d"}}"#;
 	assert_eq!(res, Some(response.into()));
@@ -135,7 +135,7 @@ fn should_subscribe_to_logs() {
 	// Check notifications (retracted)
 	handler.new_blocks(vec![], vec![], vec![], vec![h1], vec![], vec![], 0);
 	let (res, receiver) = receiver.into_future().wait().unwrap();
-	let response = r#"{"jsonrpc":"2.0","method":"eth_subscription","params":{"result":[{"address":"0x0000000000000000000000000000000000000005","blockHash":"0x3457d2fa2e3dd33c78ac681cf542e429becf718859053448748383af67e23218","blockNumber":"0x1","data":"0x","logIndex":"0x0","topics":["0x0000000000000000000000000000000000000000000000000000000000000001","0x0000000000000000000000000000000000000000000000000000000000000002","0x0000000000000000000000000000000000000000000000000000000000000000","0x0000000000000000000000000000000000000000000000000000000000000000"],"transactionHash":""#.to_owned()
+	let response = r#"{"jsonrpc":"2.0","method":"eth_subscription","params":{"result":[{"address":"0x0000000000000000000000000000000000000005","blockHash":"0x3457d2fa2e3dd33c78ac681cf542e429becf718859053448748383af67e23218","blockNumber":"0x1","data":"0x","logIndex":"0x0","removed":true,"topics":["0x0000000000000000000000000000000000000000000000000000000000000001","0x0000000000000000000000000000000000000000000000000000000000000002","0x0000000000000000000000000000000000000000000000000000000000000000","0x0000000000000000000000000000000000000000000000000000000000000000"],"transactionHash":""#.to_owned()
 		+ &format!("0x{:?}", tx_hash)
 		+ r#"","transactionIndex":"0x0","transactionLogIndex":"0x0","type":"removed"}],"subscription":"0x416d77337e24399d"}}"#;
 	assert_eq!(res, Some(response.into()));
@@ -150,6 +150,92 @@ fn should_subscribe_to_logs() {
 	assert_eq!(res, None);
 }
 
+#[test]
+fn should_notify_light_client_logs_removed() {
+	use jsonrpc_core::Error;
+	use jsonrpc_core::futures::BoxFuture;
+	use ethcore::encoded;
+	use ethcore::filter::Filter;
+	use ethcore::ids::BlockId;
+	use light::client::LightChainNotify;
+	use util::H256;
+	use v1::LightClient;
+	use v1::types::Log;
+
+	struct TestLightClient {
+		logs: Vec<Log>,
+	}
+
+	impl LightClient for TestLightClient {
+		fn block_header(&self, _id: BlockId) -> Option<encoded::Header> {
+			None
+		}
+
+		fn logs(&self, filter: Filter) -> BoxFuture<Vec<Log>, Error> {
+			let logs: Vec<Log> = self.logs.iter()
+				.filter(|log| log.block_hash.clone().map(|hash| BlockId::Hash(hash.into())) == Some(filter.from_block.clone()))
+				.cloned()
+				.collect();
+			futures::future::ok(logs).boxed()
+		}
+	}
+
+	// given
+	let el = EventLoop::spawn();
+	let block_hash: H256 = 5.into();
+	let client = TestLightClient {
+		logs: vec![Log {
+			address: 1.into(),
+			topics: vec![2.into()],
+			data: vec![].into(),
+			block_hash: Some(block_hash.into()),
+			block_number: Some(1.into()),
+			transaction_hash: Some(3.into()),
+			transaction_index: Some(0.into()),
+			log_index: Some(0.into()),
+			transaction_log_index: Some(0.into()),
+			log_type: "mined".into(),
+			removed: false,
+		}],
+	};
+
+	let pubsub = EthPubSubClient::new_test(Arc::new(client), el.remote());
+	let handler = pubsub.handler();
+	let pubsub = pubsub.to_delegate();
+
+	let mut io = MetaIoHandler::default();
+	io.extend_with(pubsub);
+
+	let mut metadata = Metadata::default();
+	let (sender, receiver) = futures::sync::mpsc::channel(8);
+	metadata.session = Some(Arc::new(Session::new(sender)));
+
+	// Subscribe
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_subscribe", "params": ["logs", {}], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x416d77337e24399d","id":1}"#;
+	assert_eq!(io.handle_request_sync(request, metadata.clone()), Some(response.to_owned()));
+
+	// Check notifications (enacted)
+	handler.new_headers(&[block_hash], &[]);
+	let (res, receiver) = receiver.into_future().wait().unwrap();
+	let response = r#"{"jsonrpc":"2.0","method":"eth_subscription","params":{"result":[{"address":"0x0000000000000000000000000000000000000001","blockHash":"0x0000000000000000000000000000000000000000000000000000000000000005","blockNumber":"0x1","data":"0x","logIndex":"0x0","removed":false,"topics":["0x0000000000000000000000000000000000000000000000000000000000000002"],"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000003","transactionIndex":"0x0","transactionLogIndex":"0x0","type":"mined"}],"subscription":"0x416d77337e24399d"}}"#;
+	assert_eq!(res, Some(response.into()));
+
+	// Check notifications (retracted)
+	handler.new_headers(&[], &[block_hash]);
+	let (res, receiver) = receiver.into_future().wait().unwrap();
+	let response = r#"{"jsonrpc":"2.0","method":"eth_subscription","params":{"result":[{"address":"0x0000000000000000000000000000000000000001","blockHash":"0x0000000000000000000000000000000000000000000000000000000000000005","blockNumber":"0x1","data":"0x","logIndex":"0x0","removed":true,"topics":["0x0000000000000000000000000000000000000000000000000000000000000002"],"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000003","transactionIndex":"0x0","transactionLogIndex":"0x0","type":"removed"}],"subscription":"0x416d77337e24399d"}}"#;
+	assert_eq!(res, Some(response.into()));
+
+	// And unsubscribe
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_unsubscribe", "params": ["0x416d77337e24399d"], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+	assert_eq!(io.handle_request_sync(request, metadata), Some(response.to_owned()));
+
+	let (res, _receiver) = receiver.into_future().wait().unwrap();
+	assert_eq!(res, None);
+}
+
 #[test]
 fn should_return_unimplemented() {
 	// given
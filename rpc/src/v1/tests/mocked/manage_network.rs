@@ -15,8 +15,13 @@
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
 use ethsync::{ManageNetwork, NetworkConfiguration};
+use light::net::Config as LightServeConfig;
+use util::Mutex;
 
-pub struct TestManageNetwork;
+#[derive(Default)]
+pub struct TestManageNetwork {
+	pub light_serve_config: Mutex<LightServeConfig>,
+}
 
 // TODO: rob, gavin (originally introduced this functions) - proper tests and test state
 impl ManageNetwork for TestManageNetwork {
@@ -27,4 +32,9 @@ impl ManageNetwork for TestManageNetwork {
 	fn start_network(&self) {}
 	fn stop_network(&self) {}
 	fn network_config(&self) -> NetworkConfiguration { NetworkConfiguration::new_local() }
+	fn light_serve_config(&self) -> Option<LightServeConfig> { Some(self.light_serve_config.lock().clone()) }
+	fn set_light_serve_config(&self, config: LightServeConfig) -> Result<(), String> {
+		*self.light_serve_config.lock() = config;
+		Ok(())
+	}
 }
@@ -72,7 +72,7 @@ impl Dependencies {
 				rpc_interface: "all".to_owned(),
 				rpc_port: 8545,
 			}),
-			network: Arc::new(TestManageNetwork),
+			network: Arc::new(TestManageNetwork::default()),
 			accounts: Arc::new(AccountProvider::transient_provider()),
 			dapps_address: Some(("127.0.0.1".into(), 18080)),
 			ws_address: Some(("127.0.0.1".into(), 18546)),
@@ -538,6 +538,7 @@ fn rpc_parity_call() {
 		trace: vec![],
 		vm_trace: None,
 		state_diff: None,
+		pre_state: None,
 	}));
 	let io = deps.default_client();
 
@@ -560,6 +561,83 @@ fn rpc_parity_call() {
 	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_parity_transaction_receipt_with_revert_reason() {
+	use ethcore::client::TransactionId;
+	use ethcore::receipt::LocalizedReceipt;
+	use rustc_hex::FromHex;
+	use util::{H256, U256};
+	use vm;
+
+	let deps = Dependencies::new();
+	let hash = H256::from(1);
+	deps.client.set_transaction_receipt(TransactionId::Hash(hash), LocalizedReceipt {
+		transaction_hash: hash,
+		transaction_index: 0,
+		block_hash: H256::from(2),
+		block_number: 0x10,
+		cumulative_gas_used: U256::from(0x5208),
+		gas_used: U256::from(0x5208),
+		contract_address: None,
+		logs: vec![],
+		log_bloom: 0.into(),
+		state_root: None,
+	});
+	deps.client.set_execution_result(Ok(Executed {
+		exception: Some(vm::Error::Reverted),
+		gas: U256::zero(),
+		gas_used: U256::from(0x5208),
+		refunded: U256::zero(),
+		cumulative_gas_used: U256::zero(),
+		logs: vec![],
+		contracts_created: vec![],
+		output: "08c379a0\
+			0000000000000000000000000000000000000000000000000000000000000020\
+			0000000000000000000000000000000000000000000000000000000000000004\
+			6e6f706500000000000000000000000000000000000000000000000000000000".from_hex().unwrap(),
+		trace: vec![],
+		vm_trace: None,
+		state_diff: None,
+		pre_state: None,
+	}));
+	let io = deps.default_client();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_getTransactionReceipt", "params":["0x0000000000000000000000000000000000000000000000000000000000000001", true], "id": 1}"#;
+	let response = format!(r#"{{"jsonrpc":"2.0","result":{{"blockHash":"0x0000000000000000000000000000000000000000000000000000000000000002","blockNumber":"0x10","contractAddress":null,"cumulativeGasUsed":"0x5208","gasUsed":"0x5208","logs":[],"logsBloom":"0x{}","revertReason":"nope","root":null,"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000001","transactionIndex":"0x0"}},"id":1}}"#, "0".repeat(512));
+
+	assert_eq!(io.handle_request_sync(request), Some(response));
+}
+
+#[test]
+fn rpc_parity_transaction_receipt_with_pruned_state() {
+	use ethcore::client::TransactionId;
+	use ethcore::executed::CallError;
+	use ethcore::receipt::LocalizedReceipt;
+	use util::{H256, U256};
+
+	let deps = Dependencies::new();
+	let hash = H256::from(1);
+	deps.client.set_transaction_receipt(TransactionId::Hash(hash), LocalizedReceipt {
+		transaction_hash: hash,
+		transaction_index: 0,
+		block_hash: H256::from(2),
+		block_number: 0x10,
+		cumulative_gas_used: U256::from(0x5208),
+		gas_used: U256::from(0x5208),
+		contract_address: None,
+		logs: vec![],
+		log_bloom: 0.into(),
+		state_root: None,
+	});
+	deps.client.set_execution_result(Err(CallError::StatePruned));
+	let io = deps.default_client();
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_getTransactionReceipt", "params":["0x0000000000000000000000000000000000000000000000000000000000000001", true], "id": 1}"#;
+	let response = format!(r#"{{"jsonrpc":"2.0","result":{{"blockHash":"0x0000000000000000000000000000000000000000000000000000000000000002","blockNumber":"0x10","contractAddress":null,"cumulativeGasUsed":"0x5208","gasUsed":"0x5208","logs":[],"logsBloom":"0x{}","revertReason":null,"root":null,"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000001","transactionIndex":"0x0"}},"id":1}}"#, "0".repeat(512));
+
+	assert_eq!(io.handle_request_sync(request), Some(response));
+}
+
 #[test]
 fn rpc_parity_node_health() {
 	let deps = Dependencies::new();
@@ -37,7 +37,7 @@ fn client_service() -> Arc<TestBlockChainClient> {
 }
 
 fn network_service() -> Arc<TestManageNetwork> {
-	Arc::new(TestManageNetwork)
+	Arc::new(TestManageNetwork::default())
 }
 
 fn updater_service() -> Arc<TestUpdater> {
@@ -195,6 +195,34 @@ fn rpc_parity_set_transactions_limit() {
 	assert_eq!(miner.transactions_limit(), 10_240_240);
 }
 
+#[test]
+fn rpc_parity_set_light_serve_params() {
+	use light::request::Kind;
+
+	let miner = miner_service();
+	let client = client_service();
+	let network = network_service();
+	let updater = updater_service();
+	let mut io = IoHandler::new();
+	io.extend_with(parity_set_client(&client, &miner, &updater, &network).to_delegate());
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_setLightServeParams", "params":[{"maxPeers":5,"recharge":"0x3e8","costs":{"execution":"0x64"}}], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":true,"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+
+	let config = network.light_serve_config.lock().clone();
+	assert_eq!(config.max_peers, Some(5));
+	assert_eq!(config.recharge, 1000);
+	assert_eq!(config.cost_overrides.get(&Kind::Execution), Some(&U256::from(100)));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "parity_setLightServeParams", "params":[{"costs":{"unknown":"0x64"}}], "id": 1}"#;
+	let response = io.handle_request_sync(request).unwrap();
+
+	assert!(response.contains("Couldn't parse parameters: Light serve params"));
+	assert_eq!(network.light_serve_config.lock().cost_overrides.len(), 1);
+}
+
 #[test]
 fn rpc_parity_set_hash_content() {
 	let miner = miner_service();
@@ -21,10 +21,11 @@ use ethcore::account_provider::AccountProvider;
 use ethcore::client::TestBlockChainClient;
 use ethcore::transaction::{Action, Transaction};
 use jsonrpc_core::IoHandler;
+use rustc_hex::ToHex;
 use util::{U256, Address};
 
 use v1::{PersonalClient, Personal, Metadata};
-use v1::helpers::dispatch::FullDispatcher;
+use v1::helpers::dispatch::{eth_data_hash, FullDispatcher};
 use v1::tests::helpers::TestMinerService;
 
 struct PersonalTester {
@@ -53,7 +54,7 @@ fn setup() -> PersonalTester {
 	let miner = miner_service();
 
 	let dispatcher = FullDispatcher::new(client, miner.clone());
-	let personal = PersonalClient::new(&opt_accounts, dispatcher, false);
+	let personal = PersonalClient::new(&opt_accounts, dispatcher, None, false);
 
 	let mut io = IoHandler::default();
 	io.extend_with(personal.to_delegate());
@@ -92,6 +93,72 @@ fn new_account() {
 	assert_eq!(res, Some(response));
 }
 
+#[test]
+fn sign() {
+	let tester = setup();
+	let address = tester.accounts.new_account("password123").unwrap();
+	let data = vec![0x5b, 0x9a];
+
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "personal_sign",
+		"params": [
+			"0x5b9a",
+			""#.to_owned() + &format!("0x{:?}", address) + r#"",
+			"password123"
+		],
+		"id": 1
+	}"#;
+
+	let signature = tester.accounts.sign(address, Some("password123".into()), eth_data_hash(data)).unwrap();
+	let response = r#"{"jsonrpc":"2.0","result":"0x"#.to_owned() + &signature.into_electrum()[..].to_hex() + r#"","id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(&request), Some(response));
+}
+
+#[test]
+fn sign_with_invalid_password() {
+	let tester = setup();
+	let address = tester.accounts.new_account("password123").unwrap();
+
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "personal_sign",
+		"params": [
+			"0x5b9a",
+			""#.to_owned() + &format!("0x{:?}", address) + r#"",
+			"password321"
+		],
+		"id": 1
+	}"#;
+
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32021,"message":"Account password is invalid or account does not exist.","data":"SStore(InvalidPassword)"},"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(&request), Some(response.into()));
+}
+
+#[test]
+fn ec_recover() {
+	let tester = setup();
+	let address = tester.accounts.new_account("password123").unwrap();
+	let data = vec![0x5b, 0x9a];
+	let signature = tester.accounts.sign(address, Some("password123".into()), eth_data_hash(data)).unwrap();
+
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "personal_ecRecover",
+		"params": [
+			"0x5b9a",
+			"0x"#.to_owned() + &signature.into_electrum()[..].to_hex() + r#""
+		],
+		"id": 1
+	}"#;
+
+	let response = r#"{"jsonrpc":"2.0","result":""#.to_owned() + &format!("0x{:?}", address) + r#"","id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(&request), Some(response));
+}
+
 #[test]
 fn sign_and_send_transaction_with_invalid_password() {
 	let tester = setup();
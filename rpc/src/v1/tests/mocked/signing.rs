@@ -23,7 +23,7 @@ use jsonrpc_core::{IoHandler, Success};
 use v1::impls::SigningQueueClient;
 use v1::metadata::Metadata;
 use v1::traits::{EthSigning, ParitySigning, Parity};
-use v1::helpers::{SignerService, SigningQueue, FullDispatcher};
+use v1::helpers::{eip712, SignerService, SigningQueue, ConfirmationPayload, FullDispatcher};
 use v1::types::ConfirmationResponse;
 use v1::tests::helpers::TestMinerService;
 use v1::tests::mocked::parity;
@@ -218,6 +218,108 @@ fn should_sign_if_account_is_unlocked() {
 	assert_eq!(tester.signer.requests().len(), 0);
 }
 
+const TYPED_DATA: &'static str = r#"{
+	"types": {
+		"EIP712Domain": [{ "name": "name", "type": "string" }, { "name": "chainId", "type": "uint256" }],
+		"Mail": [{ "name": "to", "type": "address" }, { "name": "contents", "type": "string" }]
+	},
+	"primaryType": "Mail",
+	"domain": { "name": "Ether Mail", "chainId": 1 },
+	"message": { "to": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB", "contents": "Hello, Bob!" }
+}"#;
+
+#[test]
+fn should_add_sign_typed_data_to_queue() {
+	// given
+	let tester = eth_signing();
+	let address = Address::random();
+	assert_eq!(tester.signer.requests().len(), 0);
+
+	// when
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "eth_signTypedData",
+		"params": [
+			""#.to_owned() + format!("0x{:?}", address).as_ref() + r#"",
+			"# + TYPED_DATA + r#"
+		],
+		"id": 1
+	}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x0000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000","id":1}"#;
+
+	// then
+	let promise = tester.io.handle_request(&request);
+
+	// the future must be polled at least once before request is queued.
+	let signer = tester.signer.clone();
+	::std::thread::spawn(move || loop {
+		let requests = signer.requests();
+		if requests.len() == 1 {
+			match requests[0].payload {
+				ConfirmationPayload::EIP712SignMessage(ref sender, ref data) => {
+					assert_eq!(*sender, address);
+					assert_eq!(data.primary_type, "Mail");
+				},
+				ref payload => panic!("Unexpected payload: {:?}", payload),
+			}
+			// respond
+			signer.request_confirmed(1.into(), Ok(ConfirmationResponse::Signature(0.into())));
+			break
+		}
+		::std::thread::sleep(Duration::from_millis(100))
+	});
+
+	let res = promise.wait().unwrap();
+	assert_eq!(res, Some(response.to_owned()));
+}
+
+#[test]
+fn should_sign_typed_data_if_account_is_unlocked() {
+	// given
+	let tester = eth_signing();
+	let acc = tester.accounts.insert_account(Secret::from_slice(&[69u8; 32]), "test").unwrap();
+	tester.accounts.unlock_account_permanently(acc, "test".into()).unwrap();
+	let hash = eip712::hash_structured_data(&serde_json::from_str(TYPED_DATA).unwrap()).unwrap();
+	let signature = tester.accounts.sign(acc, None, hash).unwrap();
+
+	// when
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "eth_signTypedData",
+		"params": [
+			""#.to_owned() + format!("0x{:?}", acc).as_ref() + r#"",
+			"# + TYPED_DATA + r#"
+		],
+		"id": 1
+	}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x"#.to_owned() + &signature.into_electrum()[..].to_hex() + r#"","id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(&request), Some(response));
+	assert_eq!(tester.signer.requests().len(), 0);
+}
+
+#[test]
+fn should_reject_invalid_typed_data() {
+	// given
+	let tester = eth_signing();
+	let address = Address::random();
+
+	// when
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "eth_signTypedData",
+		"params": [
+			""#.to_owned() + format!("0x{:?}", address).as_ref() + r#"",
+			"# + &TYPED_DATA.replace("\"uint256\"", "\"uint\"") + r#"
+		],
+		"id": 1
+	}"#;
+
+	// then
+	let response = tester.io.handle_request_sync(&request).unwrap();
+	assert!(response.contains("Unknown type: uint"), "Unexpected response: {}", response);
+	assert_eq!(tester.signer.requests().len(), 0);
+}
+
 #[test]
 fn should_add_transaction_to_queue() {
 	// given
@@ -19,7 +19,7 @@ use std::sync::Arc;
 use ethcore::executed::{Executed, CallError};
 use ethcore::trace::trace::{Action, Res, Call};
 use ethcore::trace::LocalizedTrace;
-use ethcore::client::TestBlockChainClient;
+use ethcore::client::{TestBlockChainClient, BlockChainClient, EachBlockWith, BlockId};
 
 use vm::CallType;
 
@@ -64,6 +64,7 @@ fn io() -> Tester {
 		trace: vec![],
 		vm_trace: None,
 		state_diff: None,
+		pre_state: None,
 	}));
 	let miner = Arc::new(TestMinerService::default());
 	let traces = TracesClient::new(&client, &miner);
@@ -119,6 +120,19 @@ fn rpc_trace_block_missing_traces() {
 	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_trace_block_not_backfilled() {
+	let tester = io();
+	*tester.client.trace_backfill_cursor.write() = Some(0x20);
+
+	let request = r#"{"jsonrpc":"2.0","method":"trace_block","params": ["0x10"],"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"Traces of these blocks are still being backfilled. Try again later or restrict the range to recent blocks.","data":"Blocks before #32 are not traced yet"},"id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+
+	let request = r#"{"jsonrpc":"2.0","method":"trace_block","params": ["0x20"],"id":1}"#;
+	assert!(tester.io.handle_request_sync(request).unwrap().contains("\"result\""));
+}
+
 #[test]
 fn rpc_trace_transaction() {
 	let tester = io();
@@ -170,6 +184,16 @@ fn rpc_trace_call() {
 	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_trace_call_with_overrides() {
+	let tester = io();
+
+	let request = r#"{"jsonrpc":"2.0","method":"trace_callWithOverrides","params":[{}, ["stateDiff", "vmTrace", "trace"], {"0x0000000000000000000000000000000000000005": {"balance": "0x100"}}, "latest"],"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"output":"0x010203","stateDiff":null,"trace":[],"vmTrace":null},"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+}
+
 #[test]
 fn rpc_trace_multi_call() {
 	let tester = io();
@@ -232,3 +256,27 @@ fn rpc_trace_replay_transaction_state_pruned() {
 
 	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
 }
+
+#[test]
+fn rpc_trace_replay_block_transactions() {
+	let tester = io();
+	tester.client.add_blocks(1, EachBlockWith::Transaction);
+	let hash = tester.client.block_body(BlockId::Number(1)).unwrap().transactions()[0].hash();
+
+	let request = r#"{"jsonrpc":"2.0","method":"trace_replayBlockTransactions","params":["0x1", ["trace", "stateDiff", "vmTrace"]],"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":[{"output":"0x010203","stateDiff":null,"trace":[],"transactionHash":""#.to_owned() + &format!("0x{:?}", hash) + r#"","vmTrace":null}],"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request), Some(response));
+}
+
+#[test]
+fn rpc_trace_replay_block_transactions_state_pruned() {
+	let tester = io();
+	tester.client.add_blocks(1, EachBlockWith::Transaction);
+	*tester.client.execution_result.write() = Some(Err(CallError::StatePruned));
+
+	let request = r#"{"jsonrpc":"2.0","method":"trace_replayBlockTransactions","params":["0x1", ["trace", "stateDiff", "vmTrace"]],"id":1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"This request is not supported because your node is running with state pruning. Run with --pruning=archive."},"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+}
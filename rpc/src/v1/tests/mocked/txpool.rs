@@ -0,0 +1,117 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+use std::sync::Arc;
+
+use ethcore::client::TestBlockChainClient;
+use ethcore::transaction::{Transaction, Action, Condition};
+use ethkey::{Generator, Random, Secret};
+use serde_json;
+
+use jsonrpc_core::IoHandler;
+use v1::tests::helpers::TestMinerService;
+use v1::{TxPool, TxPoolClient};
+
+fn io(miner: &Arc<TestMinerService>) -> IoHandler {
+	let client = Arc::new(TestBlockChainClient::new());
+	let mut io = IoHandler::default();
+	io.extend_with(TxPoolClient::new(&client, miner).to_delegate());
+	io
+}
+
+#[test]
+fn rpc_txpool_status() {
+	let miner = Arc::new(TestMinerService::default());
+	let tx = Transaction {
+		nonce: 0.into(),
+		gas_price: 1.into(),
+		gas: 21000.into(),
+		action: Action::Call(5.into()),
+		value: 1.into(),
+		data: vec![],
+	}.sign(Random.generate().unwrap().secret(), None);
+	miner.pending_transactions.lock().insert(tx.hash(), tx);
+	let io = io(&miner);
+
+	let request = r#"{"jsonrpc": "2.0", "method": "txpool_status", "params": [], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"pending":"0x1","queued":"0x0"},"id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_txpool_inspect() {
+	let miner = Arc::new(TestMinerService::default());
+	let tx = Transaction {
+		nonce: 7.into(),
+		gas_price: 2.into(),
+		gas: 21000.into(),
+		action: Action::Create,
+		value: 10.into(),
+		data: vec![],
+	}.sign(Random.generate().unwrap().secret(), None);
+	let sender = tx.sender();
+	miner.pending_transactions.lock().insert(tx.hash(), tx);
+	let io = io(&miner);
+
+	let request = r#"{"jsonrpc": "2.0", "method": "txpool_inspect", "params": [], "id": 1}"#;
+	let response = format!(
+		r#"{{"jsonrpc":"2.0","result":{{"pending":{{"0x{:?}":{{"7":"contract creation: 10 wei + 21000 gas × 2 wei"}}}},"queued":{{}}}},"id":1}}"#,
+		sender
+	);
+
+	assert_eq!(io.handle_request_sync(request), Some(response));
+}
+
+#[test]
+fn rpc_txpool_content_queues_conditional_transactions() {
+	let miner = Arc::new(TestMinerService::default());
+	let tx = |nonce: u64, secret: &Secret| Transaction {
+		nonce: nonce.into(),
+		gas_price: 1.into(),
+		gas: 21000.into(),
+		action: Action::Call(5.into()),
+		value: 1.into(),
+		data: vec![],
+	}.sign(secret, None);
+	let ready = Random.generate().unwrap();
+	let delayed = Random.generate().unwrap();
+
+	let tx0 = tx(0, ready.secret());
+	let tx1 = tx(0, delayed.secret());
+	let tx2 = tx(1, delayed.secret());
+	// the best block of the test client is 0, so the condition is not met yet.
+	miner.pending_conditions.lock().insert(tx1.hash(), Condition::Number(100));
+	for tx in vec![tx0, tx1, tx2] {
+		miner.pending_transactions.lock().insert(tx.hash(), tx);
+	}
+	let io = io(&miner);
+
+	let request = r#"{"jsonrpc": "2.0", "method": "txpool_content", "params": [], "id": 1}"#;
+	let response: serde_json::Value = serde_json::from_str(&io.handle_request_sync(request).unwrap()).unwrap();
+	let result = &response["result"];
+	let ready = format!("0x{:?}", ready.address());
+	let delayed = format!("0x{:?}", delayed.address());
+
+	assert_eq!(result["pending"].as_object().unwrap().keys().cloned().collect::<Vec<_>>(), vec![ready]);
+	assert_eq!(result["queued"].as_object().unwrap().keys().cloned().collect::<Vec<_>>(), vec![delayed.clone()]);
+	assert_eq!(result["queued"][&delayed].as_object().unwrap().keys().cloned().collect::<Vec<_>>(), vec!["0".to_owned(), "1".to_owned()]);
+	assert_eq!(result["queued"][&delayed]["0"]["condition"]["block"].as_u64(), Some(100));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "txpool_status", "params": [], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":{"pending":"0x1","queued":"0x2"},"id":1}"#;
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
@@ -19,7 +19,7 @@
 use jsonrpc_core::Error;
 use futures::BoxFuture;
 
-use v1::types::{Bytes, H160, H256, H520, TransactionRequest, RichRawTransaction};
+use v1::types::{Bytes, H160, H256, H520, TransactionRequest, RichRawTransaction, EIP712};
 
 build_rpc_trait! {
 	/// Signing methods implementation relying on unlocked accounts.
@@ -30,6 +30,10 @@ build_rpc_trait! {
 		#[rpc(meta, name = "eth_sign")]
 		fn sign(&self, Self::Metadata, H160, Bytes) -> BoxFuture<H520, Error>;
 
+		/// Signs the hash of EIP-712 typed structured data with given address signature.
+		#[rpc(meta, name = "eth_signTypedData")]
+		fn sign_typed_data(&self, Self::Metadata, H160, EIP712) -> BoxFuture<H520, Error>;
+
 		/// Sends transaction; will block waiting for signer to return the
 		/// transaction hash.
 		/// If Signer is disable it will require the account to be unlocked.
@@ -17,6 +17,7 @@
 //! Ethereum rpc interfaces.
 
 pub mod web3;
+pub mod debug;
 pub mod eth;
 pub mod eth_pubsub;
 pub mod eth_signing;
@@ -29,10 +30,12 @@ pub mod personal;
 pub mod pubsub;
 pub mod signer;
 pub mod traces;
+pub mod txpool;
 pub mod rpc;
 pub mod secretstore;
 
 pub use self::web3::Web3;
+pub use self::debug::Debug;
 pub use self::eth::{Eth, EthFilter};
 pub use self::eth_pubsub::EthPubSub;
 pub use self::eth_signing::EthSigning;
@@ -45,5 +48,6 @@ pub use self::personal::Personal;
 pub use self::pubsub::PubSub;
 pub use self::signer::Signer;
 pub use self::traces::Traces;
+pub use self::txpool::TxPool;
 pub use self::rpc::Rpc;
 pub use self::secretstore::SecretStore;
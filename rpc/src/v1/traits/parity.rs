@@ -29,7 +29,7 @@ use v1::types::{
 	TransactionStats, LocalTransactionStatus,
 	BlockNumber, ConsensusCapability, VersionInfo,
 	OperationsInfo, DappId, ChainStatus,
-	AccountInfo, HwAccountInfo, RichHeader,
+	AccountInfo, HwAccountInfo, RichHeader, Receipt,
 };
 
 build_rpc_trait! {
@@ -209,6 +209,12 @@ build_rpc_trait! {
 		#[rpc(meta, name = "parity_call")]
 		fn call(&self, Self::Metadata, Vec<CallRequest>, Trailing<BlockNumber>) -> BoxFuture<Vec<Bytes>, Error>;
 
+		/// Returns the receipt of given transaction.
+		/// If requested, the transaction is re-executed to report the reason it failed
+		/// (`null` if the state needed for re-execution is pruned).
+		#[rpc(name = "parity_getTransactionReceipt")]
+		fn transaction_receipt(&self, H256, Trailing<bool>) -> Result<Option<Receipt>, Error>;
+
 		/// Returns node's health report.
 		#[rpc(async, name = "parity_nodeHealth")]
 		fn node_health(&self) -> BoxFuture<Health, Error>;
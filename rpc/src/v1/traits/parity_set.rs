@@ -19,7 +19,7 @@
 use jsonrpc_core::Error;
 use futures::BoxFuture;
 
-use v1::types::{Bytes, H160, H256, U256, ReleaseInfo, Transaction, LocalDapp};
+use v1::types::{Bytes, H160, H256, U256, ReleaseInfo, Transaction, LocalDapp, LightServeParams};
 
 build_rpc_trait! {
 	/// Parity-specific rpc interface for operations altering the settings.
@@ -88,6 +88,12 @@ build_rpc_trait! {
 		#[rpc(name = "parity_setMode")]
 		fn set_mode(&self, String) -> Result<bool, Error>;
 
+		/// Set the light client serving parameters: maximum number of peers,
+		/// share of serving time, per-peer recharge rate and request cost overrides.
+		/// Fields which aren't given keep their current values.
+		#[rpc(name = "parity_setLightServeParams")]
+		fn set_light_serve_params(&self, LightServeParams) -> Result<bool, Error>;
+
 		/// Set the network spec. Argument must be one of: "foundation", "ropsten", "morden", "kovan", "olympic", "classic", "dev", "expanse" or a filename.
 		#[rpc(name = "parity_setChain")]
 		fn set_spec_name(&self, String) -> Result<bool, Error>;
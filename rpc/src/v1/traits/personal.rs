@@ -19,7 +19,7 @@ use jsonrpc_core::Error;
 
 use futures::BoxFuture;
 
-use v1::types::{U128, H160, H256, TransactionRequest};
+use v1::types::{Bytes, U128, H160, H256, H520, TransactionRequest};
 
 build_rpc_trait! {
 	/// Personal rpc interface. Safe (read-only) functions.
@@ -39,6 +39,15 @@ build_rpc_trait! {
 		#[rpc(name = "personal_unlockAccount")]
 		fn unlock_account(&self, H160, String, Option<U128>) -> Result<bool, Error>;
 
+		/// Signs the hash of data (with the Ethereum message prefix) using given account and password.
+		/// Requests for hardware wallet accounts are passed to the Trusted Signer.
+		#[rpc(meta, name = "personal_sign")]
+		fn sign(&self, Self::Metadata, Bytes, H160, String) -> BoxFuture<H520, Error>;
+
+		/// Returns the address of the account which produced given `personal_sign` signature.
+		#[rpc(name = "personal_ecRecover")]
+		fn ec_recover(&self, Bytes, H520) -> Result<H160, Error>;
+
 		/// Sends transaction and signs it in single call. The account is not unlocked in such case.
 		#[rpc(meta, name = "personal_sendTransaction")]
 		fn send_transaction(&self, Self::Metadata, TransactionRequest, String) -> BoxFuture<H256, Error>;
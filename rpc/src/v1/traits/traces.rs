@@ -19,7 +19,7 @@
 use jsonrpc_core::Error;
 use jsonrpc_core::futures::BoxFuture;
 use jsonrpc_macros::Trailing;
-use v1::types::{TraceFilter, LocalizedTrace, BlockNumber, Index, CallRequest, Bytes, TraceResults, H256, TraceOptions};
+use v1::types::{TraceFilter, LocalizedTrace, BlockNumber, Index, CallRequest, Bytes, TraceResults, TraceResultsWithTransactionHash, H256, TraceOptions, StateOverride};
 
 build_rpc_trait! {
 	/// Traces specific rpc interface.
@@ -46,6 +46,10 @@ build_rpc_trait! {
 		#[rpc(meta, name = "trace_call")]
 		fn call(&self, Self::Metadata, CallRequest, TraceOptions, Trailing<BlockNumber>) -> BoxFuture<TraceResults, Error>;
 
+		/// Executes the given call with accounts temporarily overridden and returns a number of possible traces for it.
+		#[rpc(meta, name = "trace_callWithOverrides")]
+		fn call_with_overrides(&self, Self::Metadata, CallRequest, TraceOptions, StateOverride, Trailing<BlockNumber>) -> BoxFuture<TraceResults, Error>;
+
 		/// Executes all given calls and returns a number of possible traces for each of it.
 		#[rpc(meta, name = "trace_callMany")]
 		fn call_many(&self, Self::Metadata, Vec<(CallRequest, TraceOptions)>, Trailing<BlockNumber>) -> BoxFuture<Vec<TraceResults>, Error>;
@@ -57,5 +61,9 @@ build_rpc_trait! {
 		/// Executes the transaction with the given hash and returns a number of possible traces for it.
 		#[rpc(name = "trace_replayTransaction")]
 		fn replay_transaction(&self, H256, TraceOptions) -> Result<TraceResults, Error>;
+
+		/// Executes all the transactions at the given block and returns a number of possible traces for each of them.
+		#[rpc(name = "trace_replayBlockTransactions")]
+		fn replay_block_transactions(&self, BlockNumber, TraceOptions) -> Result<Vec<TraceResultsWithTransactionHash>, Error>;
 	}
 }
@@ -14,6 +14,8 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
+use v1::types::{H160, H256, U256, Bytes};
+
 /// Account information.
 #[derive(Debug, Default, Clone, PartialEq, Serialize)]
 pub struct AccountInfo {
@@ -42,3 +44,36 @@ pub struct HwAccountInfo {
 	pub manufacturer: String,
 }
 
+/// Account state with Merkle proofs (used by `eth_getProof`).
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct EthAccount {
+	/// Account address
+	pub address: H160,
+	/// Account balance
+	pub balance: U256,
+	/// Account nonce
+	pub nonce: U256,
+	/// Hash of the account code
+	#[serde(rename="codeHash")]
+	pub code_hash: H256,
+	/// Root of the account storage trie
+	#[serde(rename="storageHash")]
+	pub storage_hash: H256,
+	/// State trie nodes from the root to the account
+	#[serde(rename="accountProof")]
+	pub account_proof: Vec<Bytes>,
+	/// Proofs of the requested storage keys
+	#[serde(rename="storageProof")]
+	pub storage_proof: Vec<StorageProof>,
+}
+
+/// Storage value with its Merkle proof.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct StorageProof {
+	/// Storage key
+	pub key: U256,
+	/// Storage value
+	pub value: U256,
+	/// Storage trie nodes from the root to the value
+	pub proof: Vec<Bytes>,
+}
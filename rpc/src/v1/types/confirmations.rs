@@ -21,7 +21,7 @@ use serde::{Serialize, Serializer};
 use util::Colour;
 use util::bytes::ToPretty;
 
-use v1::types::{U256, TransactionRequest, RichRawTransaction, H160, H256, H520, Bytes, TransactionCondition, Origin};
+use v1::types::{U256, TransactionRequest, RichRawTransaction, H160, H256, H520, Bytes, TransactionCondition, Origin, EIP712};
 use v1::helpers;
 
 /// Confirmation waiting in a queue
@@ -58,6 +58,7 @@ impl fmt::Display for ConfirmationPayload {
 			ConfirmationPayload::SendTransaction(ref transaction) => write!(f, "{}", transaction),
 			ConfirmationPayload::SignTransaction(ref transaction) => write!(f, "(Sign only) {}", transaction),
 			ConfirmationPayload::EthSignMessage(ref sign) => write!(f, "{}", sign),
+			ConfirmationPayload::EIP712SignMessage(ref sign) => write!(f, "{}", sign),
 			ConfirmationPayload::Decrypt(ref decrypt) => write!(f, "{}", decrypt),
 		}
 	}
@@ -93,6 +94,36 @@ impl fmt::Display for SignRequest {
 	}
 }
 
+/// EIP-712 typed data sign request
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EIP712SignRequest {
+	/// Address
+	pub address: H160,
+	/// Typed structured data to sign
+	pub data: EIP712,
+}
+
+impl From<(H160, EIP712)> for EIP712SignRequest {
+	fn from(tuple: (H160, EIP712)) -> Self {
+		EIP712SignRequest {
+			address: tuple.0,
+			data: tuple.1,
+		}
+	}
+}
+
+impl fmt::Display for EIP712SignRequest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(
+			f,
+			"sign typed data {} with {}",
+			self.data.primary_type,
+			Colour::White.bold().paint(format!("0x{:?}", self.address)),
+		)
+	}
+}
+
 /// Decrypt request
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
@@ -170,6 +201,9 @@ pub enum ConfirmationPayload {
 	/// Signature
 	#[serde(rename="sign")]
 	EthSignMessage(SignRequest),
+	/// Typed structured data signature
+	#[serde(rename="signTypedData")]
+	EIP712SignMessage(EIP712SignRequest),
 	/// Decryption
 	#[serde(rename="decrypt")]
 	Decrypt(DecryptRequest),
@@ -184,6 +218,10 @@ impl From<helpers::ConfirmationPayload> for ConfirmationPayload {
 				address: address.into(),
 				data: data.into(),
 			}),
+			helpers::ConfirmationPayload::EIP712SignMessage(address, data) => ConfirmationPayload::EIP712SignMessage(EIP712SignRequest {
+				address: address.into(),
+				data: data,
+			}),
 			helpers::ConfirmationPayload::Decrypt(address, msg) => ConfirmationPayload::Decrypt(DecryptRequest {
 				address: address.into(),
 				msg: msg.into(),
@@ -0,0 +1,422 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Geth-compatible transaction traces (`debug_traceTransaction`).
+
+use std::collections::BTreeMap;
+use rustc_hex::ToHex;
+use serde::{Serialize, Serializer};
+use ethcore::client::Executed;
+use ethcore::pod_state::PodState;
+use ethcore::trace::{FlatTrace, StructLog as EthStructLog, StructLogOptions};
+use ethcore::trace::trace::{Action, Res};
+use util::{U256 as EthU256, H256 as EthH256};
+use vm::{CallType, Error as VmError};
+use v1::types::{Bytes, H160, H256, U256};
+
+/// Options of `debug_traceTransaction`.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct DebugTraceOptions {
+	/// Don't capture memory.
+	#[serde(rename="disableMemory")]
+	pub disable_memory: bool,
+	/// Don't capture the stack.
+	#[serde(rename="disableStack")]
+	pub disable_stack: bool,
+	/// Don't capture storage.
+	#[serde(rename="disableStorage")]
+	pub disable_storage: bool,
+	/// Built-in tracer producing the result instead of struct logs:
+	/// `callTracer` or `prestateTracer`.
+	pub tracer: Option<String>,
+}
+
+impl Into<StructLogOptions> for DebugTraceOptions {
+	fn into(self) -> StructLogOptions {
+		StructLogOptions {
+			disable_memory: self.disable_memory,
+			disable_stack: self.disable_stack,
+			disable_storage: self.disable_storage,
+		}
+	}
+}
+
+fn saturating_u64(value: EthU256) -> u64 {
+	match value > u64::max_value().into() {
+		true => u64::max_value(),
+		false => value.low_u64(),
+	}
+}
+
+fn word(value: EthU256) -> String {
+	format!("{:?}", EthH256::from(value))
+}
+
+/// Machine state before executing a single instruction.
+#[derive(Debug, Serialize)]
+pub struct StructLog {
+	/// Program counter.
+	pub pc: usize,
+	/// Instruction mnemonic.
+	pub op: String,
+	/// Gas left before executing the instruction.
+	pub gas: u64,
+	/// Gas cost of the instruction.
+	#[serde(rename="gasCost")]
+	pub gas_cost: u64,
+	/// Call depth.
+	pub depth: usize,
+	/// Stack as 32-byte words, bottom first.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub stack: Option<Vec<String>>,
+	/// Memory as 32-byte words.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub memory: Option<Vec<String>>,
+	/// Storage slots accessed by the executing contract so far.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub storage: Option<BTreeMap<String, String>>,
+}
+
+impl From<EthStructLog> for StructLog {
+	fn from(log: EthStructLog) -> Self {
+		StructLog {
+			pc: log.pc,
+			op: log.op_name().into(),
+			gas: saturating_u64(log.gas),
+			gas_cost: saturating_u64(log.gas_cost),
+			depth: log.depth,
+			stack: log.stack.map(|stack| stack.into_iter().map(word).collect()),
+			memory: log.memory.map(|memory| memory.chunks(32).map(|chunk| chunk.to_hex()).collect()),
+			storage: log.storage.map(|storage| storage.into_iter().map(|(k, v)| (word(k), word(v))).collect()),
+		}
+	}
+}
+
+/// Result of `debug_traceTransaction`.
+#[derive(Debug, Serialize)]
+pub struct DebugTrace {
+	/// Gas used by the transaction.
+	pub gas: u64,
+	/// Whether the transaction failed.
+	pub failed: bool,
+	/// Output of the transaction.
+	#[serde(rename="returnValue")]
+	pub return_value: String,
+	/// Machine state before every executed instruction.
+	#[serde(rename="structLogs")]
+	pub struct_logs: Vec<StructLog>,
+}
+
+impl From<Executed<Vec<EthStructLog>>> for DebugTrace {
+	fn from(executed: Executed<Vec<EthStructLog>>) -> Self {
+		DebugTrace {
+			gas: saturating_u64(executed.gas_used),
+			failed: executed.exception.is_some(),
+			return_value: executed.output.to_hex(),
+			struct_logs: executed.vm_trace.unwrap_or_default().into_iter().map(Into::into).collect(),
+		}
+	}
+}
+
+/// A call and all its subcalls (`callTracer`).
+#[derive(Debug, Serialize)]
+pub struct CallFrame {
+	/// Kind of the call: `CALL`, `CALLCODE`, `DELEGATECALL`, `STATICCALL`, `CREATE` or `SELFDESTRUCT`.
+	#[serde(rename="type")]
+	pub call_type: String,
+	/// Sender.
+	pub from: H160,
+	/// Recipient, created contract or heir.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub to: Option<H160>,
+	/// Transferred value.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub value: Option<U256>,
+	/// Gas provided.
+	pub gas: U256,
+	/// Gas used.
+	#[serde(rename="gasUsed")]
+	pub gas_used: U256,
+	/// Input data or init code.
+	pub input: Bytes,
+	/// Output data or code of the created contract.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub output: Option<Bytes>,
+	/// Failure reason.
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub error: Option<String>,
+	/// Subcalls, in execution order.
+	#[serde(skip_serializing_if = "Vec::is_empty")]
+	pub calls: Vec<CallFrame>,
+}
+
+impl CallFrame {
+	/// Builds the call tree from flat traces ordered as produced by the tracer.
+	pub fn from_flat_traces(traces: Vec<FlatTrace>) -> Option<Self> {
+		let mut traces = traces.into_iter();
+		let mut root = match traces.next() {
+			Some(trace) => CallFrame::from(trace),
+			None => return None,
+		};
+
+		for trace in traces {
+			let address: Vec<usize> = trace.trace_address.iter().cloned().collect();
+			root.insert(&address, trace.into());
+		}
+		Some(root)
+	}
+
+	fn insert(&mut self, address: &[usize], frame: CallFrame) {
+		match address.split_first() {
+			Some((&index, rest)) if !rest.is_empty() => {
+				if let Some(parent) = self.calls.get_mut(index) {
+					parent.insert(rest, frame);
+				}
+			},
+			_ => self.calls.push(frame),
+		}
+	}
+}
+
+impl From<FlatTrace> for CallFrame {
+	fn from(trace: FlatTrace) -> Self {
+		let mut frame = match trace.action {
+			Action::Call(call) => CallFrame {
+				call_type: match call.call_type {
+					CallType::None | CallType::Call => "CALL",
+					CallType::CallCode => "CALLCODE",
+					CallType::DelegateCall => "DELEGATECALL",
+					CallType::StaticCall => "STATICCALL",
+				}.into(),
+				from: call.from.into(),
+				to: Some(call.to.into()),
+				value: Some(call.value.into()),
+				gas: call.gas.into(),
+				gas_used: 0.into(),
+				input: call.input.into(),
+				output: None,
+				error: None,
+				calls: vec![],
+			},
+			Action::Create(create) => CallFrame {
+				call_type: "CREATE".into(),
+				from: create.from.into(),
+				to: None,
+				value: Some(create.value.into()),
+				gas: create.gas.into(),
+				gas_used: 0.into(),
+				input: create.init.into(),
+				output: None,
+				error: None,
+				calls: vec![],
+			},
+			Action::Suicide(suicide) => CallFrame {
+				call_type: "SELFDESTRUCT".into(),
+				from: suicide.address.into(),
+				to: Some(suicide.refund_address.into()),
+				value: Some(suicide.balance.into()),
+				gas: 0.into(),
+				gas_used: 0.into(),
+				input: Bytes::default(),
+				output: None,
+				error: None,
+				calls: vec![],
+			},
+		};
+
+		match trace.result {
+			Res::Call(result) => {
+				frame.gas_used = result.gas_used.into();
+				frame.output = Some(result.output.into());
+			},
+			Res::Create(result) => {
+				frame.gas_used = result.gas_used.into();
+				frame.output = Some(result.code.into());
+				frame.to = Some(result.address.into());
+			},
+			// exceptional halts consume all gas of the frame. Reverted frames return the rest
+			// and are traced with their results, so they keep the gas actually used.
+			Res::FailedCall(error) | Res::FailedCreate(error) => {
+				frame.gas_used = frame.gas.clone();
+				frame.error = Some(error.to_string());
+			},
+			Res::None => {},
+		}
+		frame
+	}
+}
+
+/// State of an account before execution (`prestateTracer`).
+#[derive(Debug, Serialize)]
+pub struct PrestateAccount {
+	/// Balance.
+	pub balance: U256,
+	/// Nonce.
+	pub nonce: u64,
+	/// Code.
+	pub code: Bytes,
+	/// Storage slots accessed during execution.
+	pub storage: BTreeMap<H256, H256>,
+}
+
+/// Pre-execution state of all accessed accounts (`prestateTracer`).
+pub type Prestate = BTreeMap<H160, PrestateAccount>;
+
+fn prestate(pod: PodState) -> Prestate {
+	pod.drain().into_iter().map(|(address, account)| (address.into(), PrestateAccount {
+		balance: account.balance.into(),
+		nonce: saturating_u64(account.nonce),
+		code: account.code.unwrap_or_default().into(),
+		storage: account.storage.into_iter().map(|(k, v)| (k.into(), v.into())).collect(),
+	})).collect()
+}
+
+/// Result of `debug_traceTransaction`, depending on the requested tracer.
+#[derive(Debug)]
+pub enum DebugTraceResult {
+	/// Struct logs.
+	StructLogs(DebugTrace),
+	/// Call tree.
+	CallTracer(Option<CallFrame>),
+	/// State of accessed accounts before execution.
+	PrestateTracer(Prestate),
+}
+
+impl DebugTraceResult {
+	/// Creates the `callTracer` result from a transaction replayed with transaction tracing.
+	pub fn call_tracer(executed: Executed) -> Self {
+		let reverted = executed.exception == Some(VmError::Reverted);
+		let mut root = CallFrame::from_flat_traces(executed.trace);
+		if let Some(ref mut root) = root {
+			if reverted {
+				root.error = Some("execution reverted".into());
+			}
+		}
+		DebugTraceResult::CallTracer(root)
+	}
+
+	/// Creates the `prestateTracer` result from a transaction replayed with pre-state dumping.
+	pub fn prestate_tracer(executed: Executed) -> Self {
+		DebugTraceResult::PrestateTracer(executed.pre_state.map(prestate).unwrap_or_default())
+	}
+}
+
+impl Serialize for DebugTraceResult {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+		where S: Serializer
+	{
+		match *self {
+			DebugTraceResult::StructLogs(ref trace) => trace.serialize(serializer),
+			DebugTraceResult::CallTracer(ref frame) => frame.serialize(serializer),
+			DebugTraceResult::PrestateTracer(ref prestate) => prestate.serialize(serializer),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::BTreeMap;
+	use serde_json;
+	use ethcore::trace::{FlatTrace, TraceError, StructLog as EthStructLog};
+	use ethcore::trace::trace::{Action, Res, Call, CallResult, Create};
+	use vm::CallType;
+	use super::{StructLog, CallFrame, DebugTraceOptions};
+
+	#[test]
+	fn should_deserialize_options() {
+		let options: DebugTraceOptions = serde_json::from_str(r#"{"disableStorage":true}"#).unwrap();
+		assert_eq!(options, DebugTraceOptions { disable_storage: true, ..Default::default() });
+
+		let options: DebugTraceOptions = serde_json::from_str(r#"{"tracer":"callTracer"}"#).unwrap();
+		assert_eq!(options.tracer, Some("callTracer".into()));
+	}
+
+	fn call(to: u64, trace_address: Vec<usize>, subtraces: usize) -> FlatTrace {
+		FlatTrace {
+			action: Action::Call(Call {
+				from: 1.into(),
+				to: to.into(),
+				value: 0.into(),
+				gas: 100.into(),
+				input: vec![],
+				call_type: CallType::Call,
+			}),
+			result: Res::Call(CallResult { gas_used: 10.into(), output: vec![] }),
+			subtraces: subtraces,
+			trace_address: trace_address.into_iter().collect(),
+		}
+	}
+
+	#[test]
+	fn should_build_call_tree() {
+		let failed_create = FlatTrace {
+			action: Action::Create(Create { from: 3.into(), value: 0.into(), gas: 50.into(), init: vec![0x60] }),
+			result: Res::FailedCreate(TraceError::OutOfGas),
+			subtraces: 0,
+			trace_address: vec![0, 0].into_iter().collect(),
+		};
+		let traces = vec![call(2, vec![], 2), call(3, vec![0], 1), failed_create, call(4, vec![1], 0)];
+
+		let root = CallFrame::from_flat_traces(traces).unwrap();
+		assert_eq!(root.calls.len(), 2);
+		assert_eq!(root.calls[0].calls.len(), 1);
+		assert_eq!(root.calls[1].to, Some(4.into()));
+
+		let serialized = serde_json::to_string(&root.calls[0].calls[0]).unwrap();
+		assert_eq!(serialized, r#"{"type":"CREATE","from":"0x0000000000000000000000000000000000000003","value":"0x0","gas":"0x32","gasUsed":"0x32","input":"0x60","error":"Out of gas"}"#);
+		assert_eq!(CallFrame::from_flat_traces(vec![]).is_none(), true);
+	}
+
+	#[test]
+	fn should_serialize_struct_log() {
+		let mut storage = BTreeMap::new();
+		storage.insert(1.into(), 0x2a.into());
+		let log = StructLog::from(EthStructLog {
+			pc: 3,
+			instruction: 0x54,
+			gas: 100.into(),
+			gas_cost: 200.into(),
+			depth: 1,
+			stack: Some(vec![1.into()]),
+			memory: Some(vec![0xff; 64]),
+			storage: Some(storage),
+		});
+
+		let serialized = serde_json::to_string(&log).unwrap();
+		assert_eq!(serialized, r#"{"pc":3,"op":"SLOAD","gas":100,"gasCost":200,"depth":1,"#.to_owned()
+			+ r#""stack":["0000000000000000000000000000000000000000000000000000000000000001"],"#
+			+ r#""memory":["ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff","ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff"],"#
+			+ r#""storage":{"0000000000000000000000000000000000000000000000000000000000000001":"000000000000000000000000000000000000000000000000000000000000002a"}}"#);
+	}
+
+	#[test]
+	fn should_skip_disabled_captures() {
+		let log = StructLog::from(EthStructLog {
+			pc: 0,
+			instruction: 0x00,
+			gas: 21000.into(),
+			gas_cost: 0.into(),
+			depth: 1,
+			stack: None,
+			memory: None,
+			storage: None,
+		});
+
+		let serialized = serde_json::to_string(&log).unwrap();
+		assert_eq!(serialized, r#"{"pc":0,"op":"STOP","gas":21000,"gasCost":0,"depth":1}"#);
+	}
+}
@@ -0,0 +1,89 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! EIP-712 typed structured data.
+
+use std::collections::BTreeMap;
+use std::hash::{Hash, Hasher};
+use serde_json::Value;
+
+/// Member of a struct type.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EIP712Member {
+	/// Member name.
+	pub name: String,
+	/// Member type.
+	#[serde(rename="type")]
+	pub member_type: String,
+}
+
+/// Typed structured data to sign (EIP-712).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EIP712 {
+	/// Struct type definitions, including `EIP712Domain`.
+	pub types: BTreeMap<String, Vec<EIP712Member>>,
+	/// Type of the message.
+	#[serde(rename="primaryType")]
+	pub primary_type: String,
+	/// Signing domain.
+	pub domain: Value,
+	/// Message to sign.
+	pub message: Value,
+}
+
+// JSON values coming from deserialization never contain `NaN`.
+impl Eq for EIP712 {}
+
+impl Hash for EIP712 {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.types.hash(state);
+		self.primary_type.hash(state);
+		self.domain.to_string().hash(state);
+		self.message.to_string().hash(state);
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use super::EIP712;
+
+	#[test]
+	fn should_deserialize_typed_data() {
+		let s = r#"{
+			"types": {
+				"EIP712Domain": [{ "name": "name", "type": "string" }],
+				"Mail": [{ "name": "contents", "type": "string" }]
+			},
+			"primaryType": "Mail",
+			"domain": { "name": "Ether Mail" },
+			"message": { "contents": "Hello, Bob!" }
+		}"#;
+		let data: EIP712 = serde_json::from_str(s).unwrap();
+
+		assert_eq!(data.primary_type, "Mail");
+		assert_eq!(data.types["EIP712Domain"][0].member_type, "string");
+		assert_eq!(data.message["contents"], "Hello, Bob!");
+	}
+
+	#[test]
+	fn should_reject_unknown_fields() {
+		let s = r#"{"types": {}, "primaryType": "Mail", "domain": {}, "message": {}, "extra": 1}"#;
+		assert!(serde_json::from_str::<EIP712>(s).is_err());
+	}
+}
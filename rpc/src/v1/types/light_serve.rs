@@ -0,0 +1,120 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Light client serving parameters.
+
+use std::collections::{BTreeMap, HashMap};
+use light::net::Config;
+use light::request::Kind;
+use util::U256 as EthU256;
+use v1::types::U256;
+
+/// Light client serving parameters (`parity_setLightServeParams`).
+/// Missing fields keep their current values.
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LightServeParams {
+	/// Maximum number of light client peers to serve. 0 removes the limit.
+	#[serde(rename="maxPeers")]
+	pub max_peers: Option<usize>,
+	/// Share of the total serving time to spend on light clients, split evenly
+	/// between `maxPeers` if limited, otherwise between the connected light clients.
+	/// 1.0 is the time of one fully busy thread.
+	#[serde(rename="serveTimeShare")]
+	pub serve_time_share: Option<f64>,
+	/// Request credits each peer recharges per second.
+	pub recharge: Option<U256>,
+	/// Fixed costs by request kind (e.g. "execution", which is per gas).
+	/// Replaces all previous overrides.
+	pub costs: Option<BTreeMap<String, U256>>,
+}
+
+impl LightServeParams {
+	/// Apply the given parameters on top of a serving configuration.
+	pub fn apply_to(self, config: &mut Config) -> Result<(), String> {
+		if let Some(max_peers) = self.max_peers {
+			config.max_peers = match max_peers {
+				0 => None,
+				max_peers => Some(max_peers),
+			};
+		}
+
+		if let Some(share) = self.serve_time_share {
+			if !share.is_finite() || share <= 0.0 {
+				return Err(format!("Invalid serve time share: {}", share));
+			}
+			config.serve_time_share = share;
+		}
+
+		if let Some(recharge) = self.recharge {
+			let recharge: EthU256 = recharge.into();
+			if recharge > EthU256::from(u64::max_value()) {
+				return Err(format!("Recharge rate too high: {}", recharge));
+			}
+			config.recharge = recharge.low_u64();
+		}
+
+		if let Some(costs) = self.costs {
+			let mut cost_overrides: HashMap<Kind, EthU256> = HashMap::new();
+			for (kind, cost) in costs {
+				let kind: Kind = kind.parse()?;
+				if kind == Kind::BloomBits {
+					return Err("The cost of bloom_bits requests follows header_proof and can't be set.".into());
+				}
+				cost_overrides.insert(kind, cost.into());
+			}
+			config.cost_overrides = cost_overrides;
+		}
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use light::net::Config;
+	use light::request::Kind;
+	use super::LightServeParams;
+
+	#[test]
+	fn should_apply_params() {
+		let s = r#"{"maxPeers":10,"serveTimeShare":0.5,"costs":{"execution":"0x3e8"}}"#;
+		let params: LightServeParams = serde_json::from_str(s).unwrap();
+
+		let mut config = Config::default();
+		let recharge = config.recharge;
+		params.apply_to(&mut config).unwrap();
+
+		assert_eq!(config.max_peers, Some(10));
+		assert_eq!(config.serve_time_share, 0.5);
+		assert_eq!(config.recharge, recharge);
+		assert_eq!(config.cost_overrides.get(&Kind::Execution), Some(&1000.into()));
+	}
+
+	#[test]
+	fn should_reject_unknown_kinds() {
+		let s = r#"{"costs":{"transactions":"0x1"}}"#;
+		let params: LightServeParams = serde_json::from_str(s).unwrap();
+
+		assert!(params.apply_to(&mut Config::default()).is_err());
+
+		let s = r#"{"costs":{"bloom_bits":"0x1"}}"#;
+		let params: LightServeParams = serde_json::from_str(s).unwrap();
+
+		assert!(params.apply_to(&mut Config::default()).is_err());
+	}
+}
@@ -49,6 +49,10 @@ pub struct Receipt {
 	/// Logs bloom
 	#[serde(rename="logsBloom")]
 	pub logs_bloom: H2048,
+	/// Reason of the failure, only present if re-execution was requested.
+	/// `null` if the transaction did not fail or its state is no longer available.
+	#[serde(rename="revertReason", skip_serializing_if = "Option::is_none")]
+	pub revert_reason: Option<Option<String>>,
 }
 
 impl From<LocalizedReceipt> for Receipt {
@@ -64,6 +68,7 @@ impl From<LocalizedReceipt> for Receipt {
 			logs: r.logs.into_iter().map(Into::into).collect(),
 			state_root: r.state_root.map(Into::into),
 			logs_bloom: r.log_bloom.into(),
+			revert_reason: None,
 		}
 	}
 }
@@ -81,6 +86,7 @@ impl From<RichReceipt> for Receipt {
 			logs: r.logs.into_iter().map(Into::into).collect(),
 			state_root: r.state_root.map(Into::into),
 			logs_bloom: r.log_bloom.into(),
+			revert_reason: None,
 		}
 	}
 }
@@ -98,6 +104,7 @@ impl From<EthReceipt> for Receipt {
 			logs: r.logs.into_iter().map(Into::into).collect(),
 			state_root: r.state_root.map(Into::into),
 			logs_bloom: r.log_bloom.into(),
+			revert_reason: None,
 		}
 	}
 }
@@ -109,7 +116,7 @@ mod tests {
 
 	#[test]
 	fn receipt_serialization() {
-		let s = r#"{"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionIndex":"0x0","blockHash":"0xed76641c68a1c641aee09a94b3b471f4dc0316efe5ac19cf488e2674cf8d05b5","blockNumber":"0x4510c","cumulativeGasUsed":"0x20","gasUsed":"0x10","contractAddress":null,"logs":[{"address":"0x33990122638b9132ca29c723bdf037f1a891a70c","topics":["0xa6697e974e6a320f454390be03f74955e8978f1a6971ea6730542e37b66179bc","0x4861736852656700000000000000000000000000000000000000000000000000"],"data":"0x","blockHash":"0xed76641c68a1c641aee09a94b3b471f4dc0316efe5ac19cf488e2674cf8d05b5","blockNumber":"0x4510c","transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionIndex":"0x0","logIndex":"0x1","transactionLogIndex":null,"type":"mined"}],"root":"0x000000000000000000000000000000000000000000000000000000000000000a","logsBloom":"0x0000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000f"}"#;
+		let s = r#"{"transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionIndex":"0x0","blockHash":"0xed76641c68a1c641aee09a94b3b471f4dc0316efe5ac19cf488e2674cf8d05b5","blockNumber":"0x4510c","cumulativeGasUsed":"0x20","gasUsed":"0x10","contractAddress":null,"logs":[{"address":"0x33990122638b9132ca29c723bdf037f1a891a70c","topics":["0xa6697e974e6a320f454390be03f74955e8978f1a6971ea6730542e37b66179bc","0x4861736852656700000000000000000000000000000000000000000000000000"],"data":"0x","blockHash":"0xed76641c68a1c641aee09a94b3b471f4dc0316efe5ac19cf488e2674cf8d05b5","blockNumber":"0x4510c","transactionHash":"0x0000000000000000000000000000000000000000000000000000000000000000","transactionIndex":"0x0","logIndex":"0x1","transactionLogIndex":null,"type":"mined","removed":false}],"root":"0x000000000000000000000000000000000000000000000000000000000000000a","logsBloom":"0x0000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000f"}"#;
 
 		let receipt = Receipt {
 			transaction_hash: Some(0.into()),
@@ -133,8 +140,10 @@ mod tests {
 				transaction_log_index: None,
 				log_index: Some(1.into()),
 				log_type: "mined".into(),
+				removed: false,
 			}],
 			logs_bloom: 15.into(),
+			revert_reason: None,
 			state_root: Some(10.into()),
 		};
 
@@ -0,0 +1,108 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! State overrides for `eth_call`, `eth_estimateGas` and `trace_call`.
+
+use std::collections::BTreeMap;
+use ethcore::client::{AccountOverride as EthAccountOverride, StateOverride as EthStateOverride};
+use jsonrpc_core::Error;
+use v1::helpers::errors;
+use v1::types::{Bytes, H160, H256, U256};
+
+/// Overrides applied to accounts before executing a call, keyed by address.
+pub type StateOverride = BTreeMap<H160, AccountOverride>;
+
+/// Temporary replacement of the state of a single account.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AccountOverride {
+	/// Balance
+	pub balance: Option<U256>,
+	/// Nonce
+	pub nonce: Option<U256>,
+	/// Code
+	pub code: Option<Bytes>,
+	/// Storage replacing the whole account storage.
+	pub state: Option<BTreeMap<H256, H256>>,
+	/// Storage slots patched on top of the account storage.
+	#[serde(rename="stateDiff")]
+	pub state_diff: Option<BTreeMap<H256, H256>>,
+}
+
+fn into_storage(storage: Option<BTreeMap<H256, H256>>) -> Option<BTreeMap<::util::H256, ::util::H256>> {
+	storage.map(|storage| storage.into_iter().map(|(k, v)| (k.into(), v.into())).collect())
+}
+
+/// Converts overrides given over RPC into the ones understood by the client.
+/// Fails if both `state` and `stateDiff` are given for the same account.
+pub fn into_state_override(overrides: StateOverride) -> Result<EthStateOverride, Error> {
+	overrides.into_iter().map(|(address, account)| {
+		if account.state.is_some() && account.state_diff.is_some() {
+			return Err(errors::invalid_params("state override", format!("both state and stateDiff given for {:?}", address)));
+		}
+
+		Ok((address.into(), EthAccountOverride {
+			balance: account.balance.map(Into::into),
+			nonce: account.nonce.map(Into::into),
+			code: account.code.map(Into::into),
+			state: into_storage(account.state),
+			state_diff: into_storage(account.state_diff),
+		}))
+	}).collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use v1::types::{H160, H256};
+	use super::{StateOverride, into_state_override};
+
+	#[test]
+	fn state_override_deserialize() {
+		let s = r#"{
+			"0x0000000000000000000000000000000000000001": {
+				"balance": "0x10",
+				"nonce": "0x2",
+				"code": "0x6000",
+				"stateDiff": {
+					"0x0000000000000000000000000000000000000000000000000000000000000001": "0x0000000000000000000000000000000000000000000000000000000000000002"
+				}
+			}
+		}"#;
+		let overrides: StateOverride = serde_json::from_str(s).unwrap();
+		let account = &overrides[&H160::from(1)];
+		assert_eq!(account.balance, Some(16.into()));
+		assert_eq!(account.nonce, Some(2.into()));
+		assert_eq!(account.code, Some(vec![0x60, 0x00].into()));
+		assert_eq!(account.state, None);
+		assert_eq!(account.state_diff.as_ref().unwrap()[&H256::from(1)], H256::from(2));
+
+		let converted = into_state_override(overrides).unwrap();
+		assert_eq!(converted[&::util::Address::from(1)].balance, Some(16.into()));
+	}
+
+	#[test]
+	fn state_override_rejects_state_with_state_diff() {
+		let s = r#"{
+			"0x0000000000000000000000000000000000000001": {
+				"state": {},
+				"stateDiff": {}
+			}
+		}"#;
+		let overrides: StateOverride = serde_json::from_str(s).unwrap();
+		assert!(into_state_override(overrides).is_err());
+	}
+}
@@ -117,6 +117,15 @@ pub struct PipProtocolInfo {
 	pub difficulty: U256,
 	/// SHA3 of peer best block hash
 	pub head: String,
+	/// Number of requests served to the peer
+	#[serde(rename="requestsServed")]
+	pub requests_served: u64,
+	/// Request credits charged to the peer
+	#[serde(rename="creditsCharged")]
+	pub credits_charged: U256,
+	/// Time spent serving the peer, in nanoseconds
+	#[serde(rename="serveTime")]
+	pub serve_time: u64,
 }
 
 impl From<ethsync::PipProtocolInfo> for PipProtocolInfo {
@@ -125,6 +134,9 @@ impl From<ethsync::PipProtocolInfo> for PipProtocolInfo {
 			version: info.version,
 			difficulty: info.difficulty.into(),
 			head: info.head.hex(),
+			requests_served: info.requests_served,
+			credits_charged: info.credits_charged.into(),
+			serve_time: info.serve_time_ns,
 		}
 	}
 }
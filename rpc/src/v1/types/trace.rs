@@ -22,6 +22,7 @@ use ethcore::trace as et;
 use ethcore::state_diff;
 use ethcore::account_diff;
 use ethcore::client::Executed;
+use util::H256 as EthH256;
 use vm;
 use v1::types::{Bytes, H160, H256, U256};
 
@@ -570,6 +571,37 @@ impl From<Executed> for TraceResults {
 	}
 }
 
+#[derive(Debug, Serialize)]
+/// Traces of a transaction replayed as part of its block.
+pub struct TraceResultsWithTransactionHash {
+	/// The output of the call/create
+	pub output: Bytes,
+	/// The transaction trace.
+	pub trace: Vec<Trace>,
+	/// The VM trace.
+	#[serde(rename="vmTrace")]
+	pub vm_trace: Option<VMTrace>,
+	/// The state diff.
+	#[serde(rename="stateDiff")]
+	pub state_diff: Option<StateDiff>,
+	/// The transaction hash.
+	#[serde(rename="transactionHash")]
+	pub transaction_hash: H256,
+}
+
+impl From<(EthH256, Executed)> for TraceResultsWithTransactionHash {
+	fn from(t: (EthH256, Executed)) -> Self {
+		let (transaction_hash, executed) = t;
+		TraceResultsWithTransactionHash {
+			output: executed.output.into(),
+			trace: executed.trace.into_iter().map(Into::into).collect(),
+			vm_trace: executed.vm_trace.map(Into::into),
+			state_diff: executed.state_diff.map(Into::into),
+			transaction_hash: transaction_hash.into(),
+		}
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use serde_json;
@@ -0,0 +1,136 @@
+// Copyright 2015-2017 Parity Technologies (UK) Ltd.
+// This file is part of Parity.
+
+// Parity is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Transaction pool views (Geth-compatible).
+
+use std::collections::BTreeMap;
+use util::U256 as EthU256;
+use v1::types::{H160, U256, Transaction};
+
+/// Transactions grouped by sender and then by nonce.
+pub type TxPoolGroups<T> = BTreeMap<H160, BTreeMap<u64, T>>;
+
+fn group<T, F>(transactions: Vec<Transaction>, f: F) -> TxPoolGroups<T> where F: Fn(Transaction) -> T {
+	let mut groups = TxPoolGroups::new();
+	for tx in transactions {
+		let nonce: EthU256 = tx.nonce.into();
+		groups.entry(tx.from.clone())
+			.or_insert_with(BTreeMap::new)
+			.insert(nonce.low_u64(), f(tx));
+	}
+	groups
+}
+
+/// Human-readable summary of a transaction.
+fn summary(tx: Transaction) -> String {
+	let to = match tx.to {
+		Some(ref to) => format!("0x{:?}", to),
+		None => "contract creation".into(),
+	};
+	format!("{}: {} wei + {} gas × {} wei", to, tx.value, tx.gas, tx.gas_price)
+}
+
+/// Content of the transaction pool (`txpool_content`).
+#[derive(Debug, Default, Serialize)]
+pub struct TxPoolContent {
+	/// Transactions ready to be included in a block.
+	pub pending: TxPoolGroups<Transaction>,
+	/// Transactions waiting for an earlier nonce or a condition.
+	pub queued: TxPoolGroups<Transaction>,
+}
+
+impl TxPoolContent {
+	/// Groups the given pending and queued transactions.
+	pub fn new(pending: Vec<Transaction>, queued: Vec<Transaction>) -> Self {
+		TxPoolContent {
+			pending: group(pending, |tx| tx),
+			queued: group(queued, |tx| tx),
+		}
+	}
+}
+
+/// Textual summary of the transaction pool (`txpool_inspect`).
+#[derive(Debug, Default, Serialize)]
+pub struct TxPoolInspect {
+	/// Transactions ready to be included in a block.
+	pub pending: TxPoolGroups<String>,
+	/// Transactions waiting for an earlier nonce or a condition.
+	pub queued: TxPoolGroups<String>,
+}
+
+impl TxPoolInspect {
+	/// Summarizes the given pending and queued transactions.
+	pub fn new(pending: Vec<Transaction>, queued: Vec<Transaction>) -> Self {
+		TxPoolInspect {
+			pending: group(pending, summary),
+			queued: group(queued, summary),
+		}
+	}
+}
+
+/// Number of transactions in the transaction pool (`txpool_status`).
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct TxPoolStatus {
+	/// Number of transactions ready to be included in a block.
+	pub pending: U256,
+	/// Number of transactions waiting for an earlier nonce or a condition.
+	pub queued: U256,
+}
+
+#[cfg(test)]
+mod tests {
+	use serde_json;
+	use ethcore::transaction::{Transaction as EthTransaction, Action};
+	use ethkey::{Generator, Random};
+	use v1::types::Transaction;
+	use super::{TxPoolContent, TxPoolInspect, TxPoolStatus};
+
+	fn transaction(nonce: u64, action: Action) -> (Transaction, String) {
+		let keypair = Random.generate().unwrap();
+		let tx = EthTransaction {
+			nonce: nonce.into(),
+			gas_price: 20.into(),
+			gas: 21000.into(),
+			action: action,
+			value: 1.into(),
+			data: vec![],
+		}.sign(keypair.secret(), None);
+		let sender = format!("0x{:?}", tx.sender());
+		(Transaction::from_signed(tx, 0, u64::max_value()), sender)
+	}
+
+	#[test]
+	fn should_group_by_sender_and_nonce() {
+		let (pending, sender) = transaction(3, Action::Call(5.into()));
+		let (queued, _) = transaction(10, Action::Create);
+		let queued_sender = format!("0x{:?}", queued.from);
+
+		let inspect = TxPoolInspect::new(vec![pending.clone()], vec![queued.clone()]);
+		let serialized = serde_json::to_value(&inspect).unwrap();
+		assert_eq!(serialized["pending"][&sender]["3"], "0x0000000000000000000000000000000000000005: 1 wei + 21000 gas × 20 wei");
+		assert_eq!(serialized["queued"][&queued_sender]["10"], "contract creation: 1 wei + 21000 gas × 20 wei");
+
+		let content = TxPoolContent::new(vec![pending], vec![queued]);
+		let serialized = serde_json::to_value(&content).unwrap();
+		assert_eq!(serialized["pending"][&sender]["3"]["nonce"], "0x3");
+	}
+
+	#[test]
+	fn should_serialize_status() {
+		let status = TxPoolStatus { pending: 2.into(), queued: 16.into() };
+		assert_eq!(serde_json::to_string(&status).unwrap(), r#"{"pending":"0x2","queued":"0x10"}"#);
+	}
+}
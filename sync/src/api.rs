@@ -151,14 +151,30 @@ pub struct PipProtocolInfo {
 	pub head: H256,
 	/// Peer total difficulty if known
 	pub difficulty: U256,
+	/// Number of requests served to the peer.
+	pub requests_served: u64,
+	/// Request credits charged to the peer.
+	pub credits_charged: U256,
+	/// Time spent serving the peer, in nanoseconds.
+	pub serve_time_ns: u64,
 }
 
-impl From<light_net::Status> for PipProtocolInfo {
-	fn from(status: light_net::Status) -> Self {
+impl PipProtocolInfo {
+	fn new(status: light_net::Status, stats: light_net::ServeStats) -> Self {
 		PipProtocolInfo {
 			version: status.protocol_version,
 			head: status.head_hash,
 			difficulty: status.head_td,
+			requests_served: stats.requests,
+			credits_charged: stats.credits_charged,
+			serve_time_ns: stats.serve_time_ns,
+		}
+	}
+
+	fn from_proto(proto: &LightProtocol, peer: &PeerId) -> Option<Self> {
+		match (proto.peer_status(peer), proto.peer_serve_stats(peer)) {
+			(Some(status), Some(stats)) => Some(PipProtocolInfo::new(status, stats)),
+			_ => None,
 		}
 	}
 }
@@ -214,6 +230,8 @@ pub struct Params {
 	pub provider: Arc<::light::Provider>,
 	/// Network layer configuration.
 	pub network_config: NetworkConfiguration,
+	/// Light client serving configuration.
+	pub light_config: light_net::Config,
 	/// Other protocols to attach.
 	pub attached_protos: Vec<AttachedProtocol>,
 }
@@ -237,8 +255,6 @@ pub struct EthSync {
 impl EthSync {
 	/// Creates and register protocol with the network service
 	pub fn new(params: Params, connection_filter: Option<Arc<ConnectionFilter>>) -> Result<Arc<EthSync>, NetworkError> {
-		const MAX_LIGHTSERV_LOAD: f64 = 0.5;
-
 		let pruning_info = params.chain.pruning_info();
 		let light_proto = match params.config.serve_light {
 			false => None,
@@ -249,21 +265,19 @@ impl EthSync {
 					.map(|mut p| { p.push("request_timings"); light_net::FileStore(p) })
 					.map(|store| Box::new(store) as Box<_>);
 
-				let mut light_params = LightParams {
+				let light_params = LightParams {
 					network_id: params.config.network_id,
-					config: Default::default(),
+					config: params.light_config,
 					capabilities: Capabilities {
 						serve_headers: true,
 						serve_chain_since: Some(pruning_info.earliest_chain),
 						serve_state_since: Some(pruning_info.earliest_state),
 						tx_relay: true,
+						serve_bloom_bits: true,
 					},
 					sample_store: sample_store,
 				};
 
-				let max_peers = ::std::cmp::min(params.network_config.max_peers, 1);
-				light_params.config.load_share = MAX_LIGHTSERV_LOAD / max_peers as f64;
-
 				let mut light_proto = LightProtocol::new(params.provider, light_params);
 				light_proto.add_handler(Arc::new(TxRelay(params.chain.clone())));
 
@@ -319,7 +333,7 @@ impl SyncProvider for EthSync {
 					remote_address: session_info.remote_address,
 					local_address: session_info.local_address,
 					eth_info: eth_sync.peer_info(&peer_id),
-					pip_info: light_proto.as_ref().and_then(|lp| lp.peer_status(&peer_id)).map(Into::into),
+					pip_info: light_proto.as_ref().and_then(|lp| PipProtocolInfo::from_proto(lp, &peer_id)),
 				})
 			}).collect()
 		}).unwrap_or_else(Vec::new)
@@ -496,6 +510,10 @@ pub trait ManageNetwork : Send + Sync {
 	fn stop_network(&self);
 	/// Query the current configuration of the network
 	fn network_config(&self) -> NetworkConfiguration;
+	/// Query the light client serving configuration, if serving light clients
+	fn light_serve_config(&self) -> Option<light_net::Config>;
+	/// Replace the light client serving configuration
+	fn set_light_serve_config(&self, config: light_net::Config) -> Result<(), String>;
 }
 
 
@@ -537,6 +555,21 @@ impl ManageNetwork for EthSync {
 	fn network_config(&self) -> NetworkConfiguration {
 		NetworkConfiguration::from(self.network.config().clone())
 	}
+
+	fn light_serve_config(&self) -> Option<light_net::Config> {
+		self.light_proto.as_ref().map(|lp| lp.config())
+	}
+
+	fn set_light_serve_config(&self, config: light_net::Config) -> Result<(), String> {
+		let light_proto = match self.light_proto.as_ref() {
+			Some(lp) => lp,
+			None => return Err("Not serving light clients".into()),
+		};
+
+		light_proto.set_config(config);
+		self.network.with_context(self.light_subprotocol_name, |context| light_proto.update_flow_params(context));
+		Ok(())
+	}
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -721,6 +754,7 @@ impl LightSync {
 					serve_chain_since: None,
 					serve_state_since: None,
 					tx_relay: false,
+					serve_bloom_bits: false,
 				},
 				sample_store: None,
 			};
@@ -807,6 +841,14 @@ impl ManageNetwork for LightSync {
 	fn network_config(&self) -> NetworkConfiguration {
 		NetworkConfiguration::from(self.network.config().clone())
 	}
+
+	fn light_serve_config(&self) -> Option<light_net::Config> {
+		None
+	}
+
+	fn set_light_serve_config(&self, _config: light_net::Config) -> Result<(), String> {
+		Err("Not serving light clients".into())
+	}
 }
 
 impl LightSyncProvider for LightSync {
@@ -838,7 +880,7 @@ impl LightSyncProvider for LightSync {
 					remote_address: session_info.remote_address,
 					local_address: session_info.local_address,
 					eth_info: None,
-					pip_info: self.proto.peer_status(&peer_id).map(Into::into),
+					pip_info: PipProtocolInfo::from_proto(&self.proto, &peer_id),
 				})
 			}).collect()
 		}).unwrap_or_else(Vec::new)
@@ -93,6 +93,7 @@ impl Peer {
 				serve_chain_since: None,
 				serve_state_since: None,
 				tx_relay: true,
+				serve_bloom_bits: true,
 			},
 			sample_store: None,
 		};
@@ -116,6 +117,7 @@ impl Peer {
 				serve_chain_since: None,
 				serve_state_since: None,
 				tx_relay: false,
+				serve_bloom_bits: false,
 			},
 			sample_store: None,
 		};